@@ -17,10 +17,13 @@
 
 use crate::error::CliError;
 use crate::http::submit_batches;
-use crate::transaction::{pike_batch_builder, PIKE_NAMESPACE};
+use crate::key::load_signing_key;
 use grid_sdk::{
-    protocol::pike::payload::{Action, CreateAgentAction, PikePayloadBuilder, UpdateAgentAction},
-    protos::IntoProto,
+    protocol::pike::payload::{
+        Action, CreateAgentAction, PikePayloadBuilder, RotateAgentKeyAction, TransferAgentAction,
+        UpdateAgentAction,
+    },
+    transact::BatchBuilder,
 };
 
 pub fn do_create_agent(
@@ -35,12 +38,9 @@ pub fn do_create_agent(
         .build()
         .map_err(|err| CliError::UserError(format!("{}", err)))?;
 
-    let batch_list = pike_batch_builder(key)
-        .add_transaction(
-            &payload.into_proto()?,
-            &[PIKE_NAMESPACE.to_string()],
-            &[PIKE_NAMESPACE.to_string()],
-        )?
+    let signer = load_signing_key(key)?;
+    let batch_list = BatchBuilder::new(&signer)
+        .add_transaction(&payload)?
         .create_batch_list();
 
     submit_batches(url, wait, &batch_list)
@@ -58,12 +58,49 @@ pub fn do_update_agent(
         .build()
         .map_err(|err| CliError::UserError(format!("{}", err)))?;
 
-    let batch_list = pike_batch_builder(key)
-        .add_transaction(
-            &payload.into_proto()?,
-            &[PIKE_NAMESPACE.to_string()],
-            &[PIKE_NAMESPACE.to_string()],
-        )?
+    let signer = load_signing_key(key)?;
+    let batch_list = BatchBuilder::new(&signer)
+        .add_transaction(&payload)?
+        .create_batch_list();
+
+    submit_batches(url, wait, &batch_list)
+}
+
+pub fn do_transfer_agent(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    transfer_agent: TransferAgentAction,
+) -> Result<(), CliError> {
+    let payload = PikePayloadBuilder::new()
+        .with_action(Action::TransferAgent)
+        .with_transfer_agent(transfer_agent)
+        .build()
+        .map_err(|err| CliError::UserError(format!("{}", err)))?;
+
+    let signer = load_signing_key(key)?;
+    let batch_list = BatchBuilder::new(&signer)
+        .add_transaction(&payload)?
+        .create_batch_list();
+
+    submit_batches(url, wait, &batch_list)
+}
+
+pub fn do_rotate_agent_key(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    rotate_agent_key: RotateAgentKeyAction,
+) -> Result<(), CliError> {
+    let payload = PikePayloadBuilder::new()
+        .with_action(Action::RotateAgentKey)
+        .with_rotate_agent_key(rotate_agent_key)
+        .build()
+        .map_err(|err| CliError::UserError(format!("{}", err)))?;
+
+    let signer = load_signing_key(key)?;
+    let batch_list = BatchBuilder::new(&signer)
+        .add_transaction(&payload)?
         .create_batch_list();
 
     submit_batches(url, wait, &batch_list)
@@ -17,4 +17,5 @@
 
 pub mod agents;
 pub mod organizations;
+pub mod records;
 pub mod schemas;
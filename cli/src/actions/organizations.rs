@@ -17,12 +17,12 @@
 
 use crate::error::CliError;
 use crate::http::submit_batches;
-use crate::transaction::{pike_batch_builder, PIKE_NAMESPACE};
+use crate::key::load_signing_key;
 use grid_sdk::{
     protocol::pike::payload::{
         Action, CreateOrganizationAction, PikePayloadBuilder, UpdateOrganizationAction,
     },
-    protos::IntoProto,
+    transact::BatchBuilder,
 };
 
 pub fn do_create_organization(
@@ -37,12 +37,9 @@ pub fn do_create_organization(
         .build()
         .map_err(|err| CliError::UserError(format!("{}", err)))?;
 
-    let batch_list = pike_batch_builder(key)
-        .add_transaction(
-            &payload.into_proto()?,
-            &[PIKE_NAMESPACE.to_string()],
-            &[PIKE_NAMESPACE.to_string()],
-        )?
+    let signer = load_signing_key(key)?;
+    let batch_list = BatchBuilder::new(&signer)
+        .add_transaction(&payload)?
         .create_batch_list();
 
     submit_batches(url, wait, &batch_list)
@@ -60,12 +57,9 @@ pub fn do_update_organization(
         .build()
         .map_err(|err| CliError::UserError(format!("{}", err)))?;
 
-    let batch_list = pike_batch_builder(key)
-        .add_transaction(
-            &payload.into_proto()?,
-            &[PIKE_NAMESPACE.to_string()],
-            &[PIKE_NAMESPACE.to_string()],
-        )?
+    let signer = load_signing_key(key)?;
+    let batch_list = BatchBuilder::new(&signer)
+        .add_transaction(&payload)?
         .create_batch_list();
 
     submit_batches(url, wait, &batch_list)
@@ -0,0 +1,363 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::File;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::http::submit_batches;
+use crate::key::load_signing_key;
+use grid_client::{ClientError, GridClient};
+use grid_sdk::protocol::addressing::schema::{
+    compute_schema_address, compute_schema_version_address,
+};
+use grid_sdk::protocol::addressing::track_and_trace::{
+    compute_property_address, compute_record_address,
+};
+use grid_sdk::protocol::schema::encoding::PropertyValueSeed;
+use grid_sdk::protocol::schema::state::{PropertyDefinition, PropertyValue, Schema, SchemaList};
+use grid_sdk::protocol::schema::validate::{
+    validate_property_values, validate_schema_values, ValidationError,
+};
+use grid_sdk::protocol::track_and_trace::payload::{
+    Action, CreateRecordActionBuilder, TrackAndTracePayload, TrackAndTracePayloadBuilder,
+    UpdatePropertiesActionBuilder,
+};
+use grid_sdk::protocol::track_and_trace::state::{PropertyList, RecordList};
+use grid_sdk::protos::FromBytes;
+use grid_sdk::transact::BatchBuilder;
+use serde::de::DeserializeSeed;
+use serde_yaml::{Mapping, Value};
+
+use crate::error::CliError;
+
+/// Creates a record from a yaml file mapping property names to values. The
+/// values are validated against the schema of the record, or the version of
+/// it given by `schema_version` when not 0, before the record is submitted.
+pub fn do_create_record(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    record_id: &str,
+    schema_name: &str,
+    schema_version: u32,
+    path: &str,
+) -> Result<(), CliError> {
+    let client = GridClient::new(url);
+    let schema = fetch_schema(&client, schema_name, schema_version)?;
+
+    let definitions = schema.effective_properties();
+    let (properties, mut errors) = parse_property_values(read_yaml(path)?, |name| {
+        Ok(definitions
+            .iter()
+            .find(|definition| definition.name() == name)
+            .cloned())
+    })?;
+    if let Err(mut value_errors) = validate_schema_values(&schema, &properties) {
+        errors.append(&mut value_errors);
+    }
+    check_property_values(errors)?;
+
+    let create_record = CreateRecordActionBuilder::new()
+        .with_record_id(record_id.to_string())
+        .with_schema(schema_name.to_string())
+        .with_schema_version(schema_version)
+        .with_properties(properties)
+        .build()
+        .map_err(|err| CliError::UserError(format!("{}", err)))?;
+
+    submit_track_and_trace_payload(url, key, wait, Action::CreateRecord(create_record))
+}
+
+/// Updates properties of a record from a yaml file mapping property names to
+/// values. The values are validated against the definitions of the record's
+/// properties before they are submitted.
+pub fn do_update_properties(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    record_id: &str,
+    path: &str,
+) -> Result<(), CliError> {
+    let client = GridClient::new(url);
+    if !record_exists(&client, record_id)? {
+        return Err(CliError::UserError(format!(
+            "Record {} does not exist",
+            record_id
+        )));
+    }
+
+    let mut definitions = vec![];
+    let (properties, mut errors) = parse_property_values(read_yaml(path)?, |name| {
+        let definition = fetch_property_definition(&client, record_id, name)?;
+        definitions.extend(definition.clone());
+        Ok(definition)
+    })?;
+    if let Err(mut value_errors) = validate_property_values(&definitions, &properties) {
+        errors.append(&mut value_errors);
+    }
+    check_property_values(errors)?;
+
+    let update_properties = UpdatePropertiesActionBuilder::new()
+        .with_record_id(record_id.to_string())
+        .with_properties(properties)
+        .build()
+        .map_err(|err| CliError::UserError(format!("{}", err)))?;
+
+    submit_track_and_trace_payload(url, key, wait, Action::UpdateProperties(update_properties))
+}
+
+fn submit_track_and_trace_payload(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    action: Action,
+) -> Result<(), CliError> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| CliError::PayloadError(format!("Invalid system time: {}", err)))?
+        .as_secs();
+
+    let payload: TrackAndTracePayload = TrackAndTracePayloadBuilder::new()
+        .with_action(action)
+        .with_timestamp(timestamp)
+        .build()
+        .map_err(|err| CliError::UserError(format!("{}", err)))?;
+
+    let signer = load_signing_key(key)?;
+    let batch_list = BatchBuilder::new(&signer)
+        .add_transaction(&payload)?
+        .create_batch_list();
+
+    submit_batches(url, wait, &batch_list)
+}
+
+fn read_yaml(path: &str) -> Result<Mapping, CliError> {
+    let file = File::open(path)?;
+    Ok(serde_yaml::from_reader(file)?)
+}
+
+/// Reads property values from a mapping of property names to values, each in
+/// the encoding of its definition. Names without a definition are returned as
+/// UnknownProperty errors, so they are reported along with the errors of the
+/// values.
+fn parse_property_values<F>(
+    mapping: Mapping,
+    mut definition_of: F,
+) -> Result<(Vec<PropertyValue>, Vec<ValidationError>), CliError>
+where
+    F: FnMut(&str) -> Result<Option<PropertyDefinition>, CliError>,
+{
+    let mut values = vec![];
+    let mut errors = vec![];
+    for (name, value) in mapping {
+        let name = match name {
+            Value::String(name) => name,
+            name => {
+                return Err(CliError::InvalidYamlError(format!(
+                    "Property name is not a string: {:?}",
+                    name
+                )));
+            }
+        };
+
+        match definition_of(&name)? {
+            Some(definition) => values.push(
+                PropertyValueSeed(&definition)
+                    .deserialize(value)
+                    .map_err(|err| {
+                        CliError::InvalidYamlError(format!(
+                            "Invalid value for property {}: {}",
+                            name, err
+                        ))
+                    })?,
+            ),
+            None => errors.push(ValidationError::UnknownProperty { path: name }),
+        }
+    }
+
+    Ok((values, errors))
+}
+
+fn check_property_values(errors: Vec<ValidationError>) -> Result<(), CliError> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::ValidationError(errors))
+    }
+}
+
+fn fetch_schema(client: &GridClient, name: &str, version: u32) -> Result<Schema, CliError> {
+    let address = match version {
+        0 => compute_schema_address(name),
+        version => compute_schema_version_address(name, version),
+    };
+
+    fetch_state_entry(client, &address)?
+        .map(|data| SchemaList::from_bytes(&data))
+        .transpose()?
+        .and_then(|schemas| {
+            schemas
+                .schemas()
+                .iter()
+                .find(|schema| schema.name() == name)
+                .cloned()
+        })
+        .ok_or_else(|| match version {
+            0 => CliError::UserError(format!("Schema {} does not exist", name)),
+            version => CliError::UserError(format!(
+                "Version {} of schema {} does not exist",
+                version, name
+            )),
+        })
+}
+
+fn record_exists(client: &GridClient, record_id: &str) -> Result<bool, CliError> {
+    Ok(
+        fetch_state_entry(client, &compute_record_address(record_id))?
+            .map(|data| RecordList::from_bytes(&data))
+            .transpose()?
+            .map_or(false, |records| {
+                records
+                    .records()
+                    .iter()
+                    .any(|record| record.record_id() == record_id)
+            }),
+    )
+}
+
+fn fetch_property_definition(
+    client: &GridClient,
+    record_id: &str,
+    name: &str,
+) -> Result<Option<PropertyDefinition>, CliError> {
+    let address = compute_property_address(record_id, name, 0);
+    Ok(fetch_state_entry(client, &address)?
+        .map(|data| PropertyList::from_bytes(&data))
+        .transpose()?
+        .and_then(|properties| {
+            properties
+                .properties()
+                .iter()
+                .find(|property| property.name() == name && property.record_id() == record_id)
+                .map(|property| property.property_definition().clone())
+        }))
+}
+
+fn fetch_state_entry(client: &GridClient, address: &str) -> Result<Option<Vec<u8>>, CliError> {
+    match client.fetch_state(address, false) {
+        Ok(response) => base64::decode(&response.data.data)
+            .map(Some)
+            .map_err(|err| {
+                CliError::PayloadError(format!("Invalid state entry at {}: {}", address, err))
+            }),
+        Err(ClientError::NotFound(_)) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use grid_sdk::protocol::schema::state::{DataType, PropertyDefinitionBuilder};
+
+    fn definitions() -> Vec<PropertyDefinition> {
+        vec![
+            PropertyDefinitionBuilder::new()
+                .with_name("weight".to_string())
+                .with_data_type(DataType::Number)
+                .with_number_exponent(-2)
+                .with_number_max(5000)
+                .build()
+                .unwrap(),
+            PropertyDefinitionBuilder::new()
+                .with_name("serial".to_string())
+                .with_data_type(DataType::String)
+                .with_required(true)
+                .with_string_pattern(r"^[A-Z]{3}\d{6}$".to_string())
+                .build()
+                .unwrap(),
+        ]
+    }
+
+    fn parse(yaml: &str) -> (Vec<PropertyValue>, Vec<ValidationError>) {
+        let definitions = definitions();
+        parse_property_values(serde_yaml::from_str(yaml).unwrap(), |name| {
+            Ok(definitions
+                .iter()
+                .find(|definition| definition.name() == name)
+                .cloned())
+        })
+        .unwrap()
+    }
+
+    ///
+    /// Verifies parse_property_values reads each value in the encoding of its definition
+    ///
+    #[test]
+    fn test_parse_property_values() {
+        let (values, errors) = parse("weight: \"12.50\"\nserial: ABC123456\n");
+
+        assert!(errors.is_empty());
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].name(), "weight");
+        assert_eq!(*values[0].number_value(), 1250);
+        assert_eq!(values[1].name(), "serial");
+        assert_eq!(values[1].string_value(), "ABC123456");
+        assert_eq!(validate_property_values(&definitions(), &values), Ok(()));
+    }
+
+    ///
+    /// Verifies the property values of a yaml file are checked before they are submitted,
+    /// and that every error is reported with the path of its property
+    ///
+    #[test]
+    fn test_invalid_property_values() {
+        let (values, mut errors) = parse("weight: \"60.00\"\nserial: abc\ncolor: red\n");
+        errors.append(&mut validate_property_values(&definitions(), &values).unwrap_err());
+
+        let err = check_property_values(errors).unwrap_err();
+        match err {
+            CliError::ValidationError(ref errors) => {
+                let paths = errors.iter().map(ValidationError::path).collect::<Vec<_>>();
+                assert_eq!(paths, vec!["color", "weight", "serial"]);
+            }
+            ref err => panic!("Expected a validation error, got {:?}", err),
+        }
+        assert!(err
+            .to_string()
+            .contains("\n  color: Provided property color is not in schema"));
+    }
+
+    ///
+    /// Verifies a value that cannot be read in the encoding of its definition is rejected
+    ///
+    #[test]
+    fn test_malformed_property_value() {
+        let definitions = definitions();
+        let result =
+            parse_property_values(serde_yaml::from_str("weight: heavy\n").unwrap(), |name| {
+                Ok(definitions
+                    .iter()
+                    .find(|definition| definition.name() == name)
+                    .cloned())
+            });
+
+        match result {
+            Err(CliError::InvalidYamlError(msg)) => {
+                assert!(msg.starts_with("Invalid value for property weight"))
+            }
+            res => panic!("Expected InvalidYamlError, got {:?}", res),
+        }
+    }
+}
@@ -13,51 +13,43 @@
 // limitations under the License.
 
 use crate::http::submit_batches;
-use crate::transaction::{schema_batch_builder, GRID_SCHEMA_NAMESPACE, PIKE_NAMESPACE};
+use crate::key::load_signing_key;
+use grid_client::models::{GridPropertyDefinitionSlice, GridSchemaSlice};
+use grid_client::GridClient;
 use grid_sdk::protocol::schema::payload::{
-    Action, SchemaCreateBuilder, SchemaPayload, SchemaPayloadBuilder, SchemaUpdateBuilder,
+    Action, PropertyGroupCreateBuilder, SchemaCreateBuilder, SchemaDeprecateBuilder, SchemaPayload,
+    SchemaPayloadBuilder, SchemaTransferBuilder, SchemaUpdateBuilder,
 };
 use grid_sdk::protocol::schema::state::{DataType, PropertyDefinition, PropertyDefinitionBuilder};
-use grid_sdk::protos::IntoProto;
-use reqwest::Client;
+use grid_sdk::transact::BatchBuilder;
 
 use crate::error::CliError;
-use serde::Deserialize;
 use serde_yaml::{Mapping, Sequence, Value};
 
-#[derive(Debug, Deserialize)]
-pub struct GridSchemaSlice {
-    pub name: String,
-    pub description: String,
-    pub owner: String,
-    pub properties: Vec<GridPropertyDefinitionSlice>,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct GridPropertyDefinitionSlice {
-    pub name: String,
-    pub schema_name: String,
-    pub data_type: String,
-    pub required: bool,
-    pub description: String,
-    pub number_exponent: i64,
-    pub enum_options: Vec<String>,
-    pub struct_properties: Vec<String>,
-}
-
 pub fn display_schema(schema: &GridSchemaSlice) {
     println!(
-        "Name: {:?}\n Description: {:?}\n Owner: {:?}\n Properties:",
-        schema.name, schema.description, schema.owner,
+        "Name: {:?}\n Description: {:?}\n Owner: {:?}\n Version: {:?}\n Deprecated: {:?}
+ Parent: {:?}\n Parent Version: {:?}\n Property Groups: {:?}\n Properties:",
+        schema.name,
+        schema.description,
+        schema.owner,
+        schema.version,
+        schema.deprecated,
+        schema.parent,
+        schema.parent_version,
+        schema.property_groups,
     );
     display_schema_property_definitions(&schema.properties);
+    println!(" Effective Properties:");
+    display_schema_property_definitions(&schema.effective_properties);
 }
 
 pub fn display_schema_property_definitions(properties: &[GridPropertyDefinitionSlice]) {
     properties.iter().for_each(|def| {
         println!(
             "\tName: {:?}\n\t Data Type: {:?}\n\t Required: {:?}\n\t Description: {:?}
-        Number Exponent: {:?}\n\t Enum Options: {:?}\n\t Struct Properties: {:?}",
+        Number Exponent: {:?}\n\t Enum Options: {:?}\n\t Struct Properties: {:?}
+        Deprecated: {:?}\n\t Inherited: {:?}",
             def.name,
             def.data_type,
             def.required,
@@ -65,26 +57,26 @@ pub fn display_schema_property_definitions(properties: &[GridPropertyDefinitionS
             def.number_exponent,
             def.enum_options,
             def.struct_properties,
+            def.deprecated,
+            def.inherited,
         );
     });
 }
 
 pub fn do_list_schemas(url: &str) -> Result<(), CliError> {
-    let client = Client::new();
-    let schemas = client
-        .get(&format!("{}/schema", url))
-        .send()?
-        .json::<Vec<GridSchemaSlice>>()?;
+    let schemas = GridClient::new(url).list_schemas()?;
     schemas.iter().for_each(|schema| display_schema(schema));
     Ok(())
 }
 
 pub fn do_show_schema(url: &str, name: &str) -> Result<(), CliError> {
-    let client = Client::new();
-    let schema = client
-        .get(&format!("{}/schema/{}", url, name))
-        .send()?
-        .json::<GridSchemaSlice>()?;
+    let schema = GridClient::new(url).fetch_schema(name)?;
+    display_schema(&schema);
+    Ok(())
+}
+
+pub fn do_show_schema_version(url: &str, name: &str, version: i64) -> Result<(), CliError> {
+    let schema = GridClient::new(url).fetch_schema_version(name, version)?;
     display_schema(&schema);
     Ok(())
 }
@@ -96,16 +88,10 @@ pub fn do_create_schemas(
     path: &str,
 ) -> Result<(), CliError> {
     let payloads = parse_yaml(path, Action::SchemaCreate)?;
-    let mut batch_list_builder = schema_batch_builder(key);
+    let signer = load_signing_key(key)?;
+    let mut batch_list_builder = BatchBuilder::new(&signer);
     for payload in payloads {
-        batch_list_builder = batch_list_builder.add_transaction(
-            &payload.into_proto()?,
-            &[
-                PIKE_NAMESPACE.to_string(),
-                GRID_SCHEMA_NAMESPACE.to_string(),
-            ],
-            &[GRID_SCHEMA_NAMESPACE.to_string()],
-        )?;
+        batch_list_builder = batch_list_builder.add_transaction(&payload)?;
     }
 
     let batch_list = batch_list_builder.create_batch_list();
@@ -120,16 +106,28 @@ pub fn do_update_schemas(
     path: &str,
 ) -> Result<(), CliError> {
     let payloads = parse_yaml(path, Action::SchemaUpdate)?;
-    let mut batch_list_builder = schema_batch_builder(key);
+    let signer = load_signing_key(key)?;
+    let mut batch_list_builder = BatchBuilder::new(&signer);
+    for payload in payloads {
+        batch_list_builder = batch_list_builder.add_transaction(&payload)?;
+    }
+
+    let batch_list = batch_list_builder.create_batch_list();
+
+    submit_batches(url, wait, &batch_list)
+}
+
+pub fn do_create_property_groups(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    path: &str,
+) -> Result<(), CliError> {
+    let payloads = parse_yaml(path, Action::PropertyGroupCreate)?;
+    let signer = load_signing_key(key)?;
+    let mut batch_list_builder = BatchBuilder::new(&signer);
     for payload in payloads {
-        batch_list_builder = batch_list_builder.add_transaction(
-            &payload.into_proto()?,
-            &[
-                PIKE_NAMESPACE.to_string(),
-                GRID_SCHEMA_NAMESPACE.to_string(),
-            ],
-            &[GRID_SCHEMA_NAMESPACE.to_string()],
-        )?;
+        batch_list_builder = batch_list_builder.add_transaction(&payload)?;
     }
 
     let batch_list = batch_list_builder.create_batch_list();
@@ -137,6 +135,72 @@ pub fn do_update_schemas(
     submit_batches(url, wait, &batch_list)
 }
 
+pub fn do_transfer_schema(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    name: &str,
+    new_owner: &str,
+) -> Result<(), CliError> {
+    let transfer = SchemaTransferBuilder::new()
+        .with_schema_name(name.to_string())
+        .with_new_owner(new_owner.to_string())
+        .build()
+        .map_err(|err| {
+            CliError::PayloadError(format!("Failed to build schema payload: {}", err))
+        })?;
+
+    let payload = SchemaPayloadBuilder::new()
+        .with_action(Action::SchemaTransfer)
+        .with_schema_transfer(transfer)
+        .build()
+        .map_err(|err| {
+            CliError::PayloadError(format!("Failed to build schema payload: {}", err))
+        })?;
+
+    submit_schema_payload(url, key, wait, &payload)
+}
+
+pub fn do_deprecate_schema(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    name: &str,
+    properties: Vec<String>,
+) -> Result<(), CliError> {
+    let deprecate = SchemaDeprecateBuilder::new()
+        .with_schema_name(name.to_string())
+        .with_properties(properties)
+        .build()
+        .map_err(|err| {
+            CliError::PayloadError(format!("Failed to build schema payload: {}", err))
+        })?;
+
+    let payload = SchemaPayloadBuilder::new()
+        .with_action(Action::SchemaDeprecate)
+        .with_schema_deprecate(deprecate)
+        .build()
+        .map_err(|err| {
+            CliError::PayloadError(format!("Failed to build schema payload: {}", err))
+        })?;
+
+    submit_schema_payload(url, key, wait, &payload)
+}
+
+fn submit_schema_payload(
+    url: &str,
+    key: Option<String>,
+    wait: u64,
+    payload: &SchemaPayload,
+) -> Result<(), CliError> {
+    let signer = load_signing_key(key)?;
+    let batch_list = BatchBuilder::new(&signer)
+        .add_transaction(payload)?
+        .create_batch_list();
+
+    submit_batches(url, wait, &batch_list)
+}
+
 fn parse_yaml(path: &str, action: Action) -> Result<Vec<SchemaPayload>, CliError> {
     let file = std::fs::File::open(path)?;
     let schemas_yaml: Vec<Mapping> = serde_yaml::from_reader(file)?;
@@ -145,12 +209,19 @@ fn parse_yaml(path: &str, action: Action) -> Result<Vec<SchemaPayload>, CliError
         Action::SchemaCreate => schemas_yaml
             .iter()
             .map(|schema_yaml| {
-                let properties =
-                    parse_value_as_sequence(schema_yaml, "properties")?.ok_or_else(|| {
-                        CliError::InvalidYamlError(
+                let parent = parse_value_as_string(schema_yaml, "parent")?;
+                let property_groups =
+                    parse_value_as_vec_string(schema_yaml, "property_groups")?.unwrap_or_default();
+                // A schema composed from a parent or property groups may declare no properties
+                let properties = match parse_value_as_sequence(schema_yaml, "properties")? {
+                    Some(properties) => properties,
+                    None if parent.is_some() || !property_groups.is_empty() => vec![],
+                    None => {
+                        return Err(CliError::InvalidYamlError(
                             "Schema definition is missing `properties` field.".to_string(),
-                        )
-                    })?;
+                        ));
+                    }
+                };
                 let property_definitions = parse_properties(&properties)?;
                 let schema_name = parse_value_as_string(schema_yaml, "name")?.ok_or_else(|| {
                     CliError::InvalidYamlError("Missing `name` field for schema.".to_string())
@@ -161,6 +232,8 @@ fn parse_yaml(path: &str, action: Action) -> Result<Vec<SchemaPayload>, CliError
                     &schema_name,
                     &property_definitions,
                     schema_description,
+                    parent,
+                    &property_groups,
                 )
             })
             .collect::<Result<Vec<SchemaPayload>, _>>(),
@@ -182,6 +255,36 @@ fn parse_yaml(path: &str, action: Action) -> Result<Vec<SchemaPayload>, CliError
                 generate_update_schema_payload(&schema_name, &property_definitions)
             })
             .collect::<Result<Vec<SchemaPayload>, _>>(),
+
+        Action::PropertyGroupCreate => schemas_yaml
+            .iter()
+            .map(|group_yaml| {
+                let properties =
+                    parse_value_as_sequence(group_yaml, "properties")?.ok_or_else(|| {
+                        CliError::InvalidYamlError(
+                            "Property group definition is missing `properties` field.".to_string(),
+                        )
+                    })?;
+                let property_definitions = parse_properties(&properties)?;
+                let group_name = parse_value_as_string(group_yaml, "name")?.ok_or_else(|| {
+                    CliError::InvalidYamlError(
+                        "Missing `name` field for property group.".to_string(),
+                    )
+                })?;
+                let group_description = parse_value_as_string(group_yaml, "description")?;
+
+                generate_create_property_group_payload(
+                    &group_name,
+                    &property_definitions,
+                    group_description,
+                )
+            })
+            .collect::<Result<Vec<SchemaPayload>, _>>(),
+
+        Action::SchemaTransfer | Action::SchemaDeprecate => Err(CliError::PayloadError(format!(
+            "{:?} payloads are not read from yaml files",
+            action
+        ))),
     }
 }
 
@@ -189,12 +292,15 @@ fn generate_create_schema_payload(
     name: &str,
     properties: &[PropertyDefinition],
     description: Option<String>,
+    parent: Option<String>,
+    property_groups: &[String],
 ) -> Result<SchemaPayload, CliError> {
     let mut schema_paylod = SchemaPayloadBuilder::new();
     schema_paylod = schema_paylod.with_action(Action::SchemaCreate);
 
     let mut schema_create_action_builder = SchemaCreateBuilder::new()
         .with_schema_name(name.to_string())
+        .with_property_groups(property_groups.to_vec())
         .with_properties(properties.to_vec());
 
     schema_create_action_builder = match description {
@@ -202,6 +308,11 @@ fn generate_create_schema_payload(
         None => schema_create_action_builder,
     };
 
+    schema_create_action_builder = match parent {
+        Some(parent) => schema_create_action_builder.with_parent(parent),
+        None => schema_create_action_builder,
+    };
+
     let schema_create_action = schema_create_action_builder.build().map_err(|err| {
         CliError::PayloadError(format!("Failed to build schema payload: {}", err))
     })?;
@@ -212,6 +323,34 @@ fn generate_create_schema_payload(
         .map_err(|err| CliError::PayloadError(format!("Failed to build schema payload: {}", err)))
 }
 
+fn generate_create_property_group_payload(
+    name: &str,
+    properties: &[PropertyDefinition],
+    description: Option<String>,
+) -> Result<SchemaPayload, CliError> {
+    let mut property_group_create_action_builder = PropertyGroupCreateBuilder::new()
+        .with_name(name.to_string())
+        .with_properties(properties.to_vec());
+
+    property_group_create_action_builder = match description {
+        Some(description) => property_group_create_action_builder.with_description(description),
+        None => property_group_create_action_builder,
+    };
+
+    let property_group_create_action =
+        property_group_create_action_builder
+            .build()
+            .map_err(|err| {
+                CliError::PayloadError(format!("Failed to build schema payload: {}", err))
+            })?;
+
+    SchemaPayloadBuilder::new()
+        .with_action(Action::PropertyGroupCreate)
+        .with_property_group_create(property_group_create_action)
+        .build()
+        .map_err(|err| CliError::PayloadError(format!("Failed to build schema payload: {}", err)))
+}
+
 fn generate_update_schema_payload(
     name: &str,
     properties: &[PropertyDefinition],
@@ -303,11 +442,42 @@ fn parse_property_definition(property: &Mapping) -> Result<PropertyDefinition, C
         _ => property_definition,
     };
 
+    property_definition = parse_constraints(property, property_definition)?;
+
     property_definition.build().map_err(|err| {
         CliError::PayloadError(format!("Failed to build property definition: {}", err))
     })
 }
 
+fn parse_constraints(
+    property: &Mapping,
+    mut property_definition: PropertyDefinitionBuilder,
+) -> Result<PropertyDefinitionBuilder, CliError> {
+    if let Some(number_min) = parse_value_as_i64(property, "number_min")? {
+        property_definition = property_definition.with_number_min(number_min);
+    }
+    if let Some(number_max) = parse_value_as_i64(property, "number_max")? {
+        property_definition = property_definition.with_number_max(number_max);
+    }
+    if let Some(string_pattern) = parse_value_as_string(property, "string_pattern")? {
+        property_definition = property_definition.with_string_pattern(string_pattern);
+    }
+    if let Some(string_min_length) = parse_value_as_u32(property, "string_min_length")? {
+        property_definition = property_definition.with_string_min_length(string_min_length);
+    }
+    if let Some(string_max_length) = parse_value_as_u32(property, "string_max_length")? {
+        property_definition = property_definition.with_string_max_length(string_max_length);
+    }
+    if let Some(bytes_max_size) = parse_value_as_u32(property, "bytes_max_size")? {
+        property_definition = property_definition.with_bytes_max_size(bytes_max_size);
+    }
+    if let Some(unit) = parse_value_as_string(property, "unit")? {
+        property_definition = property_definition.with_unit(unit);
+    }
+
+    Ok(property_definition)
+}
+
 fn parse_data_type(data_type: &str) -> Result<DataType, CliError> {
     match data_type.to_lowercase().as_ref() {
         "string" => Ok(DataType::String),
@@ -380,6 +550,37 @@ fn parse_value_as_i32(property: &Mapping, key: &str) -> Result<Option<i32>, CliE
     }
 }
 
+fn parse_value_as_i64(property: &Mapping, key: &str) -> Result<Option<i64>, CliError> {
+    match property.get(&Value::String(key.to_string())) {
+        Some(value) => match value.as_i64() {
+            Some(value) => Ok(Some(value)),
+            None => Err(CliError::InvalidYamlError(format!(
+                "Value of {} has an invalid format. Expected is a yaml integer.",
+                key
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+
+fn parse_value_as_u32(property: &Mapping, key: &str) -> Result<Option<u32>, CliError> {
+    match property.get(&Value::String(key.to_string())) {
+        Some(value) => match value.as_u64() {
+            Some(value) => Ok(Some(value.to_string().parse::<u32>().map_err(|_| {
+                CliError::InvalidYamlError(format!(
+                    "Failed to parse value of {} to unsigned 32 bit integer",
+                    key
+                ))
+            })?)),
+            None => Err(CliError::InvalidYamlError(format!(
+                "Value of {} has an invalid format. Expected is a non-negative yaml integer.",
+                key
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+
 fn parse_value_as_vec_string(
     property: &Mapping,
     key: &str,
@@ -470,6 +671,27 @@ mod test {
         data_type: lat_long
         description: "Location where manufacturer is headquarted.""##;
 
+    static SENSOR_YAML_EXAMPLE: &[u8; 530] = br##"- name: "Sensor"
+  description: "Example constrained schema"
+  properties:
+    - name: "temperature"
+      data_type: NUMBER
+      description: "Operating temperature"
+      number_exponent: 0
+      number_min: -40
+      number_max: 85
+      unit: "Cel"
+    - name: "serial"
+      data_type: STRING
+      description: "Serial number"
+      string_pattern: '^[A-Z]{3}\d{6}$'
+      string_max_length: 9
+      required: true
+    - name: "firmware"
+      data_type: BYTES
+      description: "Firmware image"
+      bytes_max_size: 4096"##;
+
     ///
     /// Verifies parse_yaml returns a valid SchemaPayload with SchemaCreateAction set from a yaml
     /// containing a single schema definition
@@ -554,6 +776,113 @@ mod test {
         })
     }
 
+    ///
+    /// Verifies parse_yaml reads the constraints of property definitions and rejects
+    /// constraints that do not apply to the data type of the property
+    ///
+    #[test]
+    fn test_valid_yaml_create_schema_with_constraints() {
+        run_test(|test_yaml_file_path| {
+            let mut file =
+                File::create(test_yaml_file_path).expect("Error creating test schema yaml file.");
+
+            file.write_all(SENSOR_YAML_EXAMPLE)
+                .expect("Error writting example schema.");
+
+            let payload =
+                parse_yaml(test_yaml_file_path, Action::SchemaCreate).expect("Error parsing yaml");
+
+            let expected = generate_create_schema_payload(
+                "Sensor",
+                &create_sensor_property_definitions(),
+                Some("Example constrained schema".to_string()),
+                None,
+                &[],
+            )
+            .unwrap();
+            assert_eq!(expected, payload[0]);
+
+            let mut file =
+                File::create(test_yaml_file_path).expect("Error creating test schema yaml file.");
+            file.write_all(
+                br##"- name: "Sensor"
+  properties:
+    - name: "serial"
+      data_type: STRING
+      number_max: 10"##,
+            )
+            .expect("Error writting example schema.");
+
+            assert!(parse_yaml(test_yaml_file_path, Action::SchemaCreate).is_err());
+        })
+    }
+
+    ///
+    /// Verifies parse_yaml reads the parent and property groups of a schema, which may then
+    /// declare no properties, and reads property group definitions
+    ///
+    #[test]
+    fn test_valid_yaml_create_composed_schema() {
+        run_test(|test_yaml_file_path| {
+            let mut file =
+                File::create(test_yaml_file_path).expect("Error creating test schema yaml file.");
+            file.write_all(
+                br##"- name: "SmartLightbulb"
+  parent: "Lightbulb"
+  property_groups: ["connectivity"]"##,
+            )
+            .expect("Error writting example schema.");
+
+            let payload =
+                parse_yaml(test_yaml_file_path, Action::SchemaCreate).expect("Error parsing yaml");
+
+            let expected = generate_create_schema_payload(
+                "SmartLightbulb",
+                &[],
+                None,
+                Some("Lightbulb".to_string()),
+                &["connectivity".to_string()],
+            )
+            .unwrap();
+            assert_eq!(expected, payload[0]);
+
+            let mut file =
+                File::create(test_yaml_file_path).expect("Error creating test schema yaml file.");
+            file.write_all(br##"- name: "SmartLightbulb""##)
+                .expect("Error writting example schema.");
+
+            assert!(parse_yaml(test_yaml_file_path, Action::SchemaCreate).is_err());
+
+            let mut file =
+                File::create(test_yaml_file_path).expect("Error creating test schema yaml file.");
+            file.write_all(
+                br##"- name: "connectivity"
+  description: "Wireless connectivity"
+  properties:
+    - name: "protocol"
+      data_type: STRING
+      description: "Wireless protocol""##,
+            )
+            .expect("Error writting example property group.");
+
+            let payload = parse_yaml(test_yaml_file_path, Action::PropertyGroupCreate)
+                .expect("Error parsing yaml");
+
+            let expected = generate_create_property_group_payload(
+                "connectivity",
+                &[PropertyDefinitionBuilder::new()
+                    .with_name("protocol".to_string())
+                    .with_data_type(DataType::String)
+                    .with_description("Wireless protocol".to_string())
+                    .build()
+                    .unwrap()],
+                Some("Wireless connectivity".to_string()),
+            )
+            .unwrap();
+            assert_eq!(expected, payload[0]);
+        })
+    }
+
     ///
     /// Verifies parse_data_type returns the expected data_types for valid inputs and returns an
     /// error for a invalid input
@@ -754,6 +1083,8 @@ mod test {
             "Lightbulb",
             &create_lightbulb_property_definitions(),
             Some("Example Lightbulb schema".to_string()),
+            None,
+            &[],
         )
         .unwrap()
     }
@@ -763,6 +1094,8 @@ mod test {
             "Phone",
             &create_phone_property_definitions(),
             Some("Example phone schema".to_string()),
+            None,
+            &[],
         )
         .unwrap()
     }
@@ -798,6 +1131,37 @@ mod test {
         ]
     }
 
+    fn create_sensor_property_definitions() -> Vec<PropertyDefinition> {
+        vec![
+            PropertyDefinitionBuilder::new()
+                .with_name("temperature".to_string())
+                .with_data_type(DataType::Number)
+                .with_description("Operating temperature".to_string())
+                .with_number_exponent(0)
+                .with_number_min(-40)
+                .with_number_max(85)
+                .with_unit("Cel".to_string())
+                .build()
+                .unwrap(),
+            PropertyDefinitionBuilder::new()
+                .with_name("serial".to_string())
+                .with_data_type(DataType::String)
+                .with_description("Serial number".to_string())
+                .with_required(true)
+                .with_string_pattern(r"^[A-Z]{3}\d{6}$".to_string())
+                .with_string_max_length(9)
+                .build()
+                .unwrap(),
+            PropertyDefinitionBuilder::new()
+                .with_name("firmware".to_string())
+                .with_data_type(DataType::Bytes)
+                .with_description("Firmware image".to_string())
+                .with_bytes_max_size(4096)
+                .build()
+                .unwrap(),
+        ]
+    }
+
     fn create_phone_property_definitions() -> Vec<PropertyDefinition> {
         vec![
             make_string_property_definition("brand", true, "Name of the brand"),
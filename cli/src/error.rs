@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use grid_client::ClientError;
+use grid_sdk::protocol::schema::validate::ValidationError;
 use grid_sdk::protos;
+use grid_sdk::transact::TransactError;
 use log;
 use protobuf;
-use reqwest;
 use sabre_sdk;
 use sawtooth_sdk::signing;
 use serde_yaml;
@@ -32,9 +34,11 @@ pub enum CliError {
     SigningError(signing::Error),
     IoError(io::Error),
     ProtobufError(protobuf::ProtobufError),
-    ReqwestError(reqwest::Error),
+    GridClientError(ClientError),
     GridProtoError(protos::ProtoConversionError),
     SabreProtoError(sabre_sdk::protos::ProtoConversionError),
+    TransactError(TransactError),
+    ValidationError(Vec<ValidationError>),
 }
 
 impl StdError for CliError {
@@ -47,9 +51,11 @@ impl StdError for CliError {
             CliError::IoError(err) => Some(err),
             CliError::ProtobufError(err) => Some(err),
             CliError::SigningError(err) => Some(err),
-            CliError::ReqwestError(err) => Some(err),
+            CliError::GridClientError(err) => Some(err),
             CliError::GridProtoError(err) => Some(err),
             CliError::SabreProtoError(err) => Some(err),
+            CliError::TransactError(err) => Some(err),
+            CliError::ValidationError(_) => None,
         }
     }
 }
@@ -66,9 +72,17 @@ impl std::fmt::Display for CliError {
             CliError::LoggingInitializationError(ref err) => {
                 write!(f, "LoggingInitializationError: {}", err.description())
             }
-            CliError::ReqwestError(ref err) => write!(f, "Reqwest Error: {}", err),
+            CliError::GridClientError(ref err) => write!(f, "Grid Client Error: {}", err),
             CliError::GridProtoError(ref err) => write!(f, "Grid Proto Error: {}", err),
             CliError::SabreProtoError(ref err) => write!(f, "Sabre Proto Error: {}", err),
+            CliError::TransactError(ref err) => write!(f, "Transact Error: {}", err),
+            CliError::ValidationError(ref errors) => {
+                write!(f, "Invalid property values:")?;
+                for err in errors {
+                    write!(f, "\n  {}: {}", err.path(), err)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -102,9 +116,9 @@ impl From<protobuf::ProtobufError> for CliError {
     }
 }
 
-impl From<reqwest::Error> for CliError {
-    fn from(err: reqwest::Error) -> Self {
-        CliError::ReqwestError(err)
+impl From<ClientError> for CliError {
+    fn from(err: ClientError) -> Self {
+        CliError::GridClientError(err)
     }
 }
 
@@ -119,3 +133,9 @@ impl From<sabre_sdk::protos::ProtoConversionError> for CliError {
         CliError::SabreProtoError(err)
     }
 }
+
+impl From<TransactError> for CliError {
+    fn from(err: TransactError) -> Self {
+        CliError::TransactError(err)
+    }
+}
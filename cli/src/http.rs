@@ -16,62 +16,15 @@
  */
 
 use crate::CliError;
-use protobuf::Message;
-use reqwest::Client;
+use grid_client::GridClient;
 use sawtooth_sdk::messages::batch::BatchList;
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::time::Instant;
 
-pub fn submit_batches(url: &str, mut wait: u64, batch_list: &BatchList) -> Result<(), CliError> {
-    let bytes = batch_list.write_to_bytes()?;
+/// Submits batches to the REST API, then waits up to `wait` seconds for them
+/// to be committed
+pub fn submit_batches(url: &str, wait: u64, batch_list: &BatchList) -> Result<(), CliError> {
+    let batch_statuses = GridClient::new(url).submit_batches_and_wait(batch_list, wait)?;
 
-    let client = Client::new();
-
-    let batch_link = client
-        .post(&format!("{}/batches", url))
-        .body(bytes)
-        .send()?
-        .json::<BatchStatusLink>()?;
-
-    debug!("Response: {:#?}", batch_link);
-
-    while wait > 0 {
-        let time = Instant::now();
-
-        let batch_status = client
-            .get(&format!("{}&wait={}", batch_link.link, wait))
-            .send()?
-            .json::<BatchStatusResponse>()?;
-
-        debug!("Batch Status: {:#?}", batch_status);
-
-        if batch_status.data.iter().all(|x| x.status != "PENDING") {
-            break;
-        }
-
-        wait -= time.elapsed().as_secs()
-    }
+    debug!("Batch Statuses: {:#?}", batch_statuses);
 
     Ok(())
 }
-
-// Server Responses
-
-#[derive(Deserialize, Debug)]
-pub struct BatchStatusLink {
-    pub link: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct BatchStatusResponse {
-    pub data: Vec<BatchStatus>,
-    pub link: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct BatchStatus {
-    pub id: String,
-    pub invalid_transactions: Vec<HashMap<String, String>>,
-    pub status: String,
-}
@@ -16,12 +16,10 @@
 
 use dirs;
 use std::env;
-use std::fs::File;
-use std::io::prelude::*;
 
 use users::get_current_username;
 
-use sawtooth_sdk::signing::secp256k1::Secp256k1PrivateKey;
+use grid_sdk::transact::KeySigner;
 
 use crate::error::CliError;
 
@@ -43,11 +41,12 @@ use crate::error::CliError;
 ///
 /// # Errors
 ///
-/// If a signing error occurs, a CliError::SigningError is returned.
+/// If the key cannot be read or is invalid, a CliError::TransactError is
+/// returned.
 ///
 /// If a HOME or USER environment variable is required but cannot be
 /// retrieved from the environment, a CliError::VarError is returned.
-pub fn load_signing_key(name: Option<String>) -> Result<Secp256k1PrivateKey, CliError> {
+pub fn load_signing_key(name: Option<String>) -> Result<KeySigner, CliError> {
     let username: String = name
         .ok_or_else(|| env::var("USER"))
         .or_else(|_| {
@@ -81,20 +80,5 @@ pub fn load_signing_key(name: Option<String>) -> Result<Secp256k1PrivateKey, Cli
         )));
     }
 
-    let mut f = File::open(&private_key_filename)?;
-
-    let mut contents = String::new();
-    f.read_to_string(&mut contents)?;
-
-    let key_str = match contents.lines().next() {
-        Some(k) => k,
-        None => {
-            return Err(CliError::UserError(format!(
-                "Empty key file: {}",
-                private_key_filename.display()
-            )));
-        }
-    };
-
-    Ok(Secp256k1PrivateKey::from_hex(&key_str)?)
+    Ok(KeySigner::from_file(&private_key_filename)?)
 }
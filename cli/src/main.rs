@@ -21,21 +21,20 @@ mod actions;
 mod error;
 mod http;
 mod key;
-mod transaction;
 
 use clap::ArgMatches;
 use grid_sdk::protocol::pike::{
     payload::{
-        CreateAgentActionBuilder, CreateOrganizationActionBuilder, UpdateAgentActionBuilder,
-        UpdateOrganizationActionBuilder,
+        CreateAgentActionBuilder, CreateOrganizationActionBuilder, RotateAgentKeyActionBuilder,
+        TransferAgentActionBuilder, UpdateAgentActionBuilder, UpdateOrganizationActionBuilder,
     },
-    state::{KeyValueEntry, KeyValueEntryBuilder},
+    state::{AlternateId, KeyValueEntry, KeyValueEntryBuilder},
 };
 use simple_logger;
 
 use crate::error::CliError;
 
-use actions::{agents, organizations as orgs, schemas};
+use actions::{agents, organizations as orgs, records, schemas};
 
 const APP_NAME: &str = env!("CARGO_PKG_NAME");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -71,6 +70,17 @@ fn run() -> Result<(), CliError> {
                 (@arg metadata: --metadata +takes_value +multiple
                     "Comma-separated key value pairs stored in metadata")
             )
+            (@subcommand transfer =>
+                (about: "Approve the transfer of an agent to another organization")
+                (@arg public_key: +takes_value +required "public key")
+                (@arg org_id: +takes_value +required "ID of the organization to transfer to")
+            )
+            (@subcommand rotate_key =>
+                (name: "rotate-key")
+                (about: "Hand the identity of an agent over to a new public key. Admins may rotate a key again to recover the agent after a hostile rotation")
+                (@arg public_key: +takes_value +required "public key being replaced")
+                (@arg new_public_key: +takes_value +required "public key taking over the agent")
+            )
         )
         (@subcommand organization =>
             (about: "Update or create organization")
@@ -82,6 +92,10 @@ fn run() -> Result<(), CliError> {
                 (@arg address: +takes_value "Physical address for organization")
                 (@arg metadata: --metadata +takes_value +multiple
                     "Comma-separated key value pairs stored in metadata")
+                (@arg parent_org_id: --parent +takes_value
+                    "ID of the organization this organization is part of")
+                (@arg alternate_id: --("alternate-id") +takes_value +multiple
+                    "Identifier in another scheme, such as gln:0614141000012")
             )
             (@subcommand update =>
                 (about: "Update an organization")
@@ -90,6 +104,32 @@ fn run() -> Result<(), CliError> {
                 (@arg address: +takes_value "Physical address for organization")
                 (@arg metadata: --metadata +takes_value +multiple
                     "Comma-separated key value pairs stored in metadata")
+                (@arg parent_org_id: --parent +takes_value conflicts_with[detach]
+                    "ID of the organization to move this organization under")
+                (@arg detach: --detach
+                    "Remove this organization from the organization it is part of")
+                (@arg alternate_id: --("alternate-id") +takes_value +multiple
+                    "Identifier in another scheme, replacing the current ones")
+            )
+        )
+        (@subcommand record =>
+            (about: "Create records or update their properties")
+            (@setting SubcommandRequiredElseHelp)
+            (@subcommand create =>
+                (about: "Create a record, checking its property values against its schema first")
+                (@arg record_id: +takes_value +required "Unique ID for record")
+                (@arg schema: +takes_value +required "Name of the schema of the record")
+                (@arg path: +takes_value +required
+                    "Path to yaml file mapping property names to values")
+                (@arg schema_version: --("schema-version") +takes_value
+                    "Version of the schema to create the record with instead of the current one")
+            )
+            (@subcommand update_properties =>
+                (name: "update-properties")
+                (about: "Update properties of a record, checking the values against their definitions first")
+                (@arg record_id: +takes_value +required "ID of the record")
+                (@arg path: +takes_value +required
+                    "Path to yaml file mapping property names to values")
             )
         )
         (@subcommand schema =>
@@ -109,6 +149,28 @@ fn run() -> Result<(), CliError> {
             (@subcommand show =>
                 (about: "Show schema specified by name argument")
                 (@arg name: +takes_value +required "Name of schema")
+                (@arg schema_version: --("schema-version") +takes_value
+                    "Version of the schema to show instead of the current one")
+            )
+            (@subcommand transfer =>
+                (about: "Transfer ownership of a schema to another organization")
+                (@arg name: +takes_value +required "Name of schema")
+                (@arg new_owner: +takes_value +required "ID of the organization that will own the schema")
+            )
+            (@subcommand deprecate =>
+                (about: "Deprecate a schema, or only some of its properties")
+                (@arg name: +takes_value +required "Name of schema")
+                (@arg property: --property +takes_value +multiple
+                    "Name of a property declared by the schema (not inherited) to deprecate instead of the whole schema")
+            )
+            (@subcommand group =>
+                (about: "Create property groups shared by schemas")
+                (@setting SubcommandRequiredElseHelp)
+                (@subcommand create =>
+                    (about: "Create property groups from a yaml file")
+                    (@arg path: +takes_value +required
+                        "Path to yaml file containing a list of property group definitions")
+                )
             )
         )
     )
@@ -162,6 +224,24 @@ fn run() -> Result<(), CliError> {
 
                 agents::do_update_agent(&url, key, wait, update_agent)?
             }
+            ("transfer", Some(m)) => {
+                let transfer_agent = TransferAgentActionBuilder::new()
+                    .with_public_key(m.value_of("public_key").unwrap().into())
+                    .with_org_id(m.value_of("org_id").unwrap().into())
+                    .build()
+                    .map_err(|err| CliError::UserError(format!("{}", err)))?;
+
+                agents::do_transfer_agent(&url, key, wait, transfer_agent)?
+            }
+            ("rotate-key", Some(m)) => {
+                let rotate_agent_key = RotateAgentKeyActionBuilder::new()
+                    .with_public_key(m.value_of("public_key").unwrap().into())
+                    .with_new_public_key(m.value_of("new_public_key").unwrap().into())
+                    .build()
+                    .map_err(|err| CliError::UserError(format!("{}", err)))?;
+
+                agents::do_rotate_agent_key(&url, key, wait, rotate_agent_key)?
+            }
             _ => return Err(CliError::UserError("Subcommand not recognized".into())),
         },
         ("organization", Some(m)) => match m.subcommand() {
@@ -171,6 +251,8 @@ fn run() -> Result<(), CliError> {
                     .with_name(m.value_of("name").unwrap().into())
                     .with_address(m.value_of("address").unwrap().into())
                     .with_metadata(parse_metadata(&m)?)
+                    .with_parent_org_id(m.value_of("parent_org_id").unwrap_or("").into())
+                    .with_alternate_ids(parse_alternate_ids(&m)?)
                     .build()
                     .map_err(|err| CliError::UserError(format!("{}", err)))?;
 
@@ -182,6 +264,9 @@ fn run() -> Result<(), CliError> {
                     .with_name(m.value_of("name").unwrap().into())
                     .with_address(m.value_of("address").unwrap().into())
                     .with_metadata(parse_metadata(&m)?)
+                    .with_parent_org_id(m.value_of("parent_org_id").unwrap_or("").into())
+                    .with_detach_from_parent(m.is_present("detach"))
+                    .with_alternate_ids(parse_alternate_ids(&m)?)
                     .build()
                     .map_err(|err| CliError::UserError(format!("{}", err)))?;
 
@@ -189,6 +274,29 @@ fn run() -> Result<(), CliError> {
             }
             _ => return Err(CliError::UserError("Subcommand not recognized".into())),
         },
+        ("record", Some(m)) => match m.subcommand() {
+            ("create", Some(m)) => records::do_create_record(
+                &url,
+                key,
+                wait,
+                m.value_of("record_id").unwrap(),
+                m.value_of("schema").unwrap(),
+                match m.value_of("schema_version") {
+                    Some(_) => value_t!(m, "schema_version", u32)
+                        .map_err(|err| CliError::UserError(format!("{}", err)))?,
+                    None => 0,
+                },
+                m.value_of("path").unwrap(),
+            )?,
+            ("update-properties", Some(m)) => records::do_update_properties(
+                &url,
+                key,
+                wait,
+                m.value_of("record_id").unwrap(),
+                m.value_of("path").unwrap(),
+            )?,
+            _ => return Err(CliError::UserError("Subcommand not recognized".into())),
+        },
         ("schema", Some(m)) => match m.subcommand() {
             ("create", Some(m)) => {
                 schemas::do_create_schemas(&url, key, wait, m.value_of("path").unwrap())?
@@ -197,7 +305,40 @@ fn run() -> Result<(), CliError> {
                 schemas::do_update_schemas(&url, key, wait, m.value_of("path").unwrap())?
             }
             ("list", Some(_)) => schemas::do_list_schemas(&url)?,
-            ("show", Some(m)) => schemas::do_show_schema(&url, m.value_of("name").unwrap())?,
+            ("show", Some(m)) => match m.value_of("schema_version") {
+                Some(_) => schemas::do_show_schema_version(
+                    &url,
+                    m.value_of("name").unwrap(),
+                    value_t!(m, "schema_version", i64)
+                        .map_err(|err| CliError::UserError(format!("{}", err)))?,
+                )?,
+                None => schemas::do_show_schema(&url, m.value_of("name").unwrap())?,
+            },
+            ("transfer", Some(m)) => schemas::do_transfer_schema(
+                &url,
+                key,
+                wait,
+                m.value_of("name").unwrap(),
+                m.value_of("new_owner").unwrap(),
+            )?,
+            ("deprecate", Some(m)) => schemas::do_deprecate_schema(
+                &url,
+                key,
+                wait,
+                m.value_of("name").unwrap(),
+                m.values_of("property")
+                    .map(|values| values.map(String::from).collect())
+                    .unwrap_or_default(),
+            )?,
+            ("group", Some(m)) => match m.subcommand() {
+                ("create", Some(m)) => schemas::do_create_property_groups(
+                    &url,
+                    key,
+                    wait,
+                    m.value_of("path").unwrap(),
+                )?,
+                _ => return Err(CliError::UserError("Subcommand not recognized".into())),
+            },
             _ => return Err(CliError::UserError("Subcommand not recognized".into())),
         },
         _ => return Err(CliError::UserError("Subcommand not recognized".into())),
@@ -236,6 +377,18 @@ fn parse_metadata(matches: &ArgMatches) -> Result<Vec<KeyValueEntry>, CliError>
     Ok(key_value_entries)
 }
 
+fn parse_alternate_ids(matches: &ArgMatches) -> Result<Vec<AlternateId>, CliError> {
+    matches
+        .values_of("alternate_id")
+        .unwrap_or_default()
+        .map(|alternate_id| {
+            alternate_id.parse().map_err(|err| {
+                CliError::UserError(format!("Alternate ID malformed: {}: {}", alternate_id, err))
+            })
+        })
+        .collect()
+}
+
 fn main() {
     if let Err(e) = run() {
         error!("{:?}", e);
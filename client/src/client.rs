@@ -0,0 +1,353 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+use protobuf::Message;
+use reqwest::{Client, RequestBuilder, StatusCode, Url, UrlError};
+use sawtooth_sdk::messages::batch::BatchList;
+use serde::de::DeserializeOwned;
+
+use crate::error::ClientError;
+use crate::models::{
+    AgentSlice, BatchStatus, BatchStatusLink, BatchStatusResponse, GridSchemaSlice,
+    OrganizationSlice, PropertySlice, PropertyValueSeriesSlice, PropertyValuesQuery, RecordSlice,
+    RoleSlice, StateEntrySlice, StateListResponse, StateResponse,
+};
+
+// Number of state entries fetched by each request of a StateEntries iterator
+const STATE_PAGE_LIMIT: i32 = 100;
+
+/// A client of the REST API of a Grid daemon
+pub struct GridClient {
+    url: String,
+    client: Client,
+}
+
+impl GridClient {
+    /// Creates a client of the REST API served at `url`, such as
+    /// `http://localhost:8080`
+    pub fn new(url: &str) -> GridClient {
+        GridClient {
+            url: url.trim_end_matches('/').to_string(),
+            client: Client::new(),
+        }
+    }
+
+    pub fn list_agents(&self) -> Result<Vec<AgentSlice>, ClientError> {
+        self.send(self.client.get(self.url_of(&["agent"])?))
+    }
+
+    pub fn fetch_agent(&self, public_key: &str) -> Result<AgentSlice, ClientError> {
+        self.send(self.client.get(self.url_of(&["agent", public_key])?))
+    }
+
+    /// Lists all organizations, or only the one holding `alternate_id`, of the
+    /// form `id_type:id`
+    pub fn list_organizations(
+        &self,
+        alternate_id: Option<&str>,
+    ) -> Result<Vec<OrganizationSlice>, ClientError> {
+        let mut request = self.client.get(self.url_of(&["organization"])?);
+        if let Some(alternate_id) = alternate_id {
+            request = request.query(&[("alternate_id", alternate_id)]);
+        }
+        self.send(request)
+    }
+
+    pub fn fetch_organization(&self, org_id: &str) -> Result<OrganizationSlice, ClientError> {
+        self.send(self.client.get(self.url_of(&["organization", org_id])?))
+    }
+
+    pub fn list_child_organizations(
+        &self,
+        org_id: &str,
+    ) -> Result<Vec<OrganizationSlice>, ClientError> {
+        self.send(
+            self.client
+                .get(self.url_of(&["organization", org_id, "children"])?),
+        )
+    }
+
+    /// Lists the roles defined by all organizations, or only by `org_id`
+    pub fn list_roles(&self, org_id: Option<&str>) -> Result<Vec<RoleSlice>, ClientError> {
+        let mut request = self.client.get(self.url_of(&["role"])?);
+        if let Some(org_id) = org_id {
+            request = request.query(&[("org_id", org_id)]);
+        }
+        self.send(request)
+    }
+
+    pub fn fetch_role(&self, org_id: &str, name: &str) -> Result<RoleSlice, ClientError> {
+        self.send(self.client.get(self.url_of(&["role", org_id, name])?))
+    }
+
+    pub fn list_schemas(&self) -> Result<Vec<GridSchemaSlice>, ClientError> {
+        self.send(self.client.get(self.url_of(&["schema"])?))
+    }
+
+    pub fn fetch_schema(&self, name: &str) -> Result<GridSchemaSlice, ClientError> {
+        self.send(self.client.get(self.url_of(&["schema", name])?))
+    }
+
+    pub fn fetch_schema_version(
+        &self,
+        name: &str,
+        version: i64,
+    ) -> Result<GridSchemaSlice, ClientError> {
+        self.send(self.client.get(self.url_of(&[
+            "schema",
+            name,
+            "versions",
+            &version.to_string(),
+        ])?))
+    }
+
+    pub fn list_records(&self) -> Result<Vec<RecordSlice>, ClientError> {
+        self.send(self.client.get(self.url_of(&["record"])?))
+    }
+
+    pub fn fetch_record(&self, record_id: &str) -> Result<RecordSlice, ClientError> {
+        self.send(self.client.get(self.url_of(&["record", record_id])?))
+    }
+
+    pub fn fetch_record_property(
+        &self,
+        record_id: &str,
+        property_name: &str,
+    ) -> Result<PropertySlice, ClientError> {
+        self.send(self.client.get(self.url_of(&[
+            "record",
+            record_id,
+            "property",
+            property_name,
+        ])?))
+    }
+
+    pub fn fetch_record_property_values(
+        &self,
+        record_id: &str,
+        property_name: &str,
+        query: &PropertyValuesQuery,
+    ) -> Result<PropertyValueSeriesSlice, ClientError> {
+        self.send(
+            self.client
+                .get(self.url_of(&["record", record_id, "property", property_name, "values"])?)
+                .query(query),
+        )
+    }
+
+    pub fn fetch_state(&self, address: &str, decode: bool) -> Result<StateResponse, ClientError> {
+        self.send(
+            self.client
+                .get(self.url_of(&["state", address])?)
+                .query(&[("decode", decode)]),
+        )
+    }
+
+    /// Fetches one page of the state entries under an address prefix,
+    /// starting at the address `start`, or at the first address when it is
+    /// empty.
+    pub fn list_state_page(
+        &self,
+        prefix: &str,
+        start: &str,
+        limit: i32,
+        decode: bool,
+    ) -> Result<StateListResponse, ClientError> {
+        self.send(self.client.get(self.url_of(&["state"])?).query(&[
+            ("prefix", prefix.to_string()),
+            ("start", start.to_string()),
+            ("limit", limit.to_string()),
+            ("decode", decode.to_string()),
+        ]))
+    }
+
+    /// Iterates over every state entry under an address prefix, fetching the
+    /// pages as they are needed.
+    pub fn list_state(&self, prefix: &str, decode: bool) -> StateEntries {
+        StateEntries {
+            client: self,
+            prefix: prefix.to_string(),
+            decode,
+            next_start: Some(String::new()),
+            page: vec![].into_iter(),
+        }
+    }
+
+    /// Submits batches, returning the link to their statuses
+    pub fn submit_batches(&self, batch_list: &BatchList) -> Result<BatchStatusLink, ClientError> {
+        self.send(
+            self.client
+                .post(self.url_of(&["batches"])?)
+                .body(batch_list.write_to_bytes()?),
+        )
+    }
+
+    /// Fetches the statuses of batches. With `wait`, the REST API waits up
+    /// to that many seconds for the batches to be committed or rejected.
+    pub fn fetch_batch_statuses(
+        &self,
+        batch_ids: &[String],
+        wait: Option<u64>,
+    ) -> Result<Vec<BatchStatus>, ClientError> {
+        let wait = match wait {
+            Some(wait) => wait.to_string(),
+            None => "false".to_string(),
+        };
+
+        let response: BatchStatusResponse = self.send(
+            self.client
+                .get(self.url_of(&["batch_statuses"])?)
+                .query(&[("id", batch_ids.join(",")), ("wait", wait)]),
+        )?;
+
+        Ok(response.data)
+    }
+
+    /// Submits batches and waits up to `wait` seconds for them to be
+    /// committed or rejected, returning their last statuses. Batches still
+    /// pending once the time is up are returned as such, while rejected
+    /// batches fail with `ClientError::InvalidBatches`.
+    pub fn submit_batches_and_wait(
+        &self,
+        batch_list: &BatchList,
+        wait: u64,
+    ) -> Result<Vec<BatchStatus>, ClientError> {
+        self.submit_batches(batch_list)?;
+
+        let batch_ids = batch_list
+            .get_batches()
+            .iter()
+            .map(|batch| batch.get_header_signature().to_string())
+            .collect::<Vec<_>>();
+
+        let deadline = Instant::now() + Duration::from_secs(wait);
+        let statuses = loop {
+            let now = Instant::now();
+            let remaining = if deadline > now {
+                Some((deadline - now).as_secs().max(1))
+            } else {
+                None
+            };
+
+            let statuses = self.fetch_batch_statuses(&batch_ids, remaining)?;
+            if remaining.is_none() || statuses.iter().all(|status| status.status != "PENDING") {
+                break statuses;
+            }
+        };
+
+        if statuses.iter().any(|status| status.status == "INVALID") {
+            return Err(ClientError::InvalidBatches(statuses));
+        }
+
+        Ok(statuses)
+    }
+
+    /// Builds the URL of a resource of the REST API from its path segments,
+    /// percent-encoding each of them, so that ids containing characters such
+    /// as `/`, `?` or spaces address the right resource
+    fn url_of(&self, segments: &[&str]) -> Result<Url, ClientError> {
+        let mut url = Url::parse(&self.url)?;
+        url.path_segments_mut()
+            .map_err(|_| ClientError::UrlError(UrlError::RelativeUrlWithCannotBeABaseBase))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    /// Sends a request and decodes its response, mapping the error statuses
+    /// of the REST API to `ClientError`s
+    fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        let mut response = request.send()?;
+        let status = response.status();
+
+        if status.is_success() {
+            return Ok(response.json()?);
+        }
+
+        // The REST API describes errors with a JSON string
+        let body = response.text()?;
+        let message = serde_json::from_str::<String>(&body).unwrap_or(body);
+
+        Err(match status {
+            StatusCode::BAD_REQUEST => ClientError::BadRequest(message),
+            StatusCode::NOT_FOUND => ClientError::NotFound(message),
+            StatusCode::SERVICE_UNAVAILABLE => ClientError::ServiceUnavailable(message),
+            _ => ClientError::ResponseError {
+                status: status.as_u16(),
+                message,
+            },
+        })
+    }
+}
+
+/// Iterator over the state entries under an address prefix, created by
+/// `GridClient::list_state`
+pub struct StateEntries<'a> {
+    client: &'a GridClient,
+    prefix: String,
+    decode: bool,
+    /// The start of the next page, or `None` once the last page was fetched
+    next_start: Option<String>,
+    page: std::vec::IntoIter<StateEntrySlice>,
+}
+
+impl<'a> Iterator for StateEntries<'a> {
+    type Item = Result<StateEntrySlice, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.page.next() {
+                return Some(Ok(entry));
+            }
+
+            let start = self.next_start.take()?;
+            match self
+                .client
+                .list_state_page(&self.prefix, &start, STATE_PAGE_LIMIT, self.decode)
+            {
+                Ok(response) => {
+                    if !response.paging.next.is_empty() {
+                        self.next_start = Some(response.paging.next);
+                    }
+                    self.page = response.data.into_iter();
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // check that the path segments of a URL are percent-encoded and appended to the path of
+    // the REST API
+    fn check_url_of() {
+        let client = GridClient::new("http://localhost:8080/");
+        assert_eq!(
+            client.url_of(&["record", "lot 7/b?"]).unwrap().as_str(),
+            "http://localhost:8080/record/lot%207%2Fb%3F"
+        );
+
+        let client = GridClient::new("http://localhost:8080/grid");
+        assert_eq!(
+            client.url_of(&["agent"]).unwrap().as_str(),
+            "http://localhost:8080/grid/agent"
+        );
+    }
+}
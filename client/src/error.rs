@@ -0,0 +1,95 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use crate::models::BatchStatus;
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The request could not be sent, or its response could not be read
+    HttpError(reqwest::Error),
+    /// The batches to submit could not be serialized
+    ProtobufError(protobuf::ProtobufError),
+    /// The URL of the REST API is not a valid base for its resources
+    UrlError(reqwest::UrlError),
+    /// The REST API rejected the request as malformed
+    BadRequest(String),
+    /// The requested resource does not exist
+    NotFound(String),
+    /// The REST API could not reach the validator or the database
+    ServiceUnavailable(String),
+    /// The REST API responded with any other error status
+    ResponseError { status: u16, message: String },
+    /// Some of the submitted batches were rejected by the validator
+    InvalidBatches(Vec<BatchStatus>),
+}
+
+impl StdError for ClientError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            ClientError::HttpError(err) => Some(err),
+            ClientError::ProtobufError(err) => Some(err),
+            ClientError::UrlError(err) => Some(err),
+            ClientError::BadRequest(_) => None,
+            ClientError::NotFound(_) => None,
+            ClientError::ServiceUnavailable(_) => None,
+            ClientError::ResponseError { .. } => None,
+            ClientError::InvalidBatches(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientError::HttpError(err) => write!(f, "HTTP Error: {}", err),
+            ClientError::ProtobufError(err) => write!(f, "Protobuf Error: {}", err),
+            ClientError::UrlError(err) => write!(f, "URL Error: {}", err),
+            ClientError::BadRequest(msg) => write!(f, "Bad Request: {}", msg),
+            ClientError::NotFound(msg) => write!(f, "Not Found: {}", msg),
+            ClientError::ServiceUnavailable(msg) => write!(f, "Service Unavailable: {}", msg),
+            ClientError::ResponseError { status, message } => {
+                write!(f, "Response Error ({}): {}", status, message)
+            }
+            ClientError::InvalidBatches(statuses) => {
+                let messages = statuses
+                    .iter()
+                    .flat_map(|status| status.invalid_transactions.iter())
+                    .map(|transaction| transaction.message.as_str())
+                    .collect::<Vec<_>>();
+                write!(f, "Invalid Batches: {}", messages.join(", "))
+            }
+        }
+    }
+}
+
+impl From<reqwest::Error> for ClientError {
+    fn from(err: reqwest::Error) -> Self {
+        ClientError::HttpError(err)
+    }
+}
+
+impl From<protobuf::ProtobufError> for ClientError {
+    fn from(err: protobuf::ProtobufError) -> Self {
+        ClientError::ProtobufError(err)
+    }
+}
+
+impl From<reqwest::UrlError> for ClientError {
+    fn from(err: reqwest::UrlError) -> Self {
+        ClientError::UrlError(err)
+    }
+}
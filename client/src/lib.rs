@@ -0,0 +1,29 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Client of the Grid REST API.
+//!
+//! The `models` are the resources served by gridd, which shares them with
+//! this crate. The `http` feature, enabled by default, adds `GridClient`.
+
+#[cfg(feature = "http")]
+mod client;
+#[cfg(feature = "http")]
+mod error;
+pub mod models;
+
+#[cfg(feature = "http")]
+pub use crate::client::{GridClient, StateEntries};
+#[cfg(feature = "http")]
+pub use crate::error::ClientError;
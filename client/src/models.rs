@@ -0,0 +1,327 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The resources served by the Grid REST API, as serialized by gridd.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AgentSlice {
+    pub public_key: String,
+    pub org_id: String,
+    pub active: bool,
+    pub roles: Vec<String>,
+    pub metadata: JsonValue,
+    pub successor: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RoleSlice {
+    pub org_id: String,
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<String>,
+    pub inherit_from: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrganizationSlice {
+    pub org_id: String,
+    pub name: String,
+    pub address: String,
+    pub metadata: Vec<JsonValue>,
+    pub parent_org_id: String,
+    /// Identifiers in other schemes, of the form `id_type:id`
+    pub alternate_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GridSchemaSlice {
+    pub name: String,
+    pub description: String,
+    pub owner: String,
+    pub version: i64,
+    pub deprecated: bool,
+    pub parent: String,
+    pub parent_version: i64,
+    pub property_groups: Vec<String>,
+    pub properties: Vec<GridPropertyDefinitionSlice>,
+    pub effective_properties: Vec<GridPropertyDefinitionSlice>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GridPropertyDefinitionSlice {
+    pub name: String,
+    pub schema_name: String,
+    pub data_type: String,
+    pub required: bool,
+    pub description: String,
+    pub number_exponent: i64,
+    pub enum_options: Vec<String>,
+    pub struct_properties: Vec<String>,
+    pub deprecated: bool,
+    pub inherited: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AssociatedAgentSlice {
+    pub agent_id: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProposalSlice {
+    pub receiving_agent: String,
+    pub issuing_agent: String,
+    pub role: String,
+    pub properties: Vec<String>,
+    pub status: String,
+    pub terms: String,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordSlice {
+    pub record_id: String,
+    pub schema: String,
+    pub schema_version: i64,
+    pub owner: String,
+    pub custodian: String,
+    pub properties: Vec<PropertySlice>,
+    pub r#final: bool,
+    pub proposals: Vec<ProposalSlice>,
+    pub owner_updates: Vec<AssociatedAgentSlice>,
+    pub custodian_updates: Vec<AssociatedAgentSlice>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PropertySlice {
+    pub name: String,
+    pub record_id: String,
+    pub data_type: String,
+    pub reporters: Vec<String>,
+    pub updates: Vec<PropertyValueSlice>,
+    pub value: PropertyValueSlice,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PropertyValueSlice {
+    pub timestamp: u64,
+    pub value: Value,
+    pub reporter: ReporterSlice,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Value {
+    String(String),
+    Bool(bool),
+    Struct(Vec<StructPropertyValue>),
+    LatLong(LatLong),
+    Number(i64),
+    Enum(i32),
+    Bytes(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LatLong {
+    pub latitude: i64,
+    pub longitude: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReporterSlice {
+    pub public_key: String,
+    pub metadata: JsonValue,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StructPropertyValue {
+    pub name: String,
+    pub data_type: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PropertyValueSeriesSlice {
+    pub record_id: String,
+    pub property_name: String,
+    pub number_exponent: i64,
+    pub bucket: i64,
+    pub data: Vec<PropertyValueBucketSlice>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PropertyValueBucketSlice {
+    pub timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg: Option<f64>,
+}
+
+/// The query parameters of `GET /record/{record_id}/property/{property_name}/values`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PropertyValuesQuery {
+    /// Unix timestamp, in seconds, of the first value to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// Unix timestamp, in seconds, of the last value to include
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<u64>,
+    /// Width of the buckets, such as `15m` or `1h`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// Comma-separated aggregates among `count`, `min`, `max` and `avg`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agg: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchStatus {
+    pub id: String,
+    pub invalid_transactions: Vec<InvalidTransactionSlice>,
+    pub status: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvalidTransactionSlice {
+    pub id: String,
+    pub message: String,
+    pub details: Vec<InvalidTransactionDetailSlice>,
+    pub extended_data: String,
+    /// The family and action of the transaction's payload. Invalid
+    /// transactions are never stored by the validator, so these are only
+    /// known for the last 1000 batches submitted through the REST API
+    /// answering the request since it started, and are null otherwise.
+    pub family_name: Option<String>,
+    pub action: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InvalidTransactionDetailSlice {
+    pub property: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchStatusResponse {
+    pub data: Vec<BatchStatus>,
+    pub link: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BatchStatusLink {
+    pub link: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StateEntrySlice {
+    pub address: String,
+    pub data: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<JsonValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StateResponse {
+    pub data: StateEntrySlice,
+    pub head: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StateListResponse {
+    pub data: Vec<StateEntrySlice>,
+    pub head: String,
+    pub paging: StatePagingSlice,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatePagingSlice {
+    pub start: String,
+    pub limit: i32,
+    pub next: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    // Test that a record as served by the REST API is deserialized, including its final flag
+    // and the untagged property values
+    fn test_deserialize_record() {
+        let record: RecordSlice = serde_json::from_value(json!({
+            "record_id": "7h15-45537-15-br173",
+            "owner": "agent_1",
+            "custodian": "agent_1",
+            "properties": [{
+                "name": "location",
+                "record_id": "7h15-45537-15-br173",
+                "data_type": "Struct",
+                "reporters": ["agent_1"],
+                "updates": [],
+                "value": {
+                    "timestamp": 1_557_949_075,
+                    "value": [{
+                        "name": "position",
+                        "data_type": "LatLong",
+                        "value": { "latitude": 44_977_753, "longitude": -93_265_015 },
+                    }],
+                    "reporter": { "public_key": "agent_1", "metadata": {} },
+                },
+            }],
+            "final": true,
+            "proposals": [],
+            "owner_updates": [{ "agent_id": "agent_1", "timestamp": 1_557_949_075 }],
+            "custodian_updates": [],
+        }))
+        .unwrap();
+
+        assert!(record.r#final);
+        assert_eq!(
+            record.properties[0].value.value,
+            Value::Struct(vec![StructPropertyValue {
+                name: "position".to_string(),
+                data_type: "LatLong".to_string(),
+                value: Value::LatLong(LatLong {
+                    latitude: 44_977_753,
+                    longitude: -93_265_015,
+                }),
+            }])
+        );
+    }
+
+    #[test]
+    // Test that only the given property values query parameters are serialized
+    fn test_serialize_property_values_query() {
+        let query = PropertyValuesQuery {
+            from: Some(1_557_946_800),
+            bucket: Some("1h".to_string()),
+            ..PropertyValuesQuery::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            json!({ "from": 1_557_946_800, "bucket": "1h" })
+        );
+    }
+}
@@ -1,45 +0,0 @@
-// Copyright 2018 Cargill Incorporated
-//
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-//
-//     http://www.apache.org/licenses/LICENSE-2.0
-//
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
-
-/// Represents part of address that designates resource type
-#[derive(Debug)]
-pub enum Resource {
-    AGENT,
-    ORG,
-}
-
-/// Convert resource part to byte value in hex
-pub fn resource_to_byte(part: Resource) -> String {
-    match part {
-        Resource::AGENT => String::from("00"),
-        Resource::ORG => String::from("01"),
-    }
-}
-
-/// Convert byte string to Resource
-pub fn byte_to_resource(bytes: &str) -> Result<Resource, ResourceError> {
-    match bytes {
-        "00" => Ok(Resource::AGENT),
-        "01" => Ok(Resource::ORG),
-        _ => Err(ResourceError::UnknownResource(format!(
-            "No resource found matching byte pattern {}",
-            bytes
-        ))),
-    }
-}
-
-#[derive(Debug)]
-pub enum ResourceError {
-    UnknownResource(String),
-}
@@ -12,10 +12,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crypto::digest::Digest;
-use crypto::sha2::Sha512;
-use protobuf;
-
 cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
         use sabre_sdk::ApplyError;
@@ -31,26 +27,30 @@ cfg_if! {
     }
 }
 
-use addresser::{resource_to_byte, Resource};
-use grid_sdk::protos::pike_payload::{
-    CreateAgentAction, CreateOrganizationAction, PikePayload, PikePayload_Action as Action,
-    UpdateAgentAction, UpdateOrganizationAction,
+use grid_sdk::protocol::addressing::pike::{
+    compute_agent_address, compute_alternate_id_address, compute_org_address, compute_role_address,
+    PIKE_FAMILY_NAME, PIKE_NAMESPACE,
+};
+use grid_sdk::protocol::pike::payload::{
+    Action, CreateAgentAction, CreateOrganizationAction, CreateRoleAction, DeleteRoleAction,
+    PikePayload, RotateAgentKeyAction, TransferAgentAction, UpdateAgentAction,
+    UpdateOrganizationAction, UpdateRoleAction,
 };
-use grid_sdk::protos::pike_state::{Agent, AgentList, Organization, OrganizationList};
+use grid_sdk::protocol::pike::state::{
+    Agent, AgentBuilder, AgentList, AgentListBuilder, AlternateId, AlternateIdIndex,
+    AlternateIdIndexBuilder, AlternateIdIndexList, AlternateIdIndexListBuilder, KeyValueEntry,
+    KeyValueEntryBuilder, Organization, OrganizationBuilder, OrganizationList,
+    OrganizationListBuilder, Role, RoleBuilder, RoleList, RoleListBuilder, ORG_HISTORY_KEY,
+    TRANSFER_APPROVED_BY_KEY, TRANSFER_ORG_ID_KEY,
+};
+use grid_sdk::protocol::versioning::{payload_version, pike_payload_migrator, Migrator};
+use grid_sdk::protos::{FromBytes, IntoBytes};
 
 pub struct PikeTransactionHandler {
     family_name: String,
     family_versions: Vec<String>,
     namespaces: Vec<String>,
-}
-
-const NAMESPACE: &str = "cad11d";
-
-fn compute_address(name: &str, resource: Resource) -> String {
-    let mut sha = Sha512::new();
-    sha.input(name.as_bytes());
-
-    String::from(NAMESPACE) + &resource_to_byte(resource) + &sha.result_str()[..62].to_string()
+    payload_migrator: Migrator<String>,
 }
 
 pub struct PikeState<'a> {
@@ -63,22 +63,22 @@ impl<'a> PikeState<'a> {
     }
 
     pub fn get_agent(&mut self, public_key: &str) -> Result<Option<Agent>, ApplyError> {
-        let address = compute_address(public_key, Resource::AGENT);
+        let address = compute_agent_address(public_key);
         let d = self.context.get_state_entry(&address)?;
         match d {
             Some(packed) => {
-                let agents: AgentList = match protobuf::parse_from_bytes(packed.as_slice()) {
+                let agents = match AgentList::from_bytes(packed.as_slice()) {
                     Ok(agents) => agents,
                     Err(err) => {
                         return Err(ApplyError::InternalError(format!(
-                            "Cannot deserialize record container: {:?}",
+                            "Cannot deserialize agent list: {:?}",
                             err,
                         )))
                     }
                 };
 
-                for agent in agents.get_agents() {
-                    if agent.public_key == public_key {
+                for agent in agents.agents() {
+                    if agent.public_key() == public_key {
                         return Ok(Some(agent.clone()));
                     }
                 }
@@ -89,11 +89,11 @@ impl<'a> PikeState<'a> {
     }
 
     pub fn set_agent(&mut self, public_key: &str, new_agent: Agent) -> Result<(), ApplyError> {
-        let address = compute_address(public_key, Resource::AGENT);
+        let address = compute_agent_address(public_key);
         let d = self.context.get_state_entry(&address)?;
-        let mut agent_list = match d {
-            Some(packed) => match protobuf::parse_from_bytes(packed.as_slice()) {
-                Ok(agents) => agents,
+        let mut agents = match d {
+            Some(packed) => match AgentList::from_bytes(packed.as_slice()) {
+                Ok(agent_list) => agent_list.agents().to_vec(),
                 Err(err) => {
                     return Err(ApplyError::InternalError(format!(
                         "Cannot deserialize agent list: {}",
@@ -101,24 +101,28 @@ impl<'a> PikeState<'a> {
                     )))
                 }
             },
-            None => AgentList::new(),
+            None => vec![],
         };
         // remove old agent if it exists and sort the agents by public key
-        let agents = agent_list.get_agents().to_vec();
         let mut index = None;
         for (i, agent) in agents.iter().enumerate() {
-            if agent.public_key == public_key {
+            if agent.public_key() == public_key {
                 index = Some(i);
                 break;
             }
         }
 
         if let Some(i) = index {
-            agent_list.agents.remove(i);
+            agents.remove(i);
         }
-        agent_list.agents.push(new_agent);
-        agent_list.agents.sort_by_key(|a| a.clone().public_key);
-        let serialized = match protobuf::Message::write_to_bytes(&agent_list) {
+        agents.push(new_agent);
+        agents.sort_by_key(|a| a.public_key().to_string());
+
+        let agent_list = AgentListBuilder::new()
+            .with_agents(agents)
+            .build()
+            .map_err(|_| ApplyError::InternalError(String::from("Cannot build agent list")))?;
+        let serialized = match agent_list.into_bytes() {
             Ok(serialized) => serialized,
             Err(_) => {
                 return Err(ApplyError::InternalError(String::from(
@@ -133,11 +137,11 @@ impl<'a> PikeState<'a> {
     }
 
     pub fn get_organization(&mut self, id: &str) -> Result<Option<Organization>, ApplyError> {
-        let address = compute_address(id, Resource::ORG);
+        let address = compute_org_address(id);
         let d = self.context.get_state_entry(&address)?;
         match d {
             Some(packed) => {
-                let orgs: OrganizationList = match protobuf::parse_from_bytes(packed.as_slice()) {
+                let orgs = match OrganizationList::from_bytes(packed.as_slice()) {
                     Ok(orgs) => orgs,
                     Err(err) => {
                         return Err(ApplyError::InternalError(format!(
@@ -147,8 +151,8 @@ impl<'a> PikeState<'a> {
                     }
                 };
 
-                for org in orgs.get_organizations() {
-                    if org.org_id == id {
+                for org in orgs.organizations() {
+                    if org.org_id() == id {
                         return Ok(Some(org.clone()));
                     }
                 }
@@ -163,11 +167,11 @@ impl<'a> PikeState<'a> {
         id: &str,
         new_organization: Organization,
     ) -> Result<(), ApplyError> {
-        let address = compute_address(id, Resource::ORG);
+        let address = compute_org_address(id);
         let d = self.context.get_state_entry(&address)?;
-        let mut organization_list = match d {
-            Some(packed) => match protobuf::parse_from_bytes(packed.as_slice()) {
-                Ok(orgs) => orgs,
+        let mut organizations = match d {
+            Some(packed) => match OrganizationList::from_bytes(packed.as_slice()) {
+                Ok(org_list) => org_list.organizations().to_vec(),
                 Err(err) => {
                     return Err(ApplyError::InternalError(format!(
                         "Cannot deserialize organization list: {}",
@@ -175,26 +179,30 @@ impl<'a> PikeState<'a> {
                     )))
                 }
             },
-            None => OrganizationList::new(),
+            None => vec![],
         };
-        // remove old agent if it exists and sort the agents by public key
-        let organizations = organization_list.get_organizations().to_vec();
+        // remove old organization if it exists and sort the organizations by ID
         let mut index = None;
         for (i, organization) in organizations.iter().enumerate() {
-            if organization.org_id == id {
+            if organization.org_id() == id {
                 index = Some(i);
                 break;
             }
         }
 
         if let Some(i) = index {
-            organization_list.organizations.remove(i);
+            organizations.remove(i);
         }
-        organization_list.organizations.push(new_organization);
-        organization_list
-            .organizations
-            .sort_by_key(|o| o.clone().org_id);
-        let serialized = match protobuf::Message::write_to_bytes(&organization_list) {
+        organizations.push(new_organization);
+        organizations.sort_by_key(|o| o.org_id().to_string());
+
+        let organization_list = OrganizationListBuilder::new()
+            .with_organizations(organizations)
+            .build()
+            .map_err(|_| {
+                ApplyError::InternalError(String::from("Cannot build organization list"))
+            })?;
+        let serialized = match organization_list.into_bytes() {
             Ok(serialized) => serialized,
             Err(_) => {
                 return Err(ApplyError::InternalError(String::from(
@@ -208,15 +216,180 @@ impl<'a> PikeState<'a> {
             .map_err(|err| ApplyError::InternalError(format!("{}", err)))?;
         Ok(())
     }
+
+    pub fn get_role(&mut self, org_id: &str, name: &str) -> Result<Option<Role>, ApplyError> {
+        Ok(self
+            .get_roles_at(org_id, name)?
+            .into_iter()
+            .find(|role| role.org_id() == org_id && role.name() == name))
+    }
+
+    pub fn set_role(&mut self, new_role: Role) -> Result<(), ApplyError> {
+        let address = compute_role_address(new_role.org_id(), new_role.name());
+        let mut roles = self.get_roles_at(new_role.org_id(), new_role.name())?;
+        // remove old role if it exists and sort the roles by organization and name
+        roles.retain(|role| role.org_id() != new_role.org_id() || role.name() != new_role.name());
+        roles.push(new_role);
+        roles.sort_by_key(|r| (r.org_id().to_string(), r.name().to_string()));
+
+        self.set_roles_at(address, roles)
+    }
+
+    pub fn remove_role(&mut self, org_id: &str, name: &str) -> Result<(), ApplyError> {
+        let address = compute_role_address(org_id, name);
+        let mut roles = self.get_roles_at(org_id, name)?;
+        roles.retain(|role| role.org_id() != org_id || role.name() != name);
+
+        if roles.is_empty() {
+            self.context
+                .delete_state_entry(&address)
+                .map_err(|err| ApplyError::InternalError(format!("{}", err)))?;
+            Ok(())
+        } else {
+            self.set_roles_at(address, roles)
+        }
+    }
+
+    /// Returns the roles stored at the address of the role `name` of an
+    /// organization, which may hold other roles whose addresses collide
+    fn get_roles_at(&mut self, org_id: &str, name: &str) -> Result<Vec<Role>, ApplyError> {
+        let address = compute_role_address(org_id, name);
+        match self.context.get_state_entry(&address)? {
+            Some(packed) => match RoleList::from_bytes(packed.as_slice()) {
+                Ok(role_list) => Ok(role_list.roles().to_vec()),
+                Err(err) => Err(ApplyError::InternalError(format!(
+                    "Cannot deserialize role list: {}",
+                    err,
+                ))),
+            },
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Returns the organization holding an alternate ID, if any
+    pub fn get_alternate_id_holder(
+        &mut self,
+        alternate_id: &AlternateId,
+    ) -> Result<Option<String>, ApplyError> {
+        Ok(self
+            .get_alternate_id_entries_at(alternate_id)?
+            .into_iter()
+            .find(|entry| is_entry_of(entry, alternate_id))
+            .map(|entry| entry.org_id().to_string()))
+    }
+
+    pub fn set_alternate_id_holder(
+        &mut self,
+        alternate_id: &AlternateId,
+        org_id: &str,
+    ) -> Result<(), ApplyError> {
+        let mut entries = self.get_alternate_id_entries_at(alternate_id)?;
+        entries.retain(|entry| !is_entry_of(entry, alternate_id));
+        entries.push(
+            AlternateIdIndexBuilder::new()
+                .with_id_type(alternate_id.id_type().to_string())
+                .with_id(alternate_id.id().to_string())
+                .with_org_id(org_id.to_string())
+                .build()
+                .map_err(|_| {
+                    ApplyError::InternalError(String::from("Cannot build alternate ID index"))
+                })?,
+        );
+        entries.sort_by_key(|entry| (entry.id_type().to_string(), entry.id().to_string()));
+
+        self.set_alternate_id_entries_at(alternate_id, entries)
+    }
+
+    pub fn remove_alternate_id_holder(
+        &mut self,
+        alternate_id: &AlternateId,
+    ) -> Result<(), ApplyError> {
+        let mut entries = self.get_alternate_id_entries_at(alternate_id)?;
+        entries.retain(|entry| !is_entry_of(entry, alternate_id));
+
+        if entries.is_empty() {
+            self.context
+                .delete_state_entry(&compute_alternate_id_address(
+                    alternate_id.id_type(),
+                    alternate_id.id(),
+                ))
+                .map_err(|err| ApplyError::InternalError(format!("{}", err)))?;
+            Ok(())
+        } else {
+            self.set_alternate_id_entries_at(alternate_id, entries)
+        }
+    }
+
+    /// Returns the index entries stored at the address of an alternate ID,
+    /// which may hold the entries of other IDs whose addresses collide
+    fn get_alternate_id_entries_at(
+        &mut self,
+        alternate_id: &AlternateId,
+    ) -> Result<Vec<AlternateIdIndex>, ApplyError> {
+        let address = compute_alternate_id_address(alternate_id.id_type(), alternate_id.id());
+        match self.context.get_state_entry(&address)? {
+            Some(packed) => match AlternateIdIndexList::from_bytes(packed.as_slice()) {
+                Ok(entry_list) => Ok(entry_list.entries().to_vec()),
+                Err(err) => Err(ApplyError::InternalError(format!(
+                    "Cannot deserialize alternate ID index: {}",
+                    err,
+                ))),
+            },
+            None => Ok(vec![]),
+        }
+    }
+
+    fn set_alternate_id_entries_at(
+        &mut self,
+        alternate_id: &AlternateId,
+        entries: Vec<AlternateIdIndex>,
+    ) -> Result<(), ApplyError> {
+        let entry_list = AlternateIdIndexListBuilder::new()
+            .with_entries(entries)
+            .build()
+            .map_err(|_| {
+                ApplyError::InternalError(String::from("Cannot build alternate ID index list"))
+            })?;
+        let serialized = entry_list.into_bytes().map_err(|_| {
+            ApplyError::InternalError(String::from("Cannot serialize alternate ID index list"))
+        })?;
+        self.context
+            .set_state_entry(
+                compute_alternate_id_address(alternate_id.id_type(), alternate_id.id()),
+                serialized,
+            )
+            .map_err(|err| ApplyError::InternalError(format!("{}", err)))?;
+        Ok(())
+    }
+
+    fn set_roles_at(&mut self, address: String, roles: Vec<Role>) -> Result<(), ApplyError> {
+        let role_list = RoleListBuilder::new()
+            .with_roles(roles)
+            .build()
+            .map_err(|_| ApplyError::InternalError(String::from("Cannot build role list")))?;
+        let serialized = role_list
+            .into_bytes()
+            .map_err(|_| ApplyError::InternalError(String::from("Cannot serialize role list")))?;
+        self.context
+            .set_state_entry(address, serialized)
+            .map_err(|err| ApplyError::InternalError(format!("{}", err)))?;
+        Ok(())
+    }
+}
+
+fn is_entry_of(entry: &AlternateIdIndex, alternate_id: &AlternateId) -> bool {
+    entry.id_type() == alternate_id.id_type() && entry.id() == alternate_id.id()
 }
 
 impl PikeTransactionHandler {
     #[allow(clippy::new_without_default)]
     pub fn new() -> PikeTransactionHandler {
+        let payload_migrator = pike_payload_migrator();
         PikeTransactionHandler {
-            family_name: "pike".to_string(),
-            family_versions: vec!["0.1".to_string()],
-            namespaces: vec![NAMESPACE.to_string()],
+            family_name: PIKE_FAMILY_NAME.to_string(),
+            family_versions: payload_migrator.supported_versions(),
+            namespaces: vec![PIKE_NAMESPACE.to_string()],
+            payload_migrator,
         }
     }
 }
@@ -239,8 +412,16 @@ impl TransactionHandler for PikeTransactionHandler {
         request: &TpProcessRequest,
         context: &mut dyn TransactionContext,
     ) -> Result<(), ApplyError> {
-        let payload = protobuf::parse_from_bytes::<PikePayload>(request.get_payload())
-            .map_err(|_| ApplyError::InternalError("Failed to parse payload".into()))?;
+        let payload_bytes = self
+            .payload_migrator
+            .migrate(
+                &payload_version(request.get_header(), self.payload_migrator.current()),
+                request.get_payload(),
+            )
+            .map_err(|err| ApplyError::InvalidTransaction(format!("{}", err)))?;
+        let payload = PikePayload::from_bytes(&payload_bytes).map_err(|err| {
+            ApplyError::InvalidTransaction(format!("Cannot build pike payload: {}", err))
+        })?;
 
         let signer = request.get_header().get_signer_public_key();
         let mut state = PikeState::new(context);
@@ -248,21 +429,27 @@ impl TransactionHandler for PikeTransactionHandler {
         #[cfg(not(target_arch = "wasm32"))]
         info!(
             "{:?} {:?} {:?}",
-            payload.get_action(),
+            payload.action(),
             request.get_header().get_inputs(),
             request.get_header().get_outputs()
         );
 
-        match payload.action {
-            Action::CREATE_AGENT => create_agent(payload.get_create_agent(), signer, &mut state),
-            Action::UPDATE_AGENT => update_agent(payload.get_update_agent(), signer, &mut state),
-            Action::CREATE_ORGANIZATION => {
-                create_org(payload.get_create_organization(), signer, &mut state)
+        match payload.action() {
+            Action::CreateAgent => create_agent(payload.create_agent(), signer, &mut state),
+            Action::UpdateAgent => update_agent(payload.update_agent(), signer, &mut state),
+            Action::CreateOrganization => {
+                create_org(payload.create_organization(), signer, &mut state)
             }
-            Action::UPDATE_ORGANIZATION => {
-                update_org(payload.get_update_organization(), signer, &mut state)
+            Action::UpdateOrganization => {
+                update_org(payload.update_organization(), signer, &mut state)
+            }
+            Action::TransferAgent => transfer_agent(payload.transfer_agent(), signer, &mut state),
+            Action::CreateRole => create_role(payload.create_role(), signer, &mut state),
+            Action::UpdateRole => update_role(payload.update_role(), signer, &mut state),
+            Action::DeleteRole => delete_role(payload.delete_role(), signer, &mut state),
+            Action::RotateAgentKey => {
+                rotate_agent_key(payload.rotate_agent_key(), signer, &mut state)
             }
-            _ => Err(ApplyError::InvalidTransaction("Invalid action".into())),
         }
     }
 }
@@ -272,26 +459,28 @@ fn create_agent(
     signer: &str,
     state: &mut PikeState,
 ) -> Result<(), ApplyError> {
-    if payload.get_public_key().is_empty() {
+    if payload.public_key().is_empty() {
         return Err(ApplyError::InvalidTransaction("Public key required".into()));
     }
 
-    if payload.get_org_id().is_empty() {
+    if payload.org_id().is_empty() {
         return Err(ApplyError::InvalidTransaction(
             "Organization ID required".into(),
         ));
     }
 
+    check_metadata_keys(payload.metadata())?;
+
     // verify the signer of the transaction is authorized to create agent
-    is_admin(signer, payload.get_org_id(), state)?;
+    is_admin(signer, payload.org_id(), state)?;
 
     // Check if agent already exists
-    match state.get_agent(payload.get_public_key()) {
+    match state.get_agent(payload.public_key()) {
         Ok(None) => (),
         Ok(Some(_)) => {
             return Err(ApplyError::InvalidTransaction(format!(
                 "Agent already exists: {}",
-                payload.get_public_key(),
+                payload.public_key(),
             )))
         }
         Err(err) => {
@@ -302,19 +491,17 @@ fn create_agent(
         }
     };
 
-    let mut agent = Agent::new();
-    agent.set_public_key(payload.get_public_key().to_string());
-    agent.set_org_id(payload.get_org_id().to_string());
-    agent.set_active(payload.get_active());
-    agent.set_roles(protobuf::RepeatedField::from_vec(
-        payload.get_roles().to_vec(),
-    ));
-    agent.set_metadata(protobuf::RepeatedField::from_vec(
-        payload.get_metadata().to_vec(),
-    ));
+    let agent = AgentBuilder::new()
+        .with_public_key(payload.public_key().to_string())
+        .with_org_id(payload.org_id().to_string())
+        .with_active(*payload.active())
+        .with_roles(payload.roles().to_vec())
+        .with_metadata(payload.metadata().to_vec())
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build agent: {}", err)))?;
 
     state
-        .set_agent(payload.get_public_key(), agent)
+        .set_agent(payload.public_key(), agent)
         .map_err(|e| ApplyError::InternalError(format!("Failed to create agent: {:?}", e)))
 }
 
@@ -323,24 +510,24 @@ fn update_agent(
     signer: &str,
     state: &mut PikeState,
 ) -> Result<(), ApplyError> {
-    if payload.get_public_key().is_empty() {
+    if payload.public_key().is_empty() {
         return Err(ApplyError::InvalidTransaction("Public key required".into()));
     }
 
-    if payload.get_org_id().is_empty() {
+    if payload.org_id().is_empty() {
         return Err(ApplyError::InvalidTransaction(
             "Organization ID required".into(),
         ));
     }
     // verify the signer of the transaction is authorized to update agent
-    is_admin(signer, payload.get_org_id(), state)?;
+    is_admin(signer, payload.org_id(), state)?;
 
     // make sure agent already exists
-    let mut agent = match state.get_agent(payload.get_public_key()) {
+    let agent = match state.get_agent(payload.public_key()) {
         Ok(None) => {
             return Err(ApplyError::InvalidTransaction(format!(
                 "Agent does not exists: {}",
-                payload.get_public_key(),
+                payload.public_key(),
             )))
         }
         Ok(Some(agent)) => agent,
@@ -352,11 +539,21 @@ fn update_agent(
         }
     };
 
-    if !payload.get_roles().is_empty() {
+    // the organization of an agent only changes through a transfer approved by both
+    // organizations
+    if payload.org_id() != agent.org_id() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Agent {} belongs to organization {}, and can only change organization through a \
+             transfer",
+            payload.public_key(),
+            agent.org_id(),
+        )));
+    }
+
+    let mut roles = agent.roles().to_vec();
+    if !payload.roles().is_empty() {
         // verify that an admin is not removing the role admin from themselves.
-        if signer == payload.get_public_key()
-            && !payload.get_roles().iter().any(|role| role == "admin")
-        {
+        if signer == payload.public_key() && !payload.roles().iter().any(|role| role == "admin") {
             return Err(ApplyError::InvalidTransaction(
                 "An admin cannot remove themselves as admin. 'admin' role must be included
                     in the roles list."
@@ -364,29 +561,312 @@ fn update_agent(
             ));
         }
 
-        agent.set_roles(protobuf::RepeatedField::from_vec(
-            payload.get_roles().to_vec(),
-        ));
+        roles = payload.roles().to_vec();
+    }
+
+    let mut metadata = agent.metadata().to_vec();
+    if !payload.metadata().is_empty() {
+        check_metadata_keys(payload.metadata())?;
+        // the history and pending transfer of the agent are kept
+        metadata = payload.metadata().to_vec();
+        metadata.extend(
+            agent
+                .metadata()
+                .iter()
+                .filter(|entry| RESERVED_METADATA_KEYS.contains(&entry.key()))
+                .cloned(),
+        );
+    }
+
+    if payload.active() != agent.active() && signer == payload.public_key() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Admin may not deactivate themselves: {}",
+            signer,
+        )));
+    }
+
+    // the identity of a rotated agent is held by its successor
+    if *payload.active() && !agent.successor().is_empty() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Agent key was rotated to {} and cannot be reactivated: {}",
+            agent.successor(),
+            payload.public_key(),
+        )));
+    }
+
+    let updated_agent = AgentBuilder::new()
+        .with_public_key(agent.public_key().to_string())
+        .with_org_id(agent.org_id().to_string())
+        .with_active(*payload.active())
+        .with_roles(roles)
+        .with_metadata(metadata)
+        .with_successor(agent.successor().to_string())
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build agent: {}", err)))?;
+
+    state
+        .set_agent(payload.public_key(), updated_agent)
+        .map_err(|e| ApplyError::InternalError(format!("Failed to create agent: {:?}", e)))
+}
+
+/// Moves an agent to another organization once admins of both organizations
+/// have submitted the transfer.
+///
+/// The first approval is recorded in the agent's metadata as a pending
+/// transfer; the approval of the other organization completes it, appending
+/// the former organization to the agent's history. Roles are granted by an
+/// organization, so a transferred agent starts without any.
+fn transfer_agent(
+    payload: &TransferAgentAction,
+    signer: &str,
+    state: &mut PikeState,
+) -> Result<(), ApplyError> {
+    if payload.public_key().is_empty() {
+        return Err(ApplyError::InvalidTransaction("Public key required".into()));
     }
 
-    if !payload.get_metadata().is_empty() {
-        agent.set_metadata(protobuf::RepeatedField::from_vec(
-            payload.get_metadata().to_vec(),
+    if payload.org_id().is_empty() {
+        return Err(ApplyError::InvalidTransaction(
+            "Organization ID required".into(),
         ));
     }
 
-    if payload.get_active() != agent.get_active() {
-        if signer == payload.get_public_key() {
+    let agent = match state.get_agent(payload.public_key())? {
+        Some(agent) => agent,
+        None => {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Agent does not exists: {}",
+                payload.public_key(),
+            )))
+        }
+    };
+
+    if !agent.successor().is_empty() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Agent key was rotated to {}, which is the one to transfer: {}",
+            agent.successor(),
+            payload.public_key(),
+        )));
+    }
+
+    if agent.org_id() == payload.org_id() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Agent {} already belongs to organization {}",
+            payload.public_key(),
+            payload.org_id(),
+        )));
+    }
+
+    if state.get_organization(payload.org_id())?.is_none() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Organization does not exist: {}",
+            payload.org_id(),
+        )));
+    }
+
+    // the signer approves the transfer on behalf of the organization they administer
+    let approving_org = if is_admin(signer, agent.org_id(), state).is_ok() {
+        agent.org_id()
+    } else if is_admin(signer, payload.org_id(), state).is_ok() {
+        payload.org_id()
+    } else {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Signer is not an active admin of organization {} or {}: {}",
+            agent.org_id(),
+            payload.org_id(),
+            signer,
+        )));
+    };
+
+    let pending_org = metadata_value(agent.metadata(), TRANSFER_ORG_ID_KEY);
+    let approved_by = metadata_value(agent.metadata(), TRANSFER_APPROVED_BY_KEY);
+    let mut metadata: Vec<KeyValueEntry> = agent
+        .metadata()
+        .iter()
+        .filter(|entry| {
+            entry.key() != TRANSFER_ORG_ID_KEY && entry.key() != TRANSFER_APPROVED_BY_KEY
+        })
+        .cloned()
+        .collect();
+
+    let (org_id, roles) = if pending_org != Some(payload.org_id()) {
+        // a new transfer replaces any pending one
+        metadata.push(key_value_entry(TRANSFER_ORG_ID_KEY, payload.org_id())?);
+        metadata.push(key_value_entry(TRANSFER_APPROVED_BY_KEY, approving_org)?);
+        (agent.org_id(), agent.roles().to_vec())
+    } else if approved_by == Some(approving_org) {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Transfer of agent {} to organization {} is already approved by {}",
+            payload.public_key(),
+            payload.org_id(),
+            approving_org,
+        )));
+    } else {
+        let history = match metadata_value(agent.metadata(), ORG_HISTORY_KEY) {
+            Some(history) => format!("{},{}", history, agent.org_id()),
+            None => agent.org_id().to_string(),
+        };
+        metadata.retain(|entry| entry.key() != ORG_HISTORY_KEY);
+        metadata.push(key_value_entry(ORG_HISTORY_KEY, &history)?);
+        (payload.org_id(), vec![])
+    };
+
+    let updated_agent = AgentBuilder::new()
+        .with_public_key(agent.public_key().to_string())
+        .with_org_id(org_id.to_string())
+        .with_active(*agent.active())
+        .with_roles(roles)
+        .with_metadata(metadata)
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build agent: {}", err)))?;
+
+    state
+        .set_agent(payload.public_key(), updated_agent)
+        .map_err(|e| ApplyError::InternalError(format!("Failed to transfer agent: {:?}", e)))
+}
+
+/// Replaces the key of an agent, on behalf of the agent itself or of an admin
+/// of its organization when the key is lost.
+///
+/// The agent of the new key takes over the organization, roles and metadata
+/// of the former one, which is deactivated and points to the new key as its
+/// successor. Records keep the former key, which resolves to the new one.
+///
+/// A key is rotated by itself only once, but an admin may rotate it again to
+/// recover from a rotation made by whoever compromised it. The keys it was
+/// handed over to are then deactivated and point to the new key as well.
+fn rotate_agent_key(
+    payload: &RotateAgentKeyAction,
+    signer: &str,
+    state: &mut PikeState,
+) -> Result<(), ApplyError> {
+    if payload.public_key().is_empty() || payload.new_public_key().is_empty() {
+        return Err(ApplyError::InvalidTransaction("Public key required".into()));
+    }
+
+    if payload.public_key() == payload.new_public_key() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "New public key must differ from the rotated key: {}",
+            payload.public_key(),
+        )));
+    }
+
+    let agent = match state.get_agent(payload.public_key())? {
+        Some(agent) => agent,
+        None => {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Agent does not exists: {}",
+                payload.public_key(),
+            )))
+        }
+    };
+
+    if signer == payload.public_key() {
+        if !agent.successor().is_empty() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Agent key was already rotated to {}: {}",
+                agent.successor(),
+                payload.public_key(),
+            )));
+        }
+
+        // a deactivated agent may have had its key compromised
+        if !agent.active() {
             return Err(ApplyError::InvalidTransaction(format!(
-                "Admin may not deactivate themselves: {}",
+                "Agent is not active and cannot rotate its own key: {}",
                 signer,
             )));
         }
-        agent.set_active(payload.get_active());
+    } else {
+        // verify the signer of the transaction is authorized to rotate the key
+        is_admin(signer, agent.org_id(), state)?;
+    }
+
+    let displaced = successors(&agent, state)?;
+    if displaced
+        .iter()
+        .any(|successor| successor.public_key() == signer)
+    {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Agent key was rotated to the signer and cannot be rotated by it: {}",
+            payload.public_key(),
+        )));
+    }
+
+    if state.get_agent(payload.new_public_key())?.is_some() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Agent already exists: {}",
+            payload.new_public_key(),
+        )));
     }
+
+    let new_agent = AgentBuilder::new()
+        .with_public_key(payload.new_public_key().to_string())
+        .with_org_id(agent.org_id().to_string())
+        .with_active(true)
+        .with_roles(agent.roles().to_vec())
+        .with_metadata(agent.metadata().to_vec())
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build agent: {}", err)))?;
+
+    let rotated_agent = AgentBuilder::new()
+        .with_public_key(agent.public_key().to_string())
+        .with_org_id(agent.org_id().to_string())
+        .with_active(false)
+        .with_roles(agent.roles().to_vec())
+        .with_metadata(agent.metadata().to_vec())
+        .with_successor(payload.new_public_key().to_string())
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build agent: {}", err)))?;
+
     state
-        .set_agent(payload.get_public_key(), agent)
-        .map_err(|e| ApplyError::InternalError(format!("Failed to create agent: {:?}", e)))
+        .set_agent(payload.new_public_key(), new_agent)
+        .map_err(|e| ApplyError::InternalError(format!("Failed to create agent: {:?}", e)))?;
+    state
+        .set_agent(payload.public_key(), rotated_agent)
+        .map_err(|e| ApplyError::InternalError(format!("Failed to rotate agent key: {:?}", e)))?;
+
+    for successor in displaced {
+        let displaced_agent = AgentBuilder::new()
+            .with_public_key(successor.public_key().to_string())
+            .with_org_id(successor.org_id().to_string())
+            .with_active(false)
+            .with_roles(successor.roles().to_vec())
+            .with_metadata(successor.metadata().to_vec())
+            .with_successor(payload.new_public_key().to_string())
+            .build()
+            .map_err(|err| {
+                ApplyError::InvalidTransaction(format!("Cannot build agent: {}", err))
+            })?;
+        state
+            .set_agent(successor.public_key(), displaced_agent)
+            .map_err(|e| {
+                ApplyError::InternalError(format!("Failed to rotate agent key: {:?}", e))
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Returns the agents a rotated key was handed over to, from its successor to
+/// the current key of the agent
+fn successors(agent: &Agent, state: &mut PikeState) -> Result<Vec<Agent>, ApplyError> {
+    let mut successors: Vec<Agent> = vec![];
+    let mut successor = agent.successor().to_string();
+    while !successor.is_empty()
+        && !successors
+            .iter()
+            .any(|agent| agent.public_key() == successor.as_str())
+    {
+        match state.get_agent(&successor)? {
+            Some(next) => {
+                successor = next.successor().to_string();
+                successors.push(next);
+            }
+            None => break,
+        }
+    }
+    Ok(successors)
 }
 
 fn create_org(
@@ -394,25 +874,25 @@ fn create_org(
     signer: &str,
     state: &mut PikeState,
 ) -> Result<(), ApplyError> {
-    if payload.get_id().is_empty() {
+    if payload.org_id().is_empty() {
         return Err(ApplyError::InvalidTransaction(
             "Unique organization ID required".into(),
         ));
     }
 
-    if payload.get_name().is_empty() {
+    if payload.name().is_empty() {
         return Err(ApplyError::InvalidTransaction(
             "organization name required".into(),
         ));
     }
 
     // Check if the organization already exists
-    match state.get_organization(payload.get_id()) {
+    match state.get_organization(payload.org_id()) {
         Ok(None) => (),
         Ok(Some(_)) => {
             return Err(ApplyError::InvalidTransaction(format!(
                 "Organization already exists: {}",
-                payload.get_id(),
+                payload.org_id(),
             )))
         }
         Err(err) => {
@@ -422,18 +902,42 @@ fn create_org(
             )))
         }
     };
-    let mut organization = Organization::new();
-    organization.set_org_id(payload.get_id().to_string());
-    organization.set_name(payload.get_name().to_string());
-    organization.set_address(payload.get_address().to_string());
-    organization.set_metadata(protobuf::RepeatedField::from_vec(
-        payload.get_metadata().to_vec(),
-    ));
-    state.set_organization(payload.get_id(), organization)?;
-
-    state.get_agent(signer).map_err(|e| {
-        ApplyError::InternalError(format!("Failed to create organization: {:?}", e))
-    })?;
+
+    if !payload.parent_org_id().is_empty() {
+        if state.get_organization(payload.parent_org_id())?.is_none() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Parent organization does not exist: {}",
+                payload.parent_org_id(),
+            )));
+        }
+
+        // verify the signer of the transaction is authorized to add to the parent organization
+        is_admin(signer, payload.parent_org_id(), state)?;
+    }
+
+    check_alternate_ids(payload.org_id(), payload.alternate_ids(), state)?;
+
+    let organization = OrganizationBuilder::new()
+        .with_org_id(payload.org_id().to_string())
+        .with_name(payload.name().to_string())
+        .with_address(payload.address().to_string())
+        .with_metadata(payload.metadata().to_vec())
+        .with_parent_org_id(payload.parent_org_id().to_string())
+        .with_alternate_ids(payload.alternate_ids().to_vec())
+        .build()
+        .map_err(|err| {
+            ApplyError::InvalidTransaction(format!("Cannot build organization: {}", err))
+        })?;
+    state.set_organization(payload.org_id(), organization)?;
+
+    for alternate_id in payload.alternate_ids() {
+        state.set_alternate_id_holder(alternate_id, payload.org_id())?;
+    }
+
+    // the admins of the parent organization already administer the new one
+    if !payload.parent_org_id().is_empty() {
+        return Ok(());
+    }
 
     // Check if the agent already exists
     match state.get_agent(signer) {
@@ -441,7 +945,7 @@ fn create_org(
         Ok(Some(_)) => {
             return Err(ApplyError::InvalidTransaction(format!(
                 "Agent already exists: {}",
-                payload.get_id(),
+                payload.org_id(),
             )))
         }
         Err(err) => {
@@ -451,13 +955,13 @@ fn create_org(
             )))
         }
     };
-    let mut agent = Agent::new();
-    agent.set_public_key(signer.to_string());
-    agent.set_org_id(payload.get_id().to_string());
-    agent.set_active(true);
-    agent.set_roles(protobuf::RepeatedField::from_vec(vec![String::from(
-        "admin",
-    )]));
+    let agent = AgentBuilder::new()
+        .with_public_key(signer.to_string())
+        .with_org_id(payload.org_id().to_string())
+        .with_active(true)
+        .with_roles(vec![String::from("admin")])
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build agent: {}", err)))?;
 
     state
         .set_agent(signer, agent)
@@ -469,21 +973,21 @@ fn update_org(
     signer: &str,
     state: &mut PikeState,
 ) -> Result<(), ApplyError> {
-    if payload.get_id().is_empty() {
+    if payload.org_id().is_empty() {
         return Err(ApplyError::InvalidTransaction(
             "Unique organization ID required".into(),
         ));
     }
 
     // verify the signer of the transaction is authorized to update organization
-    is_admin(signer, payload.get_id(), state)?;
+    is_admin(signer, payload.org_id(), state)?;
 
     // Make sure the organization already exists
-    let mut organization = match state.get_organization(payload.get_id()) {
+    let organization = match state.get_organization(payload.org_id()) {
         Ok(None) => {
             return Err(ApplyError::InvalidTransaction(format!(
                 "Organization does not exist exists: {}",
-                payload.get_id(),
+                payload.org_id(),
             )))
         }
         Ok(Some(org)) => org,
@@ -495,74 +999,1619 @@ fn update_org(
         }
     };
 
-    if !payload.get_name().is_empty() {
-        organization.set_name(payload.get_name().to_string());
+    let mut name = organization.name();
+    if !payload.name().is_empty() {
+        name = payload.name();
     }
-    if !payload.get_address().is_empty() {
-        organization.set_address(payload.get_address().to_string());
+    let mut address = organization.address();
+    if !payload.address().is_empty() {
+        address = payload.address();
     }
-    if !payload.get_metadata().is_empty() {
-        organization.set_metadata(protobuf::RepeatedField::from_vec(
-            payload.get_metadata().to_vec(),
-        ));
+    let mut metadata = organization.metadata();
+    if !payload.metadata().is_empty() {
+        metadata = payload.metadata();
     }
-    state.set_organization(payload.get_id(), organization)
-}
+    let mut parent_org_id = organization.parent_org_id();
+    if payload.detach_from_parent() {
+        if !payload.parent_org_id().is_empty() {
+            return Err(ApplyError::InvalidTransaction(
+                "Cannot both detach an organization and move it under another".into(),
+            ));
+        }
+        if parent_org_id.is_empty() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Organization {} is not part of another organization",
+                payload.org_id(),
+            )));
+        }
 
-pub fn is_admin(signer: &str, org_id: &str, state: &mut PikeState) -> Result<(), ApplyError> {
-    let admin = match state.get_agent(signer) {
-        Ok(None) => {
+        // verify the signer of the transaction is authorized to remove from the parent organization
+        is_admin(signer, parent_org_id, state)?;
+
+        parent_org_id = "";
+    } else if !payload.parent_org_id().is_empty() && payload.parent_org_id() != parent_org_id {
+        if state.get_organization(payload.parent_org_id())?.is_none() {
             return Err(ApplyError::InvalidTransaction(format!(
-                "Signer is not an agent: {}",
-                signer,
-            )))
+                "Parent organization does not exist: {}",
+                payload.parent_org_id(),
+            )));
         }
-        Ok(Some(admin)) => admin,
-        Err(err) => {
+
+        // verify the signer of the transaction is authorized to add to the parent organization
+        is_admin(signer, payload.parent_org_id(), state)?;
+
+        if org_ancestry(payload.parent_org_id(), state)?
+            .iter()
+            .any(|org_id| org_id == payload.org_id())
+        {
             return Err(ApplyError::InvalidTransaction(format!(
-                "Failed to retrieve state: {}",
-                err,
-            )))
+                "Organization {} cannot be part of {}, which is part of it",
+                payload.org_id(),
+                payload.parent_org_id(),
+            )));
         }
-    };
+        parent_org_id = payload.parent_org_id();
+    }
+    let mut alternate_ids = organization.alternate_ids();
+    if !payload.alternate_ids().is_empty() {
+        check_alternate_ids(payload.org_id(), payload.alternate_ids(), state)?;
 
-    if admin.get_org_id() != org_id {
-        return Err(ApplyError::InvalidTransaction(format!(
-            "Signer is not associated with the organization: {}",
-            signer,
-        )));
+        // release the IDs being replaced so other organizations can claim them
+        for alternate_id in alternate_ids {
+            if !payload.alternate_ids().contains(alternate_id) {
+                state.remove_alternate_id_holder(alternate_id)?;
+            }
+        }
+        for alternate_id in payload.alternate_ids() {
+            state.set_alternate_id_holder(alternate_id, payload.org_id())?;
+        }
+        alternate_ids = payload.alternate_ids();
     }
-    if !admin.roles.contains(&"admin".to_string()) {
-        return Err(ApplyError::InvalidTransaction(format!(
-            "Signer is not an admin: {}",
-            signer,
-        )));
-    };
 
-    if !admin.active {
-        return Err(ApplyError::InvalidTransaction(format!(
-            "Admin is not currently an active agent: {}",
-            signer,
-        )));
+    let updated_organization = OrganizationBuilder::new()
+        .with_org_id(organization.org_id().to_string())
+        .with_name(name.to_string())
+        .with_address(address.to_string())
+        .with_metadata(metadata.to_vec())
+        .with_parent_org_id(parent_org_id.to_string())
+        .with_alternate_ids(alternate_ids.to_vec())
+        .build()
+        .map_err(|err| {
+            ApplyError::InvalidTransaction(format!("Cannot build organization: {}", err))
+        })?;
+
+    state.set_organization(payload.org_id(), updated_organization)
+}
+
+/// Checks that the alternate IDs of an organization are well formed, listed
+/// once, and not already held by another organization
+fn check_alternate_ids(
+    org_id: &str,
+    alternate_ids: &[AlternateId],
+    state: &mut PikeState,
+) -> Result<(), ApplyError> {
+    for (i, alternate_id) in alternate_ids.iter().enumerate() {
+        alternate_id
+            .validate()
+            .map_err(|err| ApplyError::InvalidTransaction(format!("{}", err)))?;
+
+        if alternate_ids[..i].contains(alternate_id) {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Alternate ID is listed more than once: {}",
+                alternate_id,
+            )));
+        }
+
+        match state.get_alternate_id_holder(alternate_id)? {
+            Some(ref holder) if holder != org_id => {
+                return Err(ApplyError::InvalidTransaction(format!(
+                    "Alternate ID {} is already held by organization {}",
+                    alternate_id, holder,
+                )))
+            }
+            _ => (),
+        }
     }
     Ok(())
 }
 
-#[cfg(target_arch = "wasm32")]
-// Sabre apply must return a bool
-fn apply(
-    request: &TpProcessRequest,
-    context: &mut dyn TransactionContext,
-) -> Result<bool, ApplyError> {
-    let handler = PikeTransactionHandler::new();
-    match handler.apply(request, context) {
-        Ok(_) => Ok(true),
-        Err(err) => Err(err),
+fn create_role(
+    payload: &CreateRoleAction,
+    signer: &str,
+    state: &mut PikeState,
+) -> Result<(), ApplyError> {
+    check_role_ids(payload.org_id(), payload.name())?;
+
+    // verify the signer of the transaction is authorized to define roles
+    is_admin(signer, payload.org_id(), state)?;
+
+    if state.get_role(payload.org_id(), payload.name())?.is_some() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Role already exists: {}",
+            payload.name(),
+        )));
     }
-}
 
-#[cfg(target_arch = "wasm32")]
+    check_inherited_roles(
+        payload.org_id(),
+        payload.name(),
+        payload.inherit_from(),
+        state,
+    )?;
+
+    let role = RoleBuilder::new()
+        .with_org_id(payload.org_id().to_string())
+        .with_name(payload.name().to_string())
+        .with_description(payload.description().to_string())
+        .with_permissions(payload.permissions().to_vec())
+        .with_inherit_from(payload.inherit_from().to_vec())
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build role: {}", err)))?;
+
+    state
+        .set_role(role)
+        .map_err(|e| ApplyError::InternalError(format!("Failed to create role: {:?}", e)))
+}
+
+fn update_role(
+    payload: &UpdateRoleAction,
+    signer: &str,
+    state: &mut PikeState,
+) -> Result<(), ApplyError> {
+    check_role_ids(payload.org_id(), payload.name())?;
+
+    // verify the signer of the transaction is authorized to define roles
+    is_admin(signer, payload.org_id(), state)?;
+
+    if state.get_role(payload.org_id(), payload.name())?.is_none() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Role does not exist: {}",
+            payload.name(),
+        )));
+    }
+
+    check_inherited_roles(
+        payload.org_id(),
+        payload.name(),
+        payload.inherit_from(),
+        state,
+    )?;
+
+    let role = RoleBuilder::new()
+        .with_org_id(payload.org_id().to_string())
+        .with_name(payload.name().to_string())
+        .with_description(payload.description().to_string())
+        .with_permissions(payload.permissions().to_vec())
+        .with_inherit_from(payload.inherit_from().to_vec())
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build role: {}", err)))?;
+
+    state
+        .set_role(role)
+        .map_err(|e| ApplyError::InternalError(format!("Failed to update role: {:?}", e)))
+}
+
+/// Deletes a role. Agents and roles still referring to it keep the name,
+/// which no longer grants anything but the permission of the same name.
+fn delete_role(
+    payload: &DeleteRoleAction,
+    signer: &str,
+    state: &mut PikeState,
+) -> Result<(), ApplyError> {
+    check_role_ids(payload.org_id(), payload.name())?;
+
+    // verify the signer of the transaction is authorized to define roles
+    is_admin(signer, payload.org_id(), state)?;
+
+    if state.get_role(payload.org_id(), payload.name())?.is_none() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Role does not exist: {}",
+            payload.name(),
+        )));
+    }
+
+    state
+        .remove_role(payload.org_id(), payload.name())
+        .map_err(|e| ApplyError::InternalError(format!("Failed to delete role: {:?}", e)))
+}
+
+fn check_role_ids(org_id: &str, name: &str) -> Result<(), ApplyError> {
+    if org_id.is_empty() {
+        return Err(ApplyError::InvalidTransaction(
+            "Organization ID required".into(),
+        ));
+    }
+
+    if name.is_empty() {
+        return Err(ApplyError::InvalidTransaction("Role name required".into()));
+    }
+
+    Ok(())
+}
+
+/// Checks that the roles inherited by the role `name` are defined by the
+/// organization, and that inheriting them does not lead back to the role
+fn check_inherited_roles(
+    org_id: &str,
+    name: &str,
+    inherit_from: &[String],
+    state: &mut PikeState,
+) -> Result<(), ApplyError> {
+    for inherited in inherit_from {
+        if state.get_role(org_id, inherited)?.is_none() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Inherited role does not exist: {}",
+                inherited,
+            )));
+        }
+    }
+
+    let mut visited: Vec<String> = vec![];
+    let mut pending: Vec<String> = inherit_from.to_vec();
+    while let Some(role) = pending.pop() {
+        if role == name {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Role {} cannot inherit from itself, directly or through other roles",
+                name,
+            )));
+        }
+        if visited.contains(&role) {
+            continue;
+        }
+        // deleted roles are skipped, as they no longer grant their permissions
+        if let Some(definition) = state.get_role(org_id, &role)? {
+            pending.extend(definition.inherit_from().iter().cloned());
+        }
+        visited.push(role);
+    }
+
+    Ok(())
+}
+
+/// Metadata keys the contract maintains, which payloads cannot set
+const RESERVED_METADATA_KEYS: [&str; 3] = [
+    ORG_HISTORY_KEY,
+    TRANSFER_ORG_ID_KEY,
+    TRANSFER_APPROVED_BY_KEY,
+];
+
+fn check_metadata_keys(metadata: &[KeyValueEntry]) -> Result<(), ApplyError> {
+    match metadata
+        .iter()
+        .find(|entry| RESERVED_METADATA_KEYS.contains(&entry.key()))
+    {
+        Some(entry) => Err(ApplyError::InvalidTransaction(format!(
+            "Metadata key is reserved: {}",
+            entry.key(),
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn metadata_value<'a>(metadata: &'a [KeyValueEntry], key: &str) -> Option<&'a str> {
+    metadata
+        .iter()
+        .find(|entry| entry.key() == key)
+        .map(KeyValueEntry::value)
+}
+
+fn key_value_entry(key: &str, value: &str) -> Result<KeyValueEntry, ApplyError> {
+    KeyValueEntryBuilder::new()
+        .with_key(key.to_string())
+        .with_value(value.to_string())
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build metadata: {}", err)))
+}
+
+pub fn is_admin(signer: &str, org_id: &str, state: &mut PikeState) -> Result<(), ApplyError> {
+    let admin = match state.get_agent(signer) {
+        Ok(None) => {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Signer is not an agent: {}",
+                signer,
+            )))
+        }
+        Ok(Some(admin)) => admin,
+        Err(err) => {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Failed to retrieve state: {}",
+                err,
+            )))
+        }
+    };
+
+    // the admins of an organization also administer the organizations it is made of
+    if !org_ancestry(org_id, state)?
+        .iter()
+        .any(|ancestor| ancestor == admin.org_id())
+    {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Signer is not associated with the organization: {}",
+            signer,
+        )));
+    }
+    if !admin.roles().contains(&"admin".to_string()) {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Signer is not an admin: {}",
+            signer,
+        )));
+    };
+
+    if !admin.active() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Admin is not currently an active agent: {}",
+            signer,
+        )));
+    }
+    Ok(())
+}
+
+/// Returns `org_id` followed by the organizations it is part of, from its
+/// parent up to the root of its hierarchy
+fn org_ancestry(org_id: &str, state: &mut PikeState) -> Result<Vec<String>, ApplyError> {
+    let mut ancestry = vec![org_id.to_string()];
+    let mut organization = state.get_organization(org_id)?;
+    while let Some(org) = organization {
+        // cycles are rejected when organizations are updated, but a corrupted
+        // hierarchy must not loop forever
+        if org.parent_org_id().is_empty()
+            || ancestry
+                .iter()
+                .any(|ancestor| ancestor == org.parent_org_id())
+        {
+            break;
+        }
+        ancestry.push(org.parent_org_id().to_string());
+        organization = state.get_organization(org.parent_org_id())?;
+    }
+    Ok(ancestry)
+}
+
+#[cfg(target_arch = "wasm32")]
+// Sabre apply must return a bool
+fn apply(
+    request: &TpProcessRequest,
+    context: &mut dyn TransactionContext,
+) -> Result<bool, ApplyError> {
+    let handler = PikeTransactionHandler::new();
+    match handler.apply(request, context) {
+        Ok(_) => Ok(true),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
 #[no_mangle]
 pub unsafe fn entrypoint(payload: WasmPtr, signer: WasmPtr, signature: WasmPtr) -> i32 {
     execute_entrypoint(payload, signer, signature, apply)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use grid_sdk::protocol::addressing::pike::PIKE_FAMILY_VERSION;
+    use grid_sdk::protocol::pike::payload::{
+        CreateOrganizationActionBuilder, CreateRoleActionBuilder, DeleteRoleActionBuilder,
+        PikePayloadBuilder, RotateAgentKeyActionBuilder, TransferAgentActionBuilder,
+        UpdateAgentActionBuilder, UpdateOrganizationActionBuilder, UpdateRoleActionBuilder,
+    };
+    use grid_sdk::protocol::pike::state::KeyValueEntryBuilder;
+    use grid_sdk::protos::IntoNative;
+    use grid_sdk::test_kit::{AgentFixture, MockTransactionContext, OrganizationFixture};
+
+    const ORG_ID: &str = "test_org";
+    const ADMIN_KEY: &str = "admin_public_key";
+    const AGENT_KEY: &str = "agent_public_key";
+
+    /// Returns a context holding ORG_ID and its active admin ADMIN_KEY
+    fn context_with_admin() -> MockTransactionContext {
+        let context = MockTransactionContext::default();
+        OrganizationFixture::new(ORG_ID)
+            .with_address("1 Main Street")
+            .add_to(&context);
+        AgentFixture::new(ADMIN_KEY, ORG_ID)
+            .with_roles(&["admin"])
+            .add_to(&context);
+        context
+    }
+
+    fn apply(
+        context: &mut MockTransactionContext,
+        payload: &PikePayload,
+        signer: &str,
+    ) -> Result<(), ApplyError> {
+        context.apply(&PikeTransactionHandler::new(), payload, signer)
+    }
+
+    fn assert_invalid(result: Result<(), ApplyError>, message: &str) {
+        match result {
+            Err(ApplyError::InvalidTransaction(err)) => assert!(
+                err.contains(message),
+                "Expected an error containing {:?}, got {:?}",
+                message,
+                err
+            ),
+            res => panic!("Expected InvalidTransaction, got {:?}", res),
+        }
+    }
+
+    fn update_agent_payload(active: bool, roles: &[&str]) -> PikePayload {
+        let action = UpdateAgentActionBuilder::new()
+            .with_org_id(ORG_ID.to_string())
+            .with_public_key(AGENT_KEY.to_string())
+            .with_active(active)
+            .with_roles(roles.iter().map(|role| role.to_string()).collect())
+            .build()
+            .unwrap();
+        PikePayloadBuilder::new()
+            .with_action(Action::UpdateAgent)
+            .with_update_agent(action)
+            .build()
+            .unwrap()
+    }
+
+    fn transfer_agent_payload(org_id: &str) -> PikePayload {
+        let action = TransferAgentActionBuilder::new()
+            .with_public_key(AGENT_KEY.to_string())
+            .with_org_id(org_id.to_string())
+            .build()
+            .unwrap();
+        PikePayloadBuilder::new()
+            .with_action(Action::TransferAgent)
+            .with_transfer_agent(action)
+            .build()
+            .unwrap()
+    }
+
+    /// Returns a context holding AGENT_KEY in ORG_ID, and "other_org" with its admin
+    fn context_for_transfer() -> MockTransactionContext {
+        let context = context_with_admin();
+        AgentFixture::new(AGENT_KEY, ORG_ID)
+            .with_roles(&["can_create_schema"])
+            .with_metadata("email", "agent@test.org")
+            .add_to(&context);
+        OrganizationFixture::new("other_org").add_to(&context);
+        AgentFixture::new("other_admin_key", "other_org")
+            .with_roles(&["admin"])
+            .add_to(&context);
+        context
+    }
+
+    fn update_org_payload(org_id: &str, name: &str) -> PikePayload {
+        let action = UpdateOrganizationActionBuilder::new()
+            .with_org_id(org_id.to_string())
+            .with_name(name.to_string())
+            .build()
+            .unwrap();
+        PikePayloadBuilder::new()
+            .with_action(Action::UpdateOrganization)
+            .with_update_organization(action)
+            .build()
+            .unwrap()
+    }
+
+    fn create_role_payload(name: &str, permissions: &[&str], inherit_from: &[&str]) -> PikePayload {
+        let action = CreateRoleActionBuilder::new()
+            .with_org_id(ORG_ID.to_string())
+            .with_name(name.to_string())
+            .with_permissions(permissions.iter().map(|p| p.to_string()).collect())
+            .with_inherit_from(inherit_from.iter().map(|r| r.to_string()).collect())
+            .build()
+            .unwrap();
+        PikePayloadBuilder::new()
+            .with_action(Action::CreateRole)
+            .with_create_role(action)
+            .build()
+            .unwrap()
+    }
+
+    fn update_role_payload(name: &str, permissions: &[&str], inherit_from: &[&str]) -> PikePayload {
+        let action = UpdateRoleActionBuilder::new()
+            .with_org_id(ORG_ID.to_string())
+            .with_name(name.to_string())
+            .with_permissions(permissions.iter().map(|p| p.to_string()).collect())
+            .with_inherit_from(inherit_from.iter().map(|r| r.to_string()).collect())
+            .build()
+            .unwrap();
+        PikePayloadBuilder::new()
+            .with_action(Action::UpdateRole)
+            .with_update_role(action)
+            .build()
+            .unwrap()
+    }
+
+    fn delete_role_payload(name: &str) -> PikePayload {
+        let action = DeleteRoleActionBuilder::new()
+            .with_org_id(ORG_ID.to_string())
+            .with_name(name.to_string())
+            .build()
+            .unwrap();
+        PikePayloadBuilder::new()
+            .with_action(Action::DeleteRole)
+            .with_delete_role(action)
+            .build()
+            .unwrap()
+    }
+
+    fn rotate_agent_key_payload(public_key: &str, new_public_key: &str) -> PikePayload {
+        let action = RotateAgentKeyActionBuilder::new()
+            .with_public_key(public_key.to_string())
+            .with_new_public_key(new_public_key.to_string())
+            .build()
+            .unwrap();
+        PikePayloadBuilder::new()
+            .with_action(Action::RotateAgentKey)
+            .with_rotate_agent_key(action)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    // Test that creating an organization stores it, and makes the signer its active admin
+    fn test_create_org_valid() {
+        let mut context = MockTransactionContext::default();
+        let payload = OrganizationFixture::new(ORG_ID)
+            .with_name("Test Org")
+            .with_metadata("gln", "0614141000012")
+            .payload();
+
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+
+        let org = context.get_organization(ORG_ID).unwrap();
+        assert_eq!(org.name(), "Test Org");
+        assert_eq!(org.metadata()[0].value(), "0614141000012");
+
+        let admin = context.get_agent(ADMIN_KEY).unwrap();
+        assert_eq!(admin.org_id(), ORG_ID);
+        assert_eq!(admin.roles(), ["admin".to_string()]);
+        assert!(*admin.active());
+    }
+
+    #[test]
+    // Test that an organization cannot be created twice
+    fn test_create_org_already_exists() {
+        let mut context = context_with_admin();
+        let payload = OrganizationFixture::new(ORG_ID).payload();
+
+        assert_invalid(
+            apply(&mut context, &payload, "other_public_key"),
+            "Organization already exists: test_org",
+        );
+    }
+
+    #[test]
+    // Test that an organization requires an ID and a name
+    fn test_create_org_missing_fields() {
+        let mut context = MockTransactionContext::default();
+
+        let payload = OrganizationFixture::new("").with_name("Test Org").payload();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Unique organization ID required",
+        );
+
+        let payload = OrganizationFixture::new(ORG_ID).with_name("").payload();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "organization name required",
+        );
+    }
+
+    #[test]
+    // Test that an existing agent cannot create an organization
+    fn test_create_org_signer_is_agent() {
+        let mut context = context_with_admin();
+        let payload = OrganizationFixture::new("other_org").payload();
+
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Agent already exists",
+        );
+    }
+
+    /// Returns a context where ORG_ID, administered by ADMIN_KEY, is made of "division",
+    /// itself made of "site" and administered by "division_admin_key"
+    fn context_with_hierarchy() -> MockTransactionContext {
+        let context = context_with_admin();
+        OrganizationFixture::new("division")
+            .with_parent(ORG_ID)
+            .add_to(&context);
+        OrganizationFixture::new("site")
+            .with_parent("division")
+            .add_to(&context);
+        AgentFixture::new("division_admin_key", "division")
+            .with_roles(&["admin"])
+            .add_to(&context);
+        context
+    }
+
+    #[test]
+    // Test that an admin can create an organization part of their own, which they administer
+    // without becoming one of its agents
+    fn test_create_org_with_parent() {
+        let mut context = context_with_admin();
+        let payload = OrganizationFixture::new("division")
+            .with_parent(ORG_ID)
+            .payload();
+
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+
+        let org = context.get_organization("division").unwrap();
+        assert_eq!(org.parent_org_id(), ORG_ID);
+        assert_eq!(context.get_agent(ADMIN_KEY).unwrap().org_id(), ORG_ID);
+
+        apply(
+            &mut context,
+            &AgentFixture::new(AGENT_KEY, "division").payload(),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        context.assert_accesses_declared();
+        assert_eq!(context.get_agent(AGENT_KEY).unwrap().org_id(), "division");
+    }
+
+    #[test]
+    // Test that an organization is only created under an existing organization the signer
+    // administers
+    fn test_create_org_with_parent_invalid() {
+        let mut context = context_with_hierarchy();
+
+        assert_invalid(
+            apply(
+                &mut context,
+                &OrganizationFixture::new("other_org")
+                    .with_parent("unknown_org")
+                    .payload(),
+                ADMIN_KEY,
+            ),
+            "Parent organization does not exist: unknown_org",
+        );
+        assert_invalid(
+            apply(
+                &mut context,
+                &OrganizationFixture::new("other_org")
+                    .with_parent(ORG_ID)
+                    .payload(),
+                "division_admin_key",
+            ),
+            "Signer is not associated with the organization: division_admin_key",
+        );
+        context.assert_no_entry(&compute_org_address("other_org"));
+    }
+
+    #[test]
+    // Test that admin rights cascade down the hierarchy, but not up
+    fn test_admin_of_ancestor_org() {
+        let mut context = context_with_hierarchy();
+
+        apply(
+            &mut context,
+            &update_org_payload("site", "Site 1"),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        context.assert_accesses_declared();
+        assert_eq!(context.get_organization("site").unwrap().name(), "Site 1");
+
+        apply(
+            &mut context,
+            &update_org_payload("site", "Site 2"),
+            "division_admin_key",
+        )
+        .unwrap();
+        assert_eq!(context.get_organization("site").unwrap().name(), "Site 2");
+
+        assert_invalid(
+            apply(
+                &mut context,
+                &update_org_payload(ORG_ID, "Corporate"),
+                "division_admin_key",
+            ),
+            "Signer is not associated with the organization: division_admin_key",
+        );
+    }
+
+    #[test]
+    // Test that an organization can be moved under another organization administered by the
+    // signer, but not under itself or one of its descendants
+    fn test_update_org_parent() {
+        let mut context = context_with_hierarchy();
+        OrganizationFixture::new("other_division")
+            .with_parent(ORG_ID)
+            .add_to(&context);
+
+        let action = UpdateOrganizationActionBuilder::new()
+            .with_org_id("site".to_string())
+            .with_parent_org_id("other_division".to_string())
+            .build()
+            .unwrap();
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::UpdateOrganization)
+            .with_update_organization(action)
+            .build()
+            .unwrap();
+        assert_invalid(
+            apply(&mut context, &payload, "division_admin_key"),
+            "Signer is not associated with the organization: division_admin_key",
+        );
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+        let site = context.get_organization("site").unwrap();
+        assert_eq!(site.parent_org_id(), "other_division");
+        assert_eq!(site.name(), "site");
+
+        let action = UpdateOrganizationActionBuilder::new()
+            .with_org_id(ORG_ID.to_string())
+            .with_parent_org_id("division".to_string())
+            .build()
+            .unwrap();
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::UpdateOrganization)
+            .with_update_organization(action)
+            .build()
+            .unwrap();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Organization test_org cannot be part of division, which is part of it",
+        );
+        assert_eq!(
+            context.get_organization(ORG_ID).unwrap().parent_org_id(),
+            ""
+        );
+    }
+
+    #[test]
+    // Test that an organization can be detached from its parent by an admin of the parent, but
+    // not by its own admins, and cannot be detached when it has no parent
+    fn test_update_org_detach_from_parent() {
+        let mut context = context_with_hierarchy();
+        AgentFixture::new("site_admin_key", "site")
+            .with_roles(&["admin"])
+            .add_to(&context);
+
+        let payload = |detach_from_parent: bool, parent_org_id: &str| {
+            let action = UpdateOrganizationActionBuilder::new()
+                .with_org_id("site".to_string())
+                .with_parent_org_id(parent_org_id.to_string())
+                .with_detach_from_parent(detach_from_parent)
+                .build()
+                .unwrap();
+            PikePayloadBuilder::new()
+                .with_action(Action::UpdateOrganization)
+                .with_update_organization(action)
+                .build()
+                .unwrap()
+        };
+
+        assert_invalid(
+            apply(&mut context, &payload(true, ORG_ID), ADMIN_KEY),
+            "Cannot both detach an organization and move it under another",
+        );
+        assert_invalid(
+            apply(&mut context, &payload(true, ""), "site_admin_key"),
+            "Signer is not associated with the organization: site_admin_key",
+        );
+
+        apply(&mut context, &payload(true, ""), "division_admin_key").unwrap();
+        context.assert_accesses_declared();
+        let site = context.get_organization("site").unwrap();
+        assert_eq!(site.parent_org_id(), "");
+        assert_eq!(site.name(), "site");
+
+        // the admins of the former parent no longer administer the organization
+        assert_invalid(
+            apply(
+                &mut context,
+                &update_org_payload("site", "renamed"),
+                "division_admin_key",
+            ),
+            "Signer is not associated with the organization: division_admin_key",
+        );
+        assert_invalid(
+            apply(&mut context, &payload(true, ""), "site_admin_key"),
+            "Organization site is not part of another organization",
+        );
+    }
+
+    /// Returns an alternate ID without checking its format, as a client may submit it
+    fn unchecked_alternate_id(id_type: &str, id: &str) -> AlternateId {
+        let mut alternate_id = grid_sdk::protos::pike_state::AlternateId::new();
+        alternate_id.set_id_type(id_type.to_string());
+        alternate_id.set_id(id.to_string());
+        alternate_id.into_native().unwrap()
+    }
+
+    fn update_alternate_ids_payload(org_id: &str, alternate_ids: Vec<AlternateId>) -> PikePayload {
+        let action = UpdateOrganizationActionBuilder::new()
+            .with_org_id(org_id.to_string())
+            .with_alternate_ids(alternate_ids)
+            .build()
+            .unwrap();
+        PikePayloadBuilder::new()
+            .with_action(Action::UpdateOrganization)
+            .with_update_organization(action)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    // Test that creating an organization reserves its alternate IDs
+    fn test_create_org_with_alternate_ids() {
+        let mut context = MockTransactionContext::default();
+        let payload = OrganizationFixture::new(ORG_ID)
+            .with_alternate_id("gln:0614141000012")
+            .with_alternate_id("duns:150483782")
+            .payload();
+
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+
+        let org = context.get_organization(ORG_ID).unwrap();
+        assert_eq!(org.alternate_ids().len(), 2);
+        assert_eq!(
+            context.get_alternate_id_holder("gln", "0614141000012"),
+            Some(ORG_ID.to_string())
+        );
+        assert_eq!(
+            context.get_alternate_id_holder("duns", "150483782"),
+            Some(ORG_ID.to_string())
+        );
+    }
+
+    #[test]
+    // Test that alternate IDs must be well formed and are held by a single organization
+    fn test_create_org_with_alternate_ids_invalid() {
+        let mut context = MockTransactionContext::default();
+        OrganizationFixture::new("other_org")
+            .with_alternate_id("gln:0614141000012")
+            .add_to(&context);
+
+        let payload = OrganizationFixture::new(ORG_ID)
+            .with_alternate_id("gln:0614141000012")
+            .payload();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Alternate ID gln:0614141000012 is already held by organization other_org",
+        );
+
+        let action = CreateOrganizationActionBuilder::new()
+            .with_org_id(ORG_ID.to_string())
+            .with_name("Test Org".to_string())
+            .with_address("".to_string())
+            .with_alternate_ids(vec![unchecked_alternate_id("gln", "0614141000013")])
+            .build()
+            .unwrap();
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::CreateOrganization)
+            .with_create_organization(action)
+            .build()
+            .unwrap();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Invalid GLN check digit: 0614141000013",
+        );
+
+        let payload = OrganizationFixture::new(ORG_ID)
+            .with_alternate_id("tax:US-12-3456789")
+            .with_alternate_id("tax:US-12-3456789")
+            .payload();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Alternate ID is listed more than once: tax:US-12-3456789",
+        );
+        assert!(context.get_organization(ORG_ID).is_none());
+    }
+
+    #[test]
+    // Test that updating the alternate IDs of an organization releases the replaced ones
+    fn test_update_org_alternate_ids() {
+        let mut context = MockTransactionContext::default();
+        OrganizationFixture::new(ORG_ID)
+            .with_alternate_id("gln:0614141000012")
+            .add_to(&context);
+        AgentFixture::new(ADMIN_KEY, ORG_ID)
+            .with_roles(&["admin"])
+            .add_to(&context);
+        OrganizationFixture::new("other_org")
+            .with_alternate_id("gln:4012345000009")
+            .add_to(&context);
+
+        let payload =
+            update_alternate_ids_payload(ORG_ID, vec!["gln:4012345000009".parse().unwrap()]);
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Alternate ID gln:4012345000009 is already held by organization other_org",
+        );
+
+        let payload = update_alternate_ids_payload(
+            ORG_ID,
+            vec![
+                "gln:0614141000005".parse().unwrap(),
+                "duns:150483782".parse().unwrap(),
+            ],
+        );
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+
+        let org = context.get_organization(ORG_ID).unwrap();
+        assert_eq!(org.alternate_ids().len(), 2);
+        assert_eq!(org.alternate_ids()[0].to_string(), "gln:0614141000005");
+        assert_eq!(
+            context.get_alternate_id_holder("gln", "0614141000012"),
+            None
+        );
+        assert_eq!(
+            context.get_alternate_id_holder("gln", "0614141000005"),
+            Some(ORG_ID.to_string())
+        );
+        assert_eq!(
+            context.get_alternate_id_holder("gln", "4012345000009"),
+            Some("other_org".to_string())
+        );
+    }
+
+    #[test]
+    // Test that an admin can create an agent of their organization
+    fn test_create_agent_valid() {
+        let mut context = context_with_admin();
+        let payload = AgentFixture::new(AGENT_KEY, ORG_ID)
+            .with_roles(&["can_create_schema"])
+            .with_metadata("email", "agent@test.org")
+            .payload();
+
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+
+        let agent = context.get_agent(AGENT_KEY).unwrap();
+        assert_eq!(
+            agent,
+            AgentFixture::new(AGENT_KEY, ORG_ID)
+                .with_roles(&["can_create_schema"])
+                .with_metadata("email", "agent@test.org")
+                .build()
+        );
+    }
+
+    #[test]
+    // Test that an agent requires a public key and an organization
+    fn test_create_agent_missing_fields() {
+        let mut context = context_with_admin();
+
+        let payload = AgentFixture::new("", ORG_ID).payload();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Public key required",
+        );
+
+        let payload = AgentFixture::new(AGENT_KEY, "").payload();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Organization ID required",
+        );
+    }
+
+    #[test]
+    // Test that an agent cannot be created twice
+    fn test_create_agent_already_exists() {
+        let mut context = context_with_admin();
+        AgentFixture::new(AGENT_KEY, ORG_ID).add_to(&context);
+        let payload = AgentFixture::new(AGENT_KEY, ORG_ID).payload();
+
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Agent already exists: agent_public_key",
+        );
+    }
+
+    #[test]
+    // Test that only active admins of the organization can create its agents
+    fn test_create_agent_signer_not_admin() {
+        let payload = AgentFixture::new(AGENT_KEY, ORG_ID).payload();
+
+        let mut context = context_with_admin();
+        assert_invalid(
+            apply(&mut context, &payload, "unknown_public_key"),
+            "Signer is not an agent: unknown_public_key",
+        );
+
+        AgentFixture::new("member_key", ORG_ID).add_to(&context);
+        assert_invalid(
+            apply(&mut context, &payload, "member_key"),
+            "Signer is not an admin: member_key",
+        );
+
+        AgentFixture::new("other_admin_key", "other_org")
+            .with_roles(&["admin"])
+            .add_to(&context);
+        assert_invalid(
+            apply(&mut context, &payload, "other_admin_key"),
+            "Signer is not associated with the organization: other_admin_key",
+        );
+
+        AgentFixture::new(ADMIN_KEY, ORG_ID)
+            .with_roles(&["admin"])
+            .inactive()
+            .add_to(&context);
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Admin is not currently an active agent: admin_public_key",
+        );
+    }
+
+    #[test]
+    // Test that an update replaces the roles and activity of an agent, and keeps the metadata
+    // when none is given
+    fn test_update_agent_valid() {
+        let mut context = context_with_admin();
+        AgentFixture::new(AGENT_KEY, ORG_ID)
+            .with_metadata("email", "agent@test.org")
+            .add_to(&context);
+
+        let payload = update_agent_payload(false, &["can_update_schema"]);
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+
+        let agent = context.get_agent(AGENT_KEY).unwrap();
+        assert!(!*agent.active());
+        assert_eq!(agent.roles(), ["can_update_schema".to_string()]);
+        assert_eq!(
+            agent.metadata(),
+            [KeyValueEntryBuilder::new()
+                .with_key("email".to_string())
+                .with_value("agent@test.org".to_string())
+                .build()
+                .unwrap()]
+        );
+    }
+
+    #[test]
+    // Test that an update without roles keeps the roles of the agent
+    fn test_update_agent_keeps_roles() {
+        let mut context = context_with_admin();
+        AgentFixture::new(AGENT_KEY, ORG_ID)
+            .with_roles(&["can_create_schema"])
+            .add_to(&context);
+
+        let payload = update_agent_payload(true, &[]);
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+
+        let agent = context.get_agent(AGENT_KEY).unwrap();
+        assert_eq!(agent.roles(), ["can_create_schema".to_string()]);
+    }
+
+    #[test]
+    // Test that an agent must exist to be updated
+    fn test_update_agent_does_not_exist() {
+        let mut context = context_with_admin();
+        let payload = update_agent_payload(true, &[]);
+
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Agent does not exists: agent_public_key",
+        );
+    }
+
+    #[test]
+    // Test that admins can neither remove their admin role nor deactivate themselves
+    fn test_update_agent_admin_updates_self() {
+        let mut context = MockTransactionContext::default();
+        AgentFixture::new(AGENT_KEY, ORG_ID)
+            .with_roles(&["admin"])
+            .add_to(&context);
+
+        let payload = update_agent_payload(true, &["can_create_schema"]);
+        assert_invalid(
+            apply(&mut context, &payload, AGENT_KEY),
+            "An admin cannot remove themselves as admin",
+        );
+
+        let payload = update_agent_payload(false, &["admin"]);
+        assert_invalid(
+            apply(&mut context, &payload, AGENT_KEY),
+            "Admin may not deactivate themselves: agent_public_key",
+        );
+    }
+
+    #[test]
+    // Test that an update replaces the fields given, and keeps the others
+    fn test_update_org_valid() {
+        let mut context = context_with_admin();
+        let payload = update_org_payload(ORG_ID, "New Name");
+
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+
+        let org = context.get_organization(ORG_ID).unwrap();
+        assert_eq!(org.name(), "New Name");
+        assert_eq!(org.address(), "1 Main Street");
+    }
+
+    #[test]
+    // Test that an organization must exist, and be administered by the signer, to be updated
+    fn test_update_org_invalid() {
+        let mut context = context_with_admin();
+
+        let payload = update_org_payload("", "New Name");
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Unique organization ID required",
+        );
+
+        AgentFixture::new("other_admin_key", "other_org")
+            .with_roles(&["admin"])
+            .add_to(&context);
+        let payload = update_org_payload("other_org", "New Name");
+        assert_invalid(
+            apply(&mut context, &payload, "other_admin_key"),
+            "Organization does not exist exists: other_org",
+        );
+
+        let payload = update_org_payload(ORG_ID, "New Name");
+        assert_invalid(
+            apply(&mut context, &payload, "other_admin_key"),
+            "Signer is not associated with the organization: other_admin_key",
+        );
+    }
+
+    #[test]
+    // Test that payloads that cannot be parsed are rejected
+    fn test_apply_invalid_payload() {
+        let mut context = context_with_admin();
+        let mut request = TpProcessRequest::new();
+        request
+            .mut_header()
+            .set_signer_public_key(ADMIN_KEY.to_string());
+        request
+            .mut_header()
+            .set_family_version(PIKE_FAMILY_VERSION.to_string());
+        // A payload whose action is unset
+        request.set_payload(vec![0x08, 0x00]);
+
+        assert_invalid(
+            PikeTransactionHandler::new().apply(&request, &mut context),
+            "Cannot build pike payload",
+        );
+        assert!(context.writes().is_empty());
+    }
+
+    #[test]
+    // Test that a reserved metadata key cannot be set by a payload
+    fn test_create_agent_reserved_metadata() {
+        let mut context = context_with_admin();
+        let payload = AgentFixture::new(AGENT_KEY, ORG_ID)
+            .with_metadata(ORG_HISTORY_KEY, "forged_org")
+            .payload();
+
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Metadata key is reserved: org_history",
+        );
+    }
+
+    #[test]
+    // Test that an admin of another organization cannot move an agent with an update
+    fn test_update_agent_org_change() {
+        let mut context = context_for_transfer();
+        let action = UpdateAgentActionBuilder::new()
+            .with_org_id("other_org".to_string())
+            .with_public_key(AGENT_KEY.to_string())
+            .with_active(true)
+            .with_roles(vec!["admin".to_string()])
+            .build()
+            .unwrap();
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::UpdateAgent)
+            .with_update_agent(action)
+            .build()
+            .unwrap();
+
+        assert_invalid(
+            apply(&mut context, &payload, "other_admin_key"),
+            "can only change organization through a transfer",
+        );
+        assert_eq!(context.get_agent(AGENT_KEY).unwrap().org_id(), ORG_ID);
+    }
+
+    #[test]
+    // Test that a transfer takes effect once admins of both organizations submitted it, and
+    // that the agent keeps a history of its organizations
+    fn test_transfer_agent_valid() {
+        let mut context = context_for_transfer();
+        let payload = transfer_agent_payload("other_org");
+
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+        let agent = context.get_agent(AGENT_KEY).unwrap();
+        assert_eq!(agent.org_id(), ORG_ID);
+        assert_eq!(
+            metadata_value(agent.metadata(), TRANSFER_ORG_ID_KEY),
+            Some("other_org")
+        );
+        assert_eq!(
+            metadata_value(agent.metadata(), TRANSFER_APPROVED_BY_KEY),
+            Some(ORG_ID)
+        );
+
+        apply(&mut context, &payload, "other_admin_key").unwrap();
+        context.assert_accesses_declared();
+        let agent = context.get_agent(AGENT_KEY).unwrap();
+        assert_eq!(agent.org_id(), "other_org");
+        assert!(agent.roles().is_empty());
+        assert_eq!(
+            metadata_value(agent.metadata(), ORG_HISTORY_KEY),
+            Some(ORG_ID)
+        );
+        assert_eq!(
+            metadata_value(agent.metadata(), "email"),
+            Some("agent@test.org")
+        );
+        assert_eq!(metadata_value(agent.metadata(), TRANSFER_ORG_ID_KEY), None);
+
+        // Moving back appends to the history
+        let payload = transfer_agent_payload(ORG_ID);
+        apply(&mut context, &payload, "other_admin_key").unwrap();
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        let agent = context.get_agent(AGENT_KEY).unwrap();
+        assert_eq!(agent.org_id(), ORG_ID);
+        assert_eq!(
+            metadata_value(agent.metadata(), ORG_HISTORY_KEY),
+            Some("test_org,other_org")
+        );
+    }
+
+    #[test]
+    // Test that one organization cannot approve a transfer on behalf of both
+    fn test_transfer_agent_approved_twice() {
+        let mut context = context_for_transfer();
+        let payload = transfer_agent_payload("other_org");
+
+        apply(&mut context, &payload, ADMIN_KEY).unwrap();
+        assert_invalid(
+            apply(&mut context, &payload, ADMIN_KEY),
+            "Transfer of agent agent_public_key to organization other_org is already approved \
+             by test_org",
+        );
+        assert_eq!(context.get_agent(AGENT_KEY).unwrap().org_id(), ORG_ID);
+    }
+
+    #[test]
+    // Test that transfers are rejected unless submitted by an admin of either organization,
+    // to an existing organization other than the agent's
+    fn test_transfer_agent_invalid() {
+        let mut context = context_for_transfer();
+        AgentFixture::new("third_admin_key", "third_org")
+            .with_roles(&["admin"])
+            .add_to(&context);
+
+        assert_invalid(
+            apply(
+                &mut context,
+                &transfer_agent_payload("other_org"),
+                "third_admin_key",
+            ),
+            "Signer is not an active admin of organization test_org or other_org",
+        );
+        assert_invalid(
+            apply(&mut context, &transfer_agent_payload(ORG_ID), ADMIN_KEY),
+            "Agent agent_public_key already belongs to organization test_org",
+        );
+        assert_invalid(
+            apply(
+                &mut context,
+                &transfer_agent_payload("unknown_org"),
+                ADMIN_KEY,
+            ),
+            "Organization does not exist: unknown_org",
+        );
+    }
+
+    #[test]
+    // Test that an agent can rotate its own key, handing its organization, roles and metadata
+    // over to the new key, and that the former key is deactivated with a successor link
+    fn test_rotate_agent_key_valid() {
+        let mut context = context_for_transfer();
+
+        apply(
+            &mut context,
+            &rotate_agent_key_payload(AGENT_KEY, "new_agent_key"),
+            AGENT_KEY,
+        )
+        .unwrap();
+        context.assert_accesses_declared();
+
+        let agent = context.get_agent("new_agent_key").unwrap();
+        assert_eq!(agent.org_id(), ORG_ID);
+        assert!(agent.active());
+        assert_eq!(agent.roles(), ["can_create_schema".to_string()]);
+        assert_eq!(
+            metadata_value(agent.metadata(), "email"),
+            Some("agent@test.org")
+        );
+        assert_eq!(agent.successor(), "");
+
+        let rotated = context.get_agent(AGENT_KEY).unwrap();
+        assert!(!rotated.active());
+        assert_eq!(rotated.successor(), "new_agent_key");
+    }
+
+    #[test]
+    // Test that an admin can rotate the key of an agent of the organization that lost it
+    fn test_rotate_agent_key_by_admin() {
+        let mut context = context_for_transfer();
+
+        apply(
+            &mut context,
+            &rotate_agent_key_payload(AGENT_KEY, "new_agent_key"),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        context.assert_accesses_declared();
+
+        assert!(context.get_agent("new_agent_key").unwrap().active());
+        assert_eq!(
+            context.get_agent(AGENT_KEY).unwrap().successor(),
+            "new_agent_key"
+        );
+    }
+
+    #[test]
+    // Test that rotations are rejected unless signed by the agent or an admin of its
+    // organization, to a key that is not an agent yet, and only once per key by the agent
+    fn test_rotate_agent_key_invalid() {
+        let mut context = context_for_transfer();
+
+        assert_invalid(
+            apply(
+                &mut context,
+                &rotate_agent_key_payload(AGENT_KEY, "new_agent_key"),
+                "other_admin_key",
+            ),
+            "Signer is not associated with the organization: other_admin_key",
+        );
+        assert_invalid(
+            apply(
+                &mut context,
+                &rotate_agent_key_payload(AGENT_KEY, ADMIN_KEY),
+                AGENT_KEY,
+            ),
+            "Agent already exists: admin_public_key",
+        );
+        assert_invalid(
+            apply(
+                &mut context,
+                &rotate_agent_key_payload(AGENT_KEY, AGENT_KEY),
+                AGENT_KEY,
+            ),
+            "New public key must differ from the rotated key",
+        );
+        context.assert_no_entry(&compute_agent_address("new_agent_key"));
+
+        apply(
+            &mut context,
+            &rotate_agent_key_payload(AGENT_KEY, "new_agent_key"),
+            AGENT_KEY,
+        )
+        .unwrap();
+        assert_invalid(
+            apply(
+                &mut context,
+                &rotate_agent_key_payload(AGENT_KEY, "third_agent_key"),
+                AGENT_KEY,
+            ),
+            "Agent key was already rotated to new_agent_key",
+        );
+        assert_invalid(
+            apply(&mut context, &update_agent_payload(true, &[]), ADMIN_KEY),
+            "Agent key was rotated to new_agent_key and cannot be reactivated",
+        );
+        assert_invalid(
+            apply(
+                &mut context,
+                &transfer_agent_payload("other_org"),
+                ADMIN_KEY,
+            ),
+            "Agent key was rotated to new_agent_key",
+        );
+    }
+
+    #[test]
+    // Test that an admin can recover an agent whose key was rotated by whoever compromised it,
+    // deactivating the keys it was handed over to, and that these keys cannot override the
+    // rotation of their predecessor themselves
+    fn test_rotate_agent_key_admin_recovery() {
+        let mut context = context_for_transfer();
+
+        apply(
+            &mut context,
+            &rotate_agent_key_payload(AGENT_KEY, "hostile_key"),
+            AGENT_KEY,
+        )
+        .unwrap();
+        apply(
+            &mut context,
+            &rotate_agent_key_payload("hostile_key", "second_hostile_key"),
+            "hostile_key",
+        )
+        .unwrap();
+
+        apply(
+            &mut context,
+            &rotate_agent_key_payload(AGENT_KEY, "recovered_key"),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        context.assert_accesses_declared();
+
+        let recovered = context.get_agent("recovered_key").unwrap();
+        assert!(recovered.active());
+        assert_eq!(recovered.org_id(), ORG_ID);
+        assert_eq!(recovered.roles(), ["can_create_schema".to_string()]);
+        for key in &[AGENT_KEY, "hostile_key", "second_hostile_key"] {
+            let agent = context.get_agent(key).unwrap();
+            assert!(!agent.active());
+            assert_eq!(agent.successor(), "recovered_key");
+        }
+
+        // A hostile successor holding the admin role cannot take the identity back
+        apply(
+            &mut context,
+            &rotate_agent_key_payload(ADMIN_KEY, "hostile_admin_key"),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        assert_invalid(
+            apply(
+                &mut context,
+                &rotate_agent_key_payload(ADMIN_KEY, "other_hostile_key"),
+                "hostile_admin_key",
+            ),
+            "Agent key was rotated to the signer and cannot be rotated by it",
+        );
+        context.assert_no_entry(&compute_agent_address("other_hostile_key"));
+    }
+
+    #[test]
+    // Test that an admin can define roles inheriting from other roles of the organization
+    fn test_create_role_valid() {
+        let mut context = context_with_admin();
+
+        apply(
+            &mut context,
+            &create_role_payload("viewer", &["record::read"], &[]),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        apply(
+            &mut context,
+            &create_role_payload("operator", &["tnt::reporter"], &["viewer"]),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        context.assert_accesses_declared();
+
+        let role = context.get_role(ORG_ID, "operator").unwrap();
+        assert_eq!(role.permissions(), ["tnt::reporter".to_string()]);
+        assert_eq!(role.inherit_from(), ["viewer".to_string()]);
+    }
+
+    #[test]
+    // Test that roles are only defined once, by admins, and inherit from existing roles
+    fn test_create_role_invalid() {
+        let mut context = context_with_admin();
+        AgentFixture::new(AGENT_KEY, ORG_ID)
+            .with_roles(&["operator"])
+            .add_to(&context);
+
+        assert_invalid(
+            apply(
+                &mut context,
+                &create_role_payload("operator", &[], &[]),
+                AGENT_KEY,
+            ),
+            "Signer is not an admin",
+        );
+        assert_invalid(
+            apply(
+                &mut context,
+                &create_role_payload("operator", &[], &["viewer"]),
+                ADMIN_KEY,
+            ),
+            "Inherited role does not exist: viewer",
+        );
+        assert_invalid(
+            apply(
+                &mut context,
+                &create_role_payload("operator", &[], &["operator"]),
+                ADMIN_KEY,
+            ),
+            "Inherited role does not exist: operator",
+        );
+
+        apply(
+            &mut context,
+            &create_role_payload("operator", &[], &[]),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        assert_invalid(
+            apply(
+                &mut context,
+                &create_role_payload("operator", &[], &[]),
+                ADMIN_KEY,
+            ),
+            "Role already exists: operator",
+        );
+    }
+
+    #[test]
+    // Test that updating a role replaces its permissions, unless it would inherit from itself
+    fn test_update_role() {
+        let mut context = context_with_admin();
+        apply(
+            &mut context,
+            &create_role_payload("viewer", &["record::read"], &[]),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        apply(
+            &mut context,
+            &create_role_payload("operator", &["tnt::reporter"], &["viewer"]),
+            ADMIN_KEY,
+        )
+        .unwrap();
+
+        apply(
+            &mut context,
+            &update_role_payload("viewer", &["record::*"], &[]),
+            ADMIN_KEY,
+        )
+        .unwrap();
+        context.assert_accesses_declared();
+        assert_eq!(
+            context.get_role(ORG_ID, "viewer").unwrap().permissions(),
+            ["record::*".to_string()]
+        );
+
+        assert_invalid(
+            apply(
+                &mut context,
+                &update_role_payload("viewer", &[], &["operator"]),
+                ADMIN_KEY,
+            ),
+            "Role viewer cannot inherit from itself",
+        );
+        assert_invalid(
+            apply(
+                &mut context,
+                &update_role_payload("auditor", &[], &[]),
+                ADMIN_KEY,
+            ),
+            "Role does not exist: auditor",
+        );
+    }
+
+    #[test]
+    // Test that deleting a role removes it from state
+    fn test_delete_role() {
+        let mut context = context_with_admin();
+        apply(
+            &mut context,
+            &create_role_payload("viewer", &["record::read"], &[]),
+            ADMIN_KEY,
+        )
+        .unwrap();
+
+        apply(&mut context, &delete_role_payload("viewer"), ADMIN_KEY).unwrap();
+        context.assert_accesses_declared();
+        assert!(context.get_role(ORG_ID, "viewer").is_none());
+        context.assert_no_entry(&compute_role_address(ORG_ID, "viewer"));
+
+        assert_invalid(
+            apply(&mut context, &delete_role_payload("viewer"), ADMIN_KEY),
+            "Role does not exist: viewer",
+        );
+    }
+}
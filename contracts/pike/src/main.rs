@@ -14,9 +14,7 @@
 
 #[macro_use]
 extern crate cfg_if;
-extern crate crypto;
 extern crate grid_sdk;
-extern crate protobuf;
 
 cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
@@ -35,7 +33,6 @@ cfg_if! {
     }
 }
 
-pub mod addresser;
 pub mod handler;
 
 //use sawtooth_sdk::processor::TransactionProcessor;
@@ -27,19 +27,22 @@ cfg_if! {
     }
 }
 
-use grid_sdk::permissions::PermissionChecker;
+use grid_sdk::permissions::{Authorization, DenyReason, PermissionChecker};
+use grid_sdk::protocol::addressing::schema::{GRID_NAMESPACE, GRID_SCHEMA_FAMILY_NAME};
 use grid_sdk::protocol::schema::payload::{
-    Action, SchemaCreateAction, SchemaPayload, SchemaUpdateAction,
+    Action, PropertyGroupCreateAction, SchemaCreateAction, SchemaDeprecateAction, SchemaPayload,
+    SchemaTransferAction, SchemaUpdateAction,
 };
-use grid_sdk::protocol::schema::state::SchemaBuilder;
+use grid_sdk::protocol::schema::state::{
+    PropertyDefinition, PropertyGroupBuilder, Schema, SchemaBuilder,
+};
+use grid_sdk::protocol::schema::validate::{resolve_properties, validation_errors_to_message};
+use grid_sdk::protocol::versioning::{payload_version, schema_payload_migrator, Migrator};
 use grid_sdk::protos::FromBytes;
 
 use crate::payload::validate_payload;
 use crate::state::GridSchemaState;
 
-pub const GRID_NAMESPACE: &str = "621dee";
-pub const PIKE_NAMESPACE: &str = "cad11d";
-
 #[cfg(target_arch = "wasm32")]
 // Sabre apply must return a bool
 fn apply(
@@ -63,15 +66,18 @@ pub struct GridSchemaTransactionHandler {
     family_name: String,
     family_versions: Vec<String>,
     namespaces: Vec<String>,
+    payload_migrator: Migrator<String>,
 }
 
 impl GridSchemaTransactionHandler {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
+        let payload_migrator = schema_payload_migrator();
         GridSchemaTransactionHandler {
-            family_name: "grid_schema".to_string(),
-            family_versions: vec!["1.0".to_string()],
+            family_name: GRID_SCHEMA_FAMILY_NAME.to_string(),
+            family_versions: payload_migrator.supported_versions(),
             namespaces: vec![GRID_NAMESPACE.to_string()],
+            payload_migrator,
         }
     }
 }
@@ -94,7 +100,14 @@ impl TransactionHandler for GridSchemaTransactionHandler {
         request: &TpProcessRequest,
         context: &mut dyn TransactionContext,
     ) -> Result<(), ApplyError> {
-        let payload = SchemaPayload::from_bytes(request.get_payload()).map_err(|err| {
+        let payload_bytes = self
+            .payload_migrator
+            .migrate(
+                &payload_version(request.get_header(), self.payload_migrator.current()),
+                request.get_payload(),
+            )
+            .map_err(|err| ApplyError::InvalidTransaction(format!("{}", err)))?;
+        let payload = SchemaPayload::from_bytes(&payload_bytes).map_err(|err| {
             ApplyError::InvalidTransaction(format!("Cannot build schema payload: {}", err))
         })?;
 
@@ -119,6 +132,18 @@ impl TransactionHandler for GridSchemaTransactionHandler {
             Action::SchemaUpdate => {
                 schema_update(payload.schema_update(), signer, &state, &perm_checker)
             }
+            Action::SchemaTransfer => {
+                schema_transfer(payload.schema_transfer(), signer, &state, &perm_checker)
+            }
+            Action::SchemaDeprecate => {
+                schema_deprecate(payload.schema_deprecate(), signer, &state, &perm_checker)
+            }
+            Action::PropertyGroupCreate => property_group_create(
+                payload.property_group_create(),
+                signer,
+                &state,
+                &perm_checker,
+            ),
         }
     }
 }
@@ -140,8 +165,6 @@ fn schema_create(
         )));
     }
 
-    check_permission(perm_checker, signer, "can_create_schema")?;
-
     let agent = match state.get_agent(signer)? {
         Some(agent) => agent,
         None => {
@@ -152,22 +175,75 @@ fn schema_create(
         }
     };
 
-    if !agent.active() {
-        return Err(ApplyError::InvalidTransaction(format!(
-            "The signer is not an active Agent: {}",
-            signer
-        )));
-    }
+    check_permission(perm_checker, signer, "can_create_schema", agent.org_id())?;
 
-    let schema = SchemaBuilder::new()
+    let mut builder = SchemaBuilder::new()
         .with_name(schema_name.into())
         .with_description(description.into())
         .with_owner(agent.org_id().into())
-        .with_properties(properties.to_vec())
+        .with_version(1)
+        .with_property_groups(payload.property_groups().to_vec())
+        .with_properties(properties.to_vec());
+
+    // The sources the schema is composed from, in the order their properties are inherited
+    let mut sources = vec![];
+
+    let parent_name = payload.parent();
+    if !parent_name.is_empty() {
+        let parent = match state.get_schema(parent_name)? {
+            Some(parent) => parent,
+            None => {
+                return Err(ApplyError::InvalidTransaction(format!(
+                    "Parent schema {} does not exist",
+                    parent_name
+                )));
+            }
+        };
+
+        if *parent.deprecated() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Parent schema {} is deprecated",
+                parent_name
+            )));
+        }
+
+        builder = builder
+            .with_parent(parent_name.into())
+            .with_parent_version(*parent.version());
+        sources.push((
+            format!("schema {}", parent_name),
+            parent.effective_properties(),
+        ));
+    }
+
+    for property_group_name in payload.property_groups() {
+        let property_group = match state.get_property_group(property_group_name)? {
+            Some(property_group) => property_group,
+            None => {
+                return Err(ApplyError::InvalidTransaction(format!(
+                    "Property group {} does not exist",
+                    property_group_name
+                )));
+            }
+        };
+        sources.push((
+            format!("property group {}", property_group_name),
+            property_group.properties().to_vec(),
+        ));
+    }
+
+    sources.push((format!("schema {}", schema_name), properties.to_vec()));
+    let inherited_properties = resolve_sources(&sources)?
+        .into_iter()
+        .filter(|resolved| !properties.iter().any(|p| p.name() == resolved.name()))
+        .collect();
+
+    let schema = builder
+        .with_inherited_properties(inherited_properties)
         .build()
         .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build schema: {}", err)))?;
 
-    state.set_schema(schema_name, schema)
+    set_schema_and_version(state, schema)
 }
 
 fn schema_update(
@@ -189,223 +265,363 @@ fn schema_update(
         }
     };
 
-    check_permission(perm_checker, signer, "can_update_schema")?;
+    check_permission(perm_checker, signer, "can_update_schema", schema.owner())?;
 
-    let agent = match state.get_agent(signer)? {
-        Some(agent) => agent,
+    // New properties may not take the name of an inherited property either
+    let mut properties = schema.effective_properties();
+    properties.sort_by_key(|p| p.name().to_string());
+
+    for property in new_properties.iter() {
+        if properties
+            .binary_search_by_key(&property.name().to_string(), |p| p.name().to_string())
+            .is_ok()
+        {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Schema already has PropertyDefination with name {}",
+                property.name()
+            )));
+        }
+    }
+    let mut properties = schema.properties().to_vec();
+    properties.append(&mut new_properties);
+
+    let version = schema.version() + 1;
+    let schema = schema
+        .into_builder()
+        .with_version(version)
+        .with_properties(properties)
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build schema: {}", err)))?;
+
+    set_schema_and_version(state, schema)
+}
+
+fn schema_transfer(
+    payload: &SchemaTransferAction,
+    signer: &str,
+    state: &GridSchemaState,
+    perm_checker: &PermissionChecker,
+) -> Result<(), ApplyError> {
+    let schema_name = payload.schema_name();
+    let new_owner = payload.new_owner();
+
+    let schema = match state.get_schema(schema_name)? {
+        Some(schema) => schema,
         None => {
             return Err(ApplyError::InvalidTransaction(format!(
-                "The signer is not an Agent: {}",
-                signer
+                "Schema with name {} does not exist",
+                schema_name
             )));
         }
     };
 
-    if !agent.active() {
+    check_permission(perm_checker, signer, "can_transfer_schema", schema.owner())?;
+
+    if schema.owner() == new_owner {
         return Err(ApplyError::InvalidTransaction(format!(
-            "The signer is not an active Agent: {}",
-            signer
+            "Schema {} is already owned by {}",
+            schema_name, new_owner
         )));
     }
 
-    if agent.org_id() != schema.owner() {
+    if state.get_organization(new_owner)?.is_none() {
         return Err(ApplyError::InvalidTransaction(format!(
-            "The signer does not belong to the correct organization: {} != {}",
-            agent.org_id(),
-            schema.owner()
+            "Organization {} does not exist",
+            new_owner
         )));
     }
 
-    let mut properties = schema.properties().to_vec();
-    properties.sort_by_key(|p| p.name().to_string());
+    let version = schema.version() + 1;
+    let schema = schema
+        .into_builder()
+        .with_owner(new_owner.into())
+        .with_version(version)
+        .build()
+        .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build schema: {}", err)))?;
 
-    for property in new_properties.iter() {
-        if properties
-            .binary_search_by_key(&property.name().to_string(), |p| p.name().to_string())
-            .is_ok()
-        {
+    set_schema_and_version(state, schema)
+}
+
+/// Deprecates a schema, or the given properties of the schema. Deprecated definitions are kept,
+/// so existing records are still validated against them, but are no longer required of new
+/// records. Only properties declared by the schema itself can be deprecated; inherited ones
+/// belong to the parent schema or property group that declares them.
+fn schema_deprecate(
+    payload: &SchemaDeprecateAction,
+    signer: &str,
+    state: &GridSchemaState,
+    perm_checker: &PermissionChecker,
+) -> Result<(), ApplyError> {
+    let schema_name = payload.schema_name();
+
+    let schema = match state.get_schema(schema_name)? {
+        Some(schema) => schema,
+        None => {
             return Err(ApplyError::InvalidTransaction(format!(
-                "Schema already has PropertyDefination with name {}",
-                property.name()
+                "Schema with name {} does not exist",
+                schema_name
             )));
         }
-    }
-    properties.append(&mut new_properties);
+    };
 
-    let schema = SchemaBuilder::new()
-        .with_name(schema.name().into())
-        .with_description(schema.description().into())
-        .with_owner(schema.owner().into())
-        .with_properties(properties)
+    check_permission(perm_checker, signer, "can_update_schema", schema.owner())?;
+
+    let version = schema.version() + 1;
+    let builder = if payload.properties().is_empty() {
+        if *schema.deprecated() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Schema {} is already deprecated",
+                schema_name
+            )));
+        }
+        schema.into_builder().with_deprecated(true)
+    } else {
+        for name in payload.properties() {
+            if !schema.properties().iter().any(|p| p.name() == name) {
+                if schema
+                    .effective_properties()
+                    .iter()
+                    .any(|p| p.name() == name)
+                {
+                    return Err(ApplyError::InvalidTransaction(format!(
+                        "Property {} is inherited by schema {} and cannot be deprecated on it",
+                        name, schema_name
+                    )));
+                }
+                return Err(ApplyError::InvalidTransaction(format!(
+                    "Schema {} has no PropertyDefinition with name {}",
+                    schema_name, name
+                )));
+            }
+        }
+
+        let properties = schema
+            .properties()
+            .iter()
+            .cloned()
+            .map(|property| {
+                if payload
+                    .properties()
+                    .iter()
+                    .any(|name| name == property.name())
+                {
+                    property
+                        .into_builder()
+                        .with_deprecated(true)
+                        .with_required(false)
+                        .build()
+                } else {
+                    Ok(property)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                ApplyError::InvalidTransaction(format!("Cannot build property: {}", err))
+            })?;
+        schema.into_builder().with_properties(properties)
+    };
+
+    let schema = builder
+        .with_version(version)
         .build()
         .map_err(|err| ApplyError::InvalidTransaction(format!("Cannot build schema: {}", err)))?;
 
-    state.set_schema(schema_name, schema)
+    set_schema_and_version(state, schema)
+}
+
+/// Creates a property group owned by the organization of the signer. Property groups are never
+/// changed once created, so the schemas including them keep the same properties.
+fn property_group_create(
+    payload: &PropertyGroupCreateAction,
+    signer: &str,
+    state: &GridSchemaState,
+    perm_checker: &PermissionChecker,
+) -> Result<(), ApplyError> {
+    let name = payload.name();
+
+    if state.get_property_group(name)?.is_some() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Property group with name {} already exists",
+            name
+        )));
+    }
+
+    let agent = match state.get_agent(signer)? {
+        Some(agent) => agent,
+        None => {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "The signer is not an Agent: {}",
+                signer
+            )));
+        }
+    };
+
+    check_permission(perm_checker, signer, "can_create_schema", agent.org_id())?;
+
+    resolve_sources(&[(
+        format!("property group {}", name),
+        payload.properties().to_vec(),
+    )])?;
+
+    let property_group = PropertyGroupBuilder::new()
+        .with_name(name.into())
+        .with_description(payload.description().into())
+        .with_owner(agent.org_id().into())
+        .with_properties(payload.properties().to_vec())
+        .build()
+        .map_err(|err| {
+            ApplyError::InvalidTransaction(format!("Cannot build property group: {}", err))
+        })?;
+
+    state.set_property_group(name, property_group)
+}
+
+/// Resolves the properties of the named sources a schema is composed from, rejecting properties
+/// defined differently by two sources
+fn resolve_sources(
+    sources: &[(String, Vec<PropertyDefinition>)],
+) -> Result<Vec<PropertyDefinition>, ApplyError> {
+    let sources = sources
+        .iter()
+        .map(|(source, definitions)| (source.as_str(), definitions.as_slice()))
+        .collect::<Vec<_>>();
+
+    resolve_properties(&sources)
+        .map_err(|errors| ApplyError::InvalidTransaction(validation_errors_to_message(&errors)))
+}
+
+/// Sets the schema in state, along with an immutable entry for its version. Versions are never
+/// overwritten, so records pinned to a version keep validating against the same definitions.
+fn set_schema_and_version(state: &GridSchemaState, schema: Schema) -> Result<(), ApplyError> {
+    let schema_name = schema.name().to_string();
+    let version = *schema.version();
+
+    if state.get_schema_version(&schema_name, version)?.is_some() {
+        return Err(ApplyError::InvalidTransaction(format!(
+            "Version {} of schema {} already exists and cannot be changed",
+            version, schema_name
+        )));
+    }
+
+    state.set_schema(&schema_name, schema.clone())?;
+    state.set_schema_version(&schema_name, schema)
 }
 
 fn check_permission(
     perm_checker: &PermissionChecker,
     signer: &str,
     permission: &str,
+    org_id: &str,
 ) -> Result<(), ApplyError> {
-    match perm_checker.has_permission(signer, permission) {
-        Ok(true) => Ok(()),
-        Ok(false) => Err(ApplyError::InvalidTransaction(format!(
+    let reason = match perm_checker.has_permission_in_org(signer, permission, org_id) {
+        Ok(Authorization::Granted) => return Ok(()),
+        Ok(Authorization::Denied(reason)) => reason,
+        Err(e) => return Err(ApplyError::InvalidTransaction(format!("{}", e))),
+    };
+
+    Err(ApplyError::InvalidTransaction(match reason {
+        DenyReason::NotAnAgent => format!("The signer is not an Agent: {}", signer),
+        DenyReason::Inactive => format!("The signer is not an active Agent: {}", signer),
+        DenyReason::WrongOrganization { agent_org_id } => format!(
+            "The signer does not belong to the correct organization: {} != {}",
+            agent_org_id, org_id
+        ),
+        DenyReason::MissingPermission => format!(
             "The signer does not have the {} permission: {}.",
             permission, signer,
-        ))),
-        Err(e) => Err(ApplyError::InvalidTransaction(format!("{}", e))),
-    }
+        ),
+    }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use std::cell::RefCell;
-    use std::collections::HashMap;
-
-    use grid_sdk::protocol::pike::state::{AgentBuilder, AgentListBuilder};
-    use grid_sdk::protocol::schema::payload::{SchemaCreateBuilder, SchemaUpdateBuilder};
-    use grid_sdk::protocol::schema::state::{
-        DataType, PropertyDefinitionBuilder, SchemaBuilder, SchemaListBuilder,
+    use grid_sdk::protocol::schema::payload::{
+        PropertyGroupCreateBuilder, SchemaCreateBuilder, SchemaDeprecateBuilder,
+        SchemaPayloadBuilder, SchemaTransferBuilder, SchemaUpdateBuilder,
+    };
+    use grid_sdk::protocol::schema::state::{DataType, PropertyDefinitionBuilder};
+    use grid_sdk::test_kit::{
+        AgentFixture, MockTransactionContext, OrganizationFixture, PropertyGroupFixture,
+        SchemaFixture,
     };
-    use grid_sdk::protos::IntoBytes;
     use sawtooth_sdk::processor::handler::ApplyError;
-    use sawtooth_sdk::processor::handler::{ContextError, TransactionContext};
-
-    use crate::state::{compute_agent_address, compute_schema_address};
-
-    #[derive(Default)]
-    /// A MockTransactionContext that can be used to test GridSchemaState
-    struct MockTransactionContext {
-        state: RefCell<HashMap<String, Vec<u8>>>,
-    }
-
-    impl TransactionContext for MockTransactionContext {
-        fn get_state_entries(
-            &self,
-            addresses: &[String],
-        ) -> Result<Vec<(String, Vec<u8>)>, ContextError> {
-            let mut results = Vec::new();
-            for addr in addresses {
-                let data = match self.state.borrow().get(addr) {
-                    Some(data) => data.clone(),
-                    None => Vec::new(),
-                };
-                results.push((addr.to_string(), data));
-            }
-            Ok(results)
-        }
 
-        fn set_state_entries(&self, entries: Vec<(String, Vec<u8>)>) -> Result<(), ContextError> {
-            for (addr, data) in entries {
-                self.state.borrow_mut().insert(addr, data);
-            }
-            Ok(())
-        }
+    /// Test helpers on the test-kit MockTransactionContext
+    trait TestContext {
+        /// Applies a payload through the TransactionHandler and checks that the handler accessed
+        /// exactly the addresses declared by the payload. Pike roles and the versions of an
+        /// updated schema are declared as address prefixes, of which the handler accesses single
+        /// entries only.
+        fn apply_with_declared_addresses(&mut self, payload: SchemaPayload, signer: &str);
 
-        /// this is not needed for these tests
-        fn delete_state_entries(&self, _addresses: &[String]) -> Result<Vec<String>, ContextError> {
-            unimplemented!()
-        }
+        fn add_agent(&self);
 
-        /// this is not needed for these tests
-        fn add_receipt_data(&self, _data: &[u8]) -> Result<(), ContextError> {
-            unimplemented!()
-        }
+        fn add_agent_inactive(&self);
 
-        /// this is not needed for these tests
-        fn add_event(
-            &self,
-            _event_type: String,
-            _attributes: Vec<(String, String)>,
-            _data: &[u8],
-        ) -> Result<(), ContextError> {
-            unimplemented!()
-        }
+        fn add_agent_wrong_organization(&self);
+
+        fn add_agent_no_roles(&self);
+
+        fn add_schema(&self);
+
+        fn add_property_group(&self);
     }
 
-    impl MockTransactionContext {
-        fn add_agent(&self) {
-            let builder = AgentBuilder::new();
-            let agent = builder
-                .with_org_id("test_org".to_string())
-                .with_public_key("agent_public_key".to_string())
-                .with_active(true)
-                .with_roles(vec![
-                    "can_create_schema".to_string(),
-                    "can_update_schema".to_string(),
-                ])
-                .build()
-                .unwrap();
+    fn location_definition() -> PropertyDefinition {
+        PropertyDefinitionBuilder::new()
+            .with_name("location".to_string())
+            .with_data_type(DataType::LatLong)
+            .with_description("GPS location".to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn agent_with_schema_roles(org_id: &str) -> AgentFixture {
+        AgentFixture::new("agent_public_key", org_id).with_roles(&[
+            "can_create_schema",
+            "can_update_schema",
+            "can_transfer_schema",
+        ])
+    }
 
-            let builder = AgentListBuilder::new();
-            let agent_list = builder.with_agents(vec![agent.clone()]).build().unwrap();
-            let agent_bytes = agent_list.into_bytes().unwrap();
-            let agent_address = compute_agent_address("agent_public_key");
-            self.set_state_entry(agent_address, agent_bytes).unwrap();
+    impl TestContext for MockTransactionContext {
+        fn apply_with_declared_addresses(&mut self, payload: SchemaPayload, signer: &str) {
+            self.apply(&GridSchemaTransactionHandler::new(), &payload, signer)
+                .expect("Failed to apply payload");
+
+            let inputs = sorted(payload.inputs(signer));
+            let mut reads = declared_as(self.reads(), &inputs);
+            reads.sort();
+            reads.dedup();
+            assert_eq!(reads, inputs);
+
+            let outputs = sorted(payload.outputs(signer));
+            let mut writes = declared_as(self.writes(), &outputs);
+            writes.sort();
+            writes.dedup();
+            assert_eq!(writes, outputs);
         }
 
-        fn add_agent_inactive(&self) {
-            let builder = AgentBuilder::new();
-            let agent = builder
-                .with_org_id("test_org".to_string())
-                .with_public_key("agent_public_key".to_string())
-                .with_active(false)
-                .with_roles(vec![
-                    "can_create_schema".to_string(),
-                    "can_update_schema".to_string(),
-                ])
-                .build()
-                .unwrap();
+        fn add_agent(&self) {
+            agent_with_schema_roles("test_org").add_to(self);
+        }
 
-            let builder = AgentListBuilder::new();
-            let agent_list = builder.with_agents(vec![agent.clone()]).build().unwrap();
-            let agent_bytes = agent_list.into_bytes().unwrap();
-            let agent_address = compute_agent_address("agent_public_key");
-            self.set_state_entry(agent_address, agent_bytes).unwrap();
+        fn add_agent_inactive(&self) {
+            agent_with_schema_roles("test_org").inactive().add_to(self);
         }
 
         fn add_agent_wrong_organization(&self) {
-            let builder = AgentBuilder::new();
-            let agent = builder
-                .with_org_id("wrong_org".to_string())
-                .with_public_key("agent_public_key".to_string())
-                .with_active(true)
-                .with_roles(vec![
-                    "can_create_schema".to_string(),
-                    "can_update_schema".to_string(),
-                ])
-                .build()
-                .unwrap();
-
-            let builder = AgentListBuilder::new();
-            let agent_list = builder.with_agents(vec![agent.clone()]).build().unwrap();
-            let agent_bytes = agent_list.into_bytes().unwrap();
-            let agent_address = compute_agent_address("agent_public_key");
-            self.set_state_entry(agent_address, agent_bytes).unwrap();
+            agent_with_schema_roles("wrong_org").add_to(self);
         }
 
         fn add_agent_no_roles(&self) {
-            let builder = AgentBuilder::new();
-            let agent = builder
-                .with_org_id("test_org".to_string())
-                .with_public_key("agent_public_key".to_string())
-                .with_active(true)
-                .build()
-                .unwrap();
-
-            let builder = AgentListBuilder::new();
-            let agent_list = builder.with_agents(vec![agent.clone()]).build().unwrap();
-            let agent_bytes = agent_list.into_bytes().unwrap();
-            let agent_address = compute_agent_address("agent_public_key");
-            self.set_state_entry(agent_address, agent_bytes).unwrap();
+            AgentFixture::new("agent_public_key", "test_org").add_to(self);
         }
 
         fn add_schema(&self) {
-            let builder = PropertyDefinitionBuilder::new();
-            let property_definition = builder
+            let property_definition = PropertyDefinitionBuilder::new()
                 .with_name("TEST".to_string())
                 .with_data_type(DataType::Enum)
                 .with_description("Optional".to_string())
@@ -417,20 +633,16 @@ mod tests {
                 .build()
                 .unwrap();
 
-            let builder = SchemaBuilder::new();
-            let schema = builder
-                .with_name("TestSchema".to_string())
-                .with_description("Test Schema".to_string())
-                .with_owner("test_org".to_string())
-                .with_properties(vec![property_definition.clone()])
-                .build()
-                .unwrap();
+            SchemaFixture::new("TestSchema", "test_org")
+                .with_description("Test Schema")
+                .with_property(property_definition)
+                .add_to(self);
+        }
 
-            let builder = SchemaListBuilder::new();
-            let schema_list = builder.with_schemas(vec![schema]).build().unwrap();
-            let schema_bytes = schema_list.into_bytes().unwrap();
-            let schema_address = compute_schema_address("TestSchema");
-            self.set_state_entry(schema_address, schema_bytes).unwrap();
+        fn add_property_group(&self) {
+            PropertyGroupFixture::new("gps", "test_org")
+                .with_property(location_definition())
+                .add_to(self);
         }
     }
 
@@ -575,6 +787,126 @@ mod tests {
         }
     }
 
+    fn sorted(mut addresses: Vec<String>) -> Vec<String> {
+        addresses.sort();
+        addresses
+    }
+
+    /// Replaces each address by the declared address prefix it falls under, if any
+    fn declared_as(addresses: Vec<String>, declared: &[String]) -> Vec<String> {
+        addresses
+            .into_iter()
+            .map(|address| {
+                declared
+                    .iter()
+                    .find(|prefix| address.starts_with(prefix.as_str()))
+                    .cloned()
+                    .unwrap_or(address)
+            })
+            .collect()
+    }
+
+    #[test]
+    // Test that the addresses declared by schema payloads are the ones the handler accesses
+    fn test_schema_payload_declared_addresses() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_agent();
+        let signer = "agent_public_key";
+
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::String)
+            .with_description("Optional".to_string())
+            .build()
+            .unwrap();
+
+        let create = SchemaPayloadBuilder::new()
+            .with_action(Action::SchemaCreate)
+            .with_schema_create(
+                SchemaCreateBuilder::new()
+                    .with_schema_name("TestSchema".to_string())
+                    .with_description("Test Schema".to_string())
+                    .with_properties(vec![property_definition.clone()])
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        transaction_context.apply_with_declared_addresses(create, signer);
+
+        let update = SchemaPayloadBuilder::new()
+            .with_action(Action::SchemaUpdate)
+            .with_schema_update(
+                SchemaUpdateBuilder::new()
+                    .with_schema_name("TestSchema".to_string())
+                    .with_properties(vec![PropertyDefinitionBuilder::new()
+                        .with_name("NEW".to_string())
+                        .with_data_type(DataType::String)
+                        .with_description("Optional".to_string())
+                        .build()
+                        .unwrap()])
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        transaction_context.apply_with_declared_addresses(update, signer);
+
+        let deprecate = SchemaPayloadBuilder::new()
+            .with_action(Action::SchemaDeprecate)
+            .with_schema_deprecate(
+                SchemaDeprecateBuilder::new()
+                    .with_schema_name("TestSchema".to_string())
+                    .with_properties(vec!["NEW".to_string()])
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        transaction_context.apply_with_declared_addresses(deprecate, signer);
+
+        let property_group_create = SchemaPayloadBuilder::new()
+            .with_action(Action::PropertyGroupCreate)
+            .with_property_group_create(
+                PropertyGroupCreateBuilder::new()
+                    .with_name("gps".to_string())
+                    .with_properties(vec![location_definition()])
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        transaction_context.apply_with_declared_addresses(property_group_create, signer);
+
+        let composed_create = SchemaPayloadBuilder::new()
+            .with_action(Action::SchemaCreate)
+            .with_schema_create(
+                SchemaCreateBuilder::new()
+                    .with_schema_name("ComposedSchema".to_string())
+                    .with_parent("TestSchema".to_string())
+                    .with_property_groups(vec!["gps".to_string()])
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        transaction_context.apply_with_declared_addresses(composed_create, signer);
+
+        OrganizationFixture::new("new_org").add_to(&transaction_context);
+        let transfer = SchemaPayloadBuilder::new()
+            .with_action(Action::SchemaTransfer)
+            .with_schema_transfer(
+                SchemaTransferBuilder::new()
+                    .with_schema_name("TestSchema".to_string())
+                    .with_new_owner("new_org".to_string())
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        transaction_context.apply_with_declared_addresses(transfer, signer);
+    }
+
     #[test]
     // Test that if the SchemaCreateAction is valid OK is returned
     fn test_create_schema_handler_valid() {
@@ -603,6 +935,140 @@ mod tests {
         assert!(schema_create(&action, signer, &state, &perm_checker).is_ok());
     }
 
+    #[test]
+    // Test that a schema extending a parent and including a property group inherits their
+    // properties, pinned to the current version of the parent
+    fn test_create_schema_handler_composed() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_property_group();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("lot".to_string())
+            .with_data_type(DataType::String)
+            .build()
+            .unwrap();
+
+        let action = SchemaCreateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_parent("TestSchema".to_string())
+            .with_property_groups(vec!["gps".to_string()])
+            .with_properties(vec![property_definition.clone()])
+            .build()
+            .unwrap();
+
+        assert!(schema_create(&action, signer, &state, &perm_checker).is_ok());
+
+        let parent = state.get_schema("TestSchema").unwrap().unwrap();
+        let schema = state.get_schema("ComposedSchema").unwrap().unwrap();
+        assert_eq!(schema.parent(), "TestSchema");
+        assert_eq!(schema.parent_version(), &1);
+        assert_eq!(schema.property_groups(), &["gps".to_string()]);
+        assert_eq!(schema.properties(), &[property_definition.clone()]);
+        assert_eq!(
+            schema.effective_properties(),
+            vec![
+                parent.properties()[0].clone(),
+                location_definition(),
+                property_definition,
+            ]
+        );
+        assert_eq!(
+            state.get_schema_version("ComposedSchema", 1).unwrap(),
+            Some(schema)
+        );
+    }
+
+    #[test]
+    // Test that a property defined differently by two of the sources of a schema is rejected,
+    // while a property defined identically is inherited once
+    fn test_create_schema_handler_conflicting_properties() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_property_group();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let conflicting = PropertyDefinitionBuilder::new()
+            .with_name("location".to_string())
+            .with_data_type(DataType::String)
+            .build()
+            .unwrap();
+
+        let action = SchemaCreateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_property_groups(vec!["gps".to_string()])
+            .with_properties(vec![conflicting])
+            .build()
+            .unwrap();
+
+        match schema_create(&action, signer, &state, &perm_checker) {
+            Ok(()) => panic!("Location conflicts, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains(
+                    "Property location is defined differently by property group gps and \
+                     schema ComposedSchema"
+                ));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+
+        let action = SchemaCreateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_property_groups(vec!["gps".to_string()])
+            .with_properties(vec![location_definition()])
+            .build()
+            .unwrap();
+
+        assert!(schema_create(&action, signer, &state, &perm_checker).is_ok());
+        let schema = state.get_schema("ComposedSchema").unwrap().unwrap();
+        assert_eq!(schema.effective_properties(), vec![location_definition()]);
+    }
+
+    #[test]
+    // Test that a schema cannot be composed from a parent or property group missing from state
+    fn test_create_schema_handler_sources_do_not_exist() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let action = SchemaCreateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_parent("TestSchema".to_string())
+            .build()
+            .unwrap();
+
+        match schema_create(&action, signer, &state, &perm_checker) {
+            Ok(()) => panic!("Parent does not exist, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Parent schema TestSchema does not exist"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+
+        let action = SchemaCreateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_property_groups(vec!["gps".to_string()])
+            .build()
+            .unwrap();
+
+        match schema_create(&action, signer, &state, &perm_checker) {
+            Ok(()) => panic!("Group does not exist, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Property group gps does not exist"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
     #[test]
     // Test that if the schema does not exist in state an InvalidTransaction is returned
     fn test_update_schema_handler_schema_does_not_exists() {
@@ -809,6 +1275,56 @@ mod tests {
         }
     }
 
+    #[test]
+    // Test that an update cannot add a property with the name of an inherited property, and
+    // keeps the schema composed from the same sources
+    fn test_update_schema_handler_inherited_property() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_property_group();
+        transaction_context.add_agent();
+        let property_group =
+            PropertyGroupFixture::new("gps", "test_org").with_property(location_definition());
+        SchemaFixture::new("ComposedSchema", "test_org")
+            .with_property_group(&property_group)
+            .add_to(&transaction_context);
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let action = SchemaUpdateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_properties(vec![location_definition()])
+            .build()
+            .unwrap();
+
+        match schema_update(&action, signer, &state, &perm_checker) {
+            Ok(()) => panic!("Location is inherited, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Schema already has PropertyDefination with name location"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("lot".to_string())
+            .with_data_type(DataType::String)
+            .build()
+            .unwrap();
+        let action = SchemaUpdateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_properties(vec![property_definition.clone()])
+            .build()
+            .unwrap();
+
+        assert!(schema_update(&action, signer, &state, &perm_checker).is_ok());
+        let schema = state.get_schema("ComposedSchema").unwrap().unwrap();
+        assert_eq!(schema.property_groups(), &["gps".to_string()]);
+        assert_eq!(
+            schema.effective_properties(),
+            vec![location_definition(), property_definition]
+        );
+    }
+
     #[test]
     // Test that if the SchemaUpdateAction is valid an OK is returned
     fn test_update_schema_handler_valid() {
@@ -836,4 +1352,364 @@ mod tests {
 
         assert!(schema_update(&action, signer, &state, &perm_checker).is_ok());
     }
+
+    #[test]
+    // Test that an update increments the version of the schema, and stores the new version
+    // without changing the previous one
+    fn test_update_schema_handler_keeps_versions() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("NEW".to_string())
+            .with_data_type(DataType::String)
+            .with_description("Optional".to_string())
+            .build()
+            .unwrap();
+
+        let action = SchemaUpdateBuilder::new()
+            .with_schema_name("TestSchema".to_string())
+            .with_properties(vec![property_definition])
+            .build()
+            .unwrap();
+
+        assert!(schema_update(&action, signer, &state, &perm_checker).is_ok());
+
+        let schema = state.get_schema("TestSchema").unwrap().unwrap();
+        assert_eq!(*schema.version(), 2);
+        assert_eq!(schema.properties().len(), 2);
+        assert_eq!(
+            state.get_schema_version("TestSchema", 2).unwrap(),
+            Some(schema)
+        );
+
+        let version_1 = state.get_schema_version("TestSchema", 1).unwrap().unwrap();
+        assert_eq!(*version_1.version(), 1);
+        assert_eq!(version_1.properties().len(), 1);
+    }
+
+    #[test]
+    // Test that if the next version of the schema is already in state an InvalidTransaction is
+    // returned, as schema versions are immutable
+    fn test_update_schema_handler_version_already_exists() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("NEW".to_string())
+            .with_data_type(DataType::String)
+            .with_description("Optional".to_string())
+            .build()
+            .unwrap();
+
+        let existing = SchemaFixture::new("TestSchema", "test_org")
+            .with_version(2)
+            .with_property(property_definition.clone())
+            .build();
+        state.set_schema_version("TestSchema", existing).unwrap();
+
+        let action = SchemaUpdateBuilder::new()
+            .with_schema_name("TestSchema".to_string())
+            .with_properties(vec![property_definition])
+            .build()
+            .unwrap();
+
+        match schema_update(&action, signer, &state, &perm_checker) {
+            Ok(()) => panic!("Schema version exists, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Version 2 of schema TestSchema already exists"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    fn transfer_action(new_owner: &str) -> SchemaTransferAction {
+        SchemaTransferBuilder::new()
+            .with_schema_name("TestSchema".to_string())
+            .with_new_owner(new_owner.to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn deprecate_action(properties: &[&str]) -> SchemaDeprecateAction {
+        SchemaDeprecateBuilder::new()
+            .with_schema_name("TestSchema".to_string())
+            .with_properties(properties.iter().map(|name| name.to_string()).collect())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    // Test that if the SchemaTransferAction is valid the schema is owned by the new organization
+    // at a new version, and the agents of the previous owner lose their rights on it
+    fn test_transfer_schema_handler_valid() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_agent();
+        OrganizationFixture::new("new_org").add_to(&transaction_context);
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        assert!(
+            schema_transfer(&transfer_action("new_org"), signer, &state, &perm_checker).is_ok()
+        );
+
+        let schema = state.get_schema("TestSchema").unwrap().unwrap();
+        assert_eq!(schema.owner(), "new_org");
+        assert_eq!(*schema.version(), 2);
+        assert_eq!(
+            state.get_schema_version("TestSchema", 2).unwrap(),
+            Some(schema)
+        );
+
+        match schema_transfer(&transfer_action("test_org"), signer, &state, &perm_checker) {
+            Ok(()) => panic!(
+                "Signer is no longer in the owner org, InvalidTransaction should be returned"
+            ),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains(
+                    "The signer does not belong to the correct organization: test_org != new_org"
+                ));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    #[test]
+    // Test that if the organization a schema is transferred to does not exist an
+    // InvalidTransaction is returned
+    fn test_transfer_schema_handler_organization_does_not_exist() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+
+        match schema_transfer(
+            &transfer_action("new_org"),
+            "agent_public_key",
+            &state,
+            &perm_checker,
+        ) {
+            Ok(()) => panic!("Organization does not exist, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Organization new_org does not exist"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    #[test]
+    // Test that if the signer does not have the can_transfer_schema permission an
+    // InvalidTransaction is returned
+    fn test_transfer_schema_handler_no_roles() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        AgentFixture::new("agent_public_key", "test_org")
+            .with_roles(&["can_update_schema"])
+            .add_to(&transaction_context);
+        OrganizationFixture::new("new_org").add_to(&transaction_context);
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+
+        match schema_transfer(
+            &transfer_action("new_org"),
+            "agent_public_key",
+            &state,
+            &perm_checker,
+        ) {
+            Ok(()) => panic!("Agent does not have roles, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("The signer does not have the can_transfer_schema permission"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    #[test]
+    // Test that a SchemaDeprecateAction without properties deprecates the schema, and cannot be
+    // applied twice
+    fn test_deprecate_schema_handler_schema() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        assert!(schema_deprecate(&deprecate_action(&[]), signer, &state, &perm_checker).is_ok());
+
+        let schema = state.get_schema("TestSchema").unwrap().unwrap();
+        assert!(*schema.deprecated());
+        assert_eq!(*schema.version(), 2);
+        assert!(!*state
+            .get_schema_version("TestSchema", 1)
+            .unwrap()
+            .unwrap()
+            .deprecated());
+
+        match schema_deprecate(&deprecate_action(&[]), signer, &state, &perm_checker) {
+            Ok(()) => panic!("Schema is already deprecated, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Schema TestSchema is already deprecated"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    #[test]
+    // Test that a SchemaDeprecateAction with properties deprecates only those properties, and
+    // that unknown properties are rejected
+    fn test_deprecate_schema_handler_properties() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        match schema_deprecate(
+            &deprecate_action(&["UNKNOWN"]),
+            signer,
+            &state,
+            &perm_checker,
+        ) {
+            Ok(()) => panic!("Property does not exist, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(
+                    err.contains("Schema TestSchema has no PropertyDefinition with name UNKNOWN")
+                );
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+
+        assert!(
+            schema_deprecate(&deprecate_action(&["TEST"]), signer, &state, &perm_checker).is_ok()
+        );
+
+        let schema = state.get_schema("TestSchema").unwrap().unwrap();
+        assert!(!*schema.deprecated());
+        assert!(*schema.properties()[0].deprecated());
+        assert_eq!(*schema.version(), 2);
+    }
+
+    #[test]
+    // Test that an inherited property cannot be deprecated on the schema inheriting it, and that
+    // a deprecated property is no longer required
+    fn test_deprecate_schema_handler_inherited_property() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_property_group();
+        transaction_context.add_agent();
+        let property_group =
+            PropertyGroupFixture::new("gps", "test_org").with_property(location_definition());
+        SchemaFixture::new("ComposedSchema", "test_org")
+            .with_property_group(&property_group)
+            .with_property(
+                PropertyDefinitionBuilder::new()
+                    .with_name("lot".to_string())
+                    .with_data_type(DataType::String)
+                    .with_required(true)
+                    .build()
+                    .unwrap(),
+            )
+            .add_to(&transaction_context);
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let action = SchemaDeprecateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_properties(vec!["location".to_string()])
+            .build()
+            .unwrap();
+
+        match schema_deprecate(&action, signer, &state, &perm_checker) {
+            Ok(()) => panic!("Location is inherited, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains(
+                    "Property location is inherited by schema ComposedSchema and cannot be \
+                     deprecated on it"
+                ));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+
+        let action = SchemaDeprecateBuilder::new()
+            .with_schema_name("ComposedSchema".to_string())
+            .with_properties(vec!["lot".to_string()])
+            .build()
+            .unwrap();
+
+        assert!(schema_deprecate(&action, signer, &state, &perm_checker).is_ok());
+
+        let schema = state.get_schema("ComposedSchema").unwrap().unwrap();
+        assert!(*schema.properties()[0].deprecated());
+        assert!(!*schema.properties()[0].required());
+    }
+
+    #[test]
+    // Test that a property group is created for the organization of the signer, and cannot be
+    // created twice
+    fn test_create_property_group_handler() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_agent();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let action = PropertyGroupCreateBuilder::new()
+            .with_name("gps".to_string())
+            .with_description("GPS location".to_string())
+            .with_properties(vec![location_definition()])
+            .build()
+            .unwrap();
+
+        assert!(property_group_create(&action, signer, &state, &perm_checker).is_ok());
+
+        let property_group = state.get_property_group("gps").unwrap().unwrap();
+        assert_eq!(property_group.owner(), "test_org");
+        assert_eq!(property_group.properties(), &[location_definition()]);
+
+        match property_group_create(&action, signer, &state, &perm_checker) {
+            Ok(()) => panic!("Group already exists, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Property group with name gps already exists"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    #[test]
+    // Test that if the agent does not have the can_create_schema role an InvalidTransaction is
+    // returned when creating a property group
+    fn test_create_property_group_handler_no_roles() {
+        let transaction_context = MockTransactionContext::default();
+        transaction_context.add_agent_no_roles();
+        let perm_checker = PermissionChecker::new(&transaction_context);
+        let state = GridSchemaState::new(&transaction_context);
+        let signer = "agent_public_key";
+
+        let action = PropertyGroupCreateBuilder::new()
+            .with_name("gps".to_string())
+            .with_properties(vec![location_definition()])
+            .build()
+            .unwrap();
+
+        match property_group_create(&action, signer, &state, &perm_checker) {
+            Ok(()) => panic!("Agent has no roles, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("The signer does not have the can_create_schema permission"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
 }
@@ -20,14 +20,24 @@ cfg_if! {
     }
 }
 
+use std::collections::HashSet;
+
 use grid_sdk::protocol::schema::payload::{
-    Action, SchemaCreateAction, SchemaPayload, SchemaUpdateAction,
+    Action, PropertyGroupCreateAction, SchemaCreateAction, SchemaDeprecateAction, SchemaPayload,
+    SchemaTransferAction, SchemaUpdateAction,
 };
+use grid_sdk::protocol::schema::state::PropertyDefinition;
+use grid_sdk::protocol::schema::validate::{validate_property_definitions, ValidationError};
 
 pub fn validate_payload(payload: &SchemaPayload) -> Result<(), ApplyError> {
     match payload.action() {
         Action::SchemaCreate => validate_schema_create_action(payload.schema_create()),
         Action::SchemaUpdate => validate_schema_update_action(payload.schema_update()),
+        Action::SchemaTransfer => validate_schema_transfer_action(payload.schema_transfer()),
+        Action::SchemaDeprecate => validate_schema_deprecate_action(payload.schema_deprecate()),
+        Action::PropertyGroupCreate => {
+            validate_property_group_create_action(payload.property_group_create())
+        }
     }
 }
 
@@ -38,12 +48,33 @@ fn validate_schema_create_action(create_action: &SchemaCreateAction) -> Result<(
         )));
     }
 
-    if create_action.properties().is_empty() {
+    // A schema composed from a parent or property groups may declare no property of its own
+    if create_action.properties().is_empty()
+        && create_action.parent().is_empty()
+        && create_action.property_groups().is_empty()
+    {
         return Err(ApplyError::InvalidTransaction(String::from(
             "Properties must not be empty",
         )));
     }
-    Ok(())
+
+    if create_action.parent() == create_action.schema_name() {
+        return Err(ApplyError::InvalidTransaction(String::from(
+            "Schema cannot extend itself",
+        )));
+    }
+
+    let mut property_groups = HashSet::new();
+    for property_group in create_action.property_groups() {
+        if !property_groups.insert(property_group) {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Property group {} is included more than once",
+                property_group
+            )));
+        }
+    }
+
+    validate_constraints(create_action.properties())
 }
 
 fn validate_schema_update_action(update_action: &SchemaUpdateAction) -> Result<(), ApplyError> {
@@ -58,9 +89,68 @@ fn validate_schema_update_action(update_action: &SchemaUpdateAction) -> Result<(
             "Properties must not be empty",
         )));
     }
+
+    validate_constraints(update_action.properties())
+}
+
+fn validate_schema_transfer_action(
+    transfer_action: &SchemaTransferAction,
+) -> Result<(), ApplyError> {
+    if transfer_action.schema_name().is_empty() {
+        return Err(ApplyError::InvalidTransaction(String::from(
+            "Schema name must be set",
+        )));
+    }
+
+    if transfer_action.new_owner().is_empty() {
+        return Err(ApplyError::InvalidTransaction(String::from(
+            "New owner must be set",
+        )));
+    }
+    Ok(())
+}
+
+fn validate_schema_deprecate_action(
+    deprecate_action: &SchemaDeprecateAction,
+) -> Result<(), ApplyError> {
+    if deprecate_action.schema_name().is_empty() {
+        return Err(ApplyError::InvalidTransaction(String::from(
+            "Schema name must be set",
+        )));
+    }
     Ok(())
 }
 
+fn validate_property_group_create_action(
+    create_action: &PropertyGroupCreateAction,
+) -> Result<(), ApplyError> {
+    if create_action.name().is_empty() {
+        return Err(ApplyError::InvalidTransaction(String::from(
+            "Property group name must be set",
+        )));
+    }
+
+    if create_action.properties().is_empty() {
+        return Err(ApplyError::InvalidTransaction(String::from(
+            "Properties must not be empty",
+        )));
+    }
+
+    validate_constraints(create_action.properties())
+}
+
+fn validate_constraints(properties: &[PropertyDefinition]) -> Result<(), ApplyError> {
+    validate_property_definitions(properties).map_err(|errors| {
+        ApplyError::InvalidTransaction(
+            errors
+                .iter()
+                .map(ValidationError::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,7 +160,7 @@ mod tests {
     };
     use grid_sdk::protocol::schema::state::{DataType, PropertyDefinitionBuilder};
     use grid_sdk::protos;
-    use grid_sdk::protos::IntoNative;
+    use grid_sdk::protos::{IntoNative, IntoProto};
 
     #[test]
     // Test a payload with a schema create action is properly validated. This test needs to use
@@ -140,6 +230,84 @@ mod tests {
         );
     }
 
+    #[test]
+    // Test a payload with a schema create action composed from a parent or property groups is
+    // valid without properties of its own, but cannot extend itself or include a group twice
+    fn test_validate_schema_create_action_composed() {
+        let mut action = protos::schema_payload::SchemaCreateAction::new();
+        action.set_schema_name("TestSchema".into());
+        action.set_parent("ParentSchema".into());
+
+        let mut payload_proto = protos::schema_payload::SchemaPayload::new();
+        payload_proto.set_action(protos::schema_payload::SchemaPayload_Action::SCHEMA_CREATE);
+        payload_proto.set_schema_create(action.clone());
+        let payload = payload_proto.clone().into_native().unwrap();
+        assert!(
+            validate_payload(&payload).is_ok(),
+            "Payload should be valid"
+        );
+
+        action.set_parent("TestSchema".into());
+        payload_proto.set_schema_create(action.clone());
+        let payload = payload_proto.clone().into_native().unwrap();
+        assert!(
+            validate_payload(&payload).is_err(),
+            "Schema cannot extend itself"
+        );
+
+        action.clear_parent();
+        action.mut_property_groups().push("gps".into());
+        action.mut_property_groups().push("gps".into());
+        payload_proto.set_schema_create(action);
+        let payload = payload_proto.into_native().unwrap();
+        match validate_payload(&payload) {
+            Ok(()) => panic!("Group is included twice, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Property group gps is included more than once"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    #[test]
+    // Test a payload with a property group create action is properly validated
+    fn test_validate_property_group_create_action() {
+        let mut payload_proto = protos::schema_payload::SchemaPayload::new();
+        payload_proto
+            .set_action(protos::schema_payload::SchemaPayload_Action::PROPERTY_GROUP_CREATE);
+
+        let mut action = protos::schema_payload::PropertyGroupCreateAction::new();
+        payload_proto.set_property_group_create(action.clone());
+        let payload = payload_proto.clone().into_native().unwrap();
+        assert!(
+            validate_payload(&payload).is_err(),
+            "Property group name must be set"
+        );
+
+        action.set_name("gps".into());
+        payload_proto.set_property_group_create(action.clone());
+        let payload = payload_proto.clone().into_native().unwrap();
+        assert!(
+            validate_payload(&payload).is_err(),
+            "Properties must not be empty"
+        );
+
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("location".to_string())
+            .with_data_type(DataType::LatLong)
+            .build()
+            .unwrap();
+        action
+            .mut_properties()
+            .push(property_definition.into_proto().unwrap());
+        payload_proto.set_property_group_create(action);
+        let payload = payload_proto.into_native().unwrap();
+        assert!(
+            validate_payload(&payload).is_ok(),
+            "Payload should be valid"
+        );
+    }
+
     #[test]
     // Test a payload with a schema update action is properly validated. This test needs to use
     // the proto directly originally to be able to mimic the scenarios possbile from creating
@@ -207,4 +375,53 @@ mod tests {
             "Payload should be valid"
         );
     }
+
+    #[test]
+    // Test a payload with a schema transfer action is properly validated
+    fn test_validate_schema_transfer_action() {
+        let mut payload_proto = protos::schema_payload::SchemaPayload::new();
+        payload_proto.set_action(protos::schema_payload::SchemaPayload_Action::SCHEMA_TRANSFER);
+
+        let mut action = protos::schema_payload::SchemaTransferAction::new();
+        action.set_schema_name("TestSchema".into());
+        payload_proto.set_schema_transfer(action.clone());
+        let payload = payload_proto.clone().into_native().unwrap();
+        assert!(validate_payload(&payload).is_err(), "New owner must be set");
+
+        action.set_new_owner("new_org".into());
+        payload_proto.set_schema_transfer(action);
+        let payload = payload_proto.into_native().unwrap();
+        assert!(
+            validate_payload(&payload).is_ok(),
+            "Payload should be valid"
+        );
+    }
+
+    #[test]
+    // Test a payload with property definitions whose constraints are invalid is rejected. The
+    // proto is used directly since the PropertyDefinitionBuilder rejects invalid constraints.
+    fn test_validate_schema_create_action_invalid_constraints() {
+        let mut property_definition = protos::schema_state::PropertyDefinition::new();
+        property_definition.set_name("serial".to_string());
+        property_definition
+            .set_data_type(protos::schema_state::PropertyDefinition_DataType::STRING);
+        property_definition.set_bytes_max_size(4096);
+
+        let mut action = protos::schema_payload::SchemaCreateAction::new();
+        action.set_schema_name("TestSchema".into());
+        action.mut_properties().push(property_definition);
+
+        let mut payload_proto = protos::schema_payload::SchemaPayload::new();
+        payload_proto.set_action(protos::schema_payload::SchemaPayload_Action::SCHEMA_CREATE);
+        payload_proto.set_schema_create(action);
+        let payload = payload_proto.into_native().unwrap();
+
+        match validate_payload(&payload) {
+            Ok(()) => panic!("Constraint is invalid, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("bytes_max_size only applies to BYTES properties"));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
 }
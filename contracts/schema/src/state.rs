@@ -12,10 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crypto::digest::Digest;
-use crypto::sha2::Sha512;
-use grid_sdk::protocol::pike::state::{Agent, AgentList};
-use grid_sdk::protocol::schema::state::{Schema, SchemaList, SchemaListBuilder};
+use grid_sdk::protocol::addressing::pike::{compute_agent_address, compute_org_address};
+use grid_sdk::protocol::addressing::schema::{
+    compute_property_group_address, compute_schema_address, compute_schema_version_address,
+};
+use grid_sdk::protocol::pike::state::{Agent, AgentList, Organization, OrganizationList};
+use grid_sdk::protocol::schema::state::{
+    PropertyGroup, PropertyGroupList, PropertyGroupListBuilder, Schema, SchemaList,
+    SchemaListBuilder,
+};
 use grid_sdk::protos::{FromBytes, IntoBytes};
 
 cfg_if! {
@@ -28,28 +33,6 @@ cfg_if! {
     }
 }
 
-pub const GRID_NAMESPACE: &str = "621dee";
-pub const GRID_SCHEMA_NAMESPACE: &str = "01";
-
-pub const PIKE_NAMESPACE: &str = "cad11d";
-pub const PIKE_AGENT_NAMESPACE: &str = "00";
-
-/// Computes the address a Pike Agent is stored at based on its public_key
-pub fn compute_agent_address(public_key: &str) -> String {
-    let mut sha = Sha512::new();
-    sha.input(public_key.as_bytes());
-
-    String::from(PIKE_NAMESPACE) + PIKE_AGENT_NAMESPACE + &sha.result_str()[..62].to_string()
-}
-
-/// Computes the address a Grid Schema is stored at based on its name
-pub fn compute_schema_address(name: &str) -> String {
-    let mut sha = Sha512::new();
-    sha.input(name.as_bytes());
-
-    String::from(GRID_NAMESPACE) + GRID_SCHEMA_NAMESPACE + &sha.result_str()[..62].to_string()
-}
-
 /// GridSchemaState is in charge of handling getting and setting state.
 pub struct GridSchemaState<'a> {
     context: &'a dyn TransactionContext,
@@ -88,10 +71,52 @@ impl<'a> GridSchemaState<'a> {
         }
     }
 
+    /// Gets a Pike Organization. Handles retrieving the correct organization from an
+    /// OrganizationList.
+    pub fn get_organization(&self, org_id: &str) -> Result<Option<Organization>, ApplyError> {
+        let address = compute_org_address(org_id);
+        let d = self.context.get_state_entry(&address)?;
+        match d {
+            Some(packed) => {
+                let orgs = match OrganizationList::from_bytes(packed.as_slice()) {
+                    Ok(orgs) => orgs,
+                    Err(err) => {
+                        return Err(ApplyError::InvalidTransaction(format!(
+                            "Cannot deserialize organization list: {:?}",
+                            err,
+                        )));
+                    }
+                };
+
+                // find the organization with the correct org_id
+                for org in orgs.organizations() {
+                    if org.org_id() == org_id {
+                        return Ok(Some(org.clone()));
+                    }
+                }
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Gets a Grid Schema. Handles retrieving the correct Schema from a SchemaList
     pub fn get_schema(&self, name: &str) -> Result<Option<Schema>, ApplyError> {
-        let address = compute_schema_address(name);
-        let d = self.context.get_state_entry(&address)?;
+        self.get_schema_at(&compute_schema_address(name), name)
+    }
+
+    /// Gets an immutable version of a Grid Schema. Handles retrieving the correct Schema from a
+    /// SchemaList
+    pub fn get_schema_version(
+        &self,
+        name: &str,
+        version: u32,
+    ) -> Result<Option<Schema>, ApplyError> {
+        self.get_schema_at(&compute_schema_version_address(name, version), name)
+    }
+
+    fn get_schema_at(&self, address: &str, name: &str) -> Result<Option<Schema>, ApplyError> {
+        let d = self.context.get_state_entry(address)?;
         match d {
             Some(packed) => {
                 let schemas = match SchemaList::from_bytes(packed.as_slice()) {
@@ -121,7 +146,22 @@ impl<'a> GridSchemaState<'a> {
     /// been a hash collision. The Schema is stored in the SchemaList, sorted by the Schema name,
     /// and set in state.
     pub fn set_schema(&self, name: &str, new_schema: Schema) -> Result<(), ApplyError> {
-        let address = compute_schema_address(name);
+        self.set_schema_at(compute_schema_address(name), name, new_schema)
+    }
+
+    /// Sets an immutable version of a Grid Schema in state, at the address of the schema's
+    /// version. The version is stored in a SchemaList, in the same way as the schema itself.
+    pub fn set_schema_version(&self, name: &str, new_schema: Schema) -> Result<(), ApplyError> {
+        let address = compute_schema_version_address(name, *new_schema.version());
+        self.set_schema_at(address, name, new_schema)
+    }
+
+    fn set_schema_at(
+        &self,
+        address: String,
+        name: &str,
+        new_schema: Schema,
+    ) -> Result<(), ApplyError> {
         let d = self.context.get_state_entry(&address)?;
         // get list of existing schemas, or an empty vec if none
         let mut schemas = match d {
@@ -173,68 +213,90 @@ impl<'a> GridSchemaState<'a> {
             .map_err(|err| ApplyError::InvalidTransaction(format!("{}", err)))?;
         Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    use std::cell::RefCell;
-    use std::collections::HashMap;
-
-    use grid_sdk::protocol::pike::state::{AgentBuilder, AgentListBuilder};
-    use grid_sdk::protocol::schema::state::{DataType, PropertyDefinitionBuilder, SchemaBuilder};
-    use sawtooth_sdk::processor::handler::{ContextError, TransactionContext};
-
-    #[derive(Default)]
-    /// A MockTransactionContext that can be used to test GridSchemaState
-    struct MockTransactionContext {
-        state: RefCell<HashMap<String, Vec<u8>>>,
-    }
-
-    impl TransactionContext for MockTransactionContext {
-        fn get_state_entries(
-            &self,
-            addresses: &[String],
-        ) -> Result<Vec<(String, Vec<u8>)>, ContextError> {
-            let mut results = Vec::new();
-            for addr in addresses {
-                let data = match self.state.borrow().get(addr) {
-                    Some(data) => data.clone(),
-                    None => Vec::new(),
+    /// Gets a Grid PropertyGroup. Handles retrieving the correct PropertyGroup from a
+    /// PropertyGroupList
+    pub fn get_property_group(&self, name: &str) -> Result<Option<PropertyGroup>, ApplyError> {
+        let address = compute_property_group_address(name);
+        let d = self.context.get_state_entry(&address)?;
+        match d {
+            Some(packed) => {
+                let property_groups = match PropertyGroupList::from_bytes(packed.as_slice()) {
+                    Ok(property_groups) => property_groups,
+                    Err(err) => {
+                        return Err(ApplyError::InvalidTransaction(format!(
+                            "Cannot deserialize property group list: {:?}",
+                            err,
+                        )));
+                    }
                 };
-                results.push((addr.to_string(), data));
-            }
-            Ok(results)
-        }
 
-        fn set_state_entries(&self, entries: Vec<(String, Vec<u8>)>) -> Result<(), ContextError> {
-            for (addr, data) in entries {
-                self.state.borrow_mut().insert(addr, data);
+                // find the property group with the correct name
+                for property_group in property_groups.property_groups() {
+                    if property_group.name() == name {
+                        return Ok(Some(property_group.clone()));
+                    }
+                }
+                Ok(None)
             }
-            Ok(())
+            None => Ok(None),
         }
+    }
 
-        /// this is not needed for these tests
-        fn delete_state_entries(&self, _addresses: &[String]) -> Result<Vec<String>, ContextError> {
-            unimplemented!()
-        }
+    /// Sets a Grid PropertyGroup in state. Handles creating a PropertyGroupList if one does not
+    /// already exist at the address the property group will be stored. The PropertyGroup is
+    /// stored in the PropertyGroupList, sorted by name, in the same way as schemas.
+    pub fn set_property_group(
+        &self,
+        name: &str,
+        new_property_group: PropertyGroup,
+    ) -> Result<(), ApplyError> {
+        let address = compute_property_group_address(name);
+        let d = self.context.get_state_entry(&address)?;
+        // get list of existing property groups, or an empty vec if none
+        let mut property_groups = match d {
+            Some(packed) => match PropertyGroupList::from_bytes(packed.as_slice()) {
+                Ok(property_group_list) => property_group_list.property_groups().to_vec(),
+                Err(err) => {
+                    return Err(ApplyError::InvalidTransaction(format!(
+                        "Cannot deserialize property group list: {}",
+                        err,
+                    )));
+                }
+            },
+            None => vec![],
+        };
 
-        /// this is not needed for these tests
-        fn add_receipt_data(&self, _data: &[u8]) -> Result<(), ContextError> {
-            unimplemented!()
-        }
+        property_groups.retain(|property_group| property_group.name() != name);
+        property_groups.push(new_property_group);
+        property_groups.sort_by_key(|p| p.name().to_string());
 
-        /// this is not needed for these tests
-        fn add_event(
-            &self,
-            _event_type: String,
-            _attributes: Vec<(String, String)>,
-            _data: &[u8],
-        ) -> Result<(), ContextError> {
-            unimplemented!()
-        }
+        let property_group_list = PropertyGroupListBuilder::new()
+            .with_property_groups(property_groups)
+            .build()
+            .map_err(|_| {
+                ApplyError::InvalidTransaction(String::from("Cannot build property group list"))
+            })?;
+
+        let serialized = property_group_list.into_bytes().map_err(|_| {
+            ApplyError::InvalidTransaction(String::from("Cannot serialize property group list"))
+        })?;
+        self.context
+            .set_state_entry(address, serialized)
+            .map_err(|err| ApplyError::InvalidTransaction(format!("{}", err)))?;
+        Ok(())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use grid_sdk::protocol::pike::state::{AgentBuilder, AgentListBuilder};
+    use grid_sdk::protocol::schema::state::{
+        DataType, PropertyDefinitionBuilder, PropertyGroupBuilder, SchemaBuilder,
+    };
+    use grid_sdk::test_kit::MockTransactionContext;
 
     #[test]
     // Test that if an agent does not exist in state, None is returned
@@ -333,4 +395,86 @@ mod tests {
         assert_eq!(schema.description(), "New Description");
     }
 
+    #[test]
+    // 1. Test that a version of a schema is not returned before it is set.
+    // 2. Test that each version of a schema is stored at its own address, and is not affected
+    //    by the current schema or by other versions.
+    fn test_grid_schema_version_state() {
+        let transaction_context = MockTransactionContext::default();
+        let state = GridSchemaState::new(&transaction_context);
+
+        assert!(state.get_schema_version("TestSchema", 1).unwrap().is_none());
+
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::String)
+            .build()
+            .unwrap();
+
+        let version_1 = SchemaBuilder::new()
+            .with_name("TestSchema".to_string())
+            .with_description("Version 1".to_string())
+            .with_owner("owner".to_string())
+            .with_version(1)
+            .with_properties(vec![property_definition.clone()])
+            .build()
+            .unwrap();
+        let version_2 = SchemaBuilder::new()
+            .with_name("TestSchema".to_string())
+            .with_description("Version 2".to_string())
+            .with_owner("owner".to_string())
+            .with_version(2)
+            .with_properties(vec![property_definition])
+            .build()
+            .unwrap();
+
+        assert!(state
+            .set_schema_version("TestSchema", version_1.clone())
+            .is_ok());
+        assert!(state
+            .set_schema_version("TestSchema", version_2.clone())
+            .is_ok());
+        assert!(state.get_schema("TestSchema").unwrap().is_none());
+
+        assert_eq!(
+            state.get_schema_version("TestSchema", 1).unwrap(),
+            Some(version_1)
+        );
+        assert_eq!(
+            state.get_schema_version("TestSchema", 2).unwrap(),
+            Some(version_2)
+        );
+    }
+
+    #[test]
+    // 1. Test that if a property group is not in state a None is returned.
+    // 2. Test that a property group set in state is returned as Some(PropertyGroup), and is
+    //    not mistaken for the schema of the same name.
+    fn test_grid_property_group_state() {
+        let transaction_context = MockTransactionContext::default();
+        let state = GridSchemaState::new(&transaction_context);
+
+        assert!(state.get_property_group("TestGroup").unwrap().is_none());
+
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::String)
+            .build()
+            .unwrap();
+        let property_group = PropertyGroupBuilder::new()
+            .with_name("TestGroup".to_string())
+            .with_owner("owner".to_string())
+            .with_properties(vec![property_definition])
+            .build()
+            .unwrap();
+
+        assert!(state
+            .set_property_group("TestGroup", property_group.clone())
+            .is_ok());
+        assert_eq!(
+            state.get_property_group("TestGroup").unwrap(),
+            Some(property_group)
+        );
+        assert!(state.get_schema("TestGroup").unwrap().is_none());
+    }
 }
@@ -29,8 +29,16 @@ cfg_if! {
     }
 }
 
+use grid_sdk::protocol::addressing::pike::PIKE_NAMESPACE;
+use grid_sdk::protocol::addressing::schema::GRID_NAMESPACE;
+use grid_sdk::protocol::addressing::track_and_trace::{
+    TRACK_AND_TRACE_FAMILY_NAME, TRACK_AND_TRACE_NAMESPACE,
+};
 use grid_sdk::protocol::errors::BuilderError;
 use grid_sdk::protocol::schema::state::{PropertyDefinition, PropertyValue};
+use grid_sdk::protocol::schema::validate::{
+    validate_property_value, validate_schema_values, validation_errors_to_message, ValidationError,
+};
 use grid_sdk::protocol::track_and_trace::payload::{
     Action, AnswerProposalAction, CreateProposalAction, CreateRecordAction, FinalizeRecordAction,
     Response, RevokeReporterAction, TrackAndTracePayload, UpdatePropertiesAction,
@@ -40,9 +48,9 @@ use grid_sdk::protocol::track_and_trace::state::{
     ProposalListBuilder, RecordBuilder, ReportedValueBuilder, ReporterBuilder, Role, Status,
 };
 
+use grid_sdk::protocol::versioning::{payload_version, track_and_trace_payload_migrator, Migrator};
 use grid_sdk::protos::FromBytes;
 
-use crate::addressing::*;
 use crate::payload::validate_payload;
 use crate::state::TrackAndTraceState;
 
@@ -52,19 +60,22 @@ pub struct TrackAndTraceTransactionHandler {
     family_name: String,
     family_versions: Vec<String>,
     namespaces: Vec<String>,
+    payload_migrator: Migrator<String>,
 }
 
 impl TrackAndTraceTransactionHandler {
     #[allow(clippy::new_without_default)]
     pub fn new() -> TrackAndTraceTransactionHandler {
+        let payload_migrator = track_and_trace_payload_migrator();
         TrackAndTraceTransactionHandler {
-            family_name: "grid_track_and_trace".to_string(),
-            family_versions: vec!["1.0".to_string()],
+            family_name: TRACK_AND_TRACE_FAMILY_NAME.to_string(),
+            family_versions: payload_migrator.supported_versions(),
             namespaces: vec![
-                get_track_and_trace_prefix(),
-                get_pike_prefix(),
-                get_grid_prefix(),
+                TRACK_AND_TRACE_NAMESPACE.to_string(),
+                PIKE_NAMESPACE.to_string(),
+                GRID_NAMESPACE.to_string(),
             ],
+            payload_migrator,
         }
     }
 
@@ -93,7 +104,7 @@ impl TrackAndTraceTransactionHandler {
         }
 
         let schema_name = payload.schema();
-        let schema = match state.get_schema(schema_name)? {
+        let current_schema = match state.get_schema(schema_name)? {
             Some(schema) => schema,
             None => {
                 return Err(ApplyError::InvalidTransaction(format!(
@@ -103,46 +114,56 @@ impl TrackAndTraceTransactionHandler {
             }
         };
 
-        let mut type_schemata: HashMap<&str, PropertyDefinition> = HashMap::new();
-        let mut required_properties: HashMap<&str, PropertyDefinition> = HashMap::new();
-        let mut provided_properties: HashMap<&str, PropertyValue> = HashMap::new();
-        for property in schema.properties() {
-            type_schemata.insert(property.name(), property.clone());
-            if *property.required() {
-                required_properties.insert(property.name(), property.clone());
-            }
-        }
-
-        for property in payload.properties() {
-            provided_properties.insert(property.name(), property.clone());
+        // Existing records keep working on a deprecated schema, but no new
+        // record can be created with any of its versions
+        if *current_schema.deprecated() {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Schema {} is deprecated",
+                schema_name
+            )));
         }
 
-        for name in required_properties.keys() {
-            if !provided_properties.contains_key(name) {
-                return Err(ApplyError::InvalidTransaction(format!(
-                    "Required property {} not provided",
-                    name
-                )));
-            }
+        // Likewise, properties deprecated on the schema can still be updated
+        // on existing records, but new records cannot report them
+        if let Some(value) = payload.properties().iter().find(|value| {
+            current_schema
+                .properties()
+                .iter()
+                .any(|property| property.name() == value.name() && *property.deprecated())
+        }) {
+            return Err(ApplyError::InvalidTransaction(format!(
+                "Property {} of schema {} is deprecated",
+                value.name(),
+                schema_name
+            )));
         }
 
-        for (provided_name, provided_properties) in provided_properties.clone() {
-            let required_type = match type_schemata.get(provided_name) {
-                Some(required_type) => required_type.data_type(),
+        let schema = match payload.schema_version() {
+            0 => current_schema,
+            version => match state.get_schema_version(schema_name, *version)? {
+                Some(schema) => schema,
                 None => {
                     return Err(ApplyError::InvalidTransaction(format!(
-                        "Provided property {} is not in schema",
-                        provided_name
+                        "Version {} of schema {} does not exist",
+                        version, schema_name
                     )));
                 }
-            };
-            let provided_type = provided_properties.data_type();
-            if provided_type != required_type {
-                return Err(ApplyError::InvalidTransaction(format!(
-                    "Value provided for {} is the wrong type",
-                    provided_name
-                )));
-            };
+            },
+        };
+
+        if let Err(errors) = validate_schema_values(&schema, payload.properties()) {
+            return Err(validation_errors_to_apply_error(&errors));
+        }
+
+        let property_definitions = schema.effective_properties();
+        let mut type_schemata: HashMap<&str, PropertyDefinition> = HashMap::new();
+        let mut provided_properties: HashMap<&str, PropertyValue> = HashMap::new();
+        for property in &property_definitions {
+            type_schemata.insert(property.name(), property.clone());
+        }
+
+        for property in payload.properties() {
+            provided_properties.insert(property.name(), property.clone());
         }
 
         let owner = AssociatedAgentBuilder::new()
@@ -154,6 +175,7 @@ impl TrackAndTraceTransactionHandler {
         let new_record = RecordBuilder::new()
             .with_record_id(record_id.to_string())
             .with_schema(schema_name.to_string())
+            .with_schema_version(*schema.version())
             .with_field_final(false)
             .with_owners(vec![owner.clone()])
             .with_custodians(vec![owner.clone()])
@@ -244,7 +266,9 @@ impl TrackAndTraceTransactionHandler {
             }
         };
 
-        if owner.agent_id() != signer || custodian.agent_id() != signer {
+        if !state.is_same_agent(owner.agent_id(), signer)?
+            || !state.is_same_agent(custodian.agent_id(), signer)?
+        {
             return Err(ApplyError::InvalidTransaction(
                 "Must be owner and custodian to finalize record".to_string(),
             ));
@@ -297,7 +321,6 @@ impl TrackAndTraceTransactionHandler {
 
         for update in updates {
             let name = update.name();
-            let data_type = update.data_type();
 
             let prop = match state.get_property(record_id, name)? {
                 Some(prop) => prop,
@@ -312,7 +335,7 @@ impl TrackAndTraceTransactionHandler {
             let mut allowed = false;
             let mut reporter_index = 0;
             for reporter in prop.reporters() {
-                if reporter.public_key() == signer && *reporter.authorized() {
+                if *reporter.authorized() && state.is_same_agent(reporter.public_key(), signer)? {
                     allowed = true;
                     reporter_index = *reporter.index();
                     break;
@@ -325,12 +348,8 @@ impl TrackAndTraceTransactionHandler {
                 )));
             }
 
-            if data_type != prop.property_definition().data_type() {
-                return Err(ApplyError::InvalidTransaction(format!(
-                    "Update has wrong type: {:?} != {:?}",
-                    data_type,
-                    prop.property_definition().data_type()
-                )));
+            if let Err(errors) = validate_property_value(prop.property_definition(), update) {
+                return Err(validation_errors_to_apply_error(&errors));
             }
 
             let page_number = prop.current_page();
@@ -487,7 +506,7 @@ impl TrackAndTraceTransactionHandler {
                     )));
                 }
             };
-            if owner.agent_id() != signer {
+            if !state.is_same_agent(owner.agent_id(), signer)? {
                 return Err(ApplyError::InvalidTransaction(String::from(
                     "Only the owner can create a proposal to change ownership",
                 )));
@@ -504,7 +523,7 @@ impl TrackAndTraceTransactionHandler {
                 }
             };
 
-            if custodian.agent_id() != signer {
+            if !state.is_same_agent(custodian.agent_id(), signer)? {
                 return Err(ApplyError::InvalidTransaction(String::from(
                     "Only the custodian can create a proposal to change custodianship",
                 )));
@@ -520,7 +539,7 @@ impl TrackAndTraceTransactionHandler {
                     )));
                 }
             };
-            if owner.agent_id() != signer {
+            if !state.is_same_agent(owner.agent_id(), signer)? {
                 return Err(ApplyError::InvalidTransaction(String::from(
                     "Only the owner can create a proposal to authorize a reporter",
                 )));
@@ -609,7 +628,7 @@ impl TrackAndTraceTransactionHandler {
 
         match response {
             Response::Cancel => {
-                if current_proposal.issuing_agent() != signer {
+                if !state.is_same_agent(current_proposal.issuing_agent(), signer)? {
                     return Err(ApplyError::InvalidTransaction(String::from(
                         "Only the issuing agent can cancel a proposal",
                     )));
@@ -618,7 +637,7 @@ impl TrackAndTraceTransactionHandler {
             }
 
             Response::Reject => {
-                if current_proposal.receiving_agent() != signer {
+                if !state.is_same_agent(current_proposal.receiving_agent(), signer)? {
                     return Err(ApplyError::InvalidTransaction(String::from(
                         "Only the receiving agent can reject a proposal",
                     )));
@@ -627,7 +646,7 @@ impl TrackAndTraceTransactionHandler {
             }
 
             Response::Accept => {
-                if current_proposal.receiving_agent() != signer {
+                if !state.is_same_agent(current_proposal.receiving_agent(), signer)? {
                     return Err(ApplyError::InvalidTransaction(String::from(
                         "Only the receiving agent can accept a proposal",
                     )));
@@ -663,7 +682,9 @@ impl TrackAndTraceTransactionHandler {
 
                 match role {
                     Role::Owner => {
-                        if owner.agent_id() != current_proposal.issuing_agent() {
+                        if state.current_agent_key(owner.agent_id())?
+                            != state.current_agent_key(current_proposal.issuing_agent())?
+                        {
                             #[cfg(not(target_arch = "wasm32"))]
                             info!("Record owner does not match the issuing agent of the proposal");
 
@@ -689,7 +710,7 @@ impl TrackAndTraceTransactionHandler {
 
                             state.set_record(record_id, updated_record)?;
 
-                            let schema = match state.get_schema(proposal_record.schema())? {
+                            let schema = match state.get_record_schema(&proposal_record)? {
                                 Some(record_type) => record_type,
                                 None => {
                                     return Err(ApplyError::InvalidTransaction(format!(
@@ -699,7 +720,7 @@ impl TrackAndTraceTransactionHandler {
                                 }
                             };
 
-                            for prop_schema in schema.properties() {
+                            for prop_schema in schema.effective_properties() {
                                 let prop =
                                     match state.get_property(record_id, prop_schema.name())? {
                                         Some(prop) => prop,
@@ -774,7 +795,9 @@ impl TrackAndTraceTransactionHandler {
                         }
                     }
                     Role::Custodian => {
-                        if custodian.agent_id() != current_proposal.issuing_agent() {
+                        if state.current_agent_key(custodian.agent_id())?
+                            != state.current_agent_key(current_proposal.issuing_agent())?
+                        {
                             #[cfg(not(target_arch = "wasm32"))]
                             info!(
                                 "Record custodian does not match the issuing agent of the proposal"
@@ -806,7 +829,9 @@ impl TrackAndTraceTransactionHandler {
                         }
                     }
                     Role::Reporter => {
-                        if owner.agent_id() != current_proposal.issuing_agent() {
+                        if state.current_agent_key(owner.agent_id())?
+                            != state.current_agent_key(current_proposal.issuing_agent())?
+                        {
                             #[cfg(not(target_arch = "wasm32"))]
                             info!("Record owner does not match the issuing agent of the proposal");
 
@@ -908,7 +933,7 @@ impl TrackAndTraceTransactionHandler {
             }
         };
 
-        if owner.agent_id() != signer {
+        if !state.is_same_agent(owner.agent_id(), signer)? {
             return Err(ApplyError::InvalidTransaction(
                 "Must be owner to revoke reporters".to_string(),
             ));
@@ -977,6 +1002,10 @@ impl TrackAndTraceTransactionHandler {
     }
 }
 
+fn validation_errors_to_apply_error(errors: &[ValidationError]) -> ApplyError {
+    ApplyError::InvalidTransaction(validation_errors_to_message(errors))
+}
+
 fn map_builder_error_to_apply_error(err: BuilderError, protocol_name: &str) -> ApplyError {
     ApplyError::InvalidTransaction(format!(
         "Failed to build {}. {}",
@@ -1003,7 +1032,14 @@ impl TransactionHandler for TrackAndTraceTransactionHandler {
         request: &TpProcessRequest,
         context: &mut dyn TransactionContext,
     ) -> Result<(), ApplyError> {
-        let payload = TrackAndTracePayload::from_bytes(request.get_payload()).map_err(|err| {
+        let payload_bytes = self
+            .payload_migrator
+            .migrate(
+                &payload_version(request.get_header(), self.payload_migrator.current()),
+                request.get_payload(),
+            )
+            .map_err(|err| ApplyError::InvalidTransaction(format!("{}", err)))?;
+        let payload = TrackAndTracePayload::from_bytes(&payload_bytes).map_err(|err| {
             ApplyError::InvalidTransaction(format!("Cannot build track and trace payload: {}", err))
         })?;
 
@@ -1068,128 +1104,151 @@ pub unsafe fn entrypoint(payload: WasmPtr, signer: WasmPtr, signature: WasmPtr)
 mod tests {
     use super::*;
 
-    use std::cell::RefCell;
-    use std::collections::HashMap;
-
-    use grid_sdk::protocol::pike::state::{AgentBuilder, AgentListBuilder};
+    use grid_sdk::protocol::addressing::track_and_trace::{
+        compute_property_address, compute_proposal_address,
+    };
     use grid_sdk::protocol::schema::state::{
-        DataType, PropertyDefinitionBuilder, PropertyValueBuilder, SchemaBuilder, SchemaListBuilder,
+        DataType, PropertyDefinitionBuilder, PropertyValueBuilder,
     };
     use grid_sdk::protocol::track_and_trace::payload::{
         AnswerProposalActionBuilder, CreateProposalActionBuilder, CreateRecordActionBuilder,
-        FinalizeRecordActionBuilder, RevokeReporterActionBuilder, UpdatePropertiesAction,
-        UpdatePropertiesActionBuilder,
+        FinalizeRecordActionBuilder, RevokeReporterActionBuilder, TrackAndTracePayloadBuilder,
+        UpdatePropertiesAction, UpdatePropertiesActionBuilder,
     };
     use grid_sdk::protocol::track_and_trace::state::{
         Property, PropertyListBuilder, PropertyPage, PropertyPageListBuilder, Proposal, Record,
-        RecordListBuilder, Role, Status,
+        Role, Status,
     };
     use grid_sdk::protos::IntoBytes;
-    use sawtooth_sdk::processor::handler::{ContextError, TransactionContext};
+    use grid_sdk::test_kit::{
+        AgentFixture, MockTransactionContext, PropertyGroupFixture, RecordFixture, SchemaFixture,
+    };
 
     const TIMESTAMP: u64 = 1;
     const RECORD_ID: &str = "test_record_action";
     const PUBLIC_KEY: &str = "agent_public_key";
     const OPTIONAL_PROPERTY_NAME: &str = "test_optional";
     const REQUIRED_PROPERTY_NAME: &str = "test_required";
+    const ADDED_PROPERTY_NAME: &str = "test_added";
     const SCHEMA_NAME: &str = "test_schema";
 
-    #[derive(Default, Debug)]
-    /// A MockTransactionContext that can be used to test TrackAndTraceState
-    struct MockTransactionContext {
-        state: RefCell<HashMap<String, Vec<u8>>>,
-    }
+    /// Test helpers on the test-kit MockTransactionContext
+    trait TestContext {
+        /// Applies a payload through the TransactionHandler, declaring the inputs and outputs
+        /// computed from the payload, and checks that the handler only accessed declared
+        /// addresses and that it accessed every declared address.
+        fn apply_with_declared_addresses(&mut self, payload: TrackAndTracePayload, signer: &str);
 
-    impl TransactionContext for MockTransactionContext {
-        fn get_state_entries(
-            &self,
-            addresses: &[String],
-        ) -> Result<Vec<(String, Vec<u8>)>, ContextError> {
-            let mut results = Vec::new();
-            for addr in addresses {
-                let data = match self.state.borrow().get(addr) {
-                    Some(data) => data.clone(),
-                    None => Vec::new(),
-                };
-                results.push((addr.to_string(), data));
-            }
-            Ok(results)
-        }
+        fn add_agent(&self, public_key: &str);
 
-        fn set_state_entries(&self, entries: Vec<(String, Vec<u8>)>) -> Result<(), ContextError> {
-            for (addr, data) in entries {
-                self.state.borrow_mut().insert(addr, data);
-            }
-            Ok(())
-        }
+        fn add_schema(&self);
 
-        /// this is not needed for these tests
-        fn delete_state_entries(&self, _addresses: &[String]) -> Result<Vec<String>, ContextError> {
-            unimplemented!()
-        }
+        /// Updates the schema to version 2, which adds a property to the first version
+        fn add_schema_version_2(&self);
 
-        /// this is not needed for these tests
-        fn add_receipt_data(&self, _data: &[u8]) -> Result<(), ContextError> {
-            unimplemented!()
-        }
+        fn add_constrained_schema(&self);
 
-        /// this is not needed for these tests
-        fn add_event(
+        fn add_deprecated_schema(&self);
+
+        /// Adds the schema with its optional property deprecated
+        fn add_schema_deprecated_property(&self);
+
+        fn add_record(&self);
+
+        fn add_property(&self, property_name: &str, property_definition: PropertyDefinition);
+
+        fn add_property_page(&self, property_name: &str, property_value: PropertyValue);
+
+        fn add_finalized_record(&self);
+
+        fn add_proposal(
             &self,
-            _event_type: String,
-            _attributes: Vec<(String, String)>,
-            _data: &[u8],
-        ) -> Result<(), ContextError> {
-            unimplemented!()
-        }
+            issuing_agent: &str,
+            receiving_agent_key: &str,
+            role: Role,
+            status: Status,
+        );
+
+        fn add_property_with_reporter(
+            &self,
+            property_name: &str,
+            reporter_key: &str,
+            authorized: bool,
+            property_definition: PropertyDefinition,
+        );
     }
 
-    impl MockTransactionContext {
-        fn add_agent(&self, public_key: &str) {
-            let builder = AgentBuilder::new();
-            let agent = builder
-                .with_org_id("test_org".to_string())
-                .with_public_key(public_key.to_string())
-                .with_active(true)
-                .with_roles(vec![])
-                .build()
-                .unwrap();
+    impl TestContext for MockTransactionContext {
+        fn apply_with_declared_addresses(&mut self, payload: TrackAndTracePayload, signer: &str) {
+            self.apply(&TrackAndTraceTransactionHandler::new(), &payload, signer)
+                .expect("Failed to apply payload");
+            self.assert_accesses_declared();
+        }
 
-            let builder = AgentListBuilder::new();
-            let agent_list = builder.with_agents(vec![agent.clone()]).build().unwrap();
-            let agent_bytes = agent_list.into_bytes().unwrap();
-            let agent_address = make_agent_address(public_key);
-            self.set_state_entry(agent_address, agent_bytes).unwrap();
+        fn add_agent(&self, public_key: &str) {
+            AgentFixture::new(public_key, "test_org").add_to(self);
         }
 
         fn add_schema(&self) {
-            let builder = SchemaBuilder::new();
-            let schema = builder
-                .with_name(SCHEMA_NAME.to_string())
-                .with_description("Test Schema".to_string())
-                .with_owner("test_org".to_string())
-                .with_properties(vec![
-                    optional_property_definition(),
-                    required_property_definition(),
-                ])
-                .build()
-                .unwrap();
-
-            let builder = SchemaListBuilder::new();
-            let schema_list = builder.with_schemas(vec![schema]).build().unwrap();
-            let schema_bytes = schema_list.into_bytes().unwrap();
-            let schema_address = make_schema_address(SCHEMA_NAME);
-            self.set_state_entry(schema_address, schema_bytes).unwrap();
+            SchemaFixture::new(SCHEMA_NAME, "test_org")
+                .with_description("Test Schema")
+                .with_property(optional_property_definition())
+                .with_property(required_property_definition())
+                .add_to(self);
+        }
+
+        fn add_schema_version_2(&self) {
+            SchemaFixture::new(SCHEMA_NAME, "test_org")
+                .with_description("Test Schema")
+                .with_version(2)
+                .with_property(optional_property_definition())
+                .with_property(required_property_definition())
+                .with_property(
+                    PropertyDefinitionBuilder::new()
+                        .with_name(ADDED_PROPERTY_NAME.to_string())
+                        .with_data_type(DataType::String)
+                        .build()
+                        .expect("Failed to build property definition"),
+                )
+                .add_to(self);
+        }
+
+        fn add_constrained_schema(&self) {
+            SchemaFixture::new(SCHEMA_NAME, "test_org")
+                .with_description("Test Schema")
+                .with_property(optional_property_definition())
+                .with_property(constrained_property_definition())
+                .add_to(self);
+        }
+
+        fn add_deprecated_schema(&self) {
+            SchemaFixture::new(SCHEMA_NAME, "test_org")
+                .with_description("Test Schema")
+                .deprecated()
+                .with_property(optional_property_definition())
+                .with_property(required_property_definition())
+                .add_to(self);
+        }
+
+        fn add_schema_deprecated_property(&self) {
+            SchemaFixture::new(SCHEMA_NAME, "test_org")
+                .with_description("Test Schema")
+                .with_version(2)
+                .with_property(
+                    optional_property_definition()
+                        .into_builder()
+                        .with_deprecated(true)
+                        .build()
+                        .expect("Failed to build property definition"),
+                )
+                .with_property(required_property_definition())
+                .add_to(self);
         }
 
         fn add_record(&self) {
-            let record_list = RecordListBuilder::new()
-                .with_records(vec![make_record()])
-                .build()
-                .unwrap();
-            let record_bytes = record_list.into_bytes().unwrap();
-            let record_address = make_record_address(RECORD_ID);
-            self.set_state_entry(record_address, record_bytes).unwrap();
+            RecordFixture::new(RECORD_ID, SCHEMA_NAME, PUBLIC_KEY)
+                .with_timestamp(TIMESTAMP)
+                .add_to(self);
         }
 
         fn add_property(&self, property_name: &str, property_definition: PropertyDefinition) {
@@ -1199,9 +1258,8 @@ mod tests {
                 .unwrap();
 
             let property_list_bytes = property_list.into_bytes().unwrap();
-            let property_list_address = make_property_address(RECORD_ID, property_name, 0);
-            self.set_state_entry(property_list_address, property_list_bytes)
-                .unwrap();
+            let property_list_address = compute_property_address(RECORD_ID, property_name, 0);
+            self.set_entry(property_list_address, property_list_bytes);
         }
 
         fn add_property_page(&self, property_name: &str, property_value: PropertyValue) {
@@ -1213,34 +1271,15 @@ mod tests {
             let property_page_list_bytes = property_page_list
                 .into_bytes()
                 .expect("Failed to write page list to bytes");
-            let address = make_property_address(RECORD_ID, property_name, 1);
-            self.set_state_entry(address, property_page_list_bytes)
-                .expect("Failed to set state");
+            let address = compute_property_address(RECORD_ID, property_name, 1);
+            self.set_entry(address, property_page_list_bytes);
         }
 
         fn add_finalized_record(&self) {
-            let associated_agent = AssociatedAgentBuilder::new()
-                .with_agent_id(PUBLIC_KEY.to_string())
+            RecordFixture::new(RECORD_ID, SCHEMA_NAME, PUBLIC_KEY)
                 .with_timestamp(TIMESTAMP)
-                .build()
-                .expect("Failed to build associated agent");
-
-            let record = RecordBuilder::new()
-                .with_record_id(RECORD_ID.to_string())
-                .with_schema(SCHEMA_NAME.to_string())
-                .with_owners(vec![associated_agent.clone()])
-                .with_custodians(vec![associated_agent.clone()])
-                .with_field_final(true)
-                .build()
-                .expect("Failed to build new_record");
-
-            let record_list = RecordListBuilder::new()
-                .with_records(vec![record])
-                .build()
-                .unwrap();
-            let record_bytes = record_list.into_bytes().unwrap();
-            let record_address = make_record_address(RECORD_ID);
-            self.set_state_entry(record_address, record_bytes).unwrap();
+                .finalized()
+                .add_to(self);
         }
 
         fn add_proposal(
@@ -1260,9 +1299,8 @@ mod tests {
                 .build()
                 .unwrap();
             let proposal_list_bytes = proposal_list.into_bytes().unwrap();
-            let proposal_list_address = make_proposal_address(RECORD_ID, receiving_agent_key);
-            self.set_state_entry(proposal_list_address, proposal_list_bytes)
-                .unwrap();
+            let proposal_list_address = compute_proposal_address(RECORD_ID, receiving_agent_key);
+            self.set_entry(proposal_list_address, proposal_list_bytes);
         }
 
         fn add_property_with_reporter(
@@ -1284,9 +1322,8 @@ mod tests {
                 .unwrap();
 
             let property_list_bytes = property_list.into_bytes().unwrap();
-            let property_list_address = make_property_address(RECORD_ID, property_name, 0);
-            self.set_state_entry(property_list_address, property_list_bytes)
-                .unwrap();
+            let property_list_address = compute_property_address(RECORD_ID, property_name, 0);
+            self.set_entry(property_list_address, property_list_bytes);
         }
     }
 
@@ -1413,6 +1450,85 @@ mod tests {
         }
     }
 
+    #[test]
+    /// Test that the CreateRecordAction is invalid if the schema is deprecated, even when the
+    /// record is pinned to one of its versions.
+    fn test_create_record_schema_deprecated() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_deprecated_schema();
+        transaction_context.add_agent(PUBLIC_KEY);
+
+        let mut state = TrackAndTraceState::new(&mut transaction_context);
+
+        let transaction_handler = TrackAndTraceTransactionHandler::new();
+
+        let properties = vec![optional_property_value(), required_property_value()];
+        let pinned_action = CreateRecordActionBuilder::new()
+            .with_record_id(RECORD_ID.to_string())
+            .with_schema(SCHEMA_NAME.to_string())
+            .with_schema_version(1)
+            .with_properties(properties.clone())
+            .build()
+            .expect("Failed to build CreateRecordAction");
+
+        for create_record_action in &[
+            create_record_action_with_properties(properties),
+            pinned_action,
+        ] {
+            match transaction_handler._create_record(
+                create_record_action,
+                &mut state,
+                PUBLIC_KEY,
+                TIMESTAMP,
+            ) {
+                Ok(()) => panic!("Schema is deprecated, InvalidTransaction should be returned"),
+                Err(ApplyError::InvalidTransaction(err)) => {
+                    assert!(err.contains(&format!("Schema {} is deprecated", SCHEMA_NAME)));
+                }
+                Err(err) => panic!("Should have gotten invalid error but got {}", err),
+            }
+        }
+    }
+
+    #[test]
+    /// Test that a CreateRecordAction is invalid if it reports a property deprecated on the
+    /// schema, and valid without it.
+    fn test_create_record_property_deprecated() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema_deprecated_property();
+        transaction_context.add_agent(PUBLIC_KEY);
+
+        let mut state = TrackAndTraceState::new(&mut transaction_context);
+
+        let transaction_handler = TrackAndTraceTransactionHandler::new();
+
+        let create_record_action = create_record_action_with_properties(vec![
+            optional_property_value(),
+            required_property_value(),
+        ]);
+        match transaction_handler._create_record(
+            &create_record_action,
+            &mut state,
+            PUBLIC_KEY,
+            TIMESTAMP,
+        ) {
+            Ok(()) => panic!("Property is deprecated, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains(&format!(
+                    "Property {} of schema {} is deprecated",
+                    OPTIONAL_PROPERTY_NAME, SCHEMA_NAME
+                )));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+
+        let create_record_action =
+            create_record_action_with_properties(vec![required_property_value()]);
+        transaction_handler
+            ._create_record(&create_record_action, &mut state, PUBLIC_KEY, TIMESTAMP)
+            .expect("Record without the deprecated property should be created");
+    }
+
     #[test]
     /// Test that if the CreateRecordAction is invalid if the a record with the same id
     /// already exists.
@@ -1562,6 +1678,199 @@ mod tests {
         }
     }
 
+    #[test]
+    /// Test that the CreateRecordAction is invalid if a property value does not satisfy the
+    /// constraints of its property definition.
+    fn test_create_record_property_value_violates_constraints() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_constrained_schema();
+        transaction_context.add_agent(PUBLIC_KEY);
+        let mut state = TrackAndTraceState::new(&mut transaction_context);
+
+        let transaction_handler = TrackAndTraceTransactionHandler::new();
+
+        let create_record_action = create_record_action_with_properties(vec![
+            optional_property_value(),
+            required_property_value(),
+        ]);
+
+        match transaction_handler._create_record(
+            &create_record_action,
+            &mut state,
+            PUBLIC_KEY,
+            TIMESTAMP,
+        ) {
+            Ok(()) => panic!(
+                "A property value violates its constraints, InvalidTransaction should be returned"
+            ),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains(&format!(
+                    "String value provided for {} does not match pattern",
+                    REQUIRED_PROPERTY_NAME
+                )));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    #[test]
+    /// Test that a record is pinned to the current version of its schema by default, or to the
+    /// version given by the CreateRecordAction, and only gets the properties of that version.
+    fn test_create_record_pinned_schema_version() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_schema_version_2();
+        transaction_context.add_agent(PUBLIC_KEY);
+        let mut state = TrackAndTraceState::new(&mut transaction_context);
+
+        let transaction_handler = TrackAndTraceTransactionHandler::new();
+        let create_record_action = CreateRecordActionBuilder::new()
+            .with_record_id(RECORD_ID.to_string())
+            .with_schema(SCHEMA_NAME.to_string())
+            .with_schema_version(1)
+            .with_properties(vec![required_property_value()])
+            .build()
+            .expect("Failed to build CreateRecordAction");
+
+        assert!(transaction_handler
+            ._create_record(&create_record_action, &mut state, PUBLIC_KEY, TIMESTAMP)
+            .is_ok());
+
+        let record = state
+            .get_record(RECORD_ID)
+            .expect("Failed to fetch record")
+            .expect("No record found");
+        assert_eq!(record, make_record());
+        assert!(state
+            .get_property(RECORD_ID, ADDED_PROPERTY_NAME)
+            .expect("Failed to fetch added property")
+            .is_none());
+
+        let create_record_action = CreateRecordActionBuilder::new()
+            .with_record_id("current_record".to_string())
+            .with_schema(SCHEMA_NAME.to_string())
+            .with_properties(vec![required_property_value()])
+            .build()
+            .expect("Failed to build CreateRecordAction");
+
+        assert!(transaction_handler
+            ._create_record(&create_record_action, &mut state, PUBLIC_KEY, TIMESTAMP)
+            .is_ok());
+
+        let record = state
+            .get_record("current_record")
+            .expect("Failed to fetch record")
+            .expect("No record found");
+        assert_eq!(*record.schema_version(), 2);
+        assert!(state
+            .get_property("current_record", ADDED_PROPERTY_NAME)
+            .expect("Failed to fetch added property")
+            .is_some());
+    }
+
+    #[test]
+    /// Test that a record of a schema composed from a parent schema and a property group gets
+    /// the inherited properties, and is validated against them.
+    fn test_create_record_composed_schema() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_agent(PUBLIC_KEY);
+        let parent = SchemaFixture::new("parent_schema", "test_org")
+            .with_property(required_property_definition());
+        parent.add_to(&transaction_context);
+        let property_group = PropertyGroupFixture::new("test_group", "test_org").with_property(
+            PropertyDefinitionBuilder::new()
+                .with_name(ADDED_PROPERTY_NAME.to_string())
+                .with_data_type(DataType::String)
+                .build()
+                .expect("Failed to build property definition"),
+        );
+        property_group.add_to(&transaction_context);
+        SchemaFixture::new(SCHEMA_NAME, "test_org")
+            .extending(&parent)
+            .with_property_group(&property_group)
+            .with_property(optional_property_definition())
+            .add_to(&transaction_context);
+        let mut state = TrackAndTraceState::new(&mut transaction_context);
+
+        let transaction_handler = TrackAndTraceTransactionHandler::new();
+
+        let create_record_action =
+            create_record_action_with_properties(vec![optional_property_value()]);
+
+        match transaction_handler._create_record(
+            &create_record_action,
+            &mut state,
+            PUBLIC_KEY,
+            TIMESTAMP,
+        ) {
+            Ok(()) => panic!("Required property is missing, InvalidTransaction should be returned"),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains(&format!(
+                    "Required property {} not provided",
+                    REQUIRED_PROPERTY_NAME
+                )));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+
+        let create_record_action = create_record_action_with_properties(vec![
+            optional_property_value(),
+            required_property_value(),
+        ]);
+
+        assert!(transaction_handler
+            ._create_record(&create_record_action, &mut state, PUBLIC_KEY, TIMESTAMP)
+            .is_ok());
+
+        for property_name in &[
+            REQUIRED_PROPERTY_NAME,
+            ADDED_PROPERTY_NAME,
+            OPTIONAL_PROPERTY_NAME,
+        ] {
+            assert!(state
+                .get_property(RECORD_ID, property_name)
+                .expect("Failed to fetch property")
+                .is_some());
+        }
+    }
+
+    #[test]
+    /// Test that if the CreateRecordAction is invalid if the version of the schema does not
+    /// exist.
+    fn test_create_record_schema_version_does_not_exist() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_agent(PUBLIC_KEY);
+        let mut state = TrackAndTraceState::new(&mut transaction_context);
+
+        let transaction_handler = TrackAndTraceTransactionHandler::new();
+        let create_record_action = CreateRecordActionBuilder::new()
+            .with_record_id(RECORD_ID.to_string())
+            .with_schema(SCHEMA_NAME.to_string())
+            .with_schema_version(2)
+            .with_properties(vec![required_property_value()])
+            .build()
+            .expect("Failed to build CreateRecordAction");
+
+        match transaction_handler._create_record(
+            &create_record_action,
+            &mut state,
+            PUBLIC_KEY,
+            TIMESTAMP,
+        ) {
+            Ok(()) => {
+                panic!("Schema version does not exist, InvalidTransaction should be returned")
+            }
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains(&format!(
+                    "Version 2 of schema {} does not exist",
+                    SCHEMA_NAME
+                )));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
     #[test]
     /// Test that if the FinalizeRecordAction is valid an OK is returned and that the record is
     /// marked as finalized
@@ -1838,7 +2147,8 @@ mod tests {
             Ok(()) => panic!("Record is does not exist, InvalidTransaction should be returned"),
             Err(ApplyError::InvalidTransaction(err)) => {
                 assert!(err.contains(&format!(
-                    "Update has wrong type: {:?} != {:?}",
+                    "Value provided for {} is the wrong type: {:?} != {:?}",
+                    REQUIRED_PROPERTY_NAME,
                     DataType::Number,
                     DataType::String
                 )));
@@ -1847,6 +2157,68 @@ mod tests {
         }
     }
 
+    #[test]
+    /// Test that the UpdatedPropertiesAction fails if the updated value does not satisfy the
+    /// constraints of the property definition.
+    fn test_update_properties_property_value_violates_constraints() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_constrained_schema();
+        transaction_context.add_agent(PUBLIC_KEY);
+        transaction_context.add_record();
+        transaction_context.add_property(REQUIRED_PROPERTY_NAME, constrained_property_definition());
+
+        let mut state = TrackAndTraceState::new(&mut transaction_context);
+
+        let property_value_too_long = PropertyValueBuilder::new()
+            .with_name(REQUIRED_PROPERTY_NAME.to_string())
+            .with_data_type(DataType::String)
+            .with_string_value("ABC1234567".to_string())
+            .build()
+            .expect("Failed to build property value");
+
+        let transaction_handler = TrackAndTraceTransactionHandler::new();
+        let update_property_action = update_property_action(vec![property_value_too_long]);
+
+        match transaction_handler._update_properties(
+            &update_property_action,
+            &mut state,
+            PUBLIC_KEY,
+            TIMESTAMP,
+        ) {
+            Ok(()) => panic!(
+                "The property value violates its constraints, InvalidTransaction should be returned"
+            ),
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains(&format!(
+                    "String value provided for {} has length 10",
+                    REQUIRED_PROPERTY_NAME
+                )));
+            }
+            Err(err) => panic!("Should have gotten invalid error but got {}", err),
+        }
+    }
+
+    #[test]
+    /// Test that the properties of an existing record can still be updated once its schema is
+    /// deprecated.
+    fn test_update_properties_schema_deprecated() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_deprecated_schema();
+        transaction_context.add_agent(PUBLIC_KEY);
+        transaction_context.add_record();
+        transaction_context.add_property(REQUIRED_PROPERTY_NAME, required_property_definition());
+        transaction_context.add_property_page(REQUIRED_PROPERTY_NAME, required_property_value());
+
+        let mut state = TrackAndTraceState::new(&mut transaction_context);
+
+        let transaction_handler = TrackAndTraceTransactionHandler::new();
+        let update_property_action = update_property_action(vec![updated_property_value()]);
+
+        assert!(transaction_handler
+            ._update_properties(&update_property_action, &mut state, PUBLIC_KEY, TIMESTAMP)
+            .is_ok());
+    }
+
     #[test]
     /// Test that if the UpdatedPropertiesAction start new PropertyPage when needed.
     fn test_update_properties_new_page() {
@@ -2374,6 +2746,48 @@ mod tests {
         assert_eq!(required_property, expected_property);
     }
 
+    #[test]
+    /// Test that when an owner proposal is accepted for a record pinned to a previous version of
+    /// its schema, the new owner reports the properties of the pinned version, and the properties
+    /// added by later versions are not required.
+    fn test_answer_proposal_accept_owner_pinned_schema_version() {
+        let mut transaction_context = MockTransactionContext::default();
+        let receiving_agent_key = "receiving_agent_key";
+        transaction_context.add_agent(PUBLIC_KEY);
+        transaction_context.add_agent(receiving_agent_key);
+        transaction_context.add_record();
+        transaction_context.add_schema();
+        transaction_context.add_schema_version_2();
+        transaction_context.add_property(OPTIONAL_PROPERTY_NAME, optional_property_definition());
+        transaction_context.add_property(REQUIRED_PROPERTY_NAME, required_property_definition());
+        transaction_context.add_proposal(
+            PUBLIC_KEY,
+            receiving_agent_key,
+            Role::Owner,
+            Status::Open,
+        );
+
+        transaction_context.apply_with_declared_addresses(
+            make_payload(Action::AnswerProposal(answer_proposal_action(
+                Role::Owner,
+                receiving_agent_key,
+                Response::Accept,
+            ))),
+            receiving_agent_key,
+        );
+
+        let required_property = transaction_context
+            .get_property(RECORD_ID, REQUIRED_PROPERTY_NAME)
+            .expect("Required property not found");
+        assert!(
+            required_property
+                .reporters()
+                .iter()
+                .any(|reporter| reporter.public_key() == receiving_agent_key
+                    && *reporter.authorized())
+        );
+    }
+
     #[test]
     /// Test that when the AnswerProposalAction, with Reponse set to Accept and Role set to Custodian,
     /// is valid an OK is returned and the proposal is updated to have status Accepted, the custodians
@@ -3145,6 +3559,210 @@ mod tests {
         }
     }
 
+    fn make_payload(action: Action) -> TrackAndTracePayload {
+        TrackAndTracePayloadBuilder::new()
+            .with_action(action)
+            .with_timestamp(TIMESTAMP)
+            .build()
+            .expect("Failed to build TrackAndTracePayload")
+    }
+
+    #[test]
+    /// Test that the addresses declared by a CreateRecord payload are the ones accessed
+    fn test_create_record_declared_addresses() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_schema();
+        transaction_context.add_agent(PUBLIC_KEY);
+
+        transaction_context.apply_with_declared_addresses(
+            make_payload(Action::CreateRecord(create_record_action_with_properties(
+                vec![required_property_value()],
+            ))),
+            PUBLIC_KEY,
+        );
+    }
+
+    #[test]
+    /// Test that the addresses declared by a FinalizeRecord payload are the ones accessed
+    fn test_finalize_record_declared_addresses() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_record();
+
+        transaction_context.apply_with_declared_addresses(
+            make_payload(Action::FinalizeRecord(create_finalize_record())),
+            PUBLIC_KEY,
+        );
+    }
+
+    #[test]
+    /// Test that the addresses declared by an UpdateProperties payload are the ones accessed,
+    /// including when a new property page is started
+    fn test_update_properties_declared_addresses() {
+        let mut transaction_context = MockTransactionContext::default();
+        transaction_context.add_record();
+        transaction_context.add_property(REQUIRED_PROPERTY_NAME, required_property_definition());
+        transaction_context.add_property_page(REQUIRED_PROPERTY_NAME, required_property_value());
+
+        let updates = std::iter::repeat(updated_property_value())
+            .take(PROPERTY_PAGE_MAX_LENGTH)
+            .collect::<Vec<_>>();
+        transaction_context.apply_with_declared_addresses(
+            make_payload(Action::UpdateProperties(update_property_action(updates))),
+            PUBLIC_KEY,
+        );
+    }
+
+    #[test]
+    /// Test that the addresses declared by a CreateProposal payload are the ones accessed
+    fn test_create_proposal_declared_addresses() {
+        let mut transaction_context = MockTransactionContext::default();
+        let receiving_agent_key = "receiving_agent_key";
+        transaction_context.add_agent(PUBLIC_KEY);
+        transaction_context.add_agent(receiving_agent_key);
+        transaction_context.add_record();
+
+        transaction_context.apply_with_declared_addresses(
+            make_payload(Action::CreateProposal(create_proposal_action(
+                Role::Owner,
+                receiving_agent_key,
+            ))),
+            PUBLIC_KEY,
+        );
+    }
+
+    #[test]
+    /// Test that the addresses declared by AnswerProposal payloads are the ones accessed, for
+    /// every role of an accepted proposal and for a rejected proposal
+    fn test_answer_proposal_declared_addresses() {
+        let receiving_agent_key = "receiving_agent_key";
+        let answers = vec![
+            (Role::Owner, Response::Accept),
+            (Role::Custodian, Response::Accept),
+            (Role::Reporter, Response::Accept),
+            (Role::Owner, Response::Reject),
+        ];
+
+        for (role, response) in answers {
+            let mut transaction_context = MockTransactionContext::default();
+            transaction_context.add_agent(PUBLIC_KEY);
+            transaction_context.add_agent(receiving_agent_key);
+            transaction_context.add_record();
+            transaction_context.add_schema();
+            transaction_context
+                .add_property(OPTIONAL_PROPERTY_NAME, optional_property_definition());
+            transaction_context
+                .add_property(REQUIRED_PROPERTY_NAME, required_property_definition());
+            transaction_context.add_proposal(
+                PUBLIC_KEY,
+                receiving_agent_key,
+                role.clone(),
+                Status::Open,
+            );
+
+            transaction_context.apply_with_declared_addresses(
+                make_payload(Action::AnswerProposal(answer_proposal_action(
+                    role,
+                    receiving_agent_key,
+                    response,
+                ))),
+                receiving_agent_key,
+            );
+        }
+    }
+
+    #[test]
+    /// Test that the addresses declared by a RevokeReporter payload are the ones accessed
+    fn test_revoke_reporter_declared_addresses() {
+        let mut transaction_context = MockTransactionContext::default();
+        let reporter_key = "reporter_key";
+        transaction_context.add_record();
+        transaction_context.add_property_with_reporter(
+            REQUIRED_PROPERTY_NAME,
+            reporter_key,
+            true,
+            required_property_definition(),
+        );
+
+        transaction_context.apply_with_declared_addresses(
+            make_payload(Action::RevokeReporter(revoke_reporter_action(
+                reporter_key,
+                vec![REQUIRED_PROPERTY_NAME.to_string()],
+            ))),
+            PUBLIC_KEY,
+        );
+    }
+
+    #[test]
+    /// Test that once the owner's key is rotated, the new key finalizes the record, and the
+    /// former key no longer can
+    fn test_finalize_record_rotated_owner_key() {
+        let mut transaction_context = MockTransactionContext::default();
+        let new_key = "new_agent_public_key";
+        transaction_context.add_record();
+        AgentFixture::new(PUBLIC_KEY, "test_org")
+            .rotated_to(new_key)
+            .add_to(&transaction_context);
+        transaction_context.add_agent(new_key);
+
+        let result = transaction_context.apply(
+            &TrackAndTraceTransactionHandler::new(),
+            &make_payload(Action::FinalizeRecord(create_finalize_record())),
+            PUBLIC_KEY,
+        );
+        match result {
+            Err(ApplyError::InvalidTransaction(err)) => {
+                assert!(err.contains("Must be owner and custodian to finalize record"));
+            }
+            res => panic!("Expected InvalidTransaction, got {:?}", res),
+        }
+
+        transaction_context.apply_with_declared_addresses(
+            make_payload(Action::FinalizeRecord(create_finalize_record())),
+            new_key,
+        );
+        let record = transaction_context
+            .get_record(RECORD_ID)
+            .expect("Record not found");
+        assert!(record.field_final());
+    }
+
+    #[test]
+    /// Test that a proposal issued with the rotated key of the custodian is accepted, although
+    /// the record stores the custodian's former key
+    fn test_answer_proposal_rotated_custodian_key() {
+        let mut transaction_context = MockTransactionContext::default();
+        let receiving_agent_key = "receiving_agent_key";
+        let new_key = "new_agent_public_key";
+        AgentFixture::new(PUBLIC_KEY, "test_org")
+            .rotated_to(new_key)
+            .add_to(&transaction_context);
+        transaction_context.add_agent(new_key);
+        transaction_context.add_agent(receiving_agent_key);
+        transaction_context.add_record();
+        transaction_context.add_proposal(
+            new_key,
+            receiving_agent_key,
+            Role::Custodian,
+            Status::Open,
+        );
+
+        transaction_context.apply_with_declared_addresses(
+            make_payload(Action::AnswerProposal(answer_proposal_action(
+                Role::Custodian,
+                receiving_agent_key,
+                Response::Accept,
+            ))),
+            receiving_agent_key,
+        );
+        let record = transaction_context
+            .get_record(RECORD_ID)
+            .expect("Record not found");
+        assert_eq!(
+            record.custodians().last().unwrap().agent_id(),
+            receiving_agent_key
+        );
+    }
+
     fn optional_property_value() -> PropertyValue {
         PropertyValueBuilder::new()
             .with_name(OPTIONAL_PROPERTY_NAME.to_string())
@@ -3271,6 +3889,18 @@ mod tests {
             .expect("Failed to build property definition")
     }
 
+    fn constrained_property_definition() -> PropertyDefinition {
+        PropertyDefinitionBuilder::new()
+            .with_name(REQUIRED_PROPERTY_NAME.to_string())
+            .with_data_type(DataType::String)
+            .with_description("Required".to_string())
+            .with_required(true)
+            .with_string_pattern(r"^[A-Z]{3}\d{6}$".to_string())
+            .with_string_max_length(9)
+            .build()
+            .expect("Failed to build property definition")
+    }
+
     fn make_record() -> Record {
         let associated_agent = AssociatedAgentBuilder::new()
             .with_agent_id(PUBLIC_KEY.to_string())
@@ -3281,6 +3911,7 @@ mod tests {
         RecordBuilder::new()
             .with_record_id(RECORD_ID.to_string())
             .with_schema(SCHEMA_NAME.to_string())
+            .with_schema_version(1)
             .with_owners(vec![associated_agent.clone()])
             .with_custodians(vec![associated_agent.clone()])
             .with_field_final(false)
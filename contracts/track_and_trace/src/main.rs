@@ -30,7 +30,6 @@ cfg_if! {
     }
 }
 
-mod addressing;
 pub mod handler;
 mod payload;
 mod state;
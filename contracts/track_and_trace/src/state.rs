@@ -22,6 +22,14 @@ cfg_if! {
     }
 }
 
+use grid_sdk::permissions::PermissionChecker;
+use grid_sdk::protocol::addressing::pike::compute_agent_address;
+use grid_sdk::protocol::addressing::schema::{
+    compute_schema_address, compute_schema_version_address,
+};
+use grid_sdk::protocol::addressing::track_and_trace::{
+    compute_property_address, compute_proposal_address, compute_record_address,
+};
 use grid_sdk::protocol::pike::state::{Agent, AgentList};
 use grid_sdk::protocol::schema::state::{Schema, SchemaList};
 use grid_sdk::protocol::track_and_trace::state::{
@@ -31,8 +39,6 @@ use grid_sdk::protocol::track_and_trace::state::{
 
 use grid_sdk::protos::{FromBytes, IntoBytes};
 
-use crate::addressing::*;
-
 pub struct TrackAndTraceState<'a> {
     context: &'a mut dyn TransactionContext,
 }
@@ -43,7 +49,7 @@ impl<'a> TrackAndTraceState<'a> {
     }
 
     pub fn get_record(&self, record_id: &str) -> Result<Option<Record>, ApplyError> {
-        let address = make_record_address(record_id);
+        let address = compute_record_address(record_id);
         let d = self.context.get_state_entry(&address)?;
         match d {
             Some(packed) => {
@@ -70,7 +76,7 @@ impl<'a> TrackAndTraceState<'a> {
     }
 
     pub fn set_record(&self, record_id: &str, record: Record) -> Result<(), ApplyError> {
-        let address = make_record_address(record_id);
+        let address = compute_record_address(record_id);
         let d = self.context.get_state_entry(&address)?;
         let mut records = match d {
             Some(packed) => match RecordList::from_bytes(packed.as_slice()) {
@@ -121,8 +127,36 @@ impl<'a> TrackAndTraceState<'a> {
     }
 
     pub fn get_schema(&self, schema_name: &str) -> Result<Option<Schema>, ApplyError> {
-        let address = make_schema_address(schema_name);
-        let d = self.context.get_state_entry(&address)?;
+        self.get_schema_at(&compute_schema_address(schema_name), schema_name)
+    }
+
+    /// Gets an immutable version of a Grid Schema
+    pub fn get_schema_version(
+        &self,
+        schema_name: &str,
+        version: u32,
+    ) -> Result<Option<Schema>, ApplyError> {
+        self.get_schema_at(
+            &compute_schema_version_address(schema_name, version),
+            schema_name,
+        )
+    }
+
+    /// Gets the version of the schema a record is pinned to. Records created before schemas
+    /// were versioned use the current schema.
+    pub fn get_record_schema(&self, record: &Record) -> Result<Option<Schema>, ApplyError> {
+        match record.schema_version() {
+            0 => self.get_schema(record.schema()),
+            version => self.get_schema_version(record.schema(), *version),
+        }
+    }
+
+    fn get_schema_at(
+        &self,
+        address: &str,
+        schema_name: &str,
+    ) -> Result<Option<Schema>, ApplyError> {
+        let d = self.context.get_state_entry(address)?;
         match d {
             Some(packed) => {
                 let schemas = match SchemaList::from_bytes(packed.as_slice()) {
@@ -149,7 +183,7 @@ impl<'a> TrackAndTraceState<'a> {
 
     /// Gets a Pike Agent. Handles retrieving the correct agent from an AgentList.
     pub fn get_agent(&self, public_key: &str) -> Result<Option<Agent>, ApplyError> {
-        let address = make_agent_address(public_key);
+        let address = compute_agent_address(public_key);
         let d = self.context.get_state_entry(&address)?;
         match d {
             Some(packed) => {
@@ -175,12 +209,28 @@ impl<'a> TrackAndTraceState<'a> {
         }
     }
 
+    /// Returns the key currently holding the identity recorded as
+    /// `public_key`, following the keys its agent was rotated to
+    pub fn current_agent_key(&self, public_key: &str) -> Result<String, ApplyError> {
+        PermissionChecker::new(&*self.context)
+            .current_key(public_key)
+            .map_err(|err| {
+                ApplyError::InternalError(format!("Cannot resolve agent identity: {}", err))
+            })
+    }
+
+    /// Returns whether `public_key` currently holds the identity recorded as
+    /// `recorded_key`
+    pub fn is_same_agent(&self, recorded_key: &str, public_key: &str) -> Result<bool, ApplyError> {
+        Ok(self.current_agent_key(recorded_key)? == public_key)
+    }
+
     pub fn get_property(
         &self,
         record_id: &str,
         property_name: &str,
     ) -> Result<Option<Property>, ApplyError> {
-        let address = make_property_address(record_id, property_name, 0);
+        let address = compute_property_address(record_id, property_name, 0);
         let d = self.context.get_state_entry(&address)?;
         match d {
             Some(packed) => {
@@ -212,7 +262,7 @@ impl<'a> TrackAndTraceState<'a> {
         property_name: &str,
         property: Property,
     ) -> Result<(), ApplyError> {
-        let address = make_property_address(record_id, property_name, 0);
+        let address = compute_property_address(record_id, property_name, 0);
         let d = self.context.get_state_entry(&address)?;
         let mut properties = match d {
             Some(packed) => match PropertyList::from_bytes(packed.as_slice()) {
@@ -271,7 +321,7 @@ impl<'a> TrackAndTraceState<'a> {
         property_name: &str,
         page: u32,
     ) -> Result<Option<PropertyPage>, ApplyError> {
-        let address = make_property_address(record_id, property_name, page);
+        let address = compute_property_address(record_id, property_name, page);
         let d = self.context.get_state_entry(&address)?;
         match d {
             Some(packed) => {
@@ -304,7 +354,7 @@ impl<'a> TrackAndTraceState<'a> {
         page_num: u32,
         property_page: PropertyPage,
     ) -> Result<(), ApplyError> {
-        let address = make_property_address(record_id, property_name, page_num);
+        let address = compute_property_address(record_id, property_name, page_num);
         let d = self.context.get_state_entry(&address)?;
         let mut pages = match d {
             Some(packed) => match PropertyPageList::from_bytes(packed.as_slice()) {
@@ -365,7 +415,7 @@ impl<'a> TrackAndTraceState<'a> {
         record_id: &str,
         agent_id: &str,
     ) -> Result<Option<ProposalList>, ApplyError> {
-        let address = make_proposal_address(record_id, agent_id);
+        let address = compute_proposal_address(record_id, agent_id);
         let d = self.context.get_state_entry(&address)?;
         match d {
             Some(packed) => match ProposalList::from_bytes(packed.as_slice()) {
@@ -385,7 +435,7 @@ impl<'a> TrackAndTraceState<'a> {
         agent_id: &str,
         proposals: ProposalList,
     ) -> Result<(), ApplyError> {
-        let address = make_proposal_address(record_id, agent_id);
+        let address = compute_proposal_address(record_id, agent_id);
         let serialized = match proposals.into_bytes() {
             Ok(serialized) => serialized,
             Err(err) => {
@@ -406,10 +456,6 @@ impl<'a> TrackAndTraceState<'a> {
 mod tests {
     use super::*;
 
-    use std::cell::RefCell;
-    use std::collections::HashMap;
-
-    use grid_sdk::protocol::pike::state::{AgentBuilder, AgentListBuilder};
     use grid_sdk::protocol::schema::state::{
         DataType, PropertyDefinition, PropertyDefinitionBuilder, PropertyValue,
         PropertyValueBuilder,
@@ -420,73 +466,11 @@ mod tests {
         ProposalListBuilder, RecordBuilder, ReportedValueBuilder, ReporterBuilder, Role, Status,
     };
 
-    use sawtooth_sdk::processor::handler::{ContextError, TransactionContext};
+    use grid_sdk::test_kit::{AgentFixture, MockTransactionContext};
 
     const RECORD_ID: &str = "test_record";
     const PROPERTY_NAME: &str = "test_property_name";
 
-    #[derive(Default, Debug)]
-    /// A MockTransactionContext that can be used to test TrackAndTraceState
-    struct MockTransactionContext {
-        state: RefCell<HashMap<String, Vec<u8>>>,
-    }
-
-    impl TransactionContext for MockTransactionContext {
-        fn get_state_entries(
-            &self,
-            addresses: &[String],
-        ) -> Result<Vec<(String, Vec<u8>)>, ContextError> {
-            let mut results = Vec::new();
-            for addr in addresses {
-                let data = match self.state.borrow().get(addr) {
-                    Some(data) => data.clone(),
-                    None => Vec::new(),
-                };
-                results.push((addr.to_string(), data));
-            }
-            Ok(results)
-        }
-
-        fn set_state_entries(&self, entries: Vec<(String, Vec<u8>)>) -> Result<(), ContextError> {
-            for (addr, data) in entries {
-                self.state.borrow_mut().insert(addr, data);
-            }
-            Ok(())
-        }
-
-        /// this is not needed for these tests
-        fn delete_state_entries(&self, _addresses: &[String]) -> Result<Vec<String>, ContextError> {
-            unimplemented!()
-        }
-
-        /// this is not needed for these tests
-        fn add_receipt_data(&self, _data: &[u8]) -> Result<(), ContextError> {
-            unimplemented!()
-        }
-
-        /// this is not needed for these tests
-        fn add_event(
-            &self,
-            _event_type: String,
-            _attributes: Vec<(String, String)>,
-            _data: &[u8],
-        ) -> Result<(), ContextError> {
-            unimplemented!()
-        }
-    }
-
-    impl MockTransactionContext {
-        fn add_agent(&self, public_key: &str) {
-            let agent_list = AgentListBuilder::new()
-                .with_agents(vec![make_agent(public_key)])
-                .build()
-                .unwrap();
-            let agent_bytes = agent_list.into_bytes().unwrap();
-            let agent_address = make_agent_address(public_key);
-            self.set_state_entry(agent_address, agent_bytes).unwrap();
-        }
-    }
-
     #[test]
     // Test that if an agent does not exist in state, None is returned
     fn test_get_agent_none() {
@@ -501,10 +485,10 @@ mod tests {
     // Test that if an agent exist in state, Some(agent) is returned
     fn test_get_agent_some() {
         let mut transaction_context = MockTransactionContext::default();
-        transaction_context.add_agent("agent_public_key");
+        let agent = AgentFixture::new("agent_public_key", "test_org").add_to(&transaction_context);
         let state = TrackAndTraceState::new(&mut transaction_context);
         let result = state.get_agent("agent_public_key").unwrap();
-        assert_eq!(result, Some(make_agent("agent_public_key")))
+        assert_eq!(result, Some(agent))
     }
 
     #[test]
@@ -688,14 +672,4 @@ mod tests {
             .build()
             .expect("Failed to build proposal list")
     }
-
-    fn make_agent(public_key: &str) -> Agent {
-        AgentBuilder::new()
-            .with_org_id("test_org".to_string())
-            .with_public_key(public_key.to_string())
-            .with_active(true)
-            .with_roles(vec![])
-            .build()
-            .expect("Failed to build agent")
-    }
 }
@@ -0,0 +1,85 @@
+-- Copyright 2019 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- ------------------------------------------------------------------------------
+
+--- The text of every searchable field is folded into a single document per
+--- entity (or, for records, per STRING property value), so that the
+--- expression indexes below can be used when filtering the view.
+CREATE FUNCTION grid_json_array_to_text(JSON []) RETURNS TEXT AS $$
+    SELECT array_to_string($1::TEXT [], ' ')
+$$ LANGUAGE SQL IMMUTABLE;
+
+CREATE INDEX IF NOT EXISTS agent_search_idx
+    ON agent
+    USING GIN (to_tsvector('simple', public_key || ' ' || metadata::TEXT));
+
+CREATE INDEX IF NOT EXISTS organization_search_idx
+    ON organization
+    USING GIN (to_tsvector('simple', org_id || ' ' || name || ' ' || address || ' ' ||
+                                     grid_json_array_to_text(metadata)));
+
+CREATE INDEX IF NOT EXISTS record_search_idx
+    ON record
+    USING GIN (to_tsvector('simple', record_id));
+
+CREATE INDEX IF NOT EXISTS reported_value_search_idx
+    ON reported_value
+    USING GIN (to_tsvector('simple', property_name || ' ' || string_value))
+    WHERE data_type = 'String';
+
+CREATE VIEW search_document
+AS
+  SELECT 'agent'::TEXT     AS entity_type,
+         agent.public_key::TEXT AS entity_id,
+         agent.org_id::TEXT AS org_id,
+         NULL::TEXT        AS schema_name,
+         agent.public_key || ' ' || agent.metadata::TEXT AS document
+  FROM   agent
+  WHERE  agent.end_block_num = 9223372036854775807
+  UNION ALL
+  SELECT 'organization'::TEXT,
+         organization.org_id::TEXT,
+         organization.org_id::TEXT,
+         NULL::TEXT,
+         organization.org_id || ' ' || organization.name || ' ' ||
+         organization.address || ' ' ||
+         grid_json_array_to_text(organization.metadata)
+  FROM   organization
+  WHERE  organization.end_block_num = 9223372036854775807
+  UNION ALL
+  SELECT 'record'::TEXT,
+         record.record_id,
+         owner.org_id::TEXT,
+         record.schema,
+         record.record_id
+  FROM   record
+         LEFT JOIN agent owner
+                ON owner.public_key = record.owners[array_upper(record.owners, 1)]
+                   AND owner.end_block_num = 9223372036854775807
+  WHERE  record.end_block_num = 9223372036854775807
+  UNION ALL
+  SELECT 'record'::TEXT,
+         record.record_id,
+         owner.org_id::TEXT,
+         record.schema,
+         reported_value.property_name || ' ' || reported_value.string_value
+  FROM   reported_value
+         JOIN record
+           ON record.record_id = reported_value.record_id
+              AND record.end_block_num = 9223372036854775807
+         LEFT JOIN agent owner
+                ON owner.public_key = record.owners[array_upper(record.owners, 1)]
+                   AND owner.end_block_num = 9223372036854775807
+  WHERE  reported_value.data_type = 'String'
+         AND reported_value.end_block_num = 9223372036854775807;
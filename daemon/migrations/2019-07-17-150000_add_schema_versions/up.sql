@@ -0,0 +1,21 @@
+-- Copyright 2019 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+ALTER TABLE grid_schema ADD COLUMN version BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE grid_property_definition ADD COLUMN schema_version BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE record ADD COLUMN schema_version BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS grid_schema_version_idx
+    ON grid_schema (name, version);
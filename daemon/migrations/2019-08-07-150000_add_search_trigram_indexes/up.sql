@@ -0,0 +1,37 @@
+-- Copyright 2019 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+--- Trigram indexes on the same documents as the full-text indexes, so that
+--- searches can match any part of an identifier or STRING value, such as a
+--- serial number, and not only the beginning of its words.
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS agent_search_trgm_idx
+    ON agent
+    USING GIN ((public_key || ' ' || metadata::TEXT) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS organization_search_trgm_idx
+    ON organization
+    USING GIN ((org_id || ' ' || name || ' ' || address || ' ' ||
+                grid_json_array_to_text(metadata)) gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS record_search_trgm_idx
+    ON record
+    USING GIN (record_id gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS reported_value_search_trgm_idx
+    ON reported_value
+    USING GIN ((property_name || ' ' || string_value) gin_trgm_ops)
+    WHERE data_type = 'String';
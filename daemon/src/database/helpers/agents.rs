@@ -26,6 +26,7 @@ use diesel::{
     result::Error::NotFound,
     QueryResult,
 };
+use std::collections::HashMap;
 
 pub fn insert_agents(conn: &PgConnection, agents: &[NewAgent]) -> QueryResult<()> {
     for agent in agents {
@@ -61,6 +62,20 @@ pub fn get_agents(conn: &PgConnection) -> QueryResult<Vec<Agent>> {
         .load::<Agent>(conn)
 }
 
+/// Returns the successor of every current agent whose key was rotated, keyed
+/// by the rotated public key
+pub fn get_agent_successors(conn: &PgConnection) -> QueryResult<HashMap<String, String>> {
+    agent::table
+        .select((agent::public_key, agent::successor))
+        .filter(
+            agent::successor
+                .ne("")
+                .and(agent::end_block_num.eq(MAX_BLOCK_NUM)),
+        )
+        .load::<(String, String)>(conn)
+        .map(|successors| successors.into_iter().collect())
+}
+
 pub fn get_agent(conn: &PgConnection, public_key: &str) -> QueryResult<Option<Agent>> {
     agent::table
         .select(agent::all_columns)
@@ -43,7 +43,12 @@ pub fn insert_grid_property_definitions(
     definitions: &[NewGridPropertyDefinition],
 ) -> QueryResult<()> {
     for definition in definitions {
-        update_definition_end_block_num(conn, &definition.name, definition.start_block_num)?;
+        update_definition_end_block_num(
+            conn,
+            &definition.schema_name,
+            &definition.name,
+            definition.start_block_num,
+        )?;
     }
 
     insert_into(grid_property_definition::table)
@@ -68,15 +73,19 @@ pub fn update_grid_schema_end_block_num(
         .map(|_| ())
 }
 
+/// Ends the current definition of a property of a schema. Schemas sharing a parent or a property
+/// group have definitions with the same name, which are left current.
 pub fn update_definition_end_block_num(
     conn: &PgConnection,
+    schema_name: &str,
     name: &str,
     current_block_num: i64,
 ) -> QueryResult<()> {
     update(grid_property_definition::table)
         .filter(
-            grid_property_definition::name
-                .eq(name)
+            grid_property_definition::schema_name
+                .eq(schema_name)
+                .and(grid_property_definition::name.eq(name))
                 .and(grid_property_definition::end_block_num.eq(MAX_BLOCK_NUM)),
         )
         .set(grid_property_definition::end_block_num.eq(current_block_num))
@@ -113,6 +122,45 @@ pub fn fetch_grid_schema(conn: &PgConnection, name: &str) -> QueryResult<Option<
         .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
 }
 
+/// Fetches a version of a schema. Versions are immutable, so the version is returned even if
+/// the schema has since been updated.
+pub fn fetch_grid_schema_version(
+    conn: &PgConnection,
+    name: &str,
+    version: i64,
+) -> QueryResult<Option<GridSchema>> {
+    grid_schema::table
+        .select(grid_schema::all_columns)
+        .filter(
+            grid_schema::name
+                .eq(name)
+                .and(grid_schema::version.eq(version)),
+        )
+        .order(grid_schema::start_block_num.desc())
+        .first(conn)
+        .map(Some)
+        .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+}
+
+/// Lists the property definitions of a version of a schema, as written along with the version
+/// at `start_block_num`
+pub fn list_grid_property_definitions_with_schema_version(
+    conn: &PgConnection,
+    schema_name: &str,
+    schema_version: i64,
+    start_block_num: i64,
+) -> QueryResult<Vec<GridPropertyDefinition>> {
+    grid_property_definition::table
+        .select(grid_property_definition::all_columns)
+        .filter(
+            grid_property_definition::schema_name
+                .eq(schema_name)
+                .and(grid_property_definition::schema_version.eq(schema_version))
+                .and(grid_property_definition::start_block_num.eq(start_block_num)),
+        )
+        .load::<GridPropertyDefinition>(conn)
+}
+
 pub fn list_grid_property_definitions_with_schema_name(
     conn: &PgConnection,
     schema_name: &str,
@@ -126,3 +174,21 @@ pub fn list_grid_property_definitions_with_schema_name(
         )
         .load::<GridPropertyDefinition>(conn)
 }
+
+pub fn fetch_grid_property_definition(
+    conn: &PgConnection,
+    schema_name: &str,
+    name: &str,
+) -> QueryResult<Option<GridPropertyDefinition>> {
+    grid_property_definition::table
+        .select(grid_property_definition::all_columns)
+        .filter(
+            grid_property_definition::schema_name
+                .eq(schema_name)
+                .and(grid_property_definition::name.eq(name))
+                .and(grid_property_definition::end_block_num.eq(MAX_BLOCK_NUM)),
+        )
+        .first(conn)
+        .map(Some)
+        .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+}
@@ -24,6 +24,8 @@ mod agents;
 mod blocks;
 mod grid_schemas;
 mod organizations;
+mod roles;
+mod search;
 mod track_and_trace;
 
 pub const MAX_BLOCK_NUM: i64 = i64::MAX;
@@ -32,4 +34,6 @@ pub use agents::*;
 pub use blocks::*;
 pub use grid_schemas::*;
 pub use organizations::*;
+pub use roles::*;
+pub use search::*;
 pub use track_and_trace::*;
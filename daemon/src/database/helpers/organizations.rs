@@ -57,10 +57,37 @@ fn update_org_end_block_num(
         .map(|_| ())
 }
 
-pub fn list_organizations(conn: &PgConnection) -> QueryResult<Vec<Organization>> {
-    organization::table
+/// Lists the current organizations, or only those holding `alternate_id`, of
+/// the form `id_type:id`
+pub fn list_organizations(
+    conn: &PgConnection,
+    alternate_id: Option<&str>,
+) -> QueryResult<Vec<Organization>> {
+    let mut query = organization::table
         .select(organization::all_columns)
         .filter(organization::end_block_num.eq(MAX_BLOCK_NUM))
+        .into_boxed();
+
+    if let Some(alternate_id) = alternate_id {
+        query = query.filter(organization::alternate_ids.contains(vec![alternate_id]));
+    }
+
+    query.load::<Organization>(conn)
+}
+
+/// Lists the current organizations directly part of `parent_org_id`
+pub fn list_child_organizations(
+    conn: &PgConnection,
+    parent_org_id: &str,
+) -> QueryResult<Vec<Organization>> {
+    organization::table
+        .select(organization::all_columns)
+        .filter(
+            organization::parent_org_id
+                .eq(parent_org_id)
+                .and(organization::end_block_num.eq(MAX_BLOCK_NUM)),
+        )
+        .order(organization::org_id)
         .load::<Organization>(conn)
 }
 
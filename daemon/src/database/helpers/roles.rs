@@ -0,0 +1,84 @@
+/*
+ * Copyright 2019 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use super::models::{NewRole, Role};
+use super::schema::role;
+use super::MAX_BLOCK_NUM;
+
+use diesel::{
+    dsl::{insert_into, update},
+    pg::PgConnection,
+    prelude::*,
+    result::Error::NotFound,
+    QueryResult,
+};
+use grid_sdk::protocol::addressing::pike::compute_role_address;
+
+/// Replaces the roles stored at the state `address` by `roles`. Every role
+/// of the previous list is ended, so roles deleted from the list, or with the
+/// whole list, are no longer current.
+pub fn insert_roles(
+    conn: &PgConnection,
+    address: &str,
+    roles: &[NewRole],
+    current_block_num: i64,
+) -> QueryResult<()> {
+    let ended_ids = get_roles(conn, None)?
+        .into_iter()
+        .filter(|role| compute_role_address(&role.org_id, &role.name) == address)
+        .map(|role| role.id)
+        .collect::<Vec<i64>>();
+
+    update(role::table)
+        .filter(role::id.eq_any(ended_ids))
+        .set(role::end_block_num.eq(current_block_num))
+        .execute(conn)?;
+
+    insert_into(role::table)
+        .values(roles)
+        .execute(conn)
+        .map(|_| ())
+}
+
+pub fn get_roles(conn: &PgConnection, org_id: Option<&str>) -> QueryResult<Vec<Role>> {
+    let mut query = role::table
+        .select(role::all_columns)
+        .filter(role::end_block_num.eq(MAX_BLOCK_NUM))
+        .into_boxed();
+
+    if let Some(org_id) = org_id {
+        query = query.filter(role::org_id.eq(org_id));
+    }
+
+    query
+        .order_by((role::org_id, role::name))
+        .load::<Role>(conn)
+}
+
+pub fn get_role(conn: &PgConnection, org_id: &str, name: &str) -> QueryResult<Option<Role>> {
+    role::table
+        .select(role::all_columns)
+        .filter(
+            role::org_id
+                .eq(org_id)
+                .and(role::name.eq(name))
+                .and(role::end_block_num.eq(MAX_BLOCK_NUM)),
+        )
+        .first(conn)
+        .map(Some)
+        .or_else(|err| if err == NotFound { Ok(None) } else { Err(err) })
+}
@@ -0,0 +1,106 @@
+/*
+ * Copyright 2019 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use super::models::{SearchFacetCount, SearchResult};
+
+use diesel::{
+    pg::PgConnection,
+    prelude::*,
+    sql_query,
+    sql_types::{Array, BigInt, Nullable, Text},
+    QueryResult,
+};
+
+/// The documents matching the `tsquery` $1, or containing every `ILIKE`
+/// pattern of $5, restricted to the entity type $2, the organization $3 and
+/// the schema $4 when they are not null
+const MATCHING_DOCUMENTS: &str = "FROM search_document \
+     WHERE (to_tsvector('simple', document) @@ to_tsquery('simple', $1) \
+     OR document ILIKE ALL($5)) \
+     AND ($2::TEXT IS NULL OR entity_type = $2) \
+     AND ($3::TEXT IS NULL OR org_id = $3) \
+     AND ($4::TEXT IS NULL OR schema_name = $4)";
+
+/// Searches the current agents, organizations and records with a Postgres
+/// `tsquery`, or with `ILIKE` patterns that match anywhere in the text,
+/// optionally restricted to an entity type, an organization or a schema,
+/// returning the `limit` best ranked entities after `offset`.
+///
+/// Records match on their id and on the values of their STRING properties;
+/// a record is returned once, with the rank of its best matching value.
+/// Entities only matching the patterns are ranked last.
+#[allow(clippy::too_many_arguments)]
+pub fn search_documents(
+    conn: &PgConnection,
+    query: &str,
+    patterns: &[String],
+    entity_type: Option<&str>,
+    org_id: Option<&str>,
+    schema_name: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> QueryResult<Vec<SearchResult>> {
+    sql_query(format!(
+        "SELECT entity_type, entity_id, org_id, schema_name, \
+         MAX(ts_rank(to_tsvector('simple', document), to_tsquery('simple', $1))) \
+         ::DOUBLE PRECISION AS rank \
+         {} \
+         GROUP BY entity_type, entity_id, org_id, schema_name \
+         ORDER BY rank DESC, entity_type, entity_id \
+         LIMIT $6 OFFSET $7",
+        MATCHING_DOCUMENTS
+    ))
+    .bind::<Text, _>(query)
+    .bind::<Nullable<Text>, _>(entity_type)
+    .bind::<Nullable<Text>, _>(org_id)
+    .bind::<Nullable<Text>, _>(schema_name)
+    .bind::<Array<Text>, _>(patterns)
+    .bind::<BigInt, _>(limit)
+    .bind::<BigInt, _>(offset)
+    .load::<SearchResult>(conn)
+}
+
+/// Counts the entities matching a search, as `search_documents` would return
+/// them without a limit, by entity type, organization and schema.
+pub fn search_facets(
+    conn: &PgConnection,
+    query: &str,
+    patterns: &[String],
+    entity_type: Option<&str>,
+    org_id: Option<&str>,
+    schema_name: Option<&str>,
+) -> QueryResult<Vec<SearchFacetCount>> {
+    sql_query(format!(
+        "WITH matches AS (\
+         SELECT DISTINCT entity_type, entity_id, org_id, schema_name {}) \
+         SELECT 'entity_type' AS facet, entity_type AS value, COUNT(*) AS count \
+         FROM matches GROUP BY entity_type \
+         UNION ALL \
+         SELECT 'org' AS facet, org_id AS value, COUNT(*) AS count \
+         FROM matches WHERE org_id IS NOT NULL GROUP BY org_id \
+         UNION ALL \
+         SELECT 'schema' AS facet, schema_name AS value, COUNT(*) AS count \
+         FROM matches WHERE schema_name IS NOT NULL GROUP BY schema_name",
+        MATCHING_DOCUMENTS
+    ))
+    .bind::<Text, _>(query)
+    .bind::<Nullable<Text>, _>(entity_type)
+    .bind::<Nullable<Text>, _>(org_id)
+    .bind::<Nullable<Text>, _>(schema_name)
+    .bind::<Array<Text>, _>(patterns)
+    .load::<SearchFacetCount>(conn)
+}
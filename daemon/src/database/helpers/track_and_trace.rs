@@ -17,7 +17,8 @@
 
 use super::models::{
     AssociatedAgent, NewAssociatedAgent, NewProperty, NewProposal, NewRecord, NewReportedValue,
-    NewReporter, Property, Proposal, Record, ReportedValueReporterToAgentMetadata, Reporter,
+    NewReporter, Property, Proposal, Record, ReportedValueBucket,
+    ReportedValueReporterToAgentMetadata, Reporter,
 };
 use super::schema::{
     associated_agent, property, proposal, record, reported_value,
@@ -30,6 +31,8 @@ use diesel::{
     pg::PgConnection,
     prelude::*,
     result::Error::NotFound,
+    sql_query,
+    sql_types::{BigInt, Text},
     QueryResult,
 };
 
@@ -368,3 +371,47 @@ pub fn list_reported_value_reporter_to_agent_metadata(
         )
         .load::<ReportedValueReporterToAgentMetadata>(conn)
 }
+
+/// Down-samples the NUMBER values reported for a property into fixed-width
+/// time buckets.
+///
+/// A reported value is re-inserted every time its property page changes, so
+/// the readings are de-duplicated on (timestamp, reporter, value) before they
+/// are aggregated. The aggregates are scaled by `10^number_exponent`.
+///
+/// Buckets start at multiples of `bucket_width`, rounding each timestamp
+/// down, so that the buckets of negative timestamps are placed like the
+/// others.
+pub fn list_reported_value_buckets(
+    conn: &PgConnection,
+    record_id: &str,
+    property_name: &str,
+    number_exponent: i64,
+    bucket_width: i64,
+    from: i64,
+    to: i64,
+) -> QueryResult<Vec<ReportedValueBucket>> {
+    sql_query(
+        "SELECT (FLOOR(readings.timestamp::NUMERIC / $3) * $3)::BIGINT AS bucket_start, \
+         COUNT(*) AS value_count, \
+         (MIN(readings.number_value) * POWER(10::NUMERIC, $4))::DOUBLE PRECISION \
+         AS min_value, \
+         (MAX(readings.number_value) * POWER(10::NUMERIC, $4))::DOUBLE PRECISION \
+         AS max_value, \
+         (AVG(readings.number_value) * POWER(10::NUMERIC, $4))::DOUBLE PRECISION \
+         AS avg_value \
+         FROM (SELECT DISTINCT timestamp, reporter_index, number_value \
+         FROM reported_value \
+         WHERE record_id = $1 AND property_name = $2 AND data_type = 'Number' \
+         AND number_value IS NOT NULL AND timestamp >= $5 AND timestamp < $6) AS readings \
+         GROUP BY bucket_start \
+         ORDER BY bucket_start",
+    )
+    .bind::<Text, _>(record_id)
+    .bind::<Text, _>(property_name)
+    .bind::<BigInt, _>(bucket_width)
+    .bind::<BigInt, _>(number_exponent)
+    .bind::<BigInt, _>(from)
+    .bind::<BigInt, _>(to)
+    .load::<ReportedValueBucket>(conn)
+}
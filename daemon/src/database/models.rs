@@ -25,7 +25,7 @@ use std::io::Write;
 
 use super::schema::{
     agent, associated_agent, block, grid_property_definition, grid_schema, organization, property,
-    proposal, record, reported_value, reporter,
+    proposal, record, reported_value, reporter, role,
 };
 
 #[derive(Insertable, Queryable)]
@@ -44,6 +44,7 @@ pub struct NewAgent {
     pub active: bool,
     pub roles: Vec<String>,
     pub metadata: JsonValue,
+    pub successor: String,
 
     // The indicators of the start and stop for the slowly-changing dimensions.
     pub start_block_num: i64,
@@ -62,6 +63,7 @@ pub struct Agent {
     pub active: bool,
     pub roles: Vec<String>,
     pub metadata: JsonValue,
+    pub successor: String,
 }
 
 #[derive(Insertable, Debug)]
@@ -71,6 +73,9 @@ pub struct NewOrganization {
     pub name: String,
     pub address: String,
     pub metadata: Vec<JsonValue>,
+    pub parent_org_id: String,
+    /// Alternate IDs of the form `id_type:id`
+    pub alternate_ids: Vec<String>,
 
     // The indicators of the start and stop for the slowly-changing dimensions.
     pub start_block_num: i64,
@@ -89,6 +94,36 @@ pub struct Organization {
     // The indicators of the start and stop for the slowly-changing dimensions.
     pub start_block_num: i64,
     pub end_block_num: i64,
+    pub parent_org_id: String,
+    pub alternate_ids: Vec<String>,
+}
+
+#[derive(Insertable, Debug)]
+#[table_name = "role"]
+pub struct NewRole {
+    pub org_id: String,
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<String>,
+    pub inherit_from: Vec<String>,
+
+    // The indicators of the start and stop for the slowly-changing dimensions.
+    pub start_block_num: i64,
+    pub end_block_num: i64,
+}
+
+#[derive(Queryable, Debug)]
+pub struct Role {
+    ///  This is the record id for the slowly-changing-dimensions table.
+    pub id: i64,
+    // The indicators of the start and stop for the slowly-changing dimensions.
+    pub start_block_num: i64,
+    pub end_block_num: i64,
+    pub org_id: String,
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<String>,
+    pub inherit_from: Vec<String>,
 }
 
 #[derive(Clone, Insertable, Debug)]
@@ -99,6 +134,11 @@ pub struct NewGridSchema {
     pub name: String,
     pub description: String,
     pub owner: String,
+    pub version: i64,
+    pub deprecated: bool,
+    pub parent: String,
+    pub parent_version: i64,
+    pub property_groups: Vec<String>,
 }
 
 #[allow(dead_code)]
@@ -110,6 +150,11 @@ pub struct GridSchema {
     pub name: String,
     pub description: String,
     pub owner: String,
+    pub version: i64,
+    pub deprecated: bool,
+    pub parent: String,
+    pub parent_version: i64,
+    pub property_groups: Vec<String>,
 }
 
 #[derive(Clone, Insertable, Debug)]
@@ -125,6 +170,9 @@ pub struct NewGridPropertyDefinition {
     pub number_exponent: i64,
     pub enum_options: Vec<String>,
     pub struct_properties: Vec<String>,
+    pub schema_version: i64,
+    pub deprecated: bool,
+    pub inherited: bool,
 }
 
 #[allow(dead_code)]
@@ -141,6 +189,9 @@ pub struct GridPropertyDefinition {
     pub number_exponent: i64,
     pub enum_options: Vec<String>,
     pub struct_properties: Vec<String>,
+    pub schema_version: i64,
+    pub deprecated: bool,
+    pub inherited: bool,
 }
 
 #[derive(SqlType, QueryId, Debug, Clone, Copy)]
@@ -260,6 +311,7 @@ pub struct NewRecord {
     pub final_: bool,
     pub owners: Vec<String>,
     pub custodians: Vec<String>,
+    pub schema_version: i64,
 }
 
 #[allow(dead_code)]
@@ -273,6 +325,7 @@ pub struct Record {
     pub final_: bool,
     pub owners: Vec<String>,
     pub custodians: Vec<String>,
+    pub schema_version: i64,
 }
 
 #[derive(Insertable, Debug, Clone, Default)]
@@ -361,6 +414,49 @@ pub struct ReportedValueReporterToAgentMetadata {
     pub reporter_end_block_num: Option<i64>,
 }
 
+/// An aggregation of the NUMBER values reported for a property over a single
+/// time bucket, with the property's number exponent already applied.
+#[derive(QueryableByName, Debug)]
+pub struct ReportedValueBucket {
+    #[sql_type = "sql_types::BigInt"]
+    pub bucket_start: i64,
+    #[sql_type = "sql_types::BigInt"]
+    pub value_count: i64,
+    #[sql_type = "sql_types::Double"]
+    pub min_value: f64,
+    #[sql_type = "sql_types::Double"]
+    pub max_value: f64,
+    #[sql_type = "sql_types::Double"]
+    pub avg_value: f64,
+}
+
+/// An agent, organization or record matching a full-text search.
+#[derive(QueryableByName, Debug)]
+pub struct SearchResult {
+    #[sql_type = "sql_types::Text"]
+    pub entity_type: String,
+    #[sql_type = "sql_types::Text"]
+    pub entity_id: String,
+    #[sql_type = "sql_types::Nullable<sql_types::Text>"]
+    pub org_id: Option<String>,
+    #[sql_type = "sql_types::Nullable<sql_types::Text>"]
+    pub schema_name: Option<String>,
+    #[sql_type = "sql_types::Double"]
+    pub rank: f64,
+}
+
+/// The number of entities matching a full-text search with a value of a
+/// facet: `entity_type`, `org` or `schema`.
+#[derive(QueryableByName, Debug)]
+pub struct SearchFacetCount {
+    #[sql_type = "sql_types::Text"]
+    pub facet: String,
+    #[sql_type = "sql_types::Text"]
+    pub value: String,
+    #[sql_type = "sql_types::BigInt"]
+    pub count: i64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
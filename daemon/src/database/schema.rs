@@ -27,6 +27,7 @@ table! {
         active -> Bool,
         roles -> Array<Text>,
         metadata -> Json,
+        successor -> Varchar,
     }
 }
 
@@ -71,6 +72,9 @@ table! {
         number_exponent -> Int8,
         enum_options -> Array<Text>,
         struct_properties -> Array<Text>,
+        schema_version -> Int8,
+        deprecated -> Bool,
+        inherited -> Bool,
     }
 }
 
@@ -82,6 +86,11 @@ table! {
         name -> Text,
         description -> Text,
         owner -> Text,
+        version -> Int8,
+        deprecated -> Bool,
+        parent -> Text,
+        parent_version -> Int8,
+        property_groups -> Array<Text>,
     }
 }
 
@@ -94,6 +103,8 @@ table! {
         metadata -> Array<Json>,
         start_block_num -> Int8,
         end_block_num -> Int8,
+        parent_org_id -> Varchar,
+        alternate_ids -> Array<Text>,
     }
 }
 
@@ -137,6 +148,7 @@ table! {
         final_ -> Bool,
         owners -> Array<Text>,
         custodians -> Array<Text>,
+        schema_version -> Int8,
     }
 }
 
@@ -162,6 +174,19 @@ table! {
     }
 }
 
+table! {
+    role (id) {
+        id -> Int8,
+        start_block_num -> Int8,
+        end_block_num -> Int8,
+        org_id -> Varchar,
+        name -> Varchar,
+        description -> Text,
+        permissions -> Array<Text>,
+        inherit_from -> Array<Text>,
+    }
+}
+
 table! {
     reporter (id) {
         id -> Int8,
@@ -226,4 +251,5 @@ allow_tables_to_appear_in_same_query!(
     record,
     reported_value,
     reporter,
+    role,
 );
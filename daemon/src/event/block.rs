@@ -19,7 +19,8 @@ use diesel::prelude::*;
 use diesel::result::Error;
 use grid_sdk::{
     protocol::{
-        pike::state::{AgentList, OrganizationList},
+        addressing::{classify_address, pike::PIKE_NAMESPACE, schema::GRID_NAMESPACE, Resource},
+        pike::state::{AgentList, OrganizationList, RoleList},
         schema::state::{DataType, PropertyDefinition, SchemaList},
         track_and_trace::state::{
             PropertyList, PropertyPageList, ProposalList, RecordList, ReportedValue,
@@ -42,15 +43,12 @@ use crate::database::{
     models::{
         Block, LatLongValue, NewAgent, NewAssociatedAgent, NewGridPropertyDefinition,
         NewGridSchema, NewOrganization, NewProperty, NewProposal, NewRecord, NewReportedValue,
-        NewReporter,
+        NewReporter, NewRole,
     },
     ConnectionPool,
 };
 
-use super::{
-    error::EventError, EventHandler, GRID_NAMESPACE, GRID_SCHEMA, PIKE_AGENT, PIKE_NAMESPACE,
-    PIKE_ORG, TRACK_AND_TRACE_PROPERTY, TRACK_AND_TRACE_PROPOSAL, TRACK_AND_TRACE_RECORD,
-};
+use super::{error::EventError, EventHandler};
 
 pub struct BlockEventHandler {
     connection_pool: ConnectionPool,
@@ -160,8 +158,15 @@ fn state_change_to_db_operation(
     state_change: &StateChange,
     block_num: i64,
 ) -> Result<DbInsertOperation, EventError> {
-    match &state_change.address[0..8] {
-        PIKE_AGENT => {
+    let resource = classify_address(&state_change.address).map_err(|err| {
+        EventError(format!(
+            "Could not handle state change unknown address: {}",
+            err
+        ))
+    })?;
+
+    match resource {
+        Resource::Agent => {
             let agents = AgentList::from_bytes(&state_change.value)
                 .map_err(|err| EventError(format!("Failed to parse agent list {}", err)))?
                 .agents()
@@ -175,6 +180,7 @@ fn state_change_to_db_operation(
                         acc.insert(md.key().to_string(), md.value().to_string());
                         acc
                     })),
+                    successor: agent.successor().to_string(),
                     start_block_num: block_num,
                     end_block_num: db::MAX_BLOCK_NUM,
                 })
@@ -182,7 +188,7 @@ fn state_change_to_db_operation(
 
             Ok(DbInsertOperation::Agents(agents))
         }
-        PIKE_ORG => {
+        Resource::Organization => {
             let orgs = OrganizationList::from_bytes(&state_change.value)
                 .map_err(|err| EventError(format!("Failed to parse organization list {}", err)))?
                 .organizations()
@@ -200,6 +206,12 @@ fn state_change_to_db_operation(
                             })
                         })
                         .collect::<Vec<JsonValue>>(),
+                    parent_org_id: org.parent_org_id().to_string(),
+                    alternate_ids: org
+                        .alternate_ids()
+                        .iter()
+                        .map(|alternate_id| alternate_id.to_string())
+                        .collect(),
                     start_block_num: block_num,
                     end_block_num: db::MAX_BLOCK_NUM,
                 })
@@ -207,7 +219,33 @@ fn state_change_to_db_operation(
 
             Ok(DbInsertOperation::Organizations(orgs))
         }
-        GRID_SCHEMA => {
+        // The alternate ID index only guards their uniqueness on-chain; the IDs
+        // are stored with their organization
+        Resource::AlternateId => Ok(DbInsertOperation::Skipped),
+        Resource::Role => {
+            // A deleted role list has no value, ending every role it held
+            let roles = RoleList::from_bytes(&state_change.value)
+                .map_err(|err| EventError(format!("Failed to parse role list {}", err)))?
+                .roles()
+                .iter()
+                .map(|role| NewRole {
+                    org_id: role.org_id().to_string(),
+                    name: role.name().to_string(),
+                    description: role.description().to_string(),
+                    permissions: role.permissions().to_vec(),
+                    inherit_from: role.inherit_from().to_vec(),
+                    start_block_num: block_num,
+                    end_block_num: db::MAX_BLOCK_NUM,
+                })
+                .collect::<Vec<NewRole>>();
+
+            Ok(DbInsertOperation::Roles(
+                state_change.address.clone(),
+                roles,
+                block_num,
+            ))
+        }
+        Resource::Schema => {
             let schema_defs = SchemaList::from_bytes(&state_change.value)
                 .map_err(|err| EventError(format!("Failed to parse schema list {}", err)))?
                 .schemas()
@@ -217,15 +255,29 @@ fn state_change_to_db_operation(
                         name: state_schema.name().to_string(),
                         description: state_schema.description().to_string(),
                         owner: state_schema.owner().to_string(),
+                        version: i64::from(*state_schema.version()),
+                        deprecated: *state_schema.deprecated(),
+                        parent: state_schema.parent().to_string(),
+                        parent_version: i64::from(*state_schema.parent_version()),
+                        property_groups: state_schema.property_groups().to_vec(),
                         start_block_num: block_num,
                         end_block_num: db::MAX_BLOCK_NUM,
                     };
 
-                    let definitions = make_property_definitions(
+                    let mut definitions = make_property_definitions(
                         block_num,
                         state_schema.name(),
-                        state_schema.properties(),
+                        i64::from(*state_schema.version()),
+                        state_schema.inherited_properties(),
+                        true,
                     );
+                    definitions.append(&mut make_property_definitions(
+                        block_num,
+                        state_schema.name(),
+                        i64::from(*state_schema.version()),
+                        state_schema.properties(),
+                        false,
+                    ));
 
                     (schema, definitions)
                 })
@@ -241,7 +293,12 @@ fn state_change_to_db_operation(
 
             Ok(DbInsertOperation::GridSchemas(schemas, definitions))
         }
-        TRACK_AND_TRACE_PROPERTY if &state_change.address[66..] == "0000" => {
+        // Each version of a schema is written along with the schema itself, which
+        // already records the version with its property definitions
+        Resource::SchemaVersion(_) => Ok(DbInsertOperation::Skipped),
+        // The properties of a property group are stored with each schema including it
+        Resource::PropertyGroup => Ok(DbInsertOperation::Skipped),
+        Resource::Property => {
             let properties = PropertyList::from_bytes(&state_change.value)
                 .map_err(|err| EventError(format!("Failed to parse property list {}", err)))?
                 .properties()
@@ -285,7 +342,7 @@ fn state_change_to_db_operation(
 
             Ok(DbInsertOperation::Properties(properties, reporters))
         }
-        TRACK_AND_TRACE_PROPERTY => {
+        Resource::PropertyPage(_) => {
             let property_pages = PropertyPageList::from_bytes(&state_change.value)
                 .map_err(|err| EventError(format!("Failed to parse property page list {}", err)))?
                 .property_pages()
@@ -312,7 +369,7 @@ fn state_change_to_db_operation(
 
             Ok(DbInsertOperation::ReportedValues(reported_values))
         }
-        TRACK_AND_TRACE_PROPOSAL => {
+        Resource::Proposal => {
             let proposals = ProposalList::from_bytes(&state_change.value)
                 .map_err(|err| EventError(format!("Failed to parse proposal list {}", err)))?
                 .proposals()
@@ -333,7 +390,7 @@ fn state_change_to_db_operation(
 
             Ok(DbInsertOperation::Proposals(proposals))
         }
-        TRACK_AND_TRACE_RECORD => {
+        Resource::Record => {
             let record_list = RecordList::from_bytes(&state_change.value)
                 .map_err(|err| EventError(format!("Failed to parse record list {}", err)))?
                 .records()
@@ -355,6 +412,7 @@ fn state_change_to_db_operation(
                         .iter()
                         .map(|x| x.agent_id().to_string())
                         .collect(),
+                    schema_version: i64::from(*record.schema_version()),
                     start_block_num: block_num,
                     end_block_num: db::MAX_BLOCK_NUM,
                 })
@@ -395,10 +453,6 @@ fn state_change_to_db_operation(
 
             Ok(DbInsertOperation::Records(records, associated_agents))
         }
-        _ => Err(EventError(format!(
-            "Could not handle state change unknown address: {}",
-            &state_change.address
-        ))),
     }
 }
 
@@ -406,11 +460,13 @@ fn state_change_to_db_operation(
 enum DbInsertOperation {
     Agents(Vec<NewAgent>),
     Organizations(Vec<NewOrganization>),
+    Roles(String, Vec<NewRole>, i64),
     GridSchemas(Vec<NewGridSchema>, Vec<NewGridPropertyDefinition>),
     Properties(Vec<NewProperty>, Vec<NewReporter>),
     ReportedValues(Vec<NewReportedValue>),
     Proposals(Vec<NewProposal>),
     Records(Vec<NewRecord>, Vec<NewAssociatedAgent>),
+    Skipped,
 }
 
 impl DbInsertOperation {
@@ -418,6 +474,9 @@ impl DbInsertOperation {
         match *self {
             DbInsertOperation::Agents(ref agents) => db::insert_agents(conn, agents),
             DbInsertOperation::Organizations(ref orgs) => db::insert_organizations(conn, orgs),
+            DbInsertOperation::Roles(ref address, ref roles, block_num) => {
+                db::insert_roles(conn, address, roles, block_num)
+            }
             DbInsertOperation::GridSchemas(ref schemas, ref defs) => {
                 db::insert_grid_schemas(conn, schemas)?;
                 db::insert_grid_property_definitions(conn, defs)
@@ -434,6 +493,7 @@ impl DbInsertOperation {
                 db::insert_records(conn, records)?;
                 db::insert_associated_agents(conn, associated_agents)
             }
+            DbInsertOperation::Skipped => Ok(()),
         }
     }
 }
@@ -525,7 +585,9 @@ fn make_reported_values(
 fn make_property_definitions(
     start_block_num: i64,
     schema_name: &str,
+    schema_version: i64,
     definitions: &[PropertyDefinition],
+    inherited: bool,
 ) -> Vec<NewGridPropertyDefinition> {
     let mut properties = Vec::new();
 
@@ -543,6 +605,9 @@ fn make_property_definitions(
                 .iter()
                 .map(|x| x.name().to_string())
                 .collect(),
+            schema_version,
+            deprecated: *def.deprecated(),
+            inherited,
             start_block_num,
             end_block_num: db::MAX_BLOCK_NUM,
         });
@@ -551,7 +616,9 @@ fn make_property_definitions(
             properties.append(&mut make_property_definitions(
                 start_block_num,
                 schema_name,
+                schema_version,
                 def.struct_properties(),
+                inherited,
             ));
         }
     }
@@ -22,6 +22,9 @@ use std::cell::RefCell;
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use grid_sdk::protocol::addressing::{
+    pike::PIKE_NAMESPACE, schema::GRID_NAMESPACE, track_and_trace::TRACK_AND_TRACE_NAMESPACE,
+};
 use protobuf::Message as _;
 
 use sawtooth_sdk::{
@@ -38,18 +41,6 @@ use crate::sawtooth_connection::SawtoothConnection;
 
 pub use super::event::error::{EventError, EventProcessorError};
 
-const PIKE_NAMESPACE: &str = "cad11d";
-const PIKE_AGENT: &str = "cad11d00";
-const PIKE_ORG: &str = "cad11d01";
-
-const GRID_NAMESPACE: &str = "621dee";
-const GRID_SCHEMA: &str = "621dee01";
-
-const TRACK_AND_TRACE_NAMESPACE: &str = "a43b46";
-const TRACK_AND_TRACE_PROPERTY: &str = "a43b46ea";
-const TRACK_AND_TRACE_PROPOSAL: &str = "a43b46aa";
-const TRACK_AND_TRACE_RECORD: &str = "a43b46ec";
-
 const SHUTDOWN_TIMEOUT: u64 = 2;
 
 pub trait EventHandler: Send {
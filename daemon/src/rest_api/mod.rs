@@ -13,26 +13,39 @@
 // limitations under the License.
 
 mod error;
+mod openapi;
 mod routes;
 
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 
 use crate::database::ConnectionPool;
 pub use crate::rest_api::error::RestApiServerError;
+use crate::rest_api::openapi::{schema_of, Parameter};
 use crate::rest_api::routes::{
-    fetch_agent, fetch_grid_schema, fetch_organization, fetch_record, fetch_record_property,
-    get_batch_statuses, list_agents, list_grid_schemas, list_organizations, list_records,
-    submit_batches,
+    fetch_agent, fetch_batch, fetch_block, fetch_grid_schema, fetch_grid_schema_version,
+    fetch_openapi, fetch_organization, fetch_record, fetch_record_property,
+    fetch_record_property_values, fetch_role, fetch_state, get_batch_statuses, get_receipts,
+    list_agents, list_child_organizations, list_grid_schemas, list_organizations, list_records,
+    list_roles, list_state, search, submit_batches,
 };
-use crate::rest_api::routes::{DbExecutor, SawtoothMessageSender};
+use crate::rest_api::routes::{
+    AgentSlice, BatchSlice, BatchStatusLink, BatchStatusResponse, BlockSlice, GridSchemaSlice,
+    OrganizationSlice, PropertySlice, PropertyValueSeriesSlice, ReceiptResponse, RecordSlice,
+    RoleSlice, SearchResponse, StateListResponse, StateResponse,
+};
+use crate::rest_api::routes::{DbExecutor, SawtoothMessageSender, SubmittedBatches};
 use actix::{Actor, Addr, Context, SyncArbiter};
-use actix_web::{http::Method, server, App};
+use actix_web::{dev::Resource, http::Method, server, App, Route};
 use sawtooth_sdk::messaging::stream::MessageSender;
+use serde_json::Value as JsonValue;
 
 pub struct AppState {
     sawtooth_connection: Addr<SawtoothMessageSender>,
     database_connection: Addr<DbExecutor>,
+    /// Shared by the workers of the server, so that the status of a batch can
+    /// be looked up by any worker
+    submitted_batches: Arc<Mutex<SubmittedBatches>>,
 }
 
 pub struct RestApiShutdownHandle {
@@ -45,46 +58,384 @@ impl RestApiShutdownHandle {
     }
 }
 
+/// A route of the REST API, along with its OpenAPI documentation.
+pub struct Endpoint {
+    pub path: &'static str,
+    pub method: Method,
+    /// Name under which the resource is registered, for use with `url_for`.
+    pub name: Option<&'static str>,
+    pub handler: fn(&mut Route<AppState>),
+    pub operation_id: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    pub parameters: Vec<Parameter>,
+    pub request_content_type: Option<&'static str>,
+    pub response: fn() -> JsonValue,
+}
+
+impl Endpoint {
+    fn register(&self, resource: &mut Resource<AppState>) {
+        if let Some(name) = self.name {
+            resource.name(name);
+        }
+        (self.handler)(resource.method(self.method.clone()));
+    }
+}
+
+/// Returns every endpoint of the REST API. Routes are registered and
+/// documented from this list only.
+pub fn endpoints() -> Vec<Endpoint> {
+    vec![
+        Endpoint {
+            path: "/batches",
+            method: Method::POST,
+            name: None,
+            handler: |r| r.with_async(submit_batches),
+            operation_id: "post_batches",
+            tag: "Sawtooth Validator",
+            summary: "Sends a BatchList to the Sawtooth Validator",
+            parameters: vec![Parameter::query(
+                "validate",
+                "boolean",
+                "Whether to check Track and Trace property values against state before submitting",
+            )],
+            request_content_type: Some("application/octet-stream"),
+            response: schema_of::<BatchStatusLink>,
+        },
+        Endpoint {
+            path: "/batch_statuses",
+            method: Method::GET,
+            name: Some("batch_statuses"),
+            handler: |r| r.with_async(get_batch_statuses),
+            operation_id: "get_batch_statuses",
+            tag: "Sawtooth Validator",
+            summary: "Fetches the committed statuses for a set of batches",
+            parameters: vec![
+                Parameter::query("id", "string", "Comma-separated list of batch ids").required(),
+                Parameter::query("wait", "integer", "A time in seconds to wait for commit"),
+            ],
+            request_content_type: None,
+            response: schema_of::<BatchStatusResponse>,
+        },
+        Endpoint {
+            path: "/batches/{batch_id}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_batch),
+            operation_id: "fetch_batch",
+            tag: "Sawtooth Validator",
+            summary: "Fetches a batch with its Grid payloads decoded",
+            parameters: vec![Parameter::path("batch_id", "Header signature of the batch")],
+            request_content_type: None,
+            response: schema_of::<BatchSlice>,
+        },
+        Endpoint {
+            path: "/receipts",
+            method: Method::GET,
+            name: Some("receipts"),
+            handler: |r| r.with_async(get_receipts),
+            operation_id: "get_receipts",
+            tag: "Sawtooth Validator",
+            summary: "Fetches the receipts for a set of transactions",
+            parameters: vec![Parameter::query(
+                "id",
+                "string",
+                "Comma-separated list of transaction ids",
+            )
+            .required()],
+            request_content_type: None,
+            response: schema_of::<ReceiptResponse>,
+        },
+        Endpoint {
+            path: "/blocks/{block_id}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_block),
+            operation_id: "fetch_block",
+            tag: "Sawtooth Validator",
+            summary: "Fetches a block from the validator",
+            parameters: vec![
+                Parameter::path("block_id", "Header signature of the block"),
+                Parameter::query(
+                    "decode",
+                    "boolean",
+                    "Include the batches of the block with their Grid payloads decoded",
+                ),
+            ],
+            request_content_type: None,
+            response: schema_of::<BlockSlice>,
+        },
+        Endpoint {
+            path: "/state",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(list_state),
+            operation_id: "list_state",
+            tag: "Sawtooth Validator",
+            summary: "Fetches the state entries under an address prefix from the validator",
+            parameters: vec![
+                Parameter::query("prefix", "string", "Address prefix of the state entries"),
+                Parameter::query("start", "string", "Address of the first entry of the page"),
+                Parameter::query("limit", "integer", "Maximum number of entries, up to 1000"),
+                Parameter::query("decode", "boolean", "Decode Grid state entries as JSON"),
+            ],
+            request_content_type: None,
+            response: schema_of::<StateListResponse>,
+        },
+        Endpoint {
+            path: "/state/{address}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_state),
+            operation_id: "fetch_state",
+            tag: "Sawtooth Validator",
+            summary: "Fetches the state entry at an address from the validator",
+            parameters: vec![
+                Parameter::path("address", "A 70 character state address"),
+                Parameter::query("decode", "boolean", "Decode Grid state entries as JSON"),
+            ],
+            request_content_type: None,
+            response: schema_of::<StateResponse>,
+        },
+        Endpoint {
+            path: "/agent",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(list_agents),
+            operation_id: "list_agents",
+            tag: "Pike",
+            summary: "Fetches a list of all agents",
+            parameters: vec![],
+            request_content_type: None,
+            response: schema_of::<Vec<AgentSlice>>,
+        },
+        Endpoint {
+            path: "/agent/{public_key}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_agent),
+            operation_id: "fetch_agent",
+            tag: "Pike",
+            summary: "Fetches a single agent by public key",
+            parameters: vec![Parameter::path("public_key", "Public key of the agent")],
+            request_content_type: None,
+            response: schema_of::<AgentSlice>,
+        },
+        Endpoint {
+            path: "/organization",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(list_organizations),
+            operation_id: "list_organizations",
+            tag: "Pike",
+            summary: "Fetches a list of all organizations",
+            parameters: vec![Parameter::query(
+                "alternate_id",
+                "string",
+                "Only list the organization holding this alternate ID, such as gln:0614141000012",
+            )],
+            request_content_type: None,
+            response: schema_of::<Vec<OrganizationSlice>>,
+        },
+        Endpoint {
+            path: "/organization/{id}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_organization),
+            operation_id: "fetch_organization",
+            tag: "Pike",
+            summary: "Fetches a single organization by id",
+            parameters: vec![Parameter::path("id", "ID of the organization")],
+            request_content_type: None,
+            response: schema_of::<OrganizationSlice>,
+        },
+        Endpoint {
+            path: "/organization/{id}/children",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(list_child_organizations),
+            operation_id: "list_child_organizations",
+            tag: "Pike",
+            summary: "Fetches the organizations directly part of an organization",
+            parameters: vec![Parameter::path("id", "ID of the parent organization")],
+            request_content_type: None,
+            response: schema_of::<Vec<OrganizationSlice>>,
+        },
+        Endpoint {
+            path: "/role",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(list_roles),
+            operation_id: "list_roles",
+            tag: "Pike",
+            summary: "Fetches a list of all roles",
+            parameters: vec![Parameter::query(
+                "org_id",
+                "string",
+                "Only list the roles defined by this organization",
+            )],
+            request_content_type: None,
+            response: schema_of::<Vec<RoleSlice>>,
+        },
+        Endpoint {
+            path: "/role/{org_id}/{name}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_role),
+            operation_id: "fetch_role",
+            tag: "Pike",
+            summary: "Fetches a single role of an organization",
+            parameters: vec![
+                Parameter::path("org_id", "ID of the organization defining the role"),
+                Parameter::path("name", "Name of the role"),
+            ],
+            request_content_type: None,
+            response: schema_of::<RoleSlice>,
+        },
+        Endpoint {
+            path: "/schema",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(list_grid_schemas),
+            operation_id: "list_schemas",
+            tag: "Grid Schema",
+            summary: "Fetches a list of all schemas",
+            parameters: vec![],
+            request_content_type: None,
+            response: schema_of::<Vec<GridSchemaSlice>>,
+        },
+        Endpoint {
+            path: "/schema/{name}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_grid_schema),
+            operation_id: "fetch_schema",
+            tag: "Grid Schema",
+            summary: "Fetches a single schema by name",
+            parameters: vec![Parameter::path("name", "Name of the schema")],
+            request_content_type: None,
+            response: schema_of::<GridSchemaSlice>,
+        },
+        Endpoint {
+            path: "/schema/{name}/versions/{version}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_grid_schema_version),
+            operation_id: "fetch_schema_version",
+            tag: "Grid Schema",
+            summary: "Fetches an immutable version of a schema",
+            parameters: vec![
+                Parameter::path("name", "Name of the schema"),
+                Parameter::path("version", "Version of the schema"),
+            ],
+            request_content_type: None,
+            response: schema_of::<GridSchemaSlice>,
+        },
+        Endpoint {
+            path: "/record",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(list_records),
+            operation_id: "list_records",
+            tag: "Track and Trace",
+            summary: "Fetches a list of all records",
+            parameters: vec![],
+            request_content_type: None,
+            response: schema_of::<Vec<RecordSlice>>,
+        },
+        Endpoint {
+            path: "/record/{record_id}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_record),
+            operation_id: "fetch_record",
+            tag: "Track and Trace",
+            summary: "Fetches a single record by id",
+            parameters: vec![Parameter::path("record_id", "ID of the record")],
+            request_content_type: None,
+            response: schema_of::<RecordSlice>,
+        },
+        Endpoint {
+            path: "/record/{record_id}/property/{property_name}",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_record_property),
+            operation_id: "fetch_property",
+            tag: "Track and Trace",
+            summary: "Fetches a single property of a record",
+            parameters: vec![
+                Parameter::path("record_id", "ID of the record"),
+                Parameter::path("property_name", "Name of the property"),
+            ],
+            request_content_type: None,
+            response: schema_of::<PropertySlice>,
+        },
+        Endpoint {
+            path: "/record/{record_id}/property/{property_name}/values",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(fetch_record_property_values),
+            operation_id: "fetch_property_values",
+            tag: "Track and Trace",
+            summary: "Fetches a down-sampled series of a NUMBER property's values",
+            parameters: vec![
+                Parameter::path("record_id", "ID of the record"),
+                Parameter::path("property_name", "Name of the NUMBER property"),
+                Parameter::query("from", "integer", "Unix timestamp of the first value"),
+                Parameter::query("to", "integer", "Unix timestamp after the last value"),
+                Parameter::query("bucket", "string", "Width of each bucket, such as 1h"),
+                Parameter::query("agg", "string", "Any of count, min, max and avg"),
+            ],
+            request_content_type: None,
+            response: schema_of::<PropertyValueSeriesSlice>,
+        },
+        Endpoint {
+            path: "/search",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.with_async(search),
+            operation_id: "search",
+            tag: "Search",
+            summary: "Searches agents, organizations and records",
+            parameters: vec![
+                Parameter::query("q", "string", "Text to search for").required(),
+                Parameter::query("type", "string", "One of agent, organization or record"),
+                Parameter::query("org", "string", "ID of an organization"),
+                Parameter::query("schema", "string", "Name of a schema"),
+                Parameter::query("limit", "integer", "Maximum number of results"),
+                Parameter::query("offset", "integer", "Number of results to skip"),
+            ],
+            request_content_type: None,
+            response: schema_of::<SearchResponse>,
+        },
+        Endpoint {
+            path: "/openapi.json",
+            method: Method::GET,
+            name: None,
+            handler: |r| r.f(fetch_openapi),
+            operation_id: "fetch_openapi",
+            tag: "Documentation",
+            summary: "Fetches the OpenAPI document of this REST API",
+            parameters: vec![],
+            request_content_type: None,
+            response: schema_of::<JsonValue>,
+        },
+    ]
+}
+
 fn create_app(
     sawtooth_connection: Addr<SawtoothMessageSender>,
     database_connection: Addr<DbExecutor>,
+    submitted_batches: Arc<Mutex<SubmittedBatches>>,
 ) -> App<AppState> {
-    App::with_state(AppState {
-        sawtooth_connection,
-        database_connection,
-    })
-    .resource("/batches", |r| {
-        r.method(Method::POST).with_async(submit_batches)
-    })
-    .resource("/batch_statuses", |r| {
-        r.name("batch_statuses");
-        r.method(Method::GET).with_async(get_batch_statuses)
-    })
-    .resource("/agent", |r| r.method(Method::GET).with_async(list_agents))
-    .resource("/agent/{public_key}", |r| {
-        r.method(Method::GET).with_async(fetch_agent)
-    })
-    .resource("/organization", |r| {
-        r.method(Method::GET).with_async(list_organizations)
-    })
-    .resource("/organization/{id}", |r| {
-        r.method(Method::GET).with_async(fetch_organization)
-    })
-    .resource("/schema", |r| {
-        r.method(Method::GET).with_async(list_grid_schemas)
-    })
-    .resource("/schema/{name}", |r| {
-        r.method(Method::GET).with_async(fetch_grid_schema)
-    })
-    .resource("/record", |r| {
-        r.method(Method::GET).with_async(list_records)
-    })
-    .resource("/record/{record_id}", |r| {
-        r.method(Method::GET).with_async(fetch_record)
-    })
-    .resource("/record/{record_id}/property/{property_name}", |r| {
-        r.method(Method::GET).with_async(fetch_record_property)
-    })
+    endpoints().into_iter().fold(
+        App::with_state(AppState {
+            sawtooth_connection,
+            database_connection,
+            submitted_batches,
+        }),
+        |app, endpoint| app.resource(endpoint.path, move |r| endpoint.register(r)),
+    )
 }
 
 pub fn run(
@@ -110,9 +461,14 @@ pub fn run(
                 });
             let db_executor_addr =
                 SyncArbiter::start(2, move || DbExecutor::new(connection_pool.clone()));
+            let submitted_batches = Arc::new(Mutex::new(SubmittedBatches::default()));
             info!("Starting Rest API at {}", &bind_url);
             let addr = server::new(move || {
-                create_app(zmq_connection_addr.clone(), db_executor_addr.clone())
+                create_app(
+                    zmq_connection_addr.clone(),
+                    db_executor_addr.clone(),
+                    submitted_batches.clone(),
+                )
             })
             .bind(bind_url)?
             .disable_signals()
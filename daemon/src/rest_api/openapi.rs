@@ -0,0 +1,464 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generation of the OpenAPI document served at `/openapi.json`.
+//!
+//! The document is built from the same `Endpoint` list that is used to
+//! register the routes of the REST API, and the schema of each response is
+//! derived from the JSON serialization of an example of the response type, so
+//! neither can drift from the code.
+
+use serde::Serialize;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::rest_api::Endpoint;
+
+const API_TITLE: &str = "Grid REST API";
+const API_DESCRIPTION: &str = "_An API providing HTTP/JSON interface to Hyperledger Grid._";
+const API_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A type that is returned by the REST API and is documented in the OpenAPI
+/// document.
+pub trait ApiSchema: Serialize + Sized {
+    /// Returns a representative value of the type. Every field that may hold
+    /// a collection should hold at least one element, so that the schema of
+    /// the elements can be derived, and every optional field should be set,
+    /// at least in one element of a collection, so that its type is known.
+    fn example() -> Self;
+
+    /// Returns the JSON schema of the type.
+    fn schema() -> JsonValue {
+        match serde_json::to_value(Self::example()) {
+            Ok(value) => schema_from_value(&value),
+            Err(_) => json!({}),
+        }
+    }
+}
+
+impl<T: ApiSchema> ApiSchema for Vec<T> {
+    fn example() -> Self {
+        vec![T::example()]
+    }
+}
+
+impl ApiSchema for JsonValue {
+    fn example() -> Self {
+        json!({})
+    }
+}
+
+/// Returns the JSON schema of an `ApiSchema` type.
+pub fn schema_of<T: ApiSchema>() -> JsonValue {
+    T::schema()
+}
+
+/// Derives a JSON schema from a JSON value, using the value's leaves as
+/// examples. The schemas of the elements of an array are merged, so that a
+/// field that is null in some elements is typed by the others.
+pub fn schema_from_value(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Null => json!({ "nullable": true }),
+        JsonValue::Bool(b) => json!({ "type": "boolean", "example": b }),
+        JsonValue::Number(n) if n.is_f64() => json!({ "type": "number", "example": n }),
+        JsonValue::Number(n) => json!({ "type": "integer", "format": "int64", "example": n }),
+        JsonValue::String(s) => json!({ "type": "string", "example": s }),
+        JsonValue::Array(items) => json!({
+            "type": "array",
+            "items": items
+                .iter()
+                .map(schema_from_value)
+                .fold(None, |merged, schema| match merged {
+                    Some(merged) => Some(merge_schemas(merged, schema)),
+                    None => Some(schema),
+                })
+                .unwrap_or_else(|| json!({})),
+        }),
+        JsonValue::Object(fields) => json!({
+            "type": "object",
+            "properties": fields
+                .iter()
+                .map(|(name, field)| (name.clone(), schema_from_value(field)))
+                .collect::<Map<String, JsonValue>>(),
+        }),
+    }
+}
+
+fn is_null_schema(schema: &JsonValue) -> bool {
+    schema.get("type").is_none() && schema.get("nullable").is_some()
+}
+
+fn with_nullable(mut schema: JsonValue) -> JsonValue {
+    schema["nullable"] = json!(true);
+    schema
+}
+
+/// Merges the schemas derived from two values of the same type. A value that
+/// is null in one of them takes the schema of the other, marked as nullable,
+/// and the fields of objects are merged field by field.
+pub fn merge_schemas(first: JsonValue, second: JsonValue) -> JsonValue {
+    if is_null_schema(&second) {
+        return if is_null_schema(&first) {
+            first
+        } else {
+            with_nullable(first)
+        };
+    }
+    if is_null_schema(&first) {
+        return with_nullable(second);
+    }
+
+    let nullable = first.get("nullable").is_some() || second.get("nullable").is_some();
+    let mut merged = match (first, second) {
+        (JsonValue::Object(mut first), JsonValue::Object(mut second)) => {
+            for key in &["properties", "items"] {
+                let merged = match (first.remove(*key), second.remove(*key)) {
+                    (Some(JsonValue::Object(mut properties)), Some(JsonValue::Object(others)))
+                        if *key == "properties" =>
+                    {
+                        for (name, other) in others {
+                            let property = match properties.remove(&name) {
+                                Some(property) => merge_schemas(property, other),
+                                None => other,
+                            };
+                            properties.insert(name, property);
+                        }
+                        Some(JsonValue::Object(properties))
+                    }
+                    (Some(items), Some(others)) => Some(merge_schemas(items, others)),
+                    (schema, other) => schema.or(other),
+                };
+                if let Some(merged) = merged {
+                    first.insert((*key).to_string(), merged);
+                }
+            }
+            JsonValue::Object(first)
+        }
+        (first, _) => first,
+    };
+
+    if nullable {
+        merged = with_nullable(merged);
+    }
+    merged
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+}
+
+/// A path or query parameter of an endpoint.
+#[derive(Clone, Debug)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub location: ParameterLocation,
+    pub description: &'static str,
+    pub schema_type: &'static str,
+    pub required: bool,
+}
+
+impl Parameter {
+    pub fn path(name: &'static str, description: &'static str) -> Self {
+        Parameter {
+            name,
+            location: ParameterLocation::Path,
+            description,
+            schema_type: "string",
+            required: true,
+        }
+    }
+
+    pub fn query(name: &'static str, schema_type: &'static str, description: &'static str) -> Self {
+        Parameter {
+            name,
+            location: ParameterLocation::Query,
+            description,
+            schema_type,
+            required: false,
+        }
+    }
+
+    pub fn required(self) -> Self {
+        Parameter {
+            required: true,
+            ..self
+        }
+    }
+
+    fn to_json(&self) -> JsonValue {
+        json!({
+            "name": self.name,
+            "in": match self.location {
+                ParameterLocation::Path => "path",
+                ParameterLocation::Query => "query",
+            },
+            "description": self.description,
+            "required": self.required,
+            "schema": { "type": self.schema_type },
+        })
+    }
+}
+
+/// Returns the names of the `{name}` segments of an actix path pattern.
+pub fn path_parameters(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| segment.starts_with('{') && segment.ends_with('}'))
+        .map(|segment| &segment[1..segment.len() - 1])
+        .collect()
+}
+
+fn operation_to_json(endpoint: &Endpoint) -> JsonValue {
+    let mut operation = json!({
+        "tags": [endpoint.tag],
+        "summary": endpoint.summary,
+        "operationId": endpoint.operation_id,
+        "parameters": endpoint
+            .parameters
+            .iter()
+            .map(Parameter::to_json)
+            .collect::<Vec<JsonValue>>(),
+        "responses": {
+            "200": {
+                "description": "Successful operation",
+                "content": {
+                    "application/json": {
+                        "schema": (endpoint.response)(),
+                    },
+                },
+            },
+            "400": { "description": "Request was malformed" },
+            "500": { "description": "Something went wrong within the REST API" },
+            "503": { "description": "The validator or database is unavailable" },
+        },
+    });
+
+    if !path_parameters(endpoint.path).is_empty() {
+        operation["responses"]["404"] =
+            json!({ "description": "Address or id did not match any resource" });
+    }
+
+    if let Some(content_type) = endpoint.request_content_type {
+        operation["requestBody"] = json!({
+            "required": true,
+            "content": {
+                content_type: {
+                    "schema": { "type": "string", "format": "binary" },
+                },
+            },
+        });
+    }
+
+    operation
+}
+
+/// Builds the OpenAPI document describing the given endpoints.
+pub fn build_spec(endpoints: &[Endpoint]) -> JsonValue {
+    let mut paths = Map::new();
+    for endpoint in endpoints {
+        let path_item = paths
+            .entry(endpoint.path.to_string())
+            .or_insert_with(|| json!({}));
+        path_item[endpoint.method.as_str().to_lowercase()] = operation_to_json(endpoint);
+    }
+
+    json!({
+        "openapi": "3.0.0",
+        "info": {
+            "version": API_VERSION,
+            "title": API_TITLE,
+            "description": API_DESCRIPTION,
+        },
+        "paths": paths,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::collections::HashSet;
+
+    use crate::rest_api::endpoints;
+    use crate::rest_api::routes::RecordSlice;
+
+    ///
+    /// Verifies every endpoint registered with the REST API is described by the generated
+    ///     OpenAPI document under its operation id, and every operation of the document is
+    ///     a registered endpoint
+    ///
+    #[test]
+    fn test_spec_matches_registered_endpoints() {
+        let endpoints = endpoints();
+        let spec = build_spec(&endpoints);
+
+        let mut registered = HashSet::new();
+        for endpoint in &endpoints {
+            let method = endpoint.method.as_str().to_lowercase();
+            assert!(
+                registered.insert((method.clone(), endpoint.path.to_string())),
+                "{} {} is registered more than once",
+                method,
+                endpoint.path
+            );
+            assert_eq!(
+                spec["paths"][endpoint.path][&method]["operationId"], endpoint.operation_id,
+                "{} {} is missing from the OpenAPI document",
+                method, endpoint.path
+            );
+        }
+
+        for (path, path_item) in spec["paths"].as_object().unwrap() {
+            for method in path_item.as_object().unwrap().keys() {
+                assert!(
+                    registered.contains(&(method.clone(), path.clone())),
+                    "{} {} is documented but not registered",
+                    method,
+                    path
+                );
+            }
+        }
+    }
+
+    /// Returns the schemas that are nullable without a type, with their location
+    fn untyped_nullable_schemas(location: &str, schema: &JsonValue) -> Vec<String> {
+        let mut untyped = vec![];
+        if schema.get("nullable").is_some() && schema.get("type").is_none() {
+            untyped.push(location.to_string());
+        }
+        if let Some(properties) = schema.get("properties").and_then(JsonValue::as_object) {
+            for (name, property) in properties {
+                untyped.extend(untyped_nullable_schemas(
+                    &format!("{}.{}", location, name),
+                    property,
+                ));
+            }
+        }
+        if let Some(items) = schema.get("items") {
+            untyped.extend(untyped_nullable_schemas(&format!("{}[]", location), items));
+        }
+        untyped
+    }
+
+    ///
+    /// Verifies every nullable schema of the generated OpenAPI document has a type, as
+    ///     required by OpenAPI 3.0
+    ///
+    #[test]
+    fn test_nullable_schemas_have_type() {
+        for endpoint in &endpoints() {
+            let untyped = untyped_nullable_schemas(endpoint.operation_id, &(endpoint.response)());
+            assert!(
+                untyped.is_empty(),
+                "untyped nullable schemas: {:?}",
+                untyped
+            );
+        }
+    }
+
+    ///
+    /// Verifies a field that is null in some elements of an array takes its type from the
+    ///     others and is marked nullable
+    ///
+    #[test]
+    fn test_schema_of_nullable_field() {
+        let schema = schema_from_value(&json!([{ "a": null, "b": 1 }, { "a": "x" }]));
+        let properties = &schema["items"]["properties"];
+        assert_eq!(properties["a"]["type"], "string");
+        assert_eq!(properties["a"]["nullable"], true);
+        assert_eq!(properties["b"]["type"], "integer");
+        assert!(properties["b"].get("nullable").is_none());
+    }
+
+    ///
+    /// Verifies every path parameter of the registered endpoints is documented
+    ///
+    #[test]
+    fn test_path_parameters_are_documented() {
+        for endpoint in &endpoints() {
+            let documented: HashSet<&str> = endpoint
+                .parameters
+                .iter()
+                .filter(|param| param.location == ParameterLocation::Path)
+                .map(|param| param.name)
+                .collect();
+            for param in path_parameters(endpoint.path) {
+                assert!(
+                    documented.contains(param),
+                    "path parameter {} of {} is not documented",
+                    param,
+                    endpoint.path
+                );
+            }
+        }
+    }
+
+    ///
+    /// Verifies the operation ids of the generated OpenAPI document are unique
+    ///
+    #[test]
+    fn test_operation_ids_are_unique() {
+        let endpoints = endpoints();
+        let mut operation_ids = HashSet::new();
+        for endpoint in &endpoints {
+            assert!(
+                operation_ids.insert(endpoint.operation_id),
+                "duplicate operation id {}",
+                endpoint.operation_id
+            );
+        }
+    }
+
+    ///
+    /// Verifies the schema of a response type lists every field of its serialization,
+    ///     including the fields of nested slices
+    ///
+    #[test]
+    fn test_schema_matches_serialized_fields() {
+        let schema = schema_of::<RecordSlice>();
+        let properties = schema["properties"].as_object().unwrap();
+        for field in &[
+            "record_id",
+            "owner",
+            "custodian",
+            "properties",
+            "final",
+            "proposals",
+            "owner_updates",
+            "custodian_updates",
+        ] {
+            assert!(properties.contains_key(*field), "missing field {}", field);
+        }
+
+        assert_eq!(properties["final"]["type"], "boolean");
+        assert_eq!(properties["properties"]["type"], "array");
+        assert!(properties["properties"]["items"]["properties"]["updates"].is_object());
+    }
+
+    ///
+    /// Verifies schemas are derived from each kind of JSON value
+    ///
+    #[test]
+    fn test_schema_from_value() {
+        assert_eq!(schema_from_value(&json!(true))["type"], "boolean");
+        assert_eq!(schema_from_value(&json!(1))["type"], "integer");
+        assert_eq!(schema_from_value(&json!(1.5))["type"], "number");
+        assert_eq!(schema_from_value(&json!("a"))["type"], "string");
+        assert_eq!(schema_from_value(&json!(["a"]))["items"]["type"], "string");
+        assert_eq!(
+            schema_from_value(&json!({ "a": 1 }))["properties"]["a"]["type"],
+            "integer"
+        );
+    }
+}
@@ -13,31 +13,39 @@
 // limitations under the License.
 
 use crate::database::{helpers as db, models::Agent};
-use crate::rest_api::{error::RestApiResponseError, routes::DbExecutor, AppState};
+use crate::rest_api::{
+    error::RestApiResponseError, openapi::ApiSchema, routes::DbExecutor, AppState,
+};
 
 use actix::{Handler, Message, SyncContext};
 use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Path};
 use futures::Future;
-use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AgentSlice {
-    pub public_key: String,
-    pub org_id: String,
-    pub active: bool,
-    pub roles: Vec<String>,
-    pub metadata: JsonValue,
-}
 
-impl AgentSlice {
-    pub fn from_agent(agent: &Agent) -> Self {
+pub use grid_client::models::AgentSlice;
+
+impl From<&Agent> for AgentSlice {
+    fn from(agent: &Agent) -> Self {
         Self {
             public_key: agent.public_key.clone(),
             org_id: agent.org_id.clone(),
             active: agent.active,
             roles: agent.roles.clone(),
             metadata: agent.metadata.clone(),
+            successor: agent.successor.clone(),
+        }
+    }
+}
+
+impl ApiSchema for AgentSlice {
+    fn example() -> Self {
+        Self {
+            public_key: "02cd3181dbd7d1539f470436ce222c53ab5e514f67809dc0095895e6cdfba97612"
+                .to_string(),
+            org_id: "philips001".to_string(),
+            active: true,
+            roles: vec!["admin".to_string()],
+            metadata: json!({ "agent_name": "Smith" }),
+            successor: "".to_string(),
         }
     }
 }
@@ -54,7 +62,7 @@ impl Handler<ListAgents> for DbExecutor {
     fn handle(&mut self, _msg: ListAgents, _: &mut SyncContext<Self>) -> Self::Result {
         let fetched_agents = db::get_agents(&*self.connection_pool.get()?)?
             .iter()
-            .map(|agent| AgentSlice::from_agent(agent))
+            .map(AgentSlice::from)
             .collect();
 
         Ok(fetched_agents)
@@ -88,7 +96,7 @@ impl Handler<FetchAgent> for DbExecutor {
 
     fn handle(&mut self, msg: FetchAgent, _: &mut SyncContext<Self>) -> Self::Result {
         let fetched_agent = match db::get_agent(&*self.connection_pool.get()?, &msg.public_key)? {
-            Some(agent) => AgentSlice::from_agent(&agent),
+            Some(agent) => AgentSlice::from(&agent),
             None => {
                 return Err(RestApiResponseError::NotFoundError(format!(
                     "Could not find agent with public key: {}",
@@ -12,31 +12,109 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::rest_api::{error::RestApiResponseError, routes::SawtoothMessageSender, AppState};
+use crate::rest_api::{
+    error::RestApiResponseError,
+    openapi::ApiSchema,
+    routes::{validate_batch_list, SawtoothMessageSender},
+    AppState,
+};
 
 use actix::{Context, Handler, Message};
-use actix_web::{AsyncResponder, HttpMessage, HttpRequest, HttpResponse, Query, State};
+use actix_web::{AsyncResponder, HttpMessage, HttpRequest, HttpResponse, Path, Query, State};
 use futures::future;
 use futures::future::Future;
-use sawtooth_sdk::messages::batch::{Batch, BatchList};
+use grid_sdk::protocol::pike::payload::PikePayload as NativePikePayload;
+use grid_sdk::protocol::schema::payload::SchemaPayload as NativeSchemaPayload;
+use grid_sdk::protocol::schema::validate::split_validation_message;
+use grid_sdk::protocol::track_and_trace::payload::TrackAndTracePayload as NativeTrackAndTracePayload;
+use grid_sdk::protos::pike_payload::PikePayload;
+use grid_sdk::protos::schema_payload::SchemaPayload;
+use grid_sdk::protos::track_and_trace_payload::TrackAndTracePayload;
+use grid_sdk::protos::FromBytes as GridFromBytes;
+use sabre_sdk::protocol::payload::{Action as SabreAction, SabrePayload};
+use sabre_sdk::protos::FromBytes;
+use sawtooth_sdk::messages::batch::{Batch, BatchHeader, BatchList};
+use sawtooth_sdk::messages::client_batch::{
+    ClientBatchGetRequest, ClientBatchGetResponse, ClientBatchGetResponse_Status,
+};
 use sawtooth_sdk::messages::client_batch_submit::{
     ClientBatchStatus, ClientBatchStatusRequest, ClientBatchStatusResponse,
-    ClientBatchStatusResponse_Status, ClientBatchSubmitRequest, ClientBatchSubmitResponse,
-    ClientBatchSubmitResponse_Status,
+    ClientBatchStatusResponse_Status, ClientBatchStatus_InvalidTransaction,
+    ClientBatchSubmitRequest, ClientBatchSubmitResponse, ClientBatchSubmitResponse_Status,
 };
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
 use sawtooth_sdk::messages::validator::Message_MessageType;
 use sawtooth_sdk::messaging::stream::MessageSender;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
 use std::time::Duration;
 use url::Url;
 use uuid::Uuid;
 
+pub use grid_client::models::{
+    BatchStatus, BatchStatusLink, BatchStatusResponse, InvalidTransactionDetailSlice,
+    InvalidTransactionSlice,
+};
+
 const DEFAULT_TIME_OUT: u32 = 300; // Max timeout 300 seconds == 5 minutes
 
+// Number of submitted batches kept to explain invalid transactions
+const SUBMITTED_BATCHES_CAPACITY: usize = 1000;
+
+const PIKE_FAMILY_NAME: &str = "pike";
+const GRID_SCHEMA_FAMILY_NAME: &str = "grid_schema";
+const TRACK_AND_TRACE_FAMILY_NAME: &str = "grid_track_and_trace";
+const SABRE_FAMILY_NAME: &str = "sabre";
+
+/// The batches most recently submitted through the REST API, by batch id.
+///
+/// Invalid transactions are never committed, so the validator cannot return
+/// them; the submitted batches are kept so that the family and action of a
+/// failed transaction can be reported with its batch status.
+#[derive(Default)]
+pub struct SubmittedBatches {
+    batches: HashMap<String, Batch>,
+    order: VecDeque<String>,
+}
+
+impl SubmittedBatches {
+    pub fn insert(&mut self, batch: Batch) {
+        let batch_id = batch.get_header_signature().to_string();
+        if self.batches.insert(batch_id.clone(), batch).is_none() {
+            self.order.push_back(batch_id);
+        }
+
+        while self.order.len() > SUBMITTED_BATCHES_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.batches.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn get(&self, batch_id: &str) -> Option<&Batch> {
+        self.batches.get(batch_id)
+    }
+}
+
+fn lock_submitted_batches(
+    submitted_batches: &Mutex<SubmittedBatches>,
+) -> Result<MutexGuard<SubmittedBatches>, RestApiResponseError> {
+    submitted_batches.lock().map_err(|_| {
+        RestApiResponseError::RequestHandlerError(
+            "The submitted batches lock is poisoned".to_string(),
+        )
+    })
+}
+
 struct SubmitBatches {
     batch_list: BatchList,
     response_url: Url,
+    /// Whether the property values of the batches are checked against state
+    /// before they are submitted
+    validate: bool,
+    submitted_batches: Arc<Mutex<SubmittedBatches>>,
 }
 
 impl Message for SubmitBatches {
@@ -46,59 +124,300 @@ impl Message for SubmitBatches {
 struct BatchStatuses {
     batch_ids: Vec<String>,
     wait: Option<u32>,
+    submitted_batches: Arc<Mutex<SubmittedBatches>>,
 }
 
 impl Message for BatchStatuses {
     type Result = Result<Vec<BatchStatus>, RestApiResponseError>;
 }
 
+/// Converts a batch status received from the validator. The message of each
+/// invalid transaction is split into the property validation errors it
+/// reports, and when the batch is given, each invalid transaction is described
+/// with the family and action of its payload.
+pub fn make_batch_status(proto: &ClientBatchStatus, batch: Option<&Batch>) -> BatchStatus {
+    BatchStatus {
+        id: proto.get_batch_id().to_string(),
+        invalid_transactions: proto
+            .get_invalid_transactions()
+            .iter()
+            .map(|invalid_transaction| {
+                let transaction = batch.and_then(|batch| {
+                    batch.get_transactions().iter().find(|transaction| {
+                        transaction.get_header_signature()
+                            == invalid_transaction.get_transaction_id()
+                    })
+                });
+                make_invalid_transaction_slice(invalid_transaction, transaction)
+            })
+            .collect(),
+        status: format!("{:?}", proto.get_status()),
+    }
+}
+
+pub fn make_invalid_transaction_slice(
+    proto: &ClientBatchStatus_InvalidTransaction,
+    transaction: Option<&Transaction>,
+) -> InvalidTransactionSlice {
+    let payload = transaction
+        .and_then(|transaction| TransactionSlice::from_proto(transaction).ok())
+        .and_then(|transaction| transaction.payload);
+
+    let (message, details) = split_validation_message(proto.get_message());
+    let details = if details.is_empty() {
+        vec![InvalidTransactionDetailSlice {
+            property: None,
+            message: message.to_string(),
+        }]
+    } else {
+        details
+            .into_iter()
+            .map(|detail| InvalidTransactionDetailSlice {
+                property: Some(detail.path),
+                message: detail.message,
+            })
+            .collect()
+    };
+
+    InvalidTransactionSlice {
+        id: proto.get_transaction_id().to_string(),
+        message: message.to_string(),
+        details,
+        extended_data: base64::encode(proto.get_extended_data()),
+        family_name: payload.as_ref().map(|payload| payload.family_name.clone()),
+        action: payload.map(|payload| payload.action),
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
-pub struct BatchStatus {
+pub struct BatchSlice {
     pub id: String,
-    pub invalid_transactions: Vec<HashMap<String, String>>,
-    pub status: String,
+    pub signer_public_key: String,
+    pub transactions: Vec<TransactionSlice>,
 }
 
-impl BatchStatus {
-    pub fn from_proto(proto: &ClientBatchStatus) -> BatchStatus {
-        BatchStatus {
-            id: proto.get_batch_id().to_string(),
-            invalid_transactions: proto
-                .get_invalid_transactions()
+impl BatchSlice {
+    pub fn from_proto(batch: &Batch) -> Result<BatchSlice, RestApiResponseError> {
+        let header: BatchHeader =
+            protobuf::parse_from_bytes(batch.get_header()).map_err(|err| {
+                RestApiResponseError::RequestHandlerError(format!(
+                    "Failed to parse batch header. {}",
+                    err.to_string()
+                ))
+            })?;
+
+        Ok(BatchSlice {
+            id: batch.get_header_signature().to_string(),
+            signer_public_key: header.get_signer_public_key().to_string(),
+            transactions: batch
+                .get_transactions()
                 .iter()
-                .map(|txn| {
-                    let mut invalid_transaction_info = HashMap::new();
-                    invalid_transaction_info
-                        .insert("id".to_string(), txn.get_transaction_id().to_string());
-                    invalid_transaction_info
-                        .insert("message".to_string(), txn.get_message().to_string());
-                    invalid_transaction_info.insert(
-                        "extended_data".to_string(),
-                        base64::encode(txn.get_extended_data()),
-                    );
-                    invalid_transaction_info
-                })
-                .collect(),
-            status: format!("{:?}", proto.get_status()),
-        }
+                .map(TransactionSlice::from_proto)
+                .collect::<Result<Vec<_>, _>>()?,
+        })
     }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct BatchStatusResponse {
-    pub data: Vec<BatchStatus>,
-    pub link: String,
+pub struct TransactionSlice {
+    pub id: String,
+    pub family_name: String,
+    pub family_version: String,
+    pub signer_public_key: String,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub payload: Option<PayloadSlice>,
+}
+
+impl TransactionSlice {
+    pub fn from_proto(transaction: &Transaction) -> Result<TransactionSlice, RestApiResponseError> {
+        let header: TransactionHeader = protobuf::parse_from_bytes(transaction.get_header())
+            .map_err(|err| {
+                RestApiResponseError::RequestHandlerError(format!(
+                    "Failed to parse transaction header. {}",
+                    err.to_string()
+                ))
+            })?;
+
+        Ok(TransactionSlice {
+            id: transaction.get_header_signature().to_string(),
+            family_name: header.get_family_name().to_string(),
+            family_version: header.get_family_version().to_string(),
+            signer_public_key: header.get_signer_public_key().to_string(),
+            inputs: header.get_inputs().to_vec(),
+            outputs: header.get_outputs().to_vec(),
+            payload: PayloadSlice::decode(
+                header.get_family_name(),
+                header.get_family_version(),
+                transaction.get_payload(),
+            ),
+        })
+    }
 }
 
+/// A Grid payload decoded from a transaction. The payload of a Sabre
+/// transaction is decoded with the family of the executed contract, and the
+/// content of the payload is its JSON serialization.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct BatchStatusLink {
-    pub link: String,
+pub struct PayloadSlice {
+    pub family_name: String,
+    pub family_version: String,
+    pub action: String,
+    pub content: JsonValue,
+}
+
+impl PayloadSlice {
+    /// Decodes the payload of a transaction of a Grid family, or of a Sabre
+    /// transaction executing a Grid contract. Returns `None` for other
+    /// families and for payloads that cannot be parsed.
+    pub fn decode(family_name: &str, family_version: &str, bytes: &[u8]) -> Option<PayloadSlice> {
+        let (action, content) = match family_name {
+            SABRE_FAMILY_NAME => {
+                return match SabrePayload::from_bytes(bytes).ok()?.action() {
+                    SabreAction::ExecuteContract(execute_contract) => PayloadSlice::decode(
+                        execute_contract.name(),
+                        execute_contract.version(),
+                        execute_contract.payload(),
+                    ),
+                    _ => None,
+                };
+            }
+            PIKE_FAMILY_NAME => {
+                let payload: PikePayload = protobuf::parse_from_bytes(bytes).ok()?;
+                (
+                    format!("{:?}", payload.get_action()),
+                    serde_json::to_value(NativePikePayload::from_bytes(bytes).ok()?).ok()?,
+                )
+            }
+            GRID_SCHEMA_FAMILY_NAME => {
+                let payload: SchemaPayload = protobuf::parse_from_bytes(bytes).ok()?;
+                (
+                    format!("{:?}", payload.get_action()),
+                    serde_json::to_value(NativeSchemaPayload::from_bytes(bytes).ok()?).ok()?,
+                )
+            }
+            TRACK_AND_TRACE_FAMILY_NAME => {
+                let payload: TrackAndTracePayload = protobuf::parse_from_bytes(bytes).ok()?;
+                (
+                    format!("{:?}", payload.get_action()),
+                    serde_json::to_value(NativeTrackAndTracePayload::from_bytes(bytes).ok()?)
+                        .ok()?,
+                )
+            }
+            _ => return None,
+        };
+
+        Some(PayloadSlice {
+            family_name: family_name.to_string(),
+            family_version: family_version.to_string(),
+            action,
+            content,
+        })
+    }
+}
+
+impl ApiSchema for BatchStatus {
+    fn example() -> Self {
+        BatchStatus {
+            id: "89807bfc9089e37e00d87d97357de14cfbc455cd608438d426a625a30a0da9a3".to_string(),
+            invalid_transactions: vec![
+                InvalidTransactionSlice {
+                    id: "540a6803971d1880ec73a96cb97815a9".to_string(),
+                    message: "Required property size not provided".to_string(),
+                    details: vec![InvalidTransactionDetailSlice {
+                        property: Some("size".to_string()),
+                        message: "Required property size not provided".to_string(),
+                    }],
+                    extended_data: "ZXJyb3IgZGF0YQ==".to_string(),
+                    family_name: Some(TRACK_AND_TRACE_FAMILY_NAME.to_string()),
+                    action: Some("CREATE_RECORD".to_string()),
+                },
+                InvalidTransactionSlice {
+                    id: "7b3e1f0d2c4a5968b7e6d5c4b3a29180".to_string(),
+                    message: "Agent already exists".to_string(),
+                    details: vec![InvalidTransactionDetailSlice {
+                        property: None,
+                        message: "Agent already exists".to_string(),
+                    }],
+                    extended_data: "".to_string(),
+                    family_name: None,
+                    action: None,
+                },
+            ],
+            status: "INVALID".to_string(),
+        }
+    }
+}
+
+impl ApiSchema for BatchSlice {
+    fn example() -> Self {
+        let public_key = "02cd3181dbd7d1539f470436ce222c53ab5e514f67809dc0095895e6cdfba97612";
+        BatchSlice {
+            id: "89807bfc9089e37e00d87d97357de14cfbc455cd608438d426a625a30a0da9a3".to_string(),
+            signer_public_key: public_key.to_string(),
+            transactions: vec![
+                TransactionSlice {
+                    id: "540a6803971d1880ec73a96cb97815a9".to_string(),
+                    family_name: SABRE_FAMILY_NAME.to_string(),
+                    family_version: "0.3".to_string(),
+                    signer_public_key: public_key.to_string(),
+                    inputs: vec!["cad11d00".to_string()],
+                    outputs: vec!["cad11d00".to_string()],
+                    payload: Some(PayloadSlice {
+                        family_name: PIKE_FAMILY_NAME.to_string(),
+                        family_version: "0.1".to_string(),
+                        action: "CREATE_AGENT".to_string(),
+                        content: json!({
+                            "action": "CREATE_AGENT",
+                            "create_agent": {
+                                "org_id": "philips001",
+                                "public_key": public_key,
+                                "active": true,
+                                "roles": ["admin"],
+                                "metadata": [{ "key": "gln", "value": "0614141000005" }],
+                            },
+                        }),
+                    }),
+                },
+                TransactionSlice {
+                    id: "7b3e1f0d2c4a5968b7e6d5c4b3a29180".to_string(),
+                    family_name: "intkey".to_string(),
+                    family_version: "1.0".to_string(),
+                    signer_public_key: public_key.to_string(),
+                    inputs: vec!["1cf126".to_string()],
+                    outputs: vec!["1cf126".to_string()],
+                    payload: None,
+                },
+            ],
+        }
+    }
+}
+
+impl ApiSchema for BatchStatusResponse {
+    fn example() -> Self {
+        BatchStatusResponse {
+            data: vec![BatchStatus::example()],
+            link: "http://localhost:8080/batch_statuses?id=89807bfc9089e37e".to_string(),
+        }
+    }
+}
+
+impl ApiSchema for BatchStatusLink {
+    fn example() -> Self {
+        BatchStatusLink {
+            link: "http://localhost:8080/batch_statuses?id=89807bfc9089e37e".to_string(),
+        }
+    }
 }
 
 impl Handler<SubmitBatches> for SawtoothMessageSender {
     type Result = Result<BatchStatusLink, RestApiResponseError>;
 
     fn handle(&mut self, msg: SubmitBatches, _: &mut Context<Self>) -> Self::Result {
+        if msg.validate {
+            validate_batch_list(&*self.sender, &msg.batch_list)?;
+        }
+
         let mut client_submit_request = ClientBatchSubmitRequest::new();
         client_submit_request.set_batches(protobuf::RepeatedField::from_vec(
             msg.batch_list.get_batches().to_vec(),
@@ -112,6 +431,11 @@ impl Handler<SubmitBatches> for SawtoothMessageSender {
 
         match process_validator_response(response_status.get_status()) {
             Ok(_) => {
+                let mut submitted_batches = lock_submitted_batches(&msg.submitted_batches)?;
+                for batch in msg.batch_list.get_batches() {
+                    submitted_batches.insert(batch.clone());
+                }
+
                 let batch_query = msg
                     .batch_list
                     .get_batches()
@@ -154,15 +478,78 @@ impl Handler<BatchStatuses> for SawtoothMessageSender {
             &batch_status_request,
         )?;
 
-        process_batch_status_response(response_status)
+        process_batch_status_response(
+            response_status,
+            &*lock_submitted_batches(&msg.submitted_batches)?,
+        )
+    }
+}
+
+struct FetchBatch {
+    batch_id: String,
+    submitted_batches: Arc<Mutex<SubmittedBatches>>,
+}
+
+impl Message for FetchBatch {
+    type Result = Result<BatchSlice, RestApiResponseError>;
+}
+
+impl Handler<FetchBatch> for SawtoothMessageSender {
+    type Result = Result<BatchSlice, RestApiResponseError>;
+
+    fn handle(&mut self, msg: FetchBatch, _: &mut Context<Self>) -> Self::Result {
+        // Invalid batches are only known to the REST API that submitted them
+        if let Some(batch) = lock_submitted_batches(&msg.submitted_batches)?.get(&msg.batch_id) {
+            return BatchSlice::from_proto(batch);
+        }
+
+        let mut batch_request = ClientBatchGetRequest::new();
+        batch_request.set_batch_id(msg.batch_id.clone());
+
+        let response: ClientBatchGetResponse = query_validator(
+            &*self.sender,
+            Message_MessageType::CLIENT_BATCH_GET_REQUEST,
+            &batch_request,
+        )?;
+
+        match response.get_status() {
+            ClientBatchGetResponse_Status::OK => BatchSlice::from_proto(response.get_batch()),
+            ClientBatchGetResponse_Status::NO_RESOURCE => Err(RestApiResponseError::NotFoundError(
+                format!("Could not find batch with id: {}", msg.batch_id),
+            )),
+            ClientBatchGetResponse_Status::INVALID_ID => Err(RestApiResponseError::BadRequest(
+                "Blockchain items are identified by 128 character hex-strings. The \
+                 batch id was invalid"
+                    .to_string(),
+            )),
+            status => Err(RestApiResponseError::SawtoothValidatorResponseError(
+                format!("Validator responded with error {:?}", status),
+            )),
+        }
     }
 }
 
 pub fn submit_batches(
-    (req, state): (HttpRequest<AppState>, State<AppState>),
+    (req, state, query): (
+        HttpRequest<AppState>,
+        State<AppState>,
+        Query<HashMap<String, String>>,
+    ),
 ) -> impl Future<Item = HttpResponse, Error = RestApiResponseError> {
     req.body().from_err().and_then(
         move |body| -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+            let validate = match query.get("validate") {
+                Some(validate) => match validate.parse::<bool>() {
+                    Ok(validate) => validate,
+                    Err(_) => {
+                        return Box::new(future::err(RestApiResponseError::BadRequest(format!(
+                            "Query validate has invalid value {}. It should be true or false",
+                            validate
+                        ))));
+                    }
+                },
+                None => false,
+            };
             let batch_list: BatchList = match protobuf::parse_from_bytes(&*body) {
                 Ok(batch_list) => batch_list,
                 Err(err) => {
@@ -182,6 +569,8 @@ pub fn submit_batches(
                 .send(SubmitBatches {
                     batch_list,
                     response_url,
+                    validate,
+                    submitted_batches: state.submitted_batches.clone(),
                 })
                 .from_err()
                 .and_then(|res| match res {
@@ -253,7 +642,11 @@ pub fn get_batch_statuses(
 
     state
         .sawtooth_connection
-        .send(BatchStatuses { batch_ids, wait })
+        .send(BatchStatuses {
+            batch_ids,
+            wait,
+            submitted_batches: state.submitted_batches.clone(),
+        })
         .from_err()
         .and_then(|res| match res {
             Ok(batch_statuses) => Ok(HttpResponse::Ok().json(BatchStatusResponse {
@@ -265,14 +658,31 @@ pub fn get_batch_statuses(
         .responder()
 }
 
-fn query_validator<T: protobuf::Message, C: protobuf::Message>(
+pub fn fetch_batch(
+    req: HttpRequest<AppState>,
+    batch_id: Path<String>,
+) -> impl Future<Item = HttpResponse, Error = RestApiResponseError> {
+    req.state()
+        .sawtooth_connection
+        .send(FetchBatch {
+            batch_id: batch_id.into_inner(),
+            submitted_batches: req.state().submitted_batches.clone(),
+        })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(batch) => Ok(HttpResponse::Ok().json(batch)),
+            Err(err) => Err(err),
+        })
+}
+
+pub fn query_validator<T: protobuf::Message, C: protobuf::Message>(
     sender: &dyn MessageSender,
     message_type: Message_MessageType,
     message: &C,
 ) -> Result<T, RestApiResponseError> {
     let content = protobuf::Message::write_to_bytes(message).map_err(|err| {
         RestApiResponseError::RequestHandlerError(format!(
-            "Failed to serialize batch submit request. {}",
+            "Failed to serialize validator request. {}",
             err.to_string()
         ))
     })?;
@@ -320,13 +730,19 @@ fn process_validator_response(
 
 fn process_batch_status_response(
     response: ClientBatchStatusResponse,
+    submitted_batches: &SubmittedBatches,
 ) -> Result<Vec<BatchStatus>, RestApiResponseError> {
     let status = response.get_status();
     match status {
         ClientBatchStatusResponse_Status::OK => Ok(response
             .get_batch_statuses()
             .iter()
-            .map(BatchStatus::from_proto)
+            .map(|batch_status| {
+                make_batch_status(
+                    batch_status,
+                    submitted_batches.get(batch_status.get_batch_id()),
+                )
+            })
             .collect()),
         ClientBatchStatusResponse_Status::INVALID_ID => Err(RestApiResponseError::BadRequest(
             "Blockchain items are identified by 128 character hex-strings. A submitted \
@@ -0,0 +1,163 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::rest_api::{
+    error::RestApiResponseError,
+    openapi::ApiSchema,
+    routes::{parse_decode, query_validator, BatchSlice, SawtoothMessageSender},
+    AppState,
+};
+
+use actix::{Context, Handler, Message};
+use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Path, Query};
+use futures::future;
+use futures::future::Future;
+use sawtooth_sdk::messages::block::{Block, BlockHeader};
+use sawtooth_sdk::messages::client_block::{
+    ClientBlockGetByIdRequest, ClientBlockGetResponse, ClientBlockGetResponse_Status,
+};
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BlockSlice {
+    pub id: String,
+    pub block_num: u64,
+    pub previous_block_id: String,
+    pub signer_public_key: String,
+    pub state_root_hash: String,
+    pub batch_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batches: Option<Vec<BatchSlice>>,
+}
+
+impl BlockSlice {
+    /// Converts a block received from the validator. When `decode` is set,
+    /// the batches of the block are included with their Grid payloads
+    /// decoded.
+    pub fn from_proto(block: &Block, decode: bool) -> Result<BlockSlice, RestApiResponseError> {
+        let header: BlockHeader =
+            protobuf::parse_from_bytes(block.get_header()).map_err(|err| {
+                RestApiResponseError::RequestHandlerError(format!(
+                    "Failed to parse block header. {}",
+                    err.to_string()
+                ))
+            })?;
+
+        let batches = if decode {
+            Some(
+                block
+                    .get_batches()
+                    .iter()
+                    .map(BatchSlice::from_proto)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+        } else {
+            None
+        };
+
+        Ok(BlockSlice {
+            id: block.get_header_signature().to_string(),
+            block_num: header.get_block_num(),
+            previous_block_id: header.get_previous_block_id().to_string(),
+            signer_public_key: header.get_signer_public_key().to_string(),
+            state_root_hash: header.get_state_root_hash().to_string(),
+            batch_ids: header.get_batch_ids().to_vec(),
+            batches,
+        })
+    }
+}
+
+impl ApiSchema for BlockSlice {
+    fn example() -> Self {
+        let batch = BatchSlice::example();
+        BlockSlice {
+            id: "f2b8c3f6e0f9e2a0a7d1d2c7e3b1fe0a4b8e35c1d0d8d8a2b37c1b0c9ec1a5d2".to_string(),
+            block_num: 42,
+            previous_block_id: "a7d1d2c7e3b1fe0a4b8e35c1d0d8d8a2b37c1b0c9ec1a5d2f2b8c3f6e0f9e2a0"
+                .to_string(),
+            signer_public_key: batch.signer_public_key.clone(),
+            state_root_hash: "0a4b8e35c1d0d8d8a2b37c1b0c9ec1a5d2f2b8c3f6e0f9e2a0a7d1d2c7e3b1fe"
+                .to_string(),
+            batch_ids: vec![batch.id.clone()],
+            batches: Some(vec![batch]),
+        }
+    }
+}
+
+struct FetchBlock {
+    block_id: String,
+    decode: bool,
+}
+
+impl Message for FetchBlock {
+    type Result = Result<BlockSlice, RestApiResponseError>;
+}
+
+impl Handler<FetchBlock> for SawtoothMessageSender {
+    type Result = Result<BlockSlice, RestApiResponseError>;
+
+    fn handle(&mut self, msg: FetchBlock, _: &mut Context<Self>) -> Self::Result {
+        let mut block_request = ClientBlockGetByIdRequest::new();
+        block_request.set_block_id(msg.block_id.clone());
+
+        let response: ClientBlockGetResponse = query_validator(
+            &*self.sender,
+            Message_MessageType::CLIENT_BLOCK_GET_BY_ID_REQUEST,
+            &block_request,
+        )?;
+
+        match response.get_status() {
+            ClientBlockGetResponse_Status::OK => {
+                BlockSlice::from_proto(response.get_block(), msg.decode)
+            }
+            ClientBlockGetResponse_Status::NO_RESOURCE => Err(RestApiResponseError::NotFoundError(
+                format!("Could not find block with id: {}", msg.block_id),
+            )),
+            ClientBlockGetResponse_Status::INVALID_ID => Err(RestApiResponseError::BadRequest(
+                "Blockchain items are identified by 128 character hex-strings. The \
+                 block id was invalid"
+                    .to_string(),
+            )),
+            status => Err(RestApiResponseError::SawtoothValidatorResponseError(
+                format!("Validator responded with error {:?}", status),
+            )),
+        }
+    }
+}
+
+pub fn fetch_block(
+    req: HttpRequest<AppState>,
+    block_id: Path<String>,
+    query: Query<HashMap<String, String>>,
+) -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+    let decode = match parse_decode(&query) {
+        Ok(decode) => decode,
+        Err(err) => return future::err(err).responder(),
+    };
+
+    req.state()
+        .sawtooth_connection
+        .send(FetchBlock {
+            block_id: block_id.into_inner(),
+            decode,
+        })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(block) => Ok(HttpResponse::Ok().json(block)),
+            Err(err) => Err(err),
+        })
+        .responder()
+}
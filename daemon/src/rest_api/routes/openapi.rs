@@ -0,0 +1,21 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::rest_api::{endpoints, openapi::build_spec, AppState};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+pub fn fetch_openapi(_req: &HttpRequest<AppState>) -> HttpResponse {
+    HttpResponse::Ok().json(build_spec(&endpoints()))
+}
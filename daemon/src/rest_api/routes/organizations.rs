@@ -13,34 +13,47 @@
 // limitations under the License.
 
 use crate::database::{helpers as db, models::Organization};
-use crate::rest_api::{error::RestApiResponseError, routes::DbExecutor, AppState};
+use crate::rest_api::{
+    error::RestApiResponseError, openapi::ApiSchema, routes::DbExecutor, AppState,
+};
 
 use actix::{Handler, Message, SyncContext};
-use actix_web::{HttpRequest, HttpResponse, Path};
-use futures::Future;
-use serde::{Deserialize, Serialize};
-use serde_json::Value as JsonValue;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct OrganizationSlice {
-    pub org_id: String,
-    pub name: String,
-    pub address: String,
-    pub metadata: Vec<JsonValue>,
-}
+use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Path, Query};
+use futures::{future, Future};
+use grid_sdk::protocol::pike::state::AlternateId;
+use std::collections::HashMap;
+
+pub use grid_client::models::OrganizationSlice;
 
-impl OrganizationSlice {
-    pub fn from_organization(organization: &Organization) -> Self {
+impl From<&Organization> for OrganizationSlice {
+    fn from(organization: &Organization) -> Self {
         Self {
             org_id: organization.org_id.clone(),
             name: organization.name.clone(),
             address: organization.address.clone(),
             metadata: organization.metadata.clone(),
+            parent_org_id: organization.parent_org_id.clone(),
+            alternate_ids: organization.alternate_ids.clone(),
         }
     }
 }
 
-struct ListOrganizations;
+impl ApiSchema for OrganizationSlice {
+    fn example() -> Self {
+        Self {
+            org_id: "philips001".to_string(),
+            name: "Philips".to_string(),
+            address: "Amstelplein 2 1096 BC Amsterdam The Netherlands".to_string(),
+            metadata: vec![json!({ "industry": "electronics" })],
+            parent_org_id: "".to_string(),
+            alternate_ids: vec!["gln:8712345000004".to_string()],
+        }
+    }
+}
+
+struct ListOrganizations {
+    alternate_id: Option<String>,
+}
 
 impl Message for ListOrganizations {
     type Result = Result<Vec<OrganizationSlice>, RestApiResponseError>;
@@ -49,26 +62,47 @@ impl Message for ListOrganizations {
 impl Handler<ListOrganizations> for DbExecutor {
     type Result = Result<Vec<OrganizationSlice>, RestApiResponseError>;
 
-    fn handle(&mut self, _msg: ListOrganizations, _: &mut SyncContext<Self>) -> Self::Result {
-        let fetched_organizations = db::list_organizations(&*self.connection_pool.get()?)?
-            .iter()
-            .map(|organization| OrganizationSlice::from_organization(organization))
-            .collect();
+    fn handle(&mut self, msg: ListOrganizations, _: &mut SyncContext<Self>) -> Self::Result {
+        let fetched_organizations = db::list_organizations(
+            &*self.connection_pool.get()?,
+            msg.alternate_id.as_ref().map(String::as_str),
+        )?
+        .iter()
+        .map(OrganizationSlice::from)
+        .collect();
         Ok(fetched_organizations)
     }
 }
 
 pub fn list_organizations(
     req: HttpRequest<AppState>,
-) -> impl Future<Item = HttpResponse, Error = RestApiResponseError> {
+    query: Query<HashMap<String, String>>,
+) -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+    // Normalize the ID as it is stored, rejecting malformed ones
+    let alternate_id = match query
+        .get("alternate_id")
+        .map(|id| id.parse::<AlternateId>())
+    {
+        Some(Ok(alternate_id)) => Some(alternate_id.to_string()),
+        Some(Err(err)) => {
+            return future::err(RestApiResponseError::BadRequest(format!(
+                "Query alternate_id has invalid value: {}",
+                err
+            )))
+            .responder()
+        }
+        None => None,
+    };
+
     req.state()
         .database_connection
-        .send(ListOrganizations)
+        .send(ListOrganizations { alternate_id })
         .from_err()
         .and_then(move |res| match res {
             Ok(organizations) => Ok(HttpResponse::Ok().json(organizations)),
             Err(err) => Err(err),
         })
+        .responder()
 }
 
 struct FetchOrganization {
@@ -85,7 +119,7 @@ impl Handler<FetchOrganization> for DbExecutor {
     fn handle(&mut self, msg: FetchOrganization, _: &mut SyncContext<Self>) -> Self::Result {
         let organization =
             match db::fetch_organization(&*self.connection_pool.get()?, &msg.organization_id)? {
-                Some(organization) => OrganizationSlice::from_organization(&organization),
+                Some(organization) => OrganizationSlice::from(&organization),
                 None => {
                     return Err(RestApiResponseError::NotFoundError(format!(
                         "Could not find organization with id: {}",
@@ -113,3 +147,47 @@ pub fn fetch_organization(
             Err(err) => Err(err),
         })
 }
+
+struct ListChildOrganizations {
+    organization_id: String,
+}
+
+impl Message for ListChildOrganizations {
+    type Result = Result<Vec<OrganizationSlice>, RestApiResponseError>;
+}
+
+impl Handler<ListChildOrganizations> for DbExecutor {
+    type Result = Result<Vec<OrganizationSlice>, RestApiResponseError>;
+
+    fn handle(&mut self, msg: ListChildOrganizations, _: &mut SyncContext<Self>) -> Self::Result {
+        let conn = self.connection_pool.get()?;
+        if db::fetch_organization(&*conn, &msg.organization_id)?.is_none() {
+            return Err(RestApiResponseError::NotFoundError(format!(
+                "Could not find organization with id: {}",
+                msg.organization_id
+            )));
+        }
+
+        let children = db::list_child_organizations(&*conn, &msg.organization_id)?
+            .iter()
+            .map(OrganizationSlice::from)
+            .collect();
+        Ok(children)
+    }
+}
+
+pub fn list_child_organizations(
+    req: HttpRequest<AppState>,
+    organization_id: Path<String>,
+) -> impl Future<Item = HttpResponse, Error = RestApiResponseError> {
+    req.state()
+        .database_connection
+        .send(ListChildOrganizations {
+            organization_id: organization_id.into_inner(),
+        })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(organizations) => Ok(HttpResponse::Ok().json(organizations)),
+            Err(err) => Err(err),
+        })
+}
@@ -0,0 +1,220 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::rest_api::{
+    error::RestApiResponseError,
+    openapi::ApiSchema,
+    routes::{query_validator, SawtoothMessageSender},
+    AppState,
+};
+
+use actix::{Context, Handler, Message};
+use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Query, State};
+use futures::future;
+use futures::future::Future;
+use sawtooth_sdk::messages::client_receipt::{
+    ClientReceiptGetRequest, ClientReceiptGetResponse, ClientReceiptGetResponse_Status,
+};
+use sawtooth_sdk::messages::events::Event;
+use sawtooth_sdk::messages::transaction_receipt::{StateChange, TransactionReceipt};
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+struct Receipts {
+    transaction_ids: Vec<String>,
+}
+
+impl Message for Receipts {
+    type Result = Result<Vec<ReceiptSlice>, RestApiResponseError>;
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReceiptSlice {
+    pub transaction_id: String,
+    pub state_changes: Vec<StateChangeSlice>,
+    pub events: Vec<EventSlice>,
+    pub data: Vec<String>,
+}
+
+impl ReceiptSlice {
+    pub fn from_proto(receipt: &TransactionReceipt) -> ReceiptSlice {
+        ReceiptSlice {
+            transaction_id: receipt.get_transaction_id().to_string(),
+            state_changes: receipt
+                .get_state_changes()
+                .iter()
+                .map(StateChangeSlice::from_proto)
+                .collect(),
+            events: receipt
+                .get_events()
+                .iter()
+                .map(EventSlice::from_proto)
+                .collect(),
+            data: receipt.get_data().iter().map(base64::encode).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StateChangeSlice {
+    pub address: String,
+    pub value: String,
+    #[serde(rename = "type")]
+    pub change_type: String,
+}
+
+impl StateChangeSlice {
+    pub fn from_proto(state_change: &StateChange) -> StateChangeSlice {
+        StateChangeSlice {
+            address: state_change.get_address().to_string(),
+            value: base64::encode(state_change.get_value()),
+            change_type: format!("{:?}", state_change.get_field_type()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EventSlice {
+    pub event_type: String,
+    pub attributes: HashMap<String, String>,
+    pub data: String,
+}
+
+impl EventSlice {
+    pub fn from_proto(event: &Event) -> EventSlice {
+        EventSlice {
+            event_type: event.get_event_type().to_string(),
+            attributes: event
+                .get_attributes()
+                .iter()
+                .map(|attribute| {
+                    (
+                        attribute.get_key().to_string(),
+                        attribute.get_value().to_string(),
+                    )
+                })
+                .collect(),
+            data: base64::encode(event.get_data()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReceiptResponse {
+    pub data: Vec<ReceiptSlice>,
+    pub link: String,
+}
+
+impl ApiSchema for ReceiptResponse {
+    fn example() -> Self {
+        let mut attributes = HashMap::new();
+        attributes.insert(
+            "address".to_string(),
+            "cad11d00d4a5cbd8ab19d4ec7fd58dc7cb6f1e3d0a4d8f4b8d6b8c2fbbb4a3e5c9e2b7".to_string(),
+        );
+
+        ReceiptResponse {
+            data: vec![ReceiptSlice {
+                transaction_id: "540a6803971d1880ec73a96cb97815a9".to_string(),
+                state_changes: vec![StateChangeSlice {
+                    address:
+                        "cad11d00d4a5cbd8ab19d4ec7fd58dc7cb6f1e3d0a4d8f4b8d6b8c2fbbb4a3e5c9e2b7"
+                            .to_string(),
+                    value: "CgZhZ2VudA==".to_string(),
+                    change_type: "SET".to_string(),
+                }],
+                events: vec![EventSlice {
+                    event_type: "sawtooth/state-delta".to_string(),
+                    attributes,
+                    data: "CgZhZ2VudA==".to_string(),
+                }],
+                data: vec!["CgZhZ2VudA==".to_string()],
+            }],
+            link: "http://localhost:8080/receipts?id=540a6803971d1880ec73a96cb97815a9".to_string(),
+        }
+    }
+}
+
+impl Handler<Receipts> for SawtoothMessageSender {
+    type Result = Result<Vec<ReceiptSlice>, RestApiResponseError>;
+
+    fn handle(&mut self, msg: Receipts, _: &mut Context<Self>) -> Self::Result {
+        let mut receipt_request = ClientReceiptGetRequest::new();
+        receipt_request.set_transaction_ids(protobuf::RepeatedField::from_vec(msg.transaction_ids));
+
+        let response: ClientReceiptGetResponse = query_validator(
+            &*self.sender,
+            Message_MessageType::CLIENT_RECEIPT_GET_REQUEST,
+            &receipt_request,
+        )?;
+
+        match response.get_status() {
+            ClientReceiptGetResponse_Status::OK => Ok(response
+                .get_receipts()
+                .iter()
+                .map(ReceiptSlice::from_proto)
+                .collect()),
+            ClientReceiptGetResponse_Status::NO_RESOURCE => {
+                Err(RestApiResponseError::NotFoundError(
+                    "Could not find a receipt for every transaction id".to_string(),
+                ))
+            }
+            ClientReceiptGetResponse_Status::INVALID_ID => Err(RestApiResponseError::BadRequest(
+                "Blockchain items are identified by 128 character hex-strings. A submitted \
+                 transaction id was invalid"
+                    .to_string(),
+            )),
+            status => Err(RestApiResponseError::SawtoothValidatorResponseError(
+                format!("Validator responded with error {:?}", status),
+            )),
+        }
+    }
+}
+
+pub fn get_receipts(
+    (state, query, req): (
+        State<AppState>,
+        Query<HashMap<String, String>>,
+        HttpRequest<AppState>,
+    ),
+) -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+    let transaction_ids = match query.get("id") {
+        Some(ids) => ids.split(',').map(ToString::to_string).collect(),
+        None => {
+            return future::err(RestApiResponseError::BadRequest(
+                "Request for receipts missing id query.".to_string(),
+            ))
+            .responder();
+        }
+    };
+
+    let response_url = match req.url_for_static("receipts") {
+        Ok(url) => format!("{}?{}", url, req.query_string()),
+        Err(err) => return Box::new(future::err(err.into())),
+    };
+
+    state
+        .sawtooth_connection
+        .send(Receipts { transaction_ids })
+        .from_err()
+        .and_then(|res| match res {
+            Ok(receipts) => Ok(HttpResponse::Ok().json(ReceiptResponse {
+                data: receipts,
+                link: response_url,
+            })),
+            Err(err) => Err(err),
+        })
+        .responder()
+}
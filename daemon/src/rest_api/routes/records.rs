@@ -15,28 +15,32 @@
 use crate::database::{
     helpers as db,
     models::{
-        AssociatedAgent, LatLongValue, Property, Proposal, Record,
+        AssociatedAgent, LatLongValue, Property, Proposal, Record, ReportedValueBucket,
         ReportedValueReporterToAgentMetadata,
     },
     ConnectionPool,
 };
 
-use crate::rest_api::{error::RestApiResponseError, routes::DbExecutor, AppState};
+use crate::rest_api::{
+    error::RestApiResponseError, openapi::ApiSchema, routes::DbExecutor, AppState,
+};
 
 use actix::{Handler, Message, SyncContext};
-use actix_web::{HttpRequest, HttpResponse, Path};
-use futures::Future;
-use serde::{Deserialize, Serialize};
+use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Path, Query};
+use futures::{future, Future};
 use serde_json::{Map, Value as JsonValue};
+use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AssociatedAgentSlice {
-    pub agent_id: String,
-    pub timestamp: u64,
-}
+pub use grid_client::models::{
+    AssociatedAgentSlice, LatLong, PropertySlice, PropertyValueBucketSlice,
+    PropertyValueSeriesSlice, PropertyValueSlice, ProposalSlice, RecordSlice, ReporterSlice,
+    StructPropertyValue, Value,
+};
 
-impl AssociatedAgentSlice {
-    pub fn from_model(associated_agent: &AssociatedAgent) -> Self {
+const DEFAULT_BUCKET_WIDTH: i64 = 3600; // 1 hour
+
+impl From<&AssociatedAgent> for AssociatedAgentSlice {
+    fn from(associated_agent: &AssociatedAgent) -> Self {
         Self {
             agent_id: associated_agent.agent_id.clone(),
             timestamp: associated_agent.timestamp as u64,
@@ -44,19 +48,8 @@ impl AssociatedAgentSlice {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ProposalSlice {
-    pub receiving_agent: String,
-    pub issuing_agent: String,
-    pub role: String,
-    pub properties: Vec<String>,
-    pub status: String,
-    pub terms: String,
-    pub timestamp: u64,
-}
-
-impl ProposalSlice {
-    pub fn from_model(proposal: &Proposal) -> Self {
+impl From<&Proposal> for ProposalSlice {
+    fn from(proposal: &Proposal) -> Self {
         Self {
             receiving_agent: proposal.receiving_agent.clone(),
             issuing_agent: proposal.issuing_agent.clone(),
@@ -69,54 +62,91 @@ impl ProposalSlice {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct RecordSlice {
-    pub record_id: String,
-    pub owner: String,
-    pub custodian: String,
-    pub properties: Vec<PropertySlice>,
-    pub r#final: bool,
-    pub proposals: Vec<ProposalSlice>,
-    pub owner_updates: Vec<AssociatedAgentSlice>,
-    pub custodian_updates: Vec<AssociatedAgentSlice>,
-}
-
-impl RecordSlice {
-    pub fn from_models(
-        record: &Record,
-        proposals: &[Proposal],
-        associated_agents: &[AssociatedAgent],
-        properties: &[PropertySlice],
-    ) -> Self {
-        let mut owner_updates: Vec<AssociatedAgentSlice> = associated_agents
-            .iter()
-            .filter(|agent| agent.role.eq("OWNER"))
-            .map(AssociatedAgentSlice::from_model)
-            .collect();
-        let mut custodian_updates: Vec<AssociatedAgentSlice> = associated_agents
-            .iter()
-            .filter(|agent| agent.role.eq("CUSTODIAN"))
-            .map(AssociatedAgentSlice::from_model)
-            .collect();
+/// Follows the successor links left by key rotations, returning the key that
+/// currently holds the identity of `public_key`
+fn current_agent_key(successors: &HashMap<String, String>, public_key: &str) -> String {
+    let mut visited = HashSet::new();
+    let mut key = public_key;
+    while let Some(successor) = successors.get(key) {
+        if !visited.insert(key) {
+            break;
+        }
+        key = successor;
+    }
+    key.to_string()
+}
 
-        owner_updates.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-        custodian_updates.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+/// Builds the slice of a record, reporting its owner and custodian by the
+/// keys currently holding their identities. The owner and custodian updates
+/// keep the keys that were recorded at the time.
+fn make_record_slice(
+    record: &Record,
+    proposals: &[Proposal],
+    associated_agents: &[AssociatedAgent],
+    properties: &[PropertySlice],
+    successors: &HashMap<String, String>,
+) -> RecordSlice {
+    let mut owner_updates: Vec<AssociatedAgentSlice> = associated_agents
+        .iter()
+        .filter(|agent| agent.role.eq("OWNER"))
+        .map(AssociatedAgentSlice::from)
+        .collect();
+    let mut custodian_updates: Vec<AssociatedAgentSlice> = associated_agents
+        .iter()
+        .filter(|agent| agent.role.eq("CUSTODIAN"))
+        .map(AssociatedAgentSlice::from)
+        .collect();
+
+    owner_updates.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    custodian_updates.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+    RecordSlice {
+        record_id: record.record_id.clone(),
+        schema: record.schema.clone(),
+        schema_version: record.schema_version,
+        owner: match owner_updates.last() {
+            Some(owner) => current_agent_key(successors, &owner.agent_id),
+            None => "".to_string(),
+        },
+        custodian: match custodian_updates.last() {
+            Some(custodian) => current_agent_key(successors, &custodian.agent_id),
+            None => "".to_string(),
+        },
+        properties: properties.to_vec(),
+        r#final: record.final_,
+        proposals: proposals.iter().map(ProposalSlice::from).collect(),
+        owner_updates,
+        custodian_updates,
+    }
+}
+
+impl ApiSchema for RecordSlice {
+    fn example() -> Self {
+        let owner = AssociatedAgentSlice {
+            agent_id: "02cd3181dbd7d1539f470436ce222c53ab5e514f67809dc0095895e6cdfba97612"
+                .to_string(),
+            timestamp: 1_557_949_075,
+        };
 
         Self {
-            record_id: record.record_id.clone(),
-            owner: match owner_updates.last() {
-                Some(owner) => owner.agent_id.clone(),
-                None => "".to_string(),
-            },
-            custodian: match custodian_updates.last() {
-                Some(custodian) => custodian.agent_id.clone(),
-                None => "".to_string(),
-            },
-            properties: properties.to_vec(),
-            r#final: record.final_,
-            proposals: proposals.iter().map(ProposalSlice::from_model).collect(),
-            owner_updates,
-            custodian_updates,
+            record_id: "7h15-45537-15-br173".to_string(),
+            schema: "Lightbulb".to_string(),
+            schema_version: 1,
+            owner: owner.agent_id.clone(),
+            custodian: owner.agent_id.clone(),
+            properties: vec![PropertySlice::example()],
+            r#final: false,
+            proposals: vec![ProposalSlice {
+                receiving_agent: owner.agent_id.clone(),
+                issuing_agent: owner.agent_id.clone(),
+                role: "OWNER".to_string(),
+                properties: vec!["location".to_string()],
+                status: "OPEN".to_string(),
+                terms: "Net 30".to_string(),
+                timestamp: 1_557_949_075,
+            }],
+            owner_updates: vec![owner.clone()],
+            custodian_updates: vec![owner],
         }
     }
 }
@@ -147,6 +177,8 @@ impl Handler<ListRecords> for DbExecutor {
             .map(|property| parse_property_slice(&self.connection_pool, property))
             .collect::<Result<Vec<PropertySlice>, _>>()?;
 
+        let successors = db::get_agent_successors(&*self.connection_pool.get()?)?;
+
         Ok(records
             .iter()
             .map(|record| {
@@ -167,7 +199,7 @@ impl Handler<ListRecords> for DbExecutor {
                     .cloned()
                     .collect();
 
-                RecordSlice::from_models(record, &props, &agents, &record_properties)
+                make_record_slice(record, &props, &agents, &record_properties, &successors)
             })
             .collect())
     }
@@ -220,11 +252,14 @@ impl Handler<FetchRecord> for DbExecutor {
         let associated_agents =
             db::list_associated_agents(&*self.connection_pool.get()?, &[msg.record_id.clone()])?;
 
-        Ok(RecordSlice::from_models(
+        let successors = db::get_agent_successors(&*self.connection_pool.get()?)?;
+
+        Ok(make_record_slice(
             &record,
             &proposals,
             &associated_agents,
             &properties,
+            &successors,
         ))
     }
 }
@@ -245,61 +280,48 @@ pub fn fetch_record(
         })
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PropertySlice {
-    pub name: String,
-    pub record_id: String,
-    pub data_type: String,
-    pub reporters: Vec<String>,
-    pub updates: Vec<PropertyValueSlice>,
-    pub value: PropertyValueSlice,
-}
-impl PropertySlice {
-    pub fn from_model(
-        property: &Property,
-        reporters: &[String],
-        data_type: &str,
-        updates: &[PropertyValueSlice],
-        value: PropertyValueSlice,
-    ) -> PropertySlice {
-        PropertySlice {
-            name: property.name.clone(),
-            record_id: property.record_id.clone(),
-            data_type: data_type.to_string(),
-            reporters: reporters.to_vec(),
-            updates: updates.to_vec(),
-            value,
-        }
+fn make_property_slice(
+    property: &Property,
+    reporters: &[String],
+    data_type: &str,
+    updates: &[PropertyValueSlice],
+    value: PropertyValueSlice,
+) -> PropertySlice {
+    PropertySlice {
+        name: property.name.clone(),
+        record_id: property.record_id.clone(),
+        data_type: data_type.to_string(),
+        reporters: reporters.to_vec(),
+        updates: updates.to_vec(),
+        value,
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PropertyValueSlice {
-    pub timestamp: u64,
-    pub value: Value,
-    pub reporter: ReporterSlice,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-#[serde(untagged)]
-pub enum Value {
-    String(String),
-    Bool(bool),
-    Struct(Vec<StructPropertyValue>),
-    LatLong(LatLong),
-    Number(i64),
-    Enum(i32),
-    Bytes(String),
-}
+impl ApiSchema for PropertySlice {
+    fn example() -> Self {
+        let value = PropertyValueSlice {
+            timestamp: 1_557_949_075,
+            value: Value::String("AQIDBA==".to_string()),
+            reporter: ReporterSlice {
+                public_key: "02cd3181dbd7d1539f470436ce222c53ab5e514f67809dc0095895e6cdfba97612"
+                    .to_string(),
+                metadata: json!({ "agent_name": "Smith" }),
+            },
+        };
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub struct LatLong {
-    pub latitude: i64,
-    pub longitude: i64,
+        PropertySlice {
+            name: "location".to_string(),
+            record_id: "7h15-45537-15-br173".to_string(),
+            data_type: "String".to_string(),
+            reporters: vec![value.reporter.public_key.clone()],
+            updates: vec![value.clone()],
+            value,
+        }
+    }
 }
 
-impl LatLong {
-    pub fn from_model(lat_long_value: LatLongValue) -> LatLong {
+impl From<LatLongValue> for LatLong {
+    fn from(lat_long_value: LatLongValue) -> Self {
         LatLong {
             latitude: lat_long_value.0,
             longitude: lat_long_value.1,
@@ -307,53 +329,36 @@ impl LatLong {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ReporterSlice {
-    pub public_key: String,
-    pub metadata: JsonValue,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub struct StructPropertyValue {
-    pub name: String,
-    pub data_type: String,
-    pub value: Value,
-}
-
-impl StructPropertyValue {
-    pub fn from_model(
-        value_name: &str,
-        reported_value_with_reporter: &ReportedValueReporterToAgentMetadata,
-        struct_values: Option<Vec<StructPropertyValue>>,
-    ) -> Result<StructPropertyValue, RestApiResponseError> {
-        Ok(StructPropertyValue {
-            name: value_name.to_string(),
-            data_type: reported_value_with_reporter.data_type.clone(),
-            value: parse_value(reported_value_with_reporter, struct_values)?,
-        })
-    }
+fn make_struct_property_value(
+    value_name: &str,
+    reported_value_with_reporter: &ReportedValueReporterToAgentMetadata,
+    struct_values: Option<Vec<StructPropertyValue>>,
+) -> Result<StructPropertyValue, RestApiResponseError> {
+    Ok(StructPropertyValue {
+        name: value_name.to_string(),
+        data_type: reported_value_with_reporter.data_type.clone(),
+        value: parse_value(reported_value_with_reporter, struct_values)?,
+    })
 }
 
-impl PropertyValueSlice {
-    pub fn from_model(
-        reported_value_with_reporter: &ReportedValueReporterToAgentMetadata,
-        struct_values: Option<Vec<StructPropertyValue>>,
-    ) -> Result<PropertyValueSlice, RestApiResponseError> {
-        Ok(PropertyValueSlice {
-            timestamp: reported_value_with_reporter.timestamp as u64,
-            value: parse_value(reported_value_with_reporter, struct_values)?,
-            reporter: ReporterSlice {
-                public_key: reported_value_with_reporter
-                    .public_key
-                    .clone()
-                    .unwrap_or_else(|| "".to_string()),
-                metadata: reported_value_with_reporter
-                    .metadata
-                    .clone()
-                    .unwrap_or_else(|| JsonValue::Object(Map::new())),
-            },
-        })
-    }
+fn make_property_value_slice(
+    reported_value_with_reporter: &ReportedValueReporterToAgentMetadata,
+    struct_values: Option<Vec<StructPropertyValue>>,
+) -> Result<PropertyValueSlice, RestApiResponseError> {
+    Ok(PropertyValueSlice {
+        timestamp: reported_value_with_reporter.timestamp as u64,
+        value: parse_value(reported_value_with_reporter, struct_values)?,
+        reporter: ReporterSlice {
+            public_key: reported_value_with_reporter
+                .public_key
+                .clone()
+                .unwrap_or_else(|| "".to_string()),
+            metadata: reported_value_with_reporter
+                .metadata
+                .clone()
+                .unwrap_or_else(|| JsonValue::Object(Map::new())),
+        },
+    })
 }
 
 fn parse_value(
@@ -391,7 +396,7 @@ fn parse_value(
         }
         "LatLong" => {
             let lat_long = match val.lat_long_value.clone() {
-                Some(lat_long_value) => LatLong::from_model(lat_long_value),
+                Some(lat_long_value) => LatLong::from(lat_long_value),
                 None => {
                     return Err(RestApiResponseError::DatabaseError(
                         "ReportedValue is of LatLong data_type, but is missing lat_long value"
@@ -527,7 +532,7 @@ fn parse_property_slice(
     // Sort updates from oldest to newest.
     updates.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
 
-    let property_info = PropertySlice::from_model(
+    let property_info = make_property_slice(
         &property,
         &active_reporters,
         &reported_value.data_type,
@@ -559,7 +564,7 @@ fn parse_reported_values(
         None
     };
 
-    PropertyValueSlice::from_model(&reported_value, struct_values)
+    make_property_value_slice(&reported_value, struct_values)
 }
 
 fn parse_struct_values(
@@ -599,18 +604,269 @@ fn parse_struct_values(
                 struct_value.reported_value_end_block_num,
                 &struct_value_names,
             )?;
-            inner_values.push(StructPropertyValue::from_model(
+            inner_values.push(make_struct_property_value(
                 value_name,
                 &struct_value,
                 Some(inner_struct_values),
             )?);
         } else {
-            inner_values.push(StructPropertyValue::from_model(
-                value_name,
-                &struct_value,
-                None,
-            )?);
+            inner_values.push(make_struct_property_value(value_name, &struct_value, None)?);
         }
     }
     Ok(inner_values)
 }
+
+fn make_property_value_bucket_slice(
+    bucket: &ReportedValueBucket,
+    aggregates: &Aggregates,
+) -> PropertyValueBucketSlice {
+    PropertyValueBucketSlice {
+        timestamp: bucket.bucket_start as u64,
+        count: if aggregates.count {
+            Some(bucket.value_count)
+        } else {
+            None
+        },
+        min: if aggregates.min {
+            Some(bucket.min_value)
+        } else {
+            None
+        },
+        max: if aggregates.max {
+            Some(bucket.max_value)
+        } else {
+            None
+        },
+        avg: if aggregates.avg {
+            Some(bucket.avg_value)
+        } else {
+            None
+        },
+    }
+}
+
+impl ApiSchema for PropertyValueSeriesSlice {
+    fn example() -> Self {
+        PropertyValueSeriesSlice {
+            record_id: "7h15-45537-15-br173".to_string(),
+            property_name: "temperature".to_string(),
+            number_exponent: -6,
+            bucket: DEFAULT_BUCKET_WIDTH,
+            data: vec![PropertyValueBucketSlice {
+                timestamp: 1_557_946_800,
+                count: Some(12),
+                min: Some(18.25),
+                max: Some(21.5),
+                avg: Some(19.875),
+            }],
+        }
+    }
+}
+
+/// The aggregate functions requested through the `agg` query parameter.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Aggregates {
+    pub count: bool,
+    pub min: bool,
+    pub max: bool,
+    pub avg: bool,
+}
+
+impl Aggregates {
+    fn parse(agg: &str) -> Result<Aggregates, RestApiResponseError> {
+        let mut aggregates = Aggregates::default();
+        for name in agg.split(',') {
+            match name.trim() {
+                "count" => aggregates.count = true,
+                "min" => aggregates.min = true,
+                "max" => aggregates.max = true,
+                "avg" => aggregates.avg = true,
+                _ => {
+                    return Err(RestApiResponseError::BadRequest(format!(
+                        "Query agg has invalid value {}. \
+                         It should be a list of count, min, max or avg",
+                        name
+                    )));
+                }
+            }
+        }
+        Ok(aggregates)
+    }
+}
+
+/// Parses a bucket width such as `30s`, `15m`, `1h`, `1d` or `1w` into seconds.
+fn parse_bucket_width(bucket: &str) -> Result<i64, RestApiResponseError> {
+    let err = || {
+        RestApiResponseError::BadRequest(format!(
+            "Query bucket has invalid value {}. \
+             It should be a positive integer followed by one of s, m, h, d or w",
+            bucket
+        ))
+    };
+
+    let split = bucket.len().checked_sub(1).ok_or_else(err)?;
+    if !bucket.is_char_boundary(split) {
+        return Err(err());
+    }
+    let (amount, unit) = bucket.split_at(split);
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(err()),
+    };
+
+    match amount.parse::<i64>() {
+        Ok(amount) if amount > 0 => amount.checked_mul(multiplier).ok_or_else(err),
+        _ => Err(err()),
+    }
+}
+
+fn parse_timestamp(
+    query: &HashMap<String, String>,
+    name: &str,
+) -> Result<Option<i64>, RestApiResponseError> {
+    match query.get(name) {
+        // Reported values are timestamped with unsigned seconds
+        Some(value) => match value.parse::<i64>() {
+            Ok(timestamp) if timestamp >= 0 => Ok(Some(timestamp)),
+            _ => Err(RestApiResponseError::BadRequest(format!(
+                "Query {} has invalid value {}. It should be a unix timestamp in seconds",
+                name, value
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+
+struct FetchRecordPropertyValues {
+    record_id: String,
+    property_name: String,
+    from: i64,
+    to: i64,
+    bucket_width: i64,
+    aggregates: Aggregates,
+}
+
+impl Message for FetchRecordPropertyValues {
+    type Result = Result<PropertyValueSeriesSlice, RestApiResponseError>;
+}
+
+impl Handler<FetchRecordPropertyValues> for DbExecutor {
+    type Result = Result<PropertyValueSeriesSlice, RestApiResponseError>;
+
+    fn handle(
+        &mut self,
+        msg: FetchRecordPropertyValues,
+        _: &mut SyncContext<Self>,
+    ) -> Self::Result {
+        let record =
+            db::fetch_record(&*self.connection_pool.get()?, &msg.record_id)?.ok_or_else(|| {
+                RestApiResponseError::NotFoundError(format!(
+                    "Could not find record with id: {}",
+                    msg.record_id
+                ))
+            })?;
+
+        let definition = db::fetch_grid_property_definition(
+            &*self.connection_pool.get()?,
+            &record.schema,
+            &msg.property_name,
+        )?
+        .ok_or_else(|| {
+            RestApiResponseError::NotFoundError(format!(
+                "Could not find property {} for record {}",
+                msg.property_name, msg.record_id
+            ))
+        })?;
+
+        if definition.data_type != "Number" {
+            return Err(RestApiResponseError::BadRequest(format!(
+                "Property {} is of {} data_type, only Number properties can be aggregated",
+                msg.property_name, definition.data_type
+            )));
+        }
+
+        let data = db::list_reported_value_buckets(
+            &*self.connection_pool.get()?,
+            &msg.record_id,
+            &msg.property_name,
+            definition.number_exponent,
+            msg.bucket_width,
+            msg.from,
+            msg.to,
+        )?
+        .iter()
+        .map(|bucket| make_property_value_bucket_slice(bucket, &msg.aggregates))
+        .collect();
+
+        Ok(PropertyValueSeriesSlice {
+            record_id: msg.record_id,
+            property_name: msg.property_name,
+            number_exponent: definition.number_exponent,
+            bucket: msg.bucket_width,
+            data,
+        })
+    }
+}
+
+pub fn fetch_record_property_values(
+    req: HttpRequest<AppState>,
+    params: Path<(String, String)>,
+    query: Query<HashMap<String, String>>,
+) -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+    let from = match parse_timestamp(&query, "from") {
+        Ok(from) => from.unwrap_or(0),
+        Err(err) => return future::err(err).responder(),
+    };
+
+    let to = match parse_timestamp(&query, "to") {
+        Ok(to) => to.unwrap_or(std::i64::MAX),
+        Err(err) => return future::err(err).responder(),
+    };
+
+    if from > to {
+        return future::err(RestApiResponseError::BadRequest(format!(
+            "Query from ({}) must not be later than to ({})",
+            from, to
+        )))
+        .responder();
+    }
+
+    let bucket_width = match query.get("bucket") {
+        Some(bucket) => match parse_bucket_width(bucket) {
+            Ok(bucket_width) => bucket_width,
+            Err(err) => return future::err(err).responder(),
+        },
+        None => DEFAULT_BUCKET_WIDTH,
+    };
+
+    let aggregates = match Aggregates::parse(
+        query
+            .get("agg")
+            .map(String::as_str)
+            .unwrap_or("min,max,avg"),
+    ) {
+        Ok(aggregates) => aggregates,
+        Err(err) => return future::err(err).responder(),
+    };
+
+    req.state()
+        .database_connection
+        .send(FetchRecordPropertyValues {
+            record_id: params.0.clone(),
+            property_name: params.1.clone(),
+            from,
+            to,
+            bucket_width,
+            aggregates,
+        })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(series) => Ok(HttpResponse::Ok().json(series)),
+            Err(err) => Err(err),
+        })
+        .responder()
+}
@@ -0,0 +1,131 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::database::{helpers as db, models::Role};
+use crate::rest_api::{
+    error::RestApiResponseError, openapi::ApiSchema, routes::DbExecutor, AppState,
+};
+
+use actix::{Handler, Message, SyncContext};
+use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Path, Query};
+use futures::Future;
+use std::collections::HashMap;
+
+pub use grid_client::models::RoleSlice;
+
+impl From<&Role> for RoleSlice {
+    fn from(role: &Role) -> Self {
+        Self {
+            org_id: role.org_id.clone(),
+            name: role.name.clone(),
+            description: role.description.clone(),
+            permissions: role.permissions.clone(),
+            inherit_from: role.inherit_from.clone(),
+        }
+    }
+}
+
+impl ApiSchema for RoleSlice {
+    fn example() -> Self {
+        Self {
+            org_id: "philips001".to_string(),
+            name: "operator".to_string(),
+            description: "Reports the temperature of shipments".to_string(),
+            permissions: vec!["tnt::reporter".to_string()],
+            inherit_from: vec!["viewer".to_string()],
+        }
+    }
+}
+
+struct ListRoles {
+    org_id: Option<String>,
+}
+
+impl Message for ListRoles {
+    type Result = Result<Vec<RoleSlice>, RestApiResponseError>;
+}
+
+impl Handler<ListRoles> for DbExecutor {
+    type Result = Result<Vec<RoleSlice>, RestApiResponseError>;
+
+    fn handle(&mut self, msg: ListRoles, _: &mut SyncContext<Self>) -> Self::Result {
+        let conn = self.connection_pool.get()?;
+        let fetched_roles = db::get_roles(&*conn, msg.org_id.as_ref().map(String::as_str))?
+            .iter()
+            .map(RoleSlice::from)
+            .collect();
+
+        Ok(fetched_roles)
+    }
+}
+
+pub fn list_roles(
+    req: HttpRequest<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+    req.state()
+        .database_connection
+        .send(ListRoles {
+            org_id: query.get("org_id").cloned(),
+        })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(roles) => Ok(HttpResponse::Ok().json(roles)),
+            Err(err) => Err(err),
+        })
+        .responder()
+}
+
+struct FetchRole {
+    org_id: String,
+    name: String,
+}
+
+impl Message for FetchRole {
+    type Result = Result<RoleSlice, RestApiResponseError>;
+}
+
+impl Handler<FetchRole> for DbExecutor {
+    type Result = Result<RoleSlice, RestApiResponseError>;
+
+    fn handle(&mut self, msg: FetchRole, _: &mut SyncContext<Self>) -> Self::Result {
+        let fetched_role =
+            match db::get_role(&*self.connection_pool.get()?, &msg.org_id, &msg.name)? {
+                Some(role) => RoleSlice::from(&role),
+                None => {
+                    return Err(RestApiResponseError::NotFoundError(format!(
+                        "Could not find role {} of organization: {}",
+                        msg.name, msg.org_id
+                    )));
+                }
+            };
+
+        Ok(fetched_role)
+    }
+}
+
+pub fn fetch_role(
+    req: HttpRequest<AppState>,
+    params: Path<(String, String)>,
+) -> impl Future<Item = HttpResponse, Error = RestApiResponseError> {
+    let (org_id, name) = params.into_inner();
+    req.state()
+        .database_connection
+        .send(FetchRole { org_id, name })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(role) => Ok(HttpResponse::Ok().json(role)),
+            Err(err) => Err(err),
+        })
+}
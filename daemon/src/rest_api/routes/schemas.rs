@@ -16,50 +16,47 @@ use crate::database::{
     helpers as db,
     models::{GridPropertyDefinition, GridSchema},
 };
-use crate::rest_api::{error::RestApiResponseError, routes::DbExecutor, AppState};
+use crate::rest_api::{
+    error::RestApiResponseError, openapi::ApiSchema, routes::DbExecutor, AppState,
+};
 
 use actix::{Handler, Message, SyncContext};
 use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Path};
 use futures::Future;
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GridSchemaSlice {
-    pub name: String,
-    pub description: String,
-    pub owner: String,
-    pub properties: Vec<GridPropertyDefinitionSlice>,
-}
-
-impl GridSchemaSlice {
-    pub fn from_schema(schema: &GridSchema, properties: Vec<GridPropertyDefinition>) -> Self {
-        Self {
-            name: schema.name.clone(),
-            description: schema.description.clone(),
-            owner: schema.owner.clone(),
-            properties: properties
-                .iter()
-                .map(|prop| GridPropertyDefinitionSlice::from_definition(prop))
-                .collect(),
-        }
+pub use grid_client::models::{GridPropertyDefinitionSlice, GridSchemaSlice};
+
+/// Makes the slice of a schema, listing the properties declared by the schema along with its
+/// effective properties, which include the properties inherited from its parent and property
+/// groups.
+fn make_schema_slice(
+    schema: &GridSchema,
+    properties: Vec<GridPropertyDefinition>,
+) -> GridSchemaSlice {
+    GridSchemaSlice {
+        name: schema.name.clone(),
+        description: schema.description.clone(),
+        owner: schema.owner.clone(),
+        version: schema.version,
+        deprecated: schema.deprecated,
+        parent: schema.parent.clone(),
+        parent_version: schema.parent_version,
+        property_groups: schema.property_groups.clone(),
+        properties: properties
+            .iter()
+            .filter(|definition| !definition.inherited)
+            .map(GridPropertyDefinitionSlice::from)
+            .collect(),
+        effective_properties: properties
+            .iter()
+            .map(GridPropertyDefinitionSlice::from)
+            .collect(),
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GridPropertyDefinitionSlice {
-    pub name: String,
-    pub schema_name: String,
-    pub data_type: String,
-    pub required: bool,
-    pub description: String,
-    pub number_exponent: i64,
-    pub enum_options: Vec<String>,
-    pub struct_properties: Vec<String>,
-}
-
-impl GridPropertyDefinitionSlice {
-    pub fn from_definition(definition: &GridPropertyDefinition) -> Self {
+impl From<&GridPropertyDefinition> for GridPropertyDefinitionSlice {
+    fn from(definition: &GridPropertyDefinition) -> Self {
         Self {
             name: definition.name.clone(),
             schema_name: definition.schema_name.clone(),
@@ -69,6 +66,42 @@ impl GridPropertyDefinitionSlice {
             number_exponent: definition.number_exponent,
             enum_options: definition.enum_options.clone(),
             struct_properties: definition.struct_properties.clone(),
+            deprecated: definition.deprecated,
+            inherited: definition.inherited,
+        }
+    }
+}
+
+impl ApiSchema for GridSchemaSlice {
+    fn example() -> Self {
+        Self {
+            name: "Lightbulb".to_string(),
+            description: "Example Lightbulb schema".to_string(),
+            owner: "philips001".to_string(),
+            version: 1,
+            deprecated: false,
+            parent: "Light".to_string(),
+            parent_version: 1,
+            property_groups: vec!["electrical".to_string()],
+            properties: vec![GridPropertyDefinitionSlice::example()],
+            effective_properties: vec![GridPropertyDefinitionSlice::example()],
+        }
+    }
+}
+
+impl ApiSchema for GridPropertyDefinitionSlice {
+    fn example() -> Self {
+        Self {
+            name: "size".to_string(),
+            schema_name: "Lightbulb".to_string(),
+            data_type: "Number".to_string(),
+            required: true,
+            description: "Lightbulb radius, in millimeters".to_string(),
+            number_exponent: -6,
+            enum_options: vec!["filament".to_string()],
+            struct_properties: vec!["watts".to_string()],
+            deprecated: false,
+            inherited: false,
         }
     }
 }
@@ -95,7 +128,7 @@ impl Handler<ListGridSchemas> for DbExecutor {
         let fetched_schemas = db::list_grid_schemas(&*self.connection_pool.get()?)?
             .iter()
             .map(|schema| {
-                GridSchemaSlice::from_schema(
+                make_schema_slice(
                     schema,
                     properties.remove(&schema.name).unwrap_or_else(|| vec![]),
                 )
@@ -137,7 +170,7 @@ impl Handler<FetchGridSchema> for DbExecutor {
         )?;
         let fetched_schema = match db::fetch_grid_schema(&*self.connection_pool.get()?, &msg.name)?
         {
-            Some(schema) => GridSchemaSlice::from_schema(&schema, properties),
+            Some(schema) => make_schema_slice(&schema, properties),
             None => {
                 return Err(RestApiResponseError::NotFoundError(format!(
                     "Could not find schema with name: {}",
@@ -165,3 +198,52 @@ pub fn fetch_grid_schema(
             Err(err) => Err(err),
         })
 }
+
+struct FetchGridSchemaVersion {
+    name: String,
+    version: i64,
+}
+
+impl Message for FetchGridSchemaVersion {
+    type Result = Result<GridSchemaSlice, RestApiResponseError>;
+}
+
+impl Handler<FetchGridSchemaVersion> for DbExecutor {
+    type Result = Result<GridSchemaSlice, RestApiResponseError>;
+
+    fn handle(&mut self, msg: FetchGridSchemaVersion, _: &mut SyncContext<Self>) -> Self::Result {
+        let conn = self.connection_pool.get()?;
+        let schema = match db::fetch_grid_schema_version(&*conn, &msg.name, msg.version)? {
+            Some(schema) => schema,
+            None => {
+                return Err(RestApiResponseError::NotFoundError(format!(
+                    "Could not find version {} of schema with name: {}",
+                    msg.version, msg.name
+                )));
+            }
+        };
+        let properties = db::list_grid_property_definitions_with_schema_version(
+            &*conn,
+            &msg.name,
+            msg.version,
+            schema.start_block_num,
+        )?;
+
+        Ok(make_schema_slice(&schema, properties))
+    }
+}
+
+pub fn fetch_grid_schema_version(
+    req: HttpRequest<AppState>,
+    params: Path<(String, i64)>,
+) -> impl Future<Item = HttpResponse, Error = RestApiResponseError> {
+    let (name, version) = params.into_inner();
+    req.state()
+        .database_connection
+        .send(FetchGridSchemaVersion { name, version })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(schema) => Ok(HttpResponse::Ok().json(schema)),
+            Err(err) => Err(err),
+        })
+}
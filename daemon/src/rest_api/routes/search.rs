@@ -0,0 +1,270 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::database::{
+    helpers as db,
+    models::{SearchFacetCount, SearchResult},
+};
+use crate::rest_api::{
+    error::RestApiResponseError, openapi::ApiSchema, routes::DbExecutor, AppState,
+};
+
+use actix::{Handler, Message, SyncContext};
+use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Query};
+use futures::{future, Future};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+const DEFAULT_LIMIT: i64 = 100;
+const MAX_LIMIT: i64 = 1000;
+
+const ENTITY_TYPES: [&str; 3] = ["agent", "organization", "record"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResultSlice {
+    pub entity_type: String,
+    pub id: String,
+    pub org_id: Option<String>,
+    pub schema: Option<String>,
+    pub rank: f64,
+}
+
+impl SearchResultSlice {
+    pub fn from_model(result: &SearchResult) -> Self {
+        Self {
+            entity_type: result.entity_type.clone(),
+            id: result.entity_id.clone(),
+            org_id: result.org_id.clone(),
+            schema: result.schema_name.clone(),
+            rank: result.rank,
+        }
+    }
+}
+
+/// Number of matching entities for each value of a facet, computed over the
+/// whole result set rather than the returned page.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchFacetsSlice {
+    pub entity_type: BTreeMap<String, usize>,
+    pub org: BTreeMap<String, usize>,
+    pub schema: BTreeMap<String, usize>,
+}
+
+impl SearchFacetsSlice {
+    pub fn from_models(counts: &[SearchFacetCount]) -> Self {
+        counts
+            .iter()
+            .fold(SearchFacetsSlice::default(), |mut facets, count| {
+                let values = match count.facet.as_str() {
+                    "entity_type" => &mut facets.entity_type,
+                    "org" => &mut facets.org,
+                    _ => &mut facets.schema,
+                };
+                values.insert(count.value.clone(), count.count as usize);
+                facets
+            })
+    }
+
+    /// The number of matching entities, each counted under its one entity type
+    pub fn total(&self) -> usize {
+        self.entity_type.values().sum()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub data: Vec<SearchResultSlice>,
+    pub facets: SearchFacetsSlice,
+    pub total: usize,
+}
+
+impl ApiSchema for SearchResponse {
+    fn example() -> Self {
+        let result = SearchResultSlice {
+            entity_type: "record".to_string(),
+            id: "7h15-45537-15-br173".to_string(),
+            org_id: Some("philips001".to_string()),
+            schema: Some("Lightbulb".to_string()),
+            rank: 0.06,
+        };
+        let agent = SearchResultSlice {
+            entity_type: "agent".to_string(),
+            id: "02cd3181dbd7d1539f470436ce222c53ab5e514f67809dc0095895e6cdfba97612".to_string(),
+            org_id: Some("philips001".to_string()),
+            schema: None,
+            rank: 0.03,
+        };
+        let mut facets = SearchFacetsSlice::default();
+        facets.entity_type.insert("record".to_string(), 1);
+        facets.entity_type.insert("agent".to_string(), 1);
+        facets.org.insert("philips001".to_string(), 2);
+        facets.schema.insert("Lightbulb".to_string(), 1);
+
+        SearchResponse {
+            data: vec![result, agent],
+            facets,
+            total: 2,
+        }
+    }
+}
+
+/// Splits free text into lowercase words. Only alphanumeric characters are
+/// kept, which also keeps the tsquery operators and the `LIKE` wildcards out
+/// of user input.
+fn search_terms(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Converts search terms into a `tsquery` that matches every term, each as
+/// the prefix of a word.
+fn to_tsquery(terms: &[String]) -> String {
+    terms
+        .iter()
+        .map(|term| format!("{}:*", term))
+        .collect::<Vec<_>>()
+        .join(" & ")
+}
+
+/// Converts search terms into `ILIKE` patterns that match each term anywhere
+/// in a document, such as `ABC` in the serial number `XYZABC123`.
+fn to_patterns(terms: &[String]) -> Vec<String> {
+    terms.iter().map(|term| format!("%{}%", term)).collect()
+}
+
+struct Search {
+    query: String,
+    patterns: Vec<String>,
+    entity_type: Option<String>,
+    org_id: Option<String>,
+    schema_name: Option<String>,
+    limit: i64,
+    offset: i64,
+}
+
+impl Message for Search {
+    type Result = Result<SearchResponse, RestApiResponseError>;
+}
+
+impl Handler<Search> for DbExecutor {
+    type Result = Result<SearchResponse, RestApiResponseError>;
+
+    fn handle(&mut self, msg: Search, _: &mut SyncContext<Self>) -> Self::Result {
+        let conn = &*self.connection_pool.get()?;
+        let entity_type = msg.entity_type.as_ref().map(String::as_str);
+        let org_id = msg.org_id.as_ref().map(String::as_str);
+        let schema_name = msg.schema_name.as_ref().map(String::as_str);
+
+        let results = db::search_documents(
+            conn,
+            &msg.query,
+            &msg.patterns,
+            entity_type,
+            org_id,
+            schema_name,
+            msg.limit,
+            msg.offset,
+        )?;
+        let facets = SearchFacetsSlice::from_models(&db::search_facets(
+            conn,
+            &msg.query,
+            &msg.patterns,
+            entity_type,
+            org_id,
+            schema_name,
+        )?);
+
+        Ok(SearchResponse {
+            total: facets.total(),
+            facets,
+            data: results.iter().map(SearchResultSlice::from_model).collect(),
+        })
+    }
+}
+
+pub fn search(
+    req: HttpRequest<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+    let terms = match query.get("q").map(|q| search_terms(q)) {
+        Some(terms) if !terms.is_empty() => terms,
+        _ => {
+            return future::err(RestApiResponseError::BadRequest(
+                "Request for search missing q query.".to_string(),
+            ))
+            .responder();
+        }
+    };
+
+    let entity_type = match query.get("type") {
+        Some(entity_type) if !ENTITY_TYPES.contains(&entity_type.as_str()) => {
+            return future::err(RestApiResponseError::BadRequest(format!(
+                "Query type has invalid value {}. It should be one of {}",
+                entity_type,
+                ENTITY_TYPES.join(", ")
+            )))
+            .responder();
+        }
+        entity_type => entity_type.cloned(),
+    };
+
+    let limit = match query.get("limit") {
+        Some(limit) => match limit.parse::<i64>() {
+            Ok(limit) if (0..=MAX_LIMIT).contains(&limit) => limit,
+            _ => {
+                return future::err(RestApiResponseError::BadRequest(format!(
+                    "Query limit has invalid value {}. It should be a number up to {}",
+                    limit, MAX_LIMIT
+                )))
+                .responder();
+            }
+        },
+        None => DEFAULT_LIMIT,
+    };
+
+    let offset = match query.get("offset") {
+        Some(offset) => match offset.parse::<usize>().map(i64::try_from) {
+            Ok(Ok(offset)) => offset,
+            _ => {
+                return future::err(RestApiResponseError::BadRequest(format!(
+                    "Query offset has invalid value {}. It should be a number",
+                    offset
+                )))
+                .responder();
+            }
+        },
+        None => 0,
+    };
+
+    req.state()
+        .database_connection
+        .send(Search {
+            query: to_tsquery(&terms),
+            patterns: to_patterns(&terms),
+            entity_type,
+            org_id: query.get("org").cloned(),
+            schema_name: query.get("schema").cloned(),
+            limit,
+            offset,
+        })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(results) => Ok(HttpResponse::Ok().json(results)),
+            Err(err) => Err(err),
+        })
+        .responder()
+}
@@ -0,0 +1,601 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::rest_api::{
+    error::RestApiResponseError,
+    openapi::ApiSchema,
+    routes::{query_validator, SawtoothMessageSender},
+    AppState,
+};
+
+use actix::{Context, Handler, Message};
+use actix_web::{AsyncResponder, HttpRequest, HttpResponse, Path, Query};
+use futures::future;
+use futures::future::Future;
+use grid_sdk::{
+    protocol::{
+        addressing::{classify_address, Resource},
+        pike::state::{
+            Agent, AgentList, AlternateIdIndex, AlternateIdIndexList, KeyValueEntry, Organization,
+            OrganizationList, Role, RoleList,
+        },
+        schema::state::{
+            DataType, PropertyDefinition, PropertyGroup, PropertyGroupList, PropertyValue, Schema,
+            SchemaList,
+        },
+        track_and_trace::state::{
+            AssociatedAgent, Property, PropertyList, PropertyPage, PropertyPageList, Proposal,
+            ProposalList, Record, RecordList,
+        },
+    },
+    protos::FromBytes,
+};
+use sawtooth_sdk::messages::client_list_control::ClientPagingControls;
+use sawtooth_sdk::messages::client_state::{
+    ClientStateGetRequest, ClientStateGetResponse, ClientStateGetResponse_Status,
+    ClientStateListRequest, ClientStateListResponse, ClientStateListResponse_Status,
+};
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashMap;
+
+pub use grid_client::models::{
+    StateEntrySlice, StateListResponse, StatePagingSlice, StateResponse,
+};
+
+const DEFAULT_STATE_LIMIT: i32 = 100;
+const MAX_STATE_LIMIT: i32 = 1000;
+
+/// Creates a state entry. When `decode` is set, the entry is decoded with the
+/// Grid state type stored at its address.
+fn make_state_entry_slice(
+    address: &str,
+    data: &[u8],
+    decode: bool,
+) -> Result<StateEntrySlice, RestApiResponseError> {
+    let decoded = if decode {
+        decode_state(address, data)?
+    } else {
+        None
+    };
+
+    Ok(StateEntrySlice {
+        address: address.to_string(),
+        data: base64::encode(data),
+        decoded,
+    })
+}
+
+impl ApiSchema for StateEntrySlice {
+    fn example() -> Self {
+        StateEntrySlice {
+            address: "cad11d00d4a5cbd8ab19d4ec7fd58dc7cb6f1e3d0a4d8f4b8d6b8c2fbbb4a3e5c9e2b7"
+                .to_string(),
+            data: "CkoKCnBoaWxpcHMwMDE=".to_string(),
+            decoded: Some(json!([{
+                "org_id": "philips001",
+                "public_key": "02cd3181dbd7d1539f470436ce222c53ab5e514f67809dc0095895e6cdfba97612",
+                "active": true,
+                "roles": ["admin"],
+                "metadata": { "agent_name": "Smith" },
+                "successor": "",
+            }])),
+        }
+    }
+}
+
+impl ApiSchema for StateResponse {
+    fn example() -> Self {
+        StateResponse {
+            data: StateEntrySlice::example(),
+            head: "f2b8c3f6e0f9e2a0a7d1d2c7e3b1fe0a4b8e35c1d0d8d8a2b37c1b0c9ec1a5d2".to_string(),
+        }
+    }
+}
+
+impl ApiSchema for StateListResponse {
+    fn example() -> Self {
+        StateListResponse {
+            data: vec![StateEntrySlice::example()],
+            head: "f2b8c3f6e0f9e2a0a7d1d2c7e3b1fe0a4b8e35c1d0d8d8a2b37c1b0c9ec1a5d2".to_string(),
+            paging: StatePagingSlice {
+                start: "cad11d00".to_string(),
+                limit: DEFAULT_STATE_LIMIT,
+                next: "cad11d00f7e4a5cbd8ab19d4ec7fd58dc7cb6f1e3d0a4d8f4b8d6b8c2fbbb4a3e5c9"
+                    .to_string(),
+            },
+        }
+    }
+}
+
+struct FetchState {
+    address: String,
+    decode: bool,
+}
+
+impl Message for FetchState {
+    type Result = Result<StateResponse, RestApiResponseError>;
+}
+
+impl Handler<FetchState> for SawtoothMessageSender {
+    type Result = Result<StateResponse, RestApiResponseError>;
+
+    fn handle(&mut self, msg: FetchState, _: &mut Context<Self>) -> Self::Result {
+        let mut state_request = ClientStateGetRequest::new();
+        state_request.set_address(msg.address.clone());
+
+        let response: ClientStateGetResponse = query_validator(
+            &*self.sender,
+            Message_MessageType::CLIENT_STATE_GET_REQUEST,
+            &state_request,
+        )?;
+
+        match response.get_status() {
+            ClientStateGetResponse_Status::OK => Ok(StateResponse {
+                data: make_state_entry_slice(&msg.address, response.get_value(), msg.decode)?,
+                head: response.get_head_id().to_string(),
+            }),
+            ClientStateGetResponse_Status::NO_RESOURCE => Err(RestApiResponseError::NotFoundError(
+                format!("Could not find state at address: {}", msg.address),
+            )),
+            ClientStateGetResponse_Status::INVALID_ADDRESS => {
+                Err(RestApiResponseError::BadRequest(format!(
+                    "State addresses are 70 character hex-strings. The address {} was invalid",
+                    msg.address
+                )))
+            }
+            status => Err(RestApiResponseError::SawtoothValidatorResponseError(
+                format!("Validator responded with error {:?}", status),
+            )),
+        }
+    }
+}
+
+pub fn fetch_state(
+    req: HttpRequest<AppState>,
+    address: Path<String>,
+    query: Query<HashMap<String, String>>,
+) -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+    let decode = match parse_decode(&query) {
+        Ok(decode) => decode,
+        Err(err) => return future::err(err).responder(),
+    };
+
+    req.state()
+        .sawtooth_connection
+        .send(FetchState {
+            address: address.into_inner(),
+            decode,
+        })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(state) => Ok(HttpResponse::Ok().json(state)),
+            Err(err) => Err(err),
+        })
+        .responder()
+}
+
+struct ListState {
+    prefix: String,
+    start: String,
+    limit: i32,
+    decode: bool,
+}
+
+impl Message for ListState {
+    type Result = Result<StateListResponse, RestApiResponseError>;
+}
+
+impl Handler<ListState> for SawtoothMessageSender {
+    type Result = Result<StateListResponse, RestApiResponseError>;
+
+    fn handle(&mut self, msg: ListState, _: &mut Context<Self>) -> Self::Result {
+        let mut paging = ClientPagingControls::new();
+        paging.set_start(msg.start.clone());
+        paging.set_limit(msg.limit);
+        let mut state_request = ClientStateListRequest::new();
+        state_request.set_address(msg.prefix.clone());
+        state_request.set_paging(paging);
+
+        let response: ClientStateListResponse = query_validator(
+            &*self.sender,
+            Message_MessageType::CLIENT_STATE_LIST_REQUEST,
+            &state_request,
+        )?;
+
+        match response.get_status() {
+            ClientStateListResponse_Status::OK => Ok(StateListResponse {
+                data: response
+                    .get_entries()
+                    .iter()
+                    .map(|entry| {
+                        make_state_entry_slice(entry.get_address(), entry.get_data(), msg.decode)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+                head: response.get_head_id().to_string(),
+                paging: StatePagingSlice {
+                    start: msg.start,
+                    limit: msg.limit,
+                    next: response.get_paging().get_next().to_string(),
+                },
+            }),
+            ClientStateListResponse_Status::NO_RESOURCE => Ok(StateListResponse {
+                data: vec![],
+                head: response.get_head_id().to_string(),
+                paging: StatePagingSlice {
+                    start: msg.start,
+                    limit: msg.limit,
+                    next: String::new(),
+                },
+            }),
+            ClientStateListResponse_Status::INVALID_ADDRESS => {
+                Err(RestApiResponseError::BadRequest(format!(
+                    "State address prefixes are hex-strings of up to 70 characters. The prefix \
+                     {} was invalid",
+                    msg.prefix
+                )))
+            }
+            ClientStateListResponse_Status::INVALID_PAGING => Err(
+                RestApiResponseError::BadRequest(format!("Paging start {} was invalid", msg.start)),
+            ),
+            status => Err(RestApiResponseError::SawtoothValidatorResponseError(
+                format!("Validator responded with error {:?}", status),
+            )),
+        }
+    }
+}
+
+pub fn list_state(
+    req: HttpRequest<AppState>,
+    query: Query<HashMap<String, String>>,
+) -> Box<Future<Item = HttpResponse, Error = RestApiResponseError>> {
+    let decode = match parse_decode(&query) {
+        Ok(decode) => decode,
+        Err(err) => return future::err(err).responder(),
+    };
+
+    let limit = match query.get("limit") {
+        Some(limit) => match limit.parse::<i32>() {
+            Ok(limit) if limit > 0 && limit <= MAX_STATE_LIMIT => limit,
+            _ => {
+                return future::err(RestApiResponseError::BadRequest(format!(
+                    "Query limit has invalid value {}. It should be a number between 1 and {}",
+                    limit, MAX_STATE_LIMIT
+                )))
+                .responder();
+            }
+        },
+        None => DEFAULT_STATE_LIMIT,
+    };
+
+    req.state()
+        .sawtooth_connection
+        .send(ListState {
+            prefix: query.get("prefix").cloned().unwrap_or_default(),
+            start: query.get("start").cloned().unwrap_or_default(),
+            limit,
+            decode,
+        })
+        .from_err()
+        .and_then(move |res| match res {
+            Ok(state) => Ok(HttpResponse::Ok().json(state)),
+            Err(err) => Err(err),
+        })
+        .responder()
+}
+
+/// Parses the optional `decode` query parameter, which defaults to false.
+pub fn parse_decode(query: &HashMap<String, String>) -> Result<bool, RestApiResponseError> {
+    match query.get("decode") {
+        Some(decode) => decode.parse::<bool>().map_err(|_| {
+            RestApiResponseError::BadRequest(format!(
+                "Query decode has invalid value {}. It should be true or false",
+                decode
+            ))
+        }),
+        None => Ok(false),
+    }
+}
+
+/// Decodes the Grid state stored at an address as JSON. Returns `None` when
+/// the address does not belong to a Pike, Schema or Track and Trace type.
+pub fn decode_state(address: &str, data: &[u8]) -> Result<Option<JsonValue>, RestApiResponseError> {
+    let decode_err = |err| {
+        RestApiResponseError::RequestHandlerError(format!(
+            "Failed to decode state at address {}: {}",
+            address, err
+        ))
+    };
+
+    let decoded = match classify_address(address) {
+        Ok(Resource::Agent) => {
+            let agents = AgentList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(agents.agents().iter().map(agent_to_json).collect())
+        }
+        Ok(Resource::Organization) => {
+            let organizations = OrganizationList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(
+                organizations
+                    .organizations()
+                    .iter()
+                    .map(organization_to_json)
+                    .collect(),
+            )
+        }
+        Ok(Resource::Role) => {
+            let roles = RoleList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(roles.roles().iter().map(role_to_json).collect())
+        }
+        Ok(Resource::AlternateId) => {
+            let entries = AlternateIdIndexList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(
+                entries
+                    .entries()
+                    .iter()
+                    .map(alternate_id_index_to_json)
+                    .collect(),
+            )
+        }
+        Ok(Resource::Schema) | Ok(Resource::SchemaVersion(_)) => {
+            let schemas = SchemaList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(schemas.schemas().iter().map(schema_to_json).collect())
+        }
+        Ok(Resource::PropertyGroup) => {
+            let property_groups = PropertyGroupList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(
+                property_groups
+                    .property_groups()
+                    .iter()
+                    .map(property_group_to_json)
+                    .collect(),
+            )
+        }
+        Ok(Resource::Property) => {
+            let properties = PropertyList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(
+                properties
+                    .properties()
+                    .iter()
+                    .map(property_to_json)
+                    .collect(),
+            )
+        }
+        Ok(Resource::PropertyPage(_)) => {
+            let pages = PropertyPageList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(
+                pages
+                    .property_pages()
+                    .iter()
+                    .map(property_page_to_json)
+                    .collect(),
+            )
+        }
+        Ok(Resource::Proposal) => {
+            let proposals = ProposalList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(proposals.proposals().iter().map(proposal_to_json).collect())
+        }
+        Ok(Resource::Record) => {
+            let records = RecordList::from_bytes(data).map_err(decode_err)?;
+            JsonValue::Array(records.records().iter().map(record_to_json).collect())
+        }
+        Err(_) => return Ok(None),
+    };
+
+    Ok(Some(decoded))
+}
+
+fn metadata_to_json(metadata: &[KeyValueEntry]) -> JsonValue {
+    JsonValue::Object(
+        metadata
+            .iter()
+            .map(|entry| (entry.key().to_string(), json!(entry.value())))
+            .collect::<Map<String, JsonValue>>(),
+    )
+}
+
+fn agent_to_json(agent: &Agent) -> JsonValue {
+    json!({
+        "org_id": agent.org_id(),
+        "public_key": agent.public_key(),
+        "active": agent.active(),
+        "roles": agent.roles(),
+        "metadata": metadata_to_json(agent.metadata()),
+        "successor": agent.successor(),
+    })
+}
+
+fn organization_to_json(organization: &Organization) -> JsonValue {
+    json!({
+        "org_id": organization.org_id(),
+        "name": organization.name(),
+        "address": organization.address(),
+        "metadata": metadata_to_json(organization.metadata()),
+        "parent_org_id": organization.parent_org_id(),
+        "alternate_ids": organization
+            .alternate_ids()
+            .iter()
+            .map(|alternate_id| alternate_id.to_string())
+            .collect::<Vec<String>>(),
+    })
+}
+
+fn alternate_id_index_to_json(entry: &AlternateIdIndex) -> JsonValue {
+    json!({
+        "id_type": entry.id_type(),
+        "id": entry.id(),
+        "org_id": entry.org_id(),
+    })
+}
+
+fn role_to_json(role: &Role) -> JsonValue {
+    json!({
+        "org_id": role.org_id(),
+        "name": role.name(),
+        "description": role.description(),
+        "permissions": role.permissions(),
+        "inherit_from": role.inherit_from(),
+    })
+}
+
+fn schema_to_json(schema: &Schema) -> JsonValue {
+    json!({
+        "name": schema.name(),
+        "description": schema.description(),
+        "owner": schema.owner(),
+        "version": schema.version(),
+        "parent": schema.parent(),
+        "parent_version": schema.parent_version(),
+        "property_groups": schema.property_groups(),
+        "properties": schema
+            .properties()
+            .iter()
+            .map(property_definition_to_json)
+            .collect::<Vec<JsonValue>>(),
+        "inherited_properties": schema
+            .inherited_properties()
+            .iter()
+            .map(property_definition_to_json)
+            .collect::<Vec<JsonValue>>(),
+    })
+}
+
+fn property_group_to_json(property_group: &PropertyGroup) -> JsonValue {
+    json!({
+        "name": property_group.name(),
+        "description": property_group.description(),
+        "owner": property_group.owner(),
+        "properties": property_group
+            .properties()
+            .iter()
+            .map(property_definition_to_json)
+            .collect::<Vec<JsonValue>>(),
+    })
+}
+
+fn property_definition_to_json(definition: &PropertyDefinition) -> JsonValue {
+    json!({
+        "name": definition.name(),
+        "data_type": format!("{:?}", definition.data_type()),
+        "required": definition.required(),
+        "description": definition.description(),
+        "number_exponent": definition.number_exponent(),
+        "enum_options": definition.enum_options(),
+        "struct_properties": definition
+            .struct_properties()
+            .iter()
+            .map(property_definition_to_json)
+            .collect::<Vec<JsonValue>>(),
+    })
+}
+
+fn property_value_to_json(value: &PropertyValue) -> JsonValue {
+    let data = match value.data_type() {
+        DataType::Bytes => json!(base64::encode(value.bytes_value())),
+        DataType::Boolean => json!(value.boolean_value()),
+        DataType::Number => json!(value.number_value()),
+        DataType::String => json!(value.string_value()),
+        DataType::Enum => json!(value.enum_value()),
+        DataType::Struct => JsonValue::Array(
+            value
+                .struct_values()
+                .iter()
+                .map(property_value_to_json)
+                .collect(),
+        ),
+        DataType::LatLong => json!({
+            "latitude": value.lat_long_value().latitude(),
+            "longitude": value.lat_long_value().longitude(),
+        }),
+    };
+
+    json!({
+        "name": value.name(),
+        "data_type": format!("{:?}", value.data_type()),
+        "value": data,
+    })
+}
+
+fn property_to_json(property: &Property) -> JsonValue {
+    json!({
+        "name": property.name(),
+        "record_id": property.record_id(),
+        "property_definition": property_definition_to_json(property.property_definition()),
+        "reporters": property
+            .reporters()
+            .iter()
+            .map(|reporter| json!({
+                "public_key": reporter.public_key(),
+                "authorized": reporter.authorized(),
+                "index": reporter.index(),
+            }))
+            .collect::<Vec<JsonValue>>(),
+        "current_page": property.current_page(),
+        "wrapped": property.wrapped(),
+    })
+}
+
+fn property_page_to_json(page: &PropertyPage) -> JsonValue {
+    json!({
+        "name": page.name(),
+        "record_id": page.record_id(),
+        "reported_values": page
+            .reported_values()
+            .iter()
+            .map(|reported_value| json!({
+                "reporter_index": reported_value.reporter_index(),
+                "timestamp": reported_value.timestamp(),
+                "value": property_value_to_json(reported_value.value()),
+            }))
+            .collect::<Vec<JsonValue>>(),
+    })
+}
+
+fn proposal_to_json(proposal: &Proposal) -> JsonValue {
+    json!({
+        "record_id": proposal.record_id(),
+        "timestamp": proposal.timestamp(),
+        "issuing_agent": proposal.issuing_agent(),
+        "receiving_agent": proposal.receiving_agent(),
+        "role": format!("{:?}", proposal.role()),
+        "properties": proposal.properties(),
+        "status": format!("{:?}", proposal.status()),
+        "terms": proposal.terms(),
+    })
+}
+
+fn associated_agent_to_json(agent: &AssociatedAgent) -> JsonValue {
+    json!({
+        "agent_id": agent.agent_id(),
+        "timestamp": agent.timestamp(),
+    })
+}
+
+fn record_to_json(record: &Record) -> JsonValue {
+    json!({
+        "record_id": record.record_id(),
+        "schema": record.schema(),
+        "owners": record
+            .owners()
+            .iter()
+            .map(associated_agent_to_json)
+            .collect::<Vec<JsonValue>>(),
+        "custodians": record
+            .custodians()
+            .iter()
+            .map(associated_agent_to_json)
+            .collect::<Vec<JsonValue>>(),
+        "final": record.field_final(),
+        "schema_version": record.schema_version(),
+    })
+}
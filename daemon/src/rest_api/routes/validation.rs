@@ -0,0 +1,201 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::rest_api::{error::RestApiResponseError, routes::query_validator};
+
+use grid_sdk::{
+    protocol::{
+        addressing::{
+            schema::{compute_schema_address, compute_schema_version_address},
+            track_and_trace::{compute_property_address, TRACK_AND_TRACE_FAMILY_NAME},
+        },
+        schema::{
+            state::{PropertyDefinition, Schema, SchemaList},
+            validate::{validate_property_values, validate_schema_values, ValidationError},
+        },
+        track_and_trace::{
+            payload::{Action, TrackAndTracePayload},
+            state::PropertyList,
+        },
+    },
+    protos::FromBytes,
+};
+use sabre_sdk::protocol::payload::{Action as SabreAction, SabrePayload};
+use sabre_sdk::protos::FromBytes as SabreFromBytes;
+use sawtooth_sdk::messages::batch::BatchList;
+use sawtooth_sdk::messages::client_state::{
+    ClientStateGetRequest, ClientStateGetResponse, ClientStateGetResponse_Status,
+};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
+use sawtooth_sdk::messages::validator::Message_MessageType;
+use sawtooth_sdk::messaging::stream::MessageSender;
+
+const SABRE_FAMILY_NAME: &str = "sabre";
+
+/// Validates the property values of the Track and Trace transactions in a
+/// batch list against the schemas and properties in state, so that values
+/// the contract would reject are refused before the batches are submitted.
+///
+/// Transactions referring to a schema or record that does not exist are left
+/// for the contract to reject.
+pub fn validate_batch_list(
+    sender: &dyn MessageSender,
+    batch_list: &BatchList,
+) -> Result<(), RestApiResponseError> {
+    for batch in batch_list.get_batches() {
+        for transaction in batch.get_transactions() {
+            validate_transaction(sender, transaction)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_transaction(
+    sender: &dyn MessageSender,
+    transaction: &Transaction,
+) -> Result<(), RestApiResponseError> {
+    let header: TransactionHeader =
+        protobuf::parse_from_bytes(transaction.get_header()).map_err(|err| {
+            RestApiResponseError::BadRequest(format!(
+                "Failed to parse transaction header. {}",
+                err.to_string()
+            ))
+        })?;
+
+    let payload =
+        match decode_track_and_trace_payload(header.get_family_name(), transaction.get_payload()) {
+            Some(payload) => payload,
+            None => return Ok(()),
+        };
+
+    let result = match payload.action() {
+        Action::CreateRecord(action) => {
+            match get_schema(sender, action.schema(), *action.schema_version())? {
+                Some(schema) => validate_schema_values(&schema, action.properties()),
+                None => Ok(()),
+            }
+        }
+        Action::UpdateProperties(action) => {
+            let mut definitions = vec![];
+            for value in action.properties() {
+                match get_property_definition(sender, action.record_id(), value.name())? {
+                    Some(definition) => definitions.push(definition),
+                    None => return Ok(()),
+                }
+            }
+            validate_property_values(&definitions, action.properties())
+        }
+        _ => Ok(()),
+    };
+
+    result.map_err(|errors| {
+        RestApiResponseError::BadRequest(format!(
+            "Transaction {} has invalid property values: {}",
+            transaction.get_header_signature(),
+            errors
+                .iter()
+                .map(ValidationError::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    })
+}
+
+/// Decodes the payload of a Track and Trace transaction, or of a Sabre
+/// transaction executing the Track and Trace contract.
+fn decode_track_and_trace_payload(family_name: &str, bytes: &[u8]) -> Option<TrackAndTracePayload> {
+    match family_name {
+        SABRE_FAMILY_NAME => match SabrePayload::from_bytes(bytes).ok()?.action() {
+            SabreAction::ExecuteContract(execute_contract) => {
+                decode_track_and_trace_payload(execute_contract.name(), execute_contract.payload())
+            }
+            _ => None,
+        },
+        TRACK_AND_TRACE_FAMILY_NAME => TrackAndTracePayload::from_bytes(bytes).ok(),
+        _ => None,
+    }
+}
+
+/// Gets the schema the values of a new record are validated against: the
+/// version the record is pinned to, or the current schema when `version` is 0,
+/// as the contract does
+fn get_schema(
+    sender: &dyn MessageSender,
+    name: &str,
+    version: u32,
+) -> Result<Option<Schema>, RestApiResponseError> {
+    let address = match version {
+        0 => compute_schema_address(name),
+        version => compute_schema_version_address(name, version),
+    };
+    let data = match get_state_entry(sender, &address)? {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    let schemas = SchemaList::from_bytes(&data).map_err(|err| {
+        RestApiResponseError::RequestHandlerError(format!("Failed to parse schema list. {}", err))
+    })?;
+
+    Ok(schemas
+        .schemas()
+        .iter()
+        .find(|schema| schema.name() == name)
+        .cloned())
+}
+
+fn get_property_definition(
+    sender: &dyn MessageSender,
+    record_id: &str,
+    property_name: &str,
+) -> Result<Option<PropertyDefinition>, RestApiResponseError> {
+    let address = compute_property_address(record_id, property_name, 0);
+    let data = match get_state_entry(sender, &address)? {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    let properties = PropertyList::from_bytes(&data).map_err(|err| {
+        RestApiResponseError::RequestHandlerError(format!("Failed to parse property list. {}", err))
+    })?;
+
+    Ok(properties
+        .properties()
+        .iter()
+        .find(|property| property.name() == property_name && property.record_id() == record_id)
+        .map(|property| property.property_definition().clone()))
+}
+
+fn get_state_entry(
+    sender: &dyn MessageSender,
+    address: &str,
+) -> Result<Option<Vec<u8>>, RestApiResponseError> {
+    let mut state_request = ClientStateGetRequest::new();
+    state_request.set_address(address.to_string());
+
+    let response: ClientStateGetResponse = query_validator(
+        sender,
+        Message_MessageType::CLIENT_STATE_GET_REQUEST,
+        &state_request,
+    )?;
+
+    match response.get_status() {
+        ClientStateGetResponse_Status::OK => Ok(Some(response.get_value().to_vec())),
+        ClientStateGetResponse_Status::NO_RESOURCE => Ok(None),
+        status => Err(RestApiResponseError::SawtoothValidatorResponseError(
+            format!("Validator responded with error {:?}", status),
+        )),
+    }
+}
@@ -21,3 +21,7 @@ extern crate cfg_if;
 pub mod permissions;
 pub mod protocol;
 pub mod protos;
+#[cfg(all(feature = "test-kit", not(target_arch = "wasm32")))]
+pub mod test_kit;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod transact;
@@ -12,8 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crypto::digest::Digest;
-use crypto::sha2::Sha512;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fmt;
 
@@ -27,18 +26,12 @@ cfg_if! {
     }
 }
 
-use crate::protocol::pike::state::{Agent, AgentList};
+use crate::protocol::addressing::pike::{
+    compute_agent_address, compute_alternate_id_address, compute_role_address,
+};
+use crate::protocol::pike::state::{Agent, AgentList, AlternateIdIndexList, Role, RoleList};
 use crate::protos::{FromBytes, ProtoConversionError};
 
-const PIKE_NAMESPACE: &str = "cad11d";
-const PIKE_AGENT_RESOURCE: &str = "00";
-
-fn compute_agent_address(public_key: &str) -> String {
-    let mut sha = Sha512::new();
-    sha.input(public_key.as_bytes());
-    String::from(PIKE_NAMESPACE) + PIKE_AGENT_RESOURCE + &sha.result_str()[..62].to_string()
-}
-
 #[derive(Debug)]
 pub enum PermissionCheckerError {
     /// Returned for an error originating at the TransactionContext.
@@ -83,11 +76,63 @@ impl From<ProtoConversionError> for PermissionCheckerError {
     }
 }
 
+/// The reason a permission is denied to an agent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DenyReason {
+    /// Returned when the public key does not belong to an agent.
+    NotAnAgent,
+    /// Returned when the agent is not active.
+    Inactive,
+    /// Returned when the agent belongs to another organization than the one
+    /// the permission is exercised in.
+    WrongOrganization { agent_org_id: String },
+    /// Returned when none of the agent's roles grant the permission.
+    MissingPermission,
+}
+
+impl fmt::Display for DenyReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DenyReason::NotAnAgent => write!(f, "not an agent"),
+            DenyReason::Inactive => write!(f, "not an active agent"),
+            DenyReason::WrongOrganization { ref agent_org_id } => {
+                write!(f, "agent of another organization: {}", agent_org_id)
+            }
+            DenyReason::MissingPermission => write!(f, "missing permission"),
+        }
+    }
+}
+
+/// The outcome of a permission check.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Authorization {
+    Granted,
+    Denied(DenyReason),
+}
+
+impl Authorization {
+    pub fn is_granted(&self) -> bool {
+        *self == Authorization::Granted
+    }
+}
+
 /// Helper struct for Pike functionality.
+///
+/// Roles grant the permission of the same name. A namespaced wildcard role,
+/// such as `schema::*`, grants every permission of its namespace, such as
+/// `schema::create` or `schema::property::update`. Roles may also imply other
+/// roles, through the table given to `with_implied_roles`.
+///
+/// When the agent's organization defines a Pike Role of the same name, the
+/// role also grants the permissions listed by the Role, wildcards included,
+/// and those of the roles it inherits from. Reading Roles requires the Pike
+/// role namespace in the transaction's inputs.
 pub struct PermissionChecker<'a> {
     /// A PermissionChecker is tied to a version of state, so it has a
     /// reference to a TransactionContext.
     context: &'a dyn TransactionContext,
+    /// The roles implied by a role, for instance `admin` implying `schema::*`.
+    implied_roles: HashMap<String, Vec<String>>,
 }
 
 impl<'a> PermissionChecker<'a> {
@@ -98,10 +143,33 @@ impl<'a> PermissionChecker<'a> {
     /// * `context` - A reference to the transaction context.
     ///
     pub fn new(context: &'a dyn TransactionContext) -> PermissionChecker {
-        PermissionChecker { context }
+        PermissionChecker {
+            context,
+            implied_roles: HashMap::new(),
+        }
     }
 
-    /// Checks whether an agent with a given public key has a certain role.
+    /// Sets the table of implied roles. Implications are transitive.
+    ///
+    /// # Arguments
+    ///
+    /// * `implied_roles` - Pairs of a role and the roles it implies.
+    ///
+    pub fn with_implied_roles(mut self, implied_roles: &[(&str, &[&str])]) -> Self {
+        self.implied_roles = implied_roles
+            .iter()
+            .map(|(role, implied)| {
+                (
+                    role.to_string(),
+                    implied.iter().map(|role| role.to_string()).collect(),
+                )
+            })
+            .collect();
+        self
+    }
+
+    /// Checks whether an agent with a given public key has a certain role,
+    /// regardless of whether it is active and of its organization.
     ///
     /// # Arguments
     ///
@@ -115,7 +183,7 @@ impl<'a> PermissionChecker<'a> {
     ) -> Result<bool, PermissionCheckerError> {
         let agent = self.get_agent(public_key)?;
         match agent {
-            Some(agent) => Ok(agent.roles().iter().any(|r| r == permission)),
+            Some(agent) => self.roles_grant(agent.org_id(), agent.roles(), permission),
             None => Err(PermissionCheckerError::InvalidPublicKey(format!(
                 "The signer is not an Agent: {}",
                 public_key
@@ -123,6 +191,167 @@ impl<'a> PermissionChecker<'a> {
         }
     }
 
+    /// Checks whether an agent with a given public key may exercise a
+    /// permission in an organization: the agent must be active, belong to
+    /// the organization and have a role granting the permission.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - Public key of a Pike agent.
+    /// * `permission` - Permission string to be checked.
+    /// * `org_id` - Organization the permission is exercised in.
+    ///
+    pub fn has_permission_in_org(
+        &self,
+        public_key: &str,
+        permission: &str,
+        org_id: &str,
+    ) -> Result<Authorization, PermissionCheckerError> {
+        let agent = match self.get_agent(public_key)? {
+            Some(agent) => agent,
+            None => return Ok(Authorization::Denied(DenyReason::NotAnAgent)),
+        };
+
+        if !agent.active() {
+            return Ok(Authorization::Denied(DenyReason::Inactive));
+        }
+
+        if agent.org_id() != org_id {
+            return Ok(Authorization::Denied(DenyReason::WrongOrganization {
+                agent_org_id: agent.org_id().to_string(),
+            }));
+        }
+
+        if !self.roles_grant(agent.org_id(), agent.roles(), permission)? {
+            return Ok(Authorization::Denied(DenyReason::MissingPermission));
+        }
+
+        Ok(Authorization::Granted)
+    }
+
+    /// Returns the key currently holding the identity recorded as
+    /// `public_key`: the key its agent was last rotated to, or the key itself
+    /// if it was never rotated. Reading the successors requires the Pike
+    /// agent namespace in the transaction's inputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - Public key stored as, for instance, a record owner.
+    ///
+    pub fn current_key(&self, public_key: &str) -> Result<String, PermissionCheckerError> {
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut current = public_key.to_string();
+
+        while visited.insert(current.clone()) {
+            match self.get_agent(&current)? {
+                Some(ref agent) if !agent.successor().is_empty() => {
+                    current = agent.successor().to_string()
+                }
+                _ => break,
+            }
+        }
+
+        Ok(current)
+    }
+
+    /// Checks whether `public_key` currently holds the identity recorded as
+    /// `recorded_key`. A key its agent was rotated away from no longer holds
+    /// the identity, including for the records it was stored in.
+    ///
+    /// # Arguments
+    ///
+    /// * `recorded_key` - Public key stored as, for instance, a record owner.
+    /// * `public_key` - Public key of a Pike agent, usually the signer.
+    ///
+    pub fn is_same_identity(
+        &self,
+        recorded_key: &str,
+        public_key: &str,
+    ) -> Result<bool, PermissionCheckerError> {
+        Ok(self.current_key(recorded_key)? == public_key)
+    }
+
+    /// Returns the organization holding an alternate ID, such as the GLN of a
+    /// trading partner, if any. Reading it requires the address of the ID in
+    /// the transaction's inputs.
+    ///
+    /// # Arguments
+    ///
+    /// * `id_type` - Identification scheme of the ID, for instance `gln`.
+    /// * `id` - The ID within its scheme.
+    ///
+    pub fn alternate_id_holder(
+        &self,
+        id_type: &str,
+        id: &str,
+    ) -> Result<Option<String>, PermissionCheckerError> {
+        let address = compute_alternate_id_address(id_type, id);
+        match self.context.get_state_entry(&address)? {
+            Some(ref packed) if !packed.is_empty() => {
+                let entry_list = AlternateIdIndexList::from_bytes(packed.as_slice())?;
+                Ok(entry_list
+                    .entries()
+                    .iter()
+                    .find(|entry| entry.id_type() == id_type && entry.id() == id)
+                    .map(|entry| entry.org_id().to_string()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns whether any of the roles, or of the roles they imply or
+    /// inherit from in the organization, grants the permission
+    fn roles_grant(
+        &self,
+        org_id: &str,
+        roles: &[String],
+        permission: &str,
+    ) -> Result<bool, PermissionCheckerError> {
+        let mut visited: HashSet<String> = HashSet::new();
+        let mut pending: Vec<String> = roles.to_vec();
+
+        while let Some(role) = pending.pop() {
+            if !visited.insert(role.clone()) {
+                continue;
+            }
+            if role_grants(&role, permission) {
+                return Ok(true);
+            }
+            if let Some(implied) = self.implied_roles.get(&role) {
+                pending.extend(implied.iter().cloned());
+            }
+            // Roles that are not defined by the organization, or no longer
+            // are, only grant the permission of their name
+            if let Some(definition) = self.get_role(org_id, &role)? {
+                if definition
+                    .permissions()
+                    .iter()
+                    .any(|granted| role_grants(granted, permission))
+                {
+                    return Ok(true);
+                }
+                pending.extend(definition.inherit_from().iter().cloned());
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn get_role(&self, org_id: &str, name: &str) -> Result<Option<Role>, PermissionCheckerError> {
+        let address = compute_role_address(org_id, name);
+        match self.context.get_state_entry(&address)? {
+            Some(packed) => {
+                let role_list = RoleList::from_bytes(packed.as_slice())?;
+                Ok(role_list
+                    .roles()
+                    .iter()
+                    .find(|role| role.org_id() == org_id && role.name() == name)
+                    .cloned())
+            }
+            None => Ok(None),
+        }
+    }
+
     fn get_agent(&self, public_key: &str) -> Result<Option<Agent>, PermissionCheckerError> {
         let address = compute_agent_address(public_key);
         let d = self.context.get_state_entry(&address)?;
@@ -141,6 +370,22 @@ impl<'a> PermissionChecker<'a> {
     }
 }
 
+/// Returns whether a role grants a permission, either by name or as the
+/// wildcard of the permission's namespace
+fn role_grants(role: &str, permission: &str) -> bool {
+    if role == permission {
+        return true;
+    }
+
+    if role.ends_with("::*") {
+        // Keep the trailing `::` so that `schema::*` does not grant `schemata::create`
+        let namespace = &role[..role.len() - 1];
+        return permission.starts_with(namespace);
+    }
+
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,7 +393,10 @@ mod tests {
     use std::cell::RefCell;
     use std::collections::HashMap;
 
-    use crate::protocol::pike::state::{AgentBuilder, AgentListBuilder};
+    use crate::protocol::pike::state::{
+        AgentBuilder, AgentListBuilder, AlternateIdIndexBuilder, AlternateIdIndexListBuilder,
+        RoleBuilder, RoleListBuilder,
+    };
     use crate::protos::IntoBytes;
 
     const ROLE_A: &str = "Role A";
@@ -325,4 +573,220 @@ mod tests {
         let result = pc.has_permission(PUBLIC_KEY, ROLE_B).unwrap();
         assert!(result);
     }
+
+    fn add_agent(context: &MockTransactionContext, active: bool, roles: &[&str]) {
+        let builder = AgentBuilder::new();
+        let agent = builder
+            .with_org_id(ORG_ID.to_string())
+            .with_public_key(PUBLIC_KEY.to_string())
+            .with_active(active)
+            .with_roles(roles.iter().map(|role| role.to_string()).collect())
+            .build()
+            .unwrap();
+        let builder = AgentListBuilder::new();
+        let agent_list = builder.with_agents(vec![agent]).build().unwrap();
+        let agent_bytes = agent_list.into_bytes().unwrap();
+        let agent_address = compute_agent_address(PUBLIC_KEY);
+        context.set_state_entry(agent_address, agent_bytes).unwrap();
+    }
+
+    #[test]
+    // Test that an active agent of the organization with the role is granted the permission
+    fn test_has_permission_in_org_granted() {
+        let context = MockTransactionContext::default();
+        add_agent(&context, true, &[ROLE_A]);
+        let pc = PermissionChecker::new(&context);
+
+        let result = pc
+            .has_permission_in_org(PUBLIC_KEY, ROLE_A, ORG_ID)
+            .unwrap();
+        assert_eq!(result, Authorization::Granted);
+    }
+
+    #[test]
+    // Test that each failed check is reported with its own reason
+    fn test_has_permission_in_org_denied() {
+        let context = MockTransactionContext::default();
+        let pc = PermissionChecker::new(&context);
+        assert_eq!(
+            pc.has_permission_in_org(PUBLIC_KEY, ROLE_A, ORG_ID)
+                .unwrap(),
+            Authorization::Denied(DenyReason::NotAnAgent)
+        );
+
+        add_agent(&context, false, &[ROLE_A]);
+        assert_eq!(
+            pc.has_permission_in_org(PUBLIC_KEY, ROLE_A, ORG_ID)
+                .unwrap(),
+            Authorization::Denied(DenyReason::Inactive)
+        );
+
+        add_agent(&context, true, &[ROLE_A]);
+        assert_eq!(
+            pc.has_permission_in_org(PUBLIC_KEY, ROLE_A, "other_org")
+                .unwrap(),
+            Authorization::Denied(DenyReason::WrongOrganization {
+                agent_org_id: ORG_ID.to_string()
+            })
+        );
+        assert_eq!(
+            pc.has_permission_in_org(PUBLIC_KEY, ROLE_B, ORG_ID)
+                .unwrap(),
+            Authorization::Denied(DenyReason::MissingPermission)
+        );
+    }
+
+    #[test]
+    // Test that a namespaced wildcard role grants the permissions of its namespace only
+    fn test_has_permission_wildcard() {
+        let context = MockTransactionContext::default();
+        add_agent(&context, true, &["schema::*"]);
+        let pc = PermissionChecker::new(&context);
+
+        assert!(pc.has_permission(PUBLIC_KEY, "schema::create").unwrap());
+        assert!(pc
+            .has_permission(PUBLIC_KEY, "schema::property::update")
+            .unwrap());
+        assert!(!pc.has_permission(PUBLIC_KEY, "tnt::reporter").unwrap());
+        assert!(!pc.has_permission(PUBLIC_KEY, "schemata::create").unwrap());
+        assert!(!pc.has_permission(PUBLIC_KEY, "schema").unwrap());
+    }
+
+    #[test]
+    // Test that implied roles are followed transitively, even through a cycle
+    fn test_has_permission_implied_roles() {
+        let context = MockTransactionContext::default();
+        add_agent(&context, true, &["admin"]);
+        let implied_roles: &[(&str, &[&str])] = &[
+            ("admin", &["schema::admin", "tnt::reporter"]),
+            ("schema::admin", &["schema::*", "admin"]),
+        ];
+        let pc = PermissionChecker::new(&context).with_implied_roles(implied_roles);
+
+        assert!(pc.has_permission(PUBLIC_KEY, "tnt::reporter").unwrap());
+        assert!(pc.has_permission(PUBLIC_KEY, "schema::create").unwrap());
+        assert!(!pc.has_permission(PUBLIC_KEY, "tnt::custodian").unwrap());
+        assert!(pc
+            .has_permission_in_org(PUBLIC_KEY, "schema::update", ORG_ID)
+            .unwrap()
+            .is_granted());
+    }
+
+    fn add_role(
+        context: &MockTransactionContext,
+        name: &str,
+        permissions: &[&str],
+        inherit: &[&str],
+    ) {
+        let role = RoleBuilder::new()
+            .with_org_id(ORG_ID.to_string())
+            .with_name(name.to_string())
+            .with_permissions(permissions.iter().map(|p| p.to_string()).collect())
+            .with_inherit_from(inherit.iter().map(|r| r.to_string()).collect())
+            .build()
+            .unwrap();
+        let role_list = RoleListBuilder::new()
+            .with_roles(vec![role])
+            .build()
+            .unwrap();
+        context
+            .set_state_entry(
+                compute_role_address(ORG_ID, name),
+                role_list.into_bytes().unwrap(),
+            )
+            .unwrap();
+    }
+
+    #[test]
+    // Test that roles defined by the organization grant their permissions and inherited ones
+    fn test_has_permission_role_registry() {
+        let context = MockTransactionContext::default();
+        add_agent(&context, true, &["operator"]);
+        add_role(&context, "operator", &["tnt::reporter"], &["viewer"]);
+        add_role(&context, "viewer", &["record::*"], &["operator"]);
+        let pc = PermissionChecker::new(&context);
+
+        assert!(pc.has_permission(PUBLIC_KEY, "operator").unwrap());
+        assert!(pc.has_permission(PUBLIC_KEY, "tnt::reporter").unwrap());
+        assert!(pc.has_permission(PUBLIC_KEY, "record::read").unwrap());
+        assert!(pc.has_permission(PUBLIC_KEY, "viewer").unwrap());
+        assert!(!pc.has_permission(PUBLIC_KEY, "schema::create").unwrap());
+        assert_eq!(
+            pc.has_permission_in_org(PUBLIC_KEY, "record::read", ORG_ID)
+                .unwrap(),
+            Authorization::Granted
+        );
+    }
+
+    #[test]
+    // Test that an identity is held by the key its agent was last rotated to, and no longer by
+    // the keys it was rotated from
+    fn test_is_same_identity() {
+        let context = MockTransactionContext::default();
+        let pc = PermissionChecker::new(&context);
+
+        for (public_key, successor) in &[("key_1", "key_2"), ("key_2", "key_3"), ("key_3", "")] {
+            let agent = AgentBuilder::new()
+                .with_org_id(ORG_ID.to_string())
+                .with_public_key(public_key.to_string())
+                .with_active(successor.is_empty())
+                .with_successor(successor.to_string())
+                .build()
+                .unwrap();
+            let agent_list = AgentListBuilder::new()
+                .with_agents(vec![agent])
+                .build()
+                .unwrap();
+            context
+                .set_state_entry(
+                    compute_agent_address(public_key),
+                    agent_list.into_bytes().unwrap(),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(pc.current_key("key_1").unwrap(), "key_3");
+        assert_eq!(pc.current_key("unknown_key").unwrap(), "unknown_key");
+        assert!(pc.is_same_identity("key_1", "key_3").unwrap());
+        assert!(pc.is_same_identity("key_3", "key_3").unwrap());
+        assert!(!pc.is_same_identity("key_1", "key_1").unwrap());
+        assert!(!pc.is_same_identity("key_1", "key_2").unwrap());
+        assert!(!pc.is_same_identity("key_1", "other_key").unwrap());
+    }
+
+    #[test]
+    // Test that an alternate ID resolves to the organization holding it
+    fn test_alternate_id_holder() {
+        let context = MockTransactionContext::default();
+        let entry = AlternateIdIndexBuilder::new()
+            .with_id_type("gln".to_string())
+            .with_id("0614141000012".to_string())
+            .with_org_id(ORG_ID.to_string())
+            .build()
+            .unwrap();
+        let entry_list = AlternateIdIndexListBuilder::new()
+            .with_entries(vec![entry])
+            .build()
+            .unwrap();
+        context
+            .set_state_entry(
+                compute_alternate_id_address("gln", "0614141000012"),
+                entry_list.into_bytes().unwrap(),
+            )
+            .unwrap();
+        let pc = PermissionChecker::new(&context);
+
+        assert_eq!(
+            pc.alternate_id_holder("gln", "0614141000012").unwrap(),
+            Some(ORG_ID.to_string())
+        );
+        assert_eq!(
+            pc.alternate_id_holder("gln", "0614141000005").unwrap(),
+            None
+        );
+        assert_eq!(
+            pc.alternate_id_holder("duns", "0614141000012").unwrap(),
+            None
+        );
+    }
 }
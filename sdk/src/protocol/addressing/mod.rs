@@ -0,0 +1,237 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Computation of the state addresses of the Grid families.
+//!
+//! Every address is 70 hex characters: a 6 character family namespace, a 2
+//! character resource type and 62 characters derived from sha512 hashes of
+//! the resource's identifiers.
+
+pub mod pike;
+pub mod schema;
+pub mod track_and_trace;
+
+use crypto::digest::Digest;
+use crypto::sha2::Sha512;
+use std::error::Error;
+use std::fmt;
+
+/// The length of a state address, in hex characters
+pub const ADDRESS_LENGTH: usize = 70;
+
+/// Returns the first `length` hex characters of the sha512 hash of `input`
+pub fn hash(input: &str, length: usize) -> String {
+    let mut sha = Sha512::new();
+    sha.input(input.as_bytes());
+    sha.result_str()[..length].to_string()
+}
+
+/// The type of resource stored at a Grid address
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resource {
+    /// A Pike agent list
+    Agent,
+    /// A Pike organization list
+    Organization,
+    /// A Pike role list
+    Role,
+    /// A Pike alternate ID index entry list
+    AlternateId,
+    /// A Grid Schema list
+    Schema,
+    /// An immutable version of a Grid Schema
+    SchemaVersion(u32),
+    /// A Grid PropertyGroup list
+    PropertyGroup,
+    /// A Track and Trace record list
+    Record,
+    /// A Track and Trace property list, the page 0 of a property
+    Property,
+    /// A page of reported values of a Track and Trace property
+    PropertyPage(u32),
+    /// A Track and Trace proposal list
+    Proposal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AddressingError {
+    /// Returned for an address that is not 70 hex characters
+    InvalidAddress(String),
+    /// Returned for an address outside of the Grid namespaces
+    UnknownNamespace(String),
+    /// Returned for an address with an unknown resource type
+    UnknownResource(String),
+}
+
+impl Error for AddressingError {}
+
+impl fmt::Display for AddressingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AddressingError::InvalidAddress(ref msg) => write!(f, "InvalidAddress: {}", msg),
+            AddressingError::UnknownNamespace(ref msg) => write!(f, "UnknownNamespace: {}", msg),
+            AddressingError::UnknownResource(ref msg) => write!(f, "UnknownResource: {}", msg),
+        }
+    }
+}
+
+/// Classifies a state address into the type of Grid resource stored at it.
+pub fn classify_address(address: &str) -> Result<Resource, AddressingError> {
+    if address.len() != ADDRESS_LENGTH || !address.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AddressingError::InvalidAddress(format!(
+            "Address must be {} hex characters: {}",
+            ADDRESS_LENGTH, address
+        )));
+    }
+
+    let namespace = &address[..6];
+    let prefix = &address[..8];
+    match namespace {
+        pike::PIKE_NAMESPACE => match prefix {
+            pike::PIKE_AGENT_PREFIX => Ok(Resource::Agent),
+            pike::PIKE_ORG_PREFIX => Ok(Resource::Organization),
+            pike::PIKE_ROLE_PREFIX => Ok(Resource::Role),
+            pike::PIKE_ALTERNATE_ID_PREFIX => Ok(Resource::AlternateId),
+            _ => Err(unknown_resource(address)),
+        },
+        schema::GRID_NAMESPACE => match prefix {
+            schema::GRID_SCHEMA_PREFIX => Ok(Resource::Schema),
+            schema::GRID_SCHEMA_VERSION_PREFIX => match u32::from_str_radix(&address[62..], 16) {
+                Ok(version) => Ok(Resource::SchemaVersion(version)),
+                Err(_) => Err(unknown_resource(address)),
+            },
+            schema::GRID_PROPERTY_GROUP_PREFIX => Ok(Resource::PropertyGroup),
+            _ => Err(unknown_resource(address)),
+        },
+        track_and_trace::TRACK_AND_TRACE_NAMESPACE => match prefix {
+            track_and_trace::TRACK_AND_TRACE_RECORD_PREFIX => Ok(Resource::Record),
+            track_and_trace::TRACK_AND_TRACE_PROPOSAL_PREFIX => Ok(Resource::Proposal),
+            track_and_trace::TRACK_AND_TRACE_PROPERTY_PREFIX => {
+                match u32::from_str_radix(&address[66..], 16) {
+                    Ok(0) => Ok(Resource::Property),
+                    Ok(page) => Ok(Resource::PropertyPage(page)),
+                    Err(_) => Err(unknown_resource(address)),
+                }
+            }
+            _ => Err(unknown_resource(address)),
+        },
+        _ => Err(AddressingError::UnknownNamespace(format!(
+            "Address is not in a Grid namespace: {}",
+            address
+        ))),
+    }
+}
+
+fn unknown_resource(address: &str) -> AddressingError {
+    AddressingError::UnknownResource(format!(
+        "Address does not match a known resource type: {}",
+        address
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Test that every computed address is classified as the resource it was computed for
+    fn test_classify_computed_addresses() {
+        assert_eq!(
+            classify_address(&pike::compute_agent_address("agent_public_key")),
+            Ok(Resource::Agent)
+        );
+        assert_eq!(
+            classify_address(&pike::compute_org_address("org_id")),
+            Ok(Resource::Organization)
+        );
+        assert_eq!(
+            classify_address(&pike::compute_role_address("org_id", "role_name")),
+            Ok(Resource::Role)
+        );
+        assert_eq!(
+            classify_address(&pike::compute_alternate_id_address("gln", "0614141000012")),
+            Ok(Resource::AlternateId)
+        );
+        assert_eq!(
+            classify_address(&schema::compute_schema_address("TestSchema")),
+            Ok(Resource::Schema)
+        );
+        assert_eq!(
+            classify_address(&schema::compute_schema_version_address("TestSchema", 3)),
+            Ok(Resource::SchemaVersion(3))
+        );
+        assert_eq!(
+            classify_address(&schema::compute_property_group_address("gps")),
+            Ok(Resource::PropertyGroup)
+        );
+        assert_eq!(
+            classify_address(&track_and_trace::compute_record_address("record_id")),
+            Ok(Resource::Record)
+        );
+        assert_eq!(
+            classify_address(&track_and_trace::compute_property_address(
+                "record_id",
+                "temperature",
+                0
+            )),
+            Ok(Resource::Property)
+        );
+        assert_eq!(
+            classify_address(&track_and_trace::compute_property_address(
+                "record_id",
+                "temperature",
+                255
+            )),
+            Ok(Resource::PropertyPage(255))
+        );
+        assert_eq!(
+            classify_address(&track_and_trace::compute_proposal_address(
+                "record_id",
+                "agent_public_key"
+            )),
+            Ok(Resource::Proposal)
+        );
+    }
+
+    #[test]
+    // Test that addresses of the wrong length or with non-hex characters are invalid
+    fn test_classify_invalid_address() {
+        assert!(match classify_address("cad11d00") {
+            Err(AddressingError::InvalidAddress(_)) => true,
+            _ => false,
+        });
+
+        let address = format!("cad11d00{}", "g".repeat(62));
+        assert!(match classify_address(&address) {
+            Err(AddressingError::InvalidAddress(_)) => true,
+            _ => false,
+        });
+    }
+
+    #[test]
+    // Test that addresses outside of the Grid namespaces or resource types are rejected
+    fn test_classify_unknown_address() {
+        let address = format!("000000{}", "0".repeat(64));
+        assert!(match classify_address(&address) {
+            Err(AddressingError::UnknownNamespace(_)) => true,
+            _ => false,
+        });
+
+        let address = format!("cad11dff{}", "0".repeat(62));
+        assert!(match classify_address(&address) {
+            Err(AddressingError::UnknownResource(_)) => true,
+            _ => false,
+        });
+    }
+}
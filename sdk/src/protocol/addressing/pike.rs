@@ -0,0 +1,97 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::hash;
+
+pub const PIKE_FAMILY_NAME: &str = "pike";
+pub const PIKE_FAMILY_VERSION: &str = "0.1";
+pub const PIKE_NAMESPACE: &str = "cad11d";
+pub const PIKE_AGENT_PREFIX: &str = "cad11d00";
+pub const PIKE_ORG_PREFIX: &str = "cad11d01";
+pub const PIKE_ROLE_PREFIX: &str = "cad11d02";
+pub const PIKE_ALTERNATE_ID_PREFIX: &str = "cad11d03";
+
+/// Computes the address a Pike Agent is stored at based on its public_key
+pub fn compute_agent_address(public_key: &str) -> String {
+    String::from(PIKE_AGENT_PREFIX) + &hash(public_key, 62)
+}
+
+/// Computes the address a Pike Organization is stored at based on its org_id
+pub fn compute_org_address(org_id: &str) -> String {
+    String::from(PIKE_ORG_PREFIX) + &hash(org_id, 62)
+}
+
+/// Computes the address prefix shared by every role of an Organization
+pub fn compute_role_address_range(org_id: &str) -> String {
+    String::from(PIKE_ROLE_PREFIX) + &hash(org_id, 32)
+}
+
+/// Computes the address a Pike Role is stored at based on the org_id of its
+/// Organization and its name
+pub fn compute_role_address(org_id: &str, name: &str) -> String {
+    compute_role_address_range(org_id) + &hash(name, 30)
+}
+
+/// Computes the address of the index entry recording which Organization holds
+/// the alternate ID `id` of type `id_type`. All the IDs of a type share an
+/// address prefix.
+pub fn compute_alternate_id_address(id_type: &str, id: &str) -> String {
+    String::from(PIKE_ALTERNATE_ID_PREFIX) + &hash(id_type, 8) + &hash(id, 54)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Test that agent addresses match the addresses stored by the Pike contract
+    fn test_compute_agent_address() {
+        assert_eq!(
+            compute_agent_address("agent_public_key"),
+            "cad11d00292d5baa6b4002893890c62b4257074a462b5961721526a9cca1769ac9aeb8"
+        );
+    }
+
+    #[test]
+    // Test that organization addresses match the addresses stored by the Pike contract
+    fn test_compute_org_address() {
+        assert_eq!(
+            compute_org_address("org_id"),
+            "cad11d01d188e799661f9ed4e1a59fc8e448f81620e41eb9d34f904c41aeda67b200be"
+        );
+    }
+
+    #[test]
+    // Test that role addresses share the address range of their organization
+    fn test_compute_role_address() {
+        let address = compute_role_address("org_id", "role_name");
+        assert_eq!(
+            address,
+            "cad11d02d188e799661f9ed4e1a59fc8e448f8169cc45e4cf89270c6d8b43ac8f84238"
+        );
+        assert!(address.starts_with(&compute_role_address_range("org_id")));
+    }
+
+    #[test]
+    // Test that the alternate IDs of a type share an address prefix, distinct from other types
+    fn test_compute_alternate_id_address() {
+        let gln = compute_alternate_id_address("gln", "0614141000012");
+        let other_gln = compute_alternate_id_address("gln", "4012345000009");
+        let duns = compute_alternate_id_address("duns", "0614141000012");
+        assert_eq!(gln.len(), 70);
+        assert!(gln.starts_with(PIKE_ALTERNATE_ID_PREFIX));
+        assert_eq!(gln[..16], other_gln[..16]);
+        assert_ne!(gln[..16], duns[..16]);
+    }
+}
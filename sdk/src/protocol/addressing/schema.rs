@@ -0,0 +1,77 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::hash;
+
+pub const GRID_SCHEMA_FAMILY_NAME: &str = "grid_schema";
+pub const GRID_SCHEMA_FAMILY_VERSION: &str = "1.0";
+pub const GRID_NAMESPACE: &str = "621dee";
+pub const GRID_SCHEMA_PREFIX: &str = "621dee01";
+pub const GRID_SCHEMA_VERSION_PREFIX: &str = "621dee02";
+pub const GRID_PROPERTY_GROUP_PREFIX: &str = "621dee03";
+
+/// Computes the address a Grid Schema is stored at based on its name
+pub fn compute_schema_address(name: &str) -> String {
+    String::from(GRID_SCHEMA_PREFIX) + &hash(name, 62)
+}
+
+/// Computes the address prefix shared by every version of a Grid Schema
+pub fn compute_schema_version_address_range(name: &str) -> String {
+    String::from(GRID_SCHEMA_VERSION_PREFIX) + &hash(name, 54)
+}
+
+/// Computes the address an immutable version of a Grid Schema is stored at
+pub fn compute_schema_version_address(name: &str, version: u32) -> String {
+    compute_schema_version_address_range(name) + &format!("{:08x}", version)
+}
+
+/// Computes the address a Grid PropertyGroup is stored at based on its name
+pub fn compute_property_group_address(name: &str) -> String {
+    String::from(GRID_PROPERTY_GROUP_PREFIX) + &hash(name, 62)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Test that schema addresses match the addresses stored by the Schema contract
+    fn test_compute_schema_address() {
+        assert_eq!(
+            compute_schema_address("TestSchema"),
+            "621dee019623a1a3ab0e3f5c4d8bc47a3ab5bd2210acbd90e7059f3baab463ee5e7722"
+        );
+    }
+
+    #[test]
+    // Test that every version of a schema shares the schema's version address range
+    fn test_compute_schema_version_address() {
+        let range = compute_schema_version_address_range("TestSchema");
+        assert_eq!(range.len(), 62);
+        assert!(range.starts_with(GRID_SCHEMA_VERSION_PREFIX));
+
+        let address = compute_schema_version_address("TestSchema", 2);
+        assert_eq!(address.len(), 70);
+        assert_eq!(address, range + "00000002");
+    }
+
+    #[test]
+    // Test that property groups are stored apart from the schemas of the same name
+    fn test_compute_property_group_address() {
+        let address = compute_property_group_address("TestSchema");
+        assert_eq!(address.len(), 70);
+        assert!(address.starts_with(GRID_PROPERTY_GROUP_PREFIX));
+        assert_eq!(address[8..], compute_schema_address("TestSchema")[8..]);
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::hash;
+
+pub const TRACK_AND_TRACE_FAMILY_NAME: &str = "grid_track_and_trace";
+pub const TRACK_AND_TRACE_FAMILY_VERSION: &str = "1.0";
+/// The first 6 characters of the sha512 hash of the family name
+pub const TRACK_AND_TRACE_NAMESPACE: &str = "a43b46";
+pub const TRACK_AND_TRACE_PROPERTY_PREFIX: &str = "a43b46ea";
+pub const TRACK_AND_TRACE_PROPOSAL_PREFIX: &str = "a43b46aa";
+pub const TRACK_AND_TRACE_RECORD_PREFIX: &str = "a43b46ec";
+
+/// Computes the address a Record is stored at based on its record_id
+pub fn compute_record_address(record_id: &str) -> String {
+    String::from(TRACK_AND_TRACE_RECORD_PREFIX) + &hash(record_id, 62)
+}
+
+/// Computes the address prefix shared by every property of a Record
+pub fn compute_property_address_range(record_id: &str) -> String {
+    String::from(TRACK_AND_TRACE_PROPERTY_PREFIX) + &hash(record_id, 36)
+}
+
+/// Computes the address prefix shared by every page of a Record's property
+pub fn compute_property_page_address_range(record_id: &str, property_name: &str) -> String {
+    compute_property_address_range(record_id) + &hash(property_name, 22)
+}
+
+/// Computes the address a page of a Record's property is stored at. Page 0
+/// holds the property itself and the following pages its reported values.
+pub fn compute_property_address(record_id: &str, property_name: &str, page: u32) -> String {
+    compute_property_page_address_range(record_id, property_name) + &num_to_page_number(page)
+}
+
+/// Computes the address the proposals to an agent for a Record are stored at
+pub fn compute_proposal_address(record_id: &str, agent_id: &str) -> String {
+    String::from(TRACK_AND_TRACE_PROPOSAL_PREFIX) + &hash(record_id, 36) + &hash(agent_id, 26)
+}
+
+/// Formats a property page number as the 4 hex characters ending its address
+pub fn num_to_page_number(page: u32) -> String {
+    format!("{:01$x}", page, 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Test that the namespace is derived from the family name
+    fn test_namespace() {
+        assert_eq!(
+            hash(TRACK_AND_TRACE_FAMILY_NAME, 6),
+            TRACK_AND_TRACE_NAMESPACE
+        );
+    }
+
+    #[test]
+    // Test that record addresses match the addresses stored by the Track and Trace contract
+    fn test_compute_record_address() {
+        assert_eq!(
+            compute_record_address("record_id"),
+            "a43b46ec38567c3ce87458e4bc7d69c104640b6269ccd742cbc413d44358f48dc6729e"
+        );
+    }
+
+    #[test]
+    // Test that property addresses end with the page number and share the record's range
+    fn test_compute_property_address() {
+        assert_eq!(
+            compute_property_address_range("record_id"),
+            "a43b46ea38567c3ce87458e4bc7d69c104640b6269cc"
+        );
+        assert_eq!(
+            compute_property_page_address_range("record_id", "temperature"),
+            "a43b46ea38567c3ce87458e4bc7d69c104640b6269ccf08bfeb8fd09b963f81f9d"
+        );
+        assert_eq!(
+            compute_property_address("record_id", "temperature", 0),
+            "a43b46ea38567c3ce87458e4bc7d69c104640b6269ccf08bfeb8fd09b963f81f9d0000"
+        );
+        assert_eq!(
+            compute_property_address("record_id", "temperature", 1),
+            "a43b46ea38567c3ce87458e4bc7d69c104640b6269ccf08bfeb8fd09b963f81f9d0001"
+        );
+        assert_eq!(
+            compute_property_address("record_id", "temperature", 255),
+            "a43b46ea38567c3ce87458e4bc7d69c104640b6269ccf08bfeb8fd09b963f81f9d00ff"
+        );
+    }
+
+    #[test]
+    // Test that proposal addresses match the addresses stored by the Track and Trace contract
+    fn test_compute_proposal_address() {
+        assert_eq!(
+            compute_proposal_address("record_id", "agent_public_key"),
+            "a43b46aa38567c3ce87458e4bc7d69c104640b6269cc292d5baa6b4002893890c62b42"
+        );
+    }
+}
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod addressing;
 pub mod errors;
 pub mod pike;
 pub mod schema;
 pub mod track_and_trace;
+pub mod versioning;
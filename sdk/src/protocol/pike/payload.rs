@@ -14,10 +14,16 @@
 
 use protobuf::Message;
 use protobuf::RepeatedField;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use std::error::Error as StdError;
 
-use crate::protocol::pike::state::KeyValueEntry;
+use crate::protocol::addressing::pike::{
+    compute_agent_address, compute_alternate_id_address, compute_org_address, compute_role_address,
+    compute_role_address_range, PIKE_AGENT_PREFIX, PIKE_ALTERNATE_ID_PREFIX, PIKE_ORG_PREFIX,
+};
+use crate::protocol::pike::state::{AlternateId, KeyValueEntry};
 use crate::protos;
 use crate::protos::{
     FromBytes, FromNative, FromProto, IntoBytes, IntoNative, IntoProto, ProtoConversionError,
@@ -25,11 +31,18 @@ use crate::protos::{
 
 /// Native implementation for PikePayload_Action
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
 pub enum Action {
     CreateAgent,
     UpdateAgent,
     CreateOrganization,
     UpdateOrganization,
+    TransferAgent,
+    CreateRole,
+    UpdateRole,
+    DeleteRole,
+    RotateAgentKey,
 }
 
 impl FromProto<protos::pike_payload::PikePayload_Action> for Action {
@@ -45,6 +58,13 @@ impl FromProto<protos::pike_payload::PikePayload_Action> for Action {
             protos::pike_payload::PikePayload_Action::UPDATE_ORGANIZATION => {
                 Ok(Action::UpdateOrganization)
             }
+            protos::pike_payload::PikePayload_Action::TRANSFER_AGENT => Ok(Action::TransferAgent),
+            protos::pike_payload::PikePayload_Action::CREATE_ROLE => Ok(Action::CreateRole),
+            protos::pike_payload::PikePayload_Action::UPDATE_ROLE => Ok(Action::UpdateRole),
+            protos::pike_payload::PikePayload_Action::DELETE_ROLE => Ok(Action::DeleteRole),
+            protos::pike_payload::PikePayload_Action::ROTATE_AGENT_KEY => {
+                Ok(Action::RotateAgentKey)
+            }
             protos::pike_payload::PikePayload_Action::ACTION_UNSET => {
                 Err(ProtoConversionError::InvalidTypeError(
                     "Cannot convert PikePayload_Action with type unset.".to_string(),
@@ -65,6 +85,13 @@ impl FromNative<Action> for protos::pike_payload::PikePayload_Action {
             Action::UpdateOrganization => {
                 Ok(protos::pike_payload::PikePayload_Action::UPDATE_ORGANIZATION)
             }
+            Action::TransferAgent => Ok(protos::pike_payload::PikePayload_Action::TRANSFER_AGENT),
+            Action::CreateRole => Ok(protos::pike_payload::PikePayload_Action::CREATE_ROLE),
+            Action::UpdateRole => Ok(protos::pike_payload::PikePayload_Action::UPDATE_ROLE),
+            Action::DeleteRole => Ok(protos::pike_payload::PikePayload_Action::DELETE_ROLE),
+            Action::RotateAgentKey => {
+                Ok(protos::pike_payload::PikePayload_Action::ROTATE_AGENT_KEY)
+            }
         }
     }
 }
@@ -74,6 +101,7 @@ impl IntoNative<Action> for protos::pike_payload::PikePayload_Action {}
 
 /// Native implementation for CreateAgentAction
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CreateAgentAction {
     org_id: String,
     public_key: String,
@@ -265,6 +293,7 @@ impl CreateAgentActionBuilder {
 
 /// Native implementation for UpdateAgentAction
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UpdateAgentAction {
     org_id: String,
     public_key: String,
@@ -454,13 +483,288 @@ impl UpdateAgentActionBuilder {
     }
 }
 
+/// Native implementation for TransferAgentAction
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TransferAgentAction {
+    public_key: String,
+    org_id: String,
+}
+
+impl TransferAgentAction {
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// The organization the agent is transferred to
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
+}
+
+impl FromProto<protos::pike_payload::TransferAgentAction> for TransferAgentAction {
+    fn from_proto(
+        transfer_agent: protos::pike_payload::TransferAgentAction,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(TransferAgentAction {
+            public_key: transfer_agent.get_public_key().to_string(),
+            org_id: transfer_agent.get_org_id().to_string(),
+        })
+    }
+}
+
+impl FromNative<TransferAgentAction> for protos::pike_payload::TransferAgentAction {
+    fn from_native(transfer_agent: TransferAgentAction) -> Result<Self, ProtoConversionError> {
+        let mut proto_transfer_agent = protos::pike_payload::TransferAgentAction::new();
+
+        proto_transfer_agent.set_public_key(transfer_agent.public_key().to_string());
+        proto_transfer_agent.set_org_id(transfer_agent.org_id().to_string());
+
+        Ok(proto_transfer_agent)
+    }
+}
+
+impl FromBytes<TransferAgentAction> for TransferAgentAction {
+    fn from_bytes(bytes: &[u8]) -> Result<TransferAgentAction, ProtoConversionError> {
+        let proto: protos::pike_payload::TransferAgentAction = protobuf::parse_from_bytes(bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get TransferAgentAction from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for TransferAgentAction {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from TransferAgentAction".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_payload::TransferAgentAction> for TransferAgentAction {}
+impl IntoNative<TransferAgentAction> for protos::pike_payload::TransferAgentAction {}
+
+#[derive(Debug)]
+pub enum TransferAgentActionBuildError {
+    MissingField(String),
+}
+
+impl StdError for TransferAgentActionBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            TransferAgentActionBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            TransferAgentActionBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TransferAgentActionBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            TransferAgentActionBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a TransferAgentAction
+#[derive(Default, Clone)]
+pub struct TransferAgentActionBuilder {
+    pub public_key: Option<String>,
+    pub org_id: Option<String>,
+}
+
+impl TransferAgentActionBuilder {
+    pub fn new() -> Self {
+        TransferAgentActionBuilder::default()
+    }
+
+    pub fn with_public_key(mut self, public_key: String) -> TransferAgentActionBuilder {
+        self.public_key = Some(public_key);
+        self
+    }
+
+    pub fn with_org_id(mut self, org_id: String) -> TransferAgentActionBuilder {
+        self.org_id = Some(org_id);
+        self
+    }
+
+    pub fn build(self) -> Result<TransferAgentAction, TransferAgentActionBuildError> {
+        let public_key = self.public_key.ok_or_else(|| {
+            TransferAgentActionBuildError::MissingField(
+                "'public_key' field is required".to_string(),
+            )
+        })?;
+
+        let org_id = self.org_id.ok_or_else(|| {
+            TransferAgentActionBuildError::MissingField("'org_id' field is required".to_string())
+        })?;
+
+        Ok(TransferAgentAction { public_key, org_id })
+    }
+}
+
+/// Native implementation for RotateAgentKeyAction
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RotateAgentKeyAction {
+    public_key: String,
+    new_public_key: String,
+}
+
+impl RotateAgentKeyAction {
+    /// The key being replaced
+    pub fn public_key(&self) -> &str {
+        &self.public_key
+    }
+
+    /// The key of the agent taking over the identity
+    pub fn new_public_key(&self) -> &str {
+        &self.new_public_key
+    }
+}
+
+impl FromProto<protos::pike_payload::RotateAgentKeyAction> for RotateAgentKeyAction {
+    fn from_proto(
+        rotate_agent_key: protos::pike_payload::RotateAgentKeyAction,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(RotateAgentKeyAction {
+            public_key: rotate_agent_key.get_public_key().to_string(),
+            new_public_key: rotate_agent_key.get_new_public_key().to_string(),
+        })
+    }
+}
+
+impl FromNative<RotateAgentKeyAction> for protos::pike_payload::RotateAgentKeyAction {
+    fn from_native(rotate_agent_key: RotateAgentKeyAction) -> Result<Self, ProtoConversionError> {
+        let mut proto_rotate_agent_key = protos::pike_payload::RotateAgentKeyAction::new();
+
+        proto_rotate_agent_key.set_public_key(rotate_agent_key.public_key().to_string());
+        proto_rotate_agent_key.set_new_public_key(rotate_agent_key.new_public_key().to_string());
+
+        Ok(proto_rotate_agent_key)
+    }
+}
+
+impl FromBytes<RotateAgentKeyAction> for RotateAgentKeyAction {
+    fn from_bytes(bytes: &[u8]) -> Result<RotateAgentKeyAction, ProtoConversionError> {
+        let proto: protos::pike_payload::RotateAgentKeyAction = protobuf::parse_from_bytes(bytes)
+            .map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get RotateAgentKeyAction from bytes".to_string(),
+            )
+        })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for RotateAgentKeyAction {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from RotateAgentKeyAction".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_payload::RotateAgentKeyAction> for RotateAgentKeyAction {}
+impl IntoNative<RotateAgentKeyAction> for protos::pike_payload::RotateAgentKeyAction {}
+
+#[derive(Debug)]
+pub enum RotateAgentKeyActionBuildError {
+    MissingField(String),
+}
+
+impl StdError for RotateAgentKeyActionBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            RotateAgentKeyActionBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            RotateAgentKeyActionBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RotateAgentKeyActionBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            RotateAgentKeyActionBuildError::MissingField(ref s) => {
+                write!(f, "MissingField: {}", s)
+            }
+        }
+    }
+}
+
+/// Builder used to create a RotateAgentKeyAction
+#[derive(Default, Clone)]
+pub struct RotateAgentKeyActionBuilder {
+    pub public_key: Option<String>,
+    pub new_public_key: Option<String>,
+}
+
+impl RotateAgentKeyActionBuilder {
+    pub fn new() -> Self {
+        RotateAgentKeyActionBuilder::default()
+    }
+
+    pub fn with_public_key(mut self, public_key: String) -> RotateAgentKeyActionBuilder {
+        self.public_key = Some(public_key);
+        self
+    }
+
+    pub fn with_new_public_key(mut self, new_public_key: String) -> RotateAgentKeyActionBuilder {
+        self.new_public_key = Some(new_public_key);
+        self
+    }
+
+    pub fn build(self) -> Result<RotateAgentKeyAction, RotateAgentKeyActionBuildError> {
+        let public_key = self.public_key.ok_or_else(|| {
+            RotateAgentKeyActionBuildError::MissingField(
+                "'public_key' field is required".to_string(),
+            )
+        })?;
+
+        let new_public_key = self.new_public_key.ok_or_else(|| {
+            RotateAgentKeyActionBuildError::MissingField(
+                "'new_public_key' field is required".to_string(),
+            )
+        })?;
+
+        Ok(RotateAgentKeyAction {
+            public_key,
+            new_public_key,
+        })
+    }
+}
+
 /// Native implementation for CreageOrganizationAction
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CreateOrganizationAction {
     org_id: String,
     name: String,
     address: String,
     metadata: Vec<KeyValueEntry>,
+    parent_org_id: String,
+    alternate_ids: Vec<AlternateId>,
 }
 
 impl CreateOrganizationAction {
@@ -479,6 +783,24 @@ impl CreateOrganizationAction {
     pub fn metadata(&self) -> &[KeyValueEntry] {
         &self.metadata
     }
+
+    pub fn parent_org_id(&self) -> &str {
+        &self.parent_org_id
+    }
+
+    pub fn alternate_ids(&self) -> &[AlternateId] {
+        &self.alternate_ids
+    }
+
+    /// The addresses of the index entries reserving the alternate IDs
+    fn alternate_id_addresses(&self) -> Vec<String> {
+        self.alternate_ids
+            .iter()
+            .map(|alternate_id| {
+                compute_alternate_id_address(alternate_id.id_type(), alternate_id.id())
+            })
+            .collect()
+    }
 }
 
 impl FromProto<protos::pike_payload::CreateOrganizationAction> for CreateOrganizationAction {
@@ -495,6 +817,13 @@ impl FromProto<protos::pike_payload::CreateOrganizationAction> for CreateOrganiz
                 .into_iter()
                 .map(KeyValueEntry::from_proto)
                 .collect::<Result<Vec<KeyValueEntry>, ProtoConversionError>>()?,
+            parent_org_id: create_org.get_parent_org_id().to_string(),
+            alternate_ids: create_org
+                .get_alternate_ids()
+                .to_vec()
+                .into_iter()
+                .map(AlternateId::from_proto)
+                .collect::<Result<Vec<AlternateId>, ProtoConversionError>>()?,
         })
     }
 }
@@ -515,6 +844,15 @@ impl FromNative<CreateOrganizationAction> for protos::pike_payload::CreateOrgani
                 .collect::<Result<Vec<protos::pike_state::KeyValueEntry>, ProtoConversionError>>(
                 )?,
         ));
+        proto_create_org.set_parent_org_id(create_org.parent_org_id().to_string());
+        proto_create_org.set_alternate_ids(RepeatedField::from_vec(
+            create_org
+                .alternate_ids()
+                .to_vec()
+                .into_iter()
+                .map(AlternateId::into_proto)
+                .collect::<Result<Vec<protos::pike_state::AlternateId>, ProtoConversionError>>()?,
+        ));
 
         Ok(proto_create_org)
     }
@@ -583,6 +921,8 @@ pub struct CreateOrganizationActionBuilder {
     pub name: Option<String>,
     pub address: Option<String>,
     pub metadata: Vec<KeyValueEntry>,
+    pub parent_org_id: Option<String>,
+    pub alternate_ids: Vec<AlternateId>,
 }
 
 impl CreateOrganizationActionBuilder {
@@ -613,6 +953,19 @@ impl CreateOrganizationActionBuilder {
         self
     }
 
+    pub fn with_parent_org_id(mut self, parent_org_id: String) -> CreateOrganizationActionBuilder {
+        self.parent_org_id = Some(parent_org_id);
+        self
+    }
+
+    pub fn with_alternate_ids(
+        mut self,
+        alternate_ids: Vec<AlternateId>,
+    ) -> CreateOrganizationActionBuilder {
+        self.alternate_ids = alternate_ids;
+        self
+    }
+
     pub fn build(self) -> Result<CreateOrganizationAction, CreateOrganizationActionBuildError> {
         let org_id = self.org_id.ok_or_else(|| {
             CreateOrganizationActionBuildError::MissingField(
@@ -632,22 +985,32 @@ impl CreateOrganizationActionBuilder {
 
         let metadata = self.metadata;
 
+        let parent_org_id = self.parent_org_id.unwrap_or_default();
+
+        let alternate_ids = self.alternate_ids;
+
         Ok(CreateOrganizationAction {
             org_id,
             name,
             address,
             metadata,
+            parent_org_id,
+            alternate_ids,
         })
     }
 }
 
 /// Native implementation for UpdateOrganizationAction
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UpdateOrganizationAction {
     org_id: String,
     name: String,
     address: String,
     metadata: Vec<KeyValueEntry>,
+    parent_org_id: String,
+    alternate_ids: Vec<AlternateId>,
+    detach_from_parent: bool,
 }
 
 impl UpdateOrganizationAction {
@@ -666,6 +1029,18 @@ impl UpdateOrganizationAction {
     pub fn metadata(&self) -> &[KeyValueEntry] {
         &self.metadata
     }
+
+    pub fn parent_org_id(&self) -> &str {
+        &self.parent_org_id
+    }
+
+    pub fn alternate_ids(&self) -> &[AlternateId] {
+        &self.alternate_ids
+    }
+
+    pub fn detach_from_parent(&self) -> bool {
+        self.detach_from_parent
+    }
 }
 
 impl FromProto<protos::pike_payload::UpdateOrganizationAction> for UpdateOrganizationAction {
@@ -682,6 +1057,14 @@ impl FromProto<protos::pike_payload::UpdateOrganizationAction> for UpdateOrganiz
                 .into_iter()
                 .map(KeyValueEntry::from_proto)
                 .collect::<Result<Vec<KeyValueEntry>, ProtoConversionError>>()?,
+            parent_org_id: create_org.get_parent_org_id().to_string(),
+            alternate_ids: create_org
+                .get_alternate_ids()
+                .to_vec()
+                .into_iter()
+                .map(AlternateId::from_proto)
+                .collect::<Result<Vec<AlternateId>, ProtoConversionError>>()?,
+            detach_from_parent: create_org.get_detach_from_parent(),
         })
     }
 }
@@ -702,6 +1085,16 @@ impl FromNative<UpdateOrganizationAction> for protos::pike_payload::UpdateOrgani
                 .collect::<Result<Vec<protos::pike_state::KeyValueEntry>, ProtoConversionError>>(
                 )?,
         ));
+        proto_update_org.set_parent_org_id(update_org.parent_org_id().to_string());
+        proto_update_org.set_alternate_ids(RepeatedField::from_vec(
+            update_org
+                .alternate_ids()
+                .to_vec()
+                .into_iter()
+                .map(AlternateId::into_proto)
+                .collect::<Result<Vec<protos::pike_state::AlternateId>, ProtoConversionError>>()?,
+        ));
+        proto_update_org.set_detach_from_parent(update_org.detach_from_parent());
 
         Ok(proto_update_org)
     }
@@ -770,6 +1163,9 @@ pub struct UpdateOrganizationActionBuilder {
     pub name: Option<String>,
     pub address: Option<String>,
     pub metadata: Vec<KeyValueEntry>,
+    pub parent_org_id: Option<String>,
+    pub alternate_ids: Vec<AlternateId>,
+    pub detach_from_parent: bool,
 }
 
 impl UpdateOrganizationActionBuilder {
@@ -800,6 +1196,27 @@ impl UpdateOrganizationActionBuilder {
         self
     }
 
+    pub fn with_parent_org_id(mut self, parent_org_id: String) -> UpdateOrganizationActionBuilder {
+        self.parent_org_id = Some(parent_org_id);
+        self
+    }
+
+    pub fn with_alternate_ids(
+        mut self,
+        alternate_ids: Vec<AlternateId>,
+    ) -> UpdateOrganizationActionBuilder {
+        self.alternate_ids = alternate_ids;
+        self
+    }
+
+    pub fn with_detach_from_parent(
+        mut self,
+        detach_from_parent: bool,
+    ) -> UpdateOrganizationActionBuilder {
+        self.detach_from_parent = detach_from_parent;
+        self
+    }
+
     pub fn build(self) -> Result<UpdateOrganizationAction, UpdateOrganizationActionBuildError> {
         let org_id = self.org_id.ok_or_else(|| {
             UpdateOrganizationActionBuildError::MissingField(
@@ -813,36 +1230,536 @@ impl UpdateOrganizationActionBuilder {
 
         let metadata = self.metadata;
 
+        let parent_org_id = self.parent_org_id.unwrap_or_default();
+
+        let alternate_ids = self.alternate_ids;
+
+        let detach_from_parent = self.detach_from_parent;
+
         Ok(UpdateOrganizationAction {
             org_id,
             name,
             address,
             metadata,
+            parent_org_id,
+            alternate_ids,
+            detach_from_parent,
         })
     }
 }
 
-/// Native implementation for PikePayload
-#[derive(Debug, Clone, PartialEq)]
-pub struct PikePayload {
-    action: Action,
-    create_agent: CreateAgentAction,
-    update_agent: UpdateAgentAction,
-    create_organization: CreateOrganizationAction,
-    update_organization: UpdateOrganizationAction,
+/// Native implementation for CreateRoleAction
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CreateRoleAction {
+    org_id: String,
+    name: String,
+    description: String,
+    permissions: Vec<String>,
+    inherit_from: Vec<String>,
 }
 
-impl PikePayload {
-    pub fn action(&self) -> &Action {
-        &self.action
+impl CreateRoleAction {
+    pub fn org_id(&self) -> &str {
+        &self.org_id
     }
 
-    pub fn create_agent(&self) -> &CreateAgentAction {
-        &self.create_agent
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
-    pub fn update_agent(&self) -> &UpdateAgentAction {
-        &self.update_agent
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn permissions(&self) -> &[String] {
+        &self.permissions
+    }
+
+    pub fn inherit_from(&self) -> &[String] {
+        &self.inherit_from
+    }
+}
+
+impl FromProto<protos::pike_payload::CreateRoleAction> for CreateRoleAction {
+    fn from_proto(
+        create_role: protos::pike_payload::CreateRoleAction,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(CreateRoleAction {
+            org_id: create_role.get_org_id().to_string(),
+            name: create_role.get_name().to_string(),
+            description: create_role.get_description().to_string(),
+            permissions: create_role.get_permissions().to_vec(),
+            inherit_from: create_role.get_inherit_from().to_vec(),
+        })
+    }
+}
+
+impl FromNative<CreateRoleAction> for protos::pike_payload::CreateRoleAction {
+    fn from_native(create_role: CreateRoleAction) -> Result<Self, ProtoConversionError> {
+        let mut proto_create_role = protos::pike_payload::CreateRoleAction::new();
+
+        proto_create_role.set_org_id(create_role.org_id().to_string());
+        proto_create_role.set_name(create_role.name().to_string());
+        proto_create_role.set_description(create_role.description().to_string());
+        proto_create_role
+            .set_permissions(RepeatedField::from_vec(create_role.permissions().to_vec()));
+        proto_create_role
+            .set_inherit_from(RepeatedField::from_vec(create_role.inherit_from().to_vec()));
+
+        Ok(proto_create_role)
+    }
+}
+
+impl FromBytes<CreateRoleAction> for CreateRoleAction {
+    fn from_bytes(bytes: &[u8]) -> Result<CreateRoleAction, ProtoConversionError> {
+        let proto: protos::pike_payload::CreateRoleAction = protobuf::parse_from_bytes(bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get CreateRoleAction from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for CreateRoleAction {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from CreateRoleAction".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_payload::CreateRoleAction> for CreateRoleAction {}
+impl IntoNative<CreateRoleAction> for protos::pike_payload::CreateRoleAction {}
+
+#[derive(Debug)]
+pub enum CreateRoleActionBuildError {
+    MissingField(String),
+}
+
+impl StdError for CreateRoleActionBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            CreateRoleActionBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            CreateRoleActionBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for CreateRoleActionBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            CreateRoleActionBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a CreateRoleAction
+#[derive(Default, Clone)]
+pub struct CreateRoleActionBuilder {
+    pub org_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub inherit_from: Vec<String>,
+}
+
+impl CreateRoleActionBuilder {
+    pub fn new() -> Self {
+        CreateRoleActionBuilder::default()
+    }
+
+    pub fn with_org_id(mut self, org_id: String) -> CreateRoleActionBuilder {
+        self.org_id = Some(org_id);
+        self
+    }
+
+    pub fn with_name(mut self, name: String) -> CreateRoleActionBuilder {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn with_description(mut self, description: String) -> CreateRoleActionBuilder {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_permissions(mut self, permissions: Vec<String>) -> CreateRoleActionBuilder {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn with_inherit_from(mut self, inherit_from: Vec<String>) -> CreateRoleActionBuilder {
+        self.inherit_from = inherit_from;
+        self
+    }
+
+    pub fn build(self) -> Result<CreateRoleAction, CreateRoleActionBuildError> {
+        let org_id = self.org_id.ok_or_else(|| {
+            CreateRoleActionBuildError::MissingField("'org_id' field is required".to_string())
+        })?;
+
+        let name = self.name.ok_or_else(|| {
+            CreateRoleActionBuildError::MissingField("'name' field is required".to_string())
+        })?;
+
+        let description = self.description.unwrap_or_default();
+
+        Ok(CreateRoleAction {
+            org_id,
+            name,
+            description,
+            permissions: self.permissions,
+            inherit_from: self.inherit_from,
+        })
+    }
+}
+
+/// Native implementation for UpdateRoleAction
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpdateRoleAction {
+    org_id: String,
+    name: String,
+    description: String,
+    permissions: Vec<String>,
+    inherit_from: Vec<String>,
+}
+
+impl UpdateRoleAction {
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn permissions(&self) -> &[String] {
+        &self.permissions
+    }
+
+    pub fn inherit_from(&self) -> &[String] {
+        &self.inherit_from
+    }
+}
+
+impl FromProto<protos::pike_payload::UpdateRoleAction> for UpdateRoleAction {
+    fn from_proto(
+        update_role: protos::pike_payload::UpdateRoleAction,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(UpdateRoleAction {
+            org_id: update_role.get_org_id().to_string(),
+            name: update_role.get_name().to_string(),
+            description: update_role.get_description().to_string(),
+            permissions: update_role.get_permissions().to_vec(),
+            inherit_from: update_role.get_inherit_from().to_vec(),
+        })
+    }
+}
+
+impl FromNative<UpdateRoleAction> for protos::pike_payload::UpdateRoleAction {
+    fn from_native(update_role: UpdateRoleAction) -> Result<Self, ProtoConversionError> {
+        let mut proto_update_role = protos::pike_payload::UpdateRoleAction::new();
+
+        proto_update_role.set_org_id(update_role.org_id().to_string());
+        proto_update_role.set_name(update_role.name().to_string());
+        proto_update_role.set_description(update_role.description().to_string());
+        proto_update_role
+            .set_permissions(RepeatedField::from_vec(update_role.permissions().to_vec()));
+        proto_update_role
+            .set_inherit_from(RepeatedField::from_vec(update_role.inherit_from().to_vec()));
+
+        Ok(proto_update_role)
+    }
+}
+
+impl FromBytes<UpdateRoleAction> for UpdateRoleAction {
+    fn from_bytes(bytes: &[u8]) -> Result<UpdateRoleAction, ProtoConversionError> {
+        let proto: protos::pike_payload::UpdateRoleAction = protobuf::parse_from_bytes(bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get UpdateRoleAction from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for UpdateRoleAction {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from UpdateRoleAction".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_payload::UpdateRoleAction> for UpdateRoleAction {}
+impl IntoNative<UpdateRoleAction> for protos::pike_payload::UpdateRoleAction {}
+
+#[derive(Debug)]
+pub enum UpdateRoleActionBuildError {
+    MissingField(String),
+}
+
+impl StdError for UpdateRoleActionBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            UpdateRoleActionBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            UpdateRoleActionBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for UpdateRoleActionBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            UpdateRoleActionBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a UpdateRoleAction
+#[derive(Default, Clone)]
+pub struct UpdateRoleActionBuilder {
+    pub org_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub inherit_from: Vec<String>,
+}
+
+impl UpdateRoleActionBuilder {
+    pub fn new() -> Self {
+        UpdateRoleActionBuilder::default()
+    }
+
+    pub fn with_org_id(mut self, org_id: String) -> UpdateRoleActionBuilder {
+        self.org_id = Some(org_id);
+        self
+    }
+
+    pub fn with_name(mut self, name: String) -> UpdateRoleActionBuilder {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn with_description(mut self, description: String) -> UpdateRoleActionBuilder {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_permissions(mut self, permissions: Vec<String>) -> UpdateRoleActionBuilder {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn with_inherit_from(mut self, inherit_from: Vec<String>) -> UpdateRoleActionBuilder {
+        self.inherit_from = inherit_from;
+        self
+    }
+
+    pub fn build(self) -> Result<UpdateRoleAction, UpdateRoleActionBuildError> {
+        let org_id = self.org_id.ok_or_else(|| {
+            UpdateRoleActionBuildError::MissingField("'org_id' field is required".to_string())
+        })?;
+
+        let name = self.name.ok_or_else(|| {
+            UpdateRoleActionBuildError::MissingField("'name' field is required".to_string())
+        })?;
+
+        let description = self.description.unwrap_or_default();
+
+        Ok(UpdateRoleAction {
+            org_id,
+            name,
+            description,
+            permissions: self.permissions,
+            inherit_from: self.inherit_from,
+        })
+    }
+}
+
+/// Native implementation for DeleteRoleAction
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeleteRoleAction {
+    org_id: String,
+    name: String,
+}
+
+impl DeleteRoleAction {
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl FromProto<protos::pike_payload::DeleteRoleAction> for DeleteRoleAction {
+    fn from_proto(
+        delete_role: protos::pike_payload::DeleteRoleAction,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(DeleteRoleAction {
+            org_id: delete_role.get_org_id().to_string(),
+            name: delete_role.get_name().to_string(),
+        })
+    }
+}
+
+impl FromNative<DeleteRoleAction> for protos::pike_payload::DeleteRoleAction {
+    fn from_native(delete_role: DeleteRoleAction) -> Result<Self, ProtoConversionError> {
+        let mut proto_delete_role = protos::pike_payload::DeleteRoleAction::new();
+
+        proto_delete_role.set_org_id(delete_role.org_id().to_string());
+        proto_delete_role.set_name(delete_role.name().to_string());
+
+        Ok(proto_delete_role)
+    }
+}
+
+impl FromBytes<DeleteRoleAction> for DeleteRoleAction {
+    fn from_bytes(bytes: &[u8]) -> Result<DeleteRoleAction, ProtoConversionError> {
+        let proto: protos::pike_payload::DeleteRoleAction = protobuf::parse_from_bytes(bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get DeleteRoleAction from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for DeleteRoleAction {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from DeleteRoleAction".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_payload::DeleteRoleAction> for DeleteRoleAction {}
+impl IntoNative<DeleteRoleAction> for protos::pike_payload::DeleteRoleAction {}
+
+#[derive(Debug)]
+pub enum DeleteRoleActionBuildError {
+    MissingField(String),
+}
+
+impl StdError for DeleteRoleActionBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            DeleteRoleActionBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            DeleteRoleActionBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for DeleteRoleActionBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            DeleteRoleActionBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a DeleteRoleAction
+#[derive(Default, Clone)]
+pub struct DeleteRoleActionBuilder {
+    pub org_id: Option<String>,
+    pub name: Option<String>,
+}
+
+impl DeleteRoleActionBuilder {
+    pub fn new() -> Self {
+        DeleteRoleActionBuilder::default()
+    }
+
+    pub fn with_org_id(mut self, org_id: String) -> DeleteRoleActionBuilder {
+        self.org_id = Some(org_id);
+        self
+    }
+
+    pub fn with_name(mut self, name: String) -> DeleteRoleActionBuilder {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn build(self) -> Result<DeleteRoleAction, DeleteRoleActionBuildError> {
+        let org_id = self.org_id.ok_or_else(|| {
+            DeleteRoleActionBuildError::MissingField("'org_id' field is required".to_string())
+        })?;
+
+        let name = self.name.ok_or_else(|| {
+            DeleteRoleActionBuildError::MissingField("'name' field is required".to_string())
+        })?;
+
+        Ok(DeleteRoleAction { org_id, name })
+    }
+}
+
+/// Native implementation for PikePayload
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PikePayload {
+    action: Action,
+    create_agent: CreateAgentAction,
+    update_agent: UpdateAgentAction,
+    create_organization: CreateOrganizationAction,
+    update_organization: UpdateOrganizationAction,
+    transfer_agent: TransferAgentAction,
+    create_role: CreateRoleAction,
+    update_role: UpdateRoleAction,
+    delete_role: DeleteRoleAction,
+    rotate_agent_key: RotateAgentKeyAction,
+}
+
+impl PikePayload {
+    pub fn action(&self) -> &Action {
+        &self.action
+    }
+
+    pub fn create_agent(&self) -> &CreateAgentAction {
+        &self.create_agent
+    }
+
+    pub fn update_agent(&self) -> &UpdateAgentAction {
+        &self.update_agent
     }
 
     pub fn create_organization(&self) -> &CreateOrganizationAction {
@@ -852,6 +1769,158 @@ impl PikePayload {
     pub fn update_organization(&self) -> &UpdateOrganizationAction {
         &self.update_organization
     }
+
+    pub fn transfer_agent(&self) -> &TransferAgentAction {
+        &self.transfer_agent
+    }
+
+    pub fn create_role(&self) -> &CreateRoleAction {
+        &self.create_role
+    }
+
+    pub fn update_role(&self) -> &UpdateRoleAction {
+        &self.update_role
+    }
+
+    pub fn delete_role(&self) -> &DeleteRoleAction {
+        &self.delete_role
+    }
+
+    pub fn rotate_agent_key(&self) -> &RotateAgentKeyAction {
+        &self.rotate_agent_key
+    }
+
+    /// Returns the addresses the Pike contract reads when `signer_public_key`
+    /// submits this payload.
+    pub fn inputs(&self, signer_public_key: &str) -> Vec<String> {
+        let mut inputs = match self.action {
+            // Admin rights are checked against the ancestors of the
+            // organization, which are only known from state
+            Action::CreateAgent => vec![
+                compute_agent_address(signer_public_key),
+                compute_agent_address(self.create_agent.public_key()),
+                PIKE_ORG_PREFIX.to_string(),
+            ],
+            Action::UpdateAgent => vec![
+                compute_agent_address(signer_public_key),
+                compute_agent_address(self.update_agent.public_key()),
+                PIKE_ORG_PREFIX.to_string(),
+            ],
+            Action::CreateOrganization if self.create_organization.parent_org_id().is_empty() => {
+                vec![
+                    compute_agent_address(signer_public_key),
+                    compute_org_address(self.create_organization.org_id()),
+                ]
+            }
+            Action::CreateOrganization => vec![
+                compute_agent_address(signer_public_key),
+                PIKE_ORG_PREFIX.to_string(),
+            ],
+            // The index entries of the replaced IDs are only known from state
+            Action::UpdateOrganization if !self.update_organization.alternate_ids().is_empty() => {
+                vec![
+                    compute_agent_address(signer_public_key),
+                    PIKE_ORG_PREFIX.to_string(),
+                    PIKE_ALTERNATE_ID_PREFIX.to_string(),
+                ]
+            }
+            Action::UpdateOrganization => vec![
+                compute_agent_address(signer_public_key),
+                PIKE_ORG_PREFIX.to_string(),
+            ],
+            Action::TransferAgent => vec![
+                compute_agent_address(signer_public_key),
+                compute_agent_address(self.transfer_agent.public_key()),
+                PIKE_ORG_PREFIX.to_string(),
+            ],
+            // The inherited roles are read along with the role itself
+            Action::CreateRole => vec![
+                compute_agent_address(signer_public_key),
+                compute_role_address_range(self.create_role.org_id()),
+                PIKE_ORG_PREFIX.to_string(),
+            ],
+            Action::UpdateRole => vec![
+                compute_agent_address(signer_public_key),
+                compute_role_address_range(self.update_role.org_id()),
+                PIKE_ORG_PREFIX.to_string(),
+            ],
+            Action::DeleteRole => vec![
+                compute_agent_address(signer_public_key),
+                compute_role_address(self.delete_role.org_id(), self.delete_role.name()),
+                PIKE_ORG_PREFIX.to_string(),
+            ],
+            // Only an admin rotating the key of another agent is checked
+            // against the organizations
+            Action::RotateAgentKey if signer_public_key == self.rotate_agent_key.public_key() => {
+                vec![
+                    compute_agent_address(self.rotate_agent_key.public_key()),
+                    compute_agent_address(self.rotate_agent_key.new_public_key()),
+                ]
+            }
+            // An admin may override the successor of a rotated key, whose
+            // chain of successors is only known from state
+            Action::RotateAgentKey => {
+                vec![PIKE_AGENT_PREFIX.to_string(), PIKE_ORG_PREFIX.to_string()]
+            }
+        };
+        if self.action == Action::CreateOrganization {
+            inputs.extend(self.create_organization.alternate_id_addresses());
+        }
+        inputs.sort();
+        inputs.dedup();
+        inputs
+    }
+
+    /// Returns the addresses the Pike contract writes when `signer_public_key`
+    /// submits this payload.
+    pub fn outputs(&self, signer_public_key: &str) -> Vec<String> {
+        let mut outputs = match self.action {
+            Action::CreateAgent => vec![compute_agent_address(self.create_agent.public_key())],
+            Action::UpdateAgent => vec![compute_agent_address(self.update_agent.public_key())],
+            // Unless it is part of another organization, whose admins
+            // administer it, the signer becomes its first admin
+            Action::CreateOrganization if !self.create_organization.parent_org_id().is_empty() => {
+                vec![compute_org_address(self.create_organization.org_id())]
+            }
+            Action::CreateOrganization => vec![
+                compute_agent_address(signer_public_key),
+                compute_org_address(self.create_organization.org_id()),
+            ],
+            Action::UpdateOrganization if !self.update_organization.alternate_ids().is_empty() => {
+                vec![
+                    compute_org_address(self.update_organization.org_id()),
+                    PIKE_ALTERNATE_ID_PREFIX.to_string(),
+                ]
+            }
+            Action::UpdateOrganization => {
+                vec![compute_org_address(self.update_organization.org_id())]
+            }
+            Action::TransferAgent => vec![compute_agent_address(self.transfer_agent.public_key())],
+            Action::CreateRole => vec![compute_role_address(
+                self.create_role.org_id(),
+                self.create_role.name(),
+            )],
+            Action::UpdateRole => vec![compute_role_address(
+                self.update_role.org_id(),
+                self.update_role.name(),
+            )],
+            Action::DeleteRole => vec![compute_role_address(
+                self.delete_role.org_id(),
+                self.delete_role.name(),
+            )],
+            Action::RotateAgentKey if signer_public_key == self.rotate_agent_key.public_key() => {
+                vec![
+                    compute_agent_address(self.rotate_agent_key.public_key()),
+                    compute_agent_address(self.rotate_agent_key.new_public_key()),
+                ]
+            }
+            Action::RotateAgentKey => vec![PIKE_AGENT_PREFIX.to_string()],
+        };
+        if self.action == Action::CreateOrganization {
+            outputs.extend(self.create_organization.alternate_id_addresses());
+        }
+        outputs
+    }
 }
 
 impl FromProto<protos::pike_payload::PikePayload> for PikePayload {
@@ -868,6 +1937,13 @@ impl FromProto<protos::pike_payload::PikePayload> for PikePayload {
             update_organization: UpdateOrganizationAction::from_proto(
                 payload.get_update_organization().clone(),
             )?,
+            transfer_agent: TransferAgentAction::from_proto(payload.get_transfer_agent().clone())?,
+            create_role: CreateRoleAction::from_proto(payload.get_create_role().clone())?,
+            update_role: UpdateRoleAction::from_proto(payload.get_update_role().clone())?,
+            delete_role: DeleteRoleAction::from_proto(payload.get_delete_role().clone())?,
+            rotate_agent_key: RotateAgentKeyAction::from_proto(
+                payload.get_rotate_agent_key().clone(),
+            )?,
         })
     }
 }
@@ -881,6 +1957,11 @@ impl FromNative<PikePayload> for protos::pike_payload::PikePayload {
         proto_payload.set_update_agent(payload.update_agent().clone().into_proto()?);
         proto_payload.set_create_organization(payload.create_organization().clone().into_proto()?);
         proto_payload.set_update_organization(payload.update_organization().clone().into_proto()?);
+        proto_payload.set_transfer_agent(payload.transfer_agent().clone().into_proto()?);
+        proto_payload.set_create_role(payload.create_role().clone().into_proto()?);
+        proto_payload.set_update_role(payload.update_role().clone().into_proto()?);
+        proto_payload.set_delete_role(payload.delete_role().clone().into_proto()?);
+        proto_payload.set_rotate_agent_key(payload.rotate_agent_key().clone().into_proto()?);
 
         Ok(proto_payload)
     }
@@ -948,6 +2029,11 @@ pub struct PikePayloadBuilder {
     pub update_agent: Option<UpdateAgentAction>,
     pub create_organization: Option<CreateOrganizationAction>,
     pub update_organization: Option<UpdateOrganizationAction>,
+    pub transfer_agent: Option<TransferAgentAction>,
+    pub create_role: Option<CreateRoleAction>,
+    pub update_role: Option<UpdateRoleAction>,
+    pub delete_role: Option<DeleteRoleAction>,
+    pub rotate_agent_key: Option<RotateAgentKeyAction>,
 }
 
 impl PikePayloadBuilder {
@@ -986,6 +2072,37 @@ impl PikePayloadBuilder {
         self
     }
 
+    pub fn with_transfer_agent(
+        mut self,
+        transfer_agent: TransferAgentAction,
+    ) -> PikePayloadBuilder {
+        self.transfer_agent = Some(transfer_agent);
+        self
+    }
+
+    pub fn with_create_role(mut self, create_role: CreateRoleAction) -> PikePayloadBuilder {
+        self.create_role = Some(create_role);
+        self
+    }
+
+    pub fn with_update_role(mut self, update_role: UpdateRoleAction) -> PikePayloadBuilder {
+        self.update_role = Some(update_role);
+        self
+    }
+
+    pub fn with_delete_role(mut self, delete_role: DeleteRoleAction) -> PikePayloadBuilder {
+        self.delete_role = Some(delete_role);
+        self
+    }
+
+    pub fn with_rotate_agent_key(
+        mut self,
+        rotate_agent_key: RotateAgentKeyAction,
+    ) -> PikePayloadBuilder {
+        self.rotate_agent_key = Some(rotate_agent_key);
+        self
+    }
+
     pub fn build(self) -> Result<PikePayload, PikePayloadBuildError> {
         let action = self.action.ok_or_else(|| {
             PikePayloadBuildError::MissingField("'action' field is required".to_string())
@@ -1039,12 +2156,77 @@ impl PikePayloadBuilder {
             }
         };
 
+        let transfer_agent = {
+            if action == Action::TransferAgent {
+                self.transfer_agent.ok_or_else(|| {
+                    PikePayloadBuildError::MissingField(
+                        "'transfer_agent' field is required".to_string(),
+                    )
+                })?
+            } else {
+                TransferAgentAction::default()
+            }
+        };
+
+        let create_role = {
+            if action == Action::CreateRole {
+                self.create_role.ok_or_else(|| {
+                    PikePayloadBuildError::MissingField(
+                        "'create_role' field is required".to_string(),
+                    )
+                })?
+            } else {
+                CreateRoleAction::default()
+            }
+        };
+
+        let update_role = {
+            if action == Action::UpdateRole {
+                self.update_role.ok_or_else(|| {
+                    PikePayloadBuildError::MissingField(
+                        "'update_role' field is required".to_string(),
+                    )
+                })?
+            } else {
+                UpdateRoleAction::default()
+            }
+        };
+
+        let delete_role = {
+            if action == Action::DeleteRole {
+                self.delete_role.ok_or_else(|| {
+                    PikePayloadBuildError::MissingField(
+                        "'delete_role' field is required".to_string(),
+                    )
+                })?
+            } else {
+                DeleteRoleAction::default()
+            }
+        };
+
+        let rotate_agent_key = {
+            if action == Action::RotateAgentKey {
+                self.rotate_agent_key.ok_or_else(|| {
+                    PikePayloadBuildError::MissingField(
+                        "'rotate_agent_key' field is required".to_string(),
+                    )
+                })?
+            } else {
+                RotateAgentKeyAction::default()
+            }
+        };
+
         Ok(PikePayload {
             action,
             create_agent,
             update_agent,
             create_organization,
             update_organization,
+            transfer_agent,
+            create_role,
+            update_role,
+            delete_role,
+            rotate_agent_key,
         })
     }
 }
@@ -1222,6 +2404,7 @@ mod tests {
         assert_eq!(update_organization.org_id(), "organization");
         assert_eq!(update_organization.name(), "name");
         assert_eq!(update_organization.address(), "address");
+        assert!(!update_organization.detach_from_parent());
     }
 
     #[test]
@@ -1232,11 +2415,23 @@ mod tests {
             .with_org_id("organization".to_string())
             .with_name("name".to_string())
             .with_address("address".to_string())
+            .with_parent_org_id("parent".to_string())
+            .build()
+            .unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+        let org = UpdateOrganizationAction::from_bytes(&bytes).unwrap();
+        assert_eq!(org, original);
+
+        let original = UpdateOrganizationActionBuilder::new()
+            .with_org_id("organization".to_string())
+            .with_detach_from_parent(true)
             .build()
             .unwrap();
 
         let bytes = original.clone().into_bytes().unwrap();
         let org = UpdateOrganizationAction::from_bytes(&bytes).unwrap();
+        assert!(org.detach_from_parent());
         assert_eq!(org, original);
     }
 
@@ -1398,4 +2593,297 @@ mod tests {
         let payload = PikePayload::from_bytes(&bytes).unwrap();
         assert_eq!(payload, original);
     }
+
+    #[test]
+    // check that creating an organization reads and writes the organization and the agent of
+    // the signer, who becomes its admin
+    fn check_create_organization_addresses() {
+        let builder = CreateOrganizationActionBuilder::new();
+        let action = builder
+            .with_org_id("organization".to_string())
+            .with_name("name".to_string())
+            .with_address("address".to_string())
+            .build()
+            .unwrap();
+
+        let builder = PikePayloadBuilder::new();
+        let payload = builder
+            .with_action(Action::CreateOrganization)
+            .with_create_organization(action)
+            .build()
+            .unwrap();
+
+        let mut addresses = vec![
+            compute_agent_address("signer"),
+            compute_org_address("organization"),
+        ];
+        addresses.sort();
+        assert_eq!(payload.inputs("signer"), addresses);
+        assert_eq!(payload.outputs("signer"), addresses);
+
+        // The admins of the parent organization administer the new one
+        let action = CreateOrganizationActionBuilder::new()
+            .with_org_id("organization".to_string())
+            .with_name("name".to_string())
+            .with_address("address".to_string())
+            .with_parent_org_id("parent".to_string())
+            .build()
+            .unwrap();
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::CreateOrganization)
+            .with_create_organization(action)
+            .build()
+            .unwrap();
+
+        let mut inputs = vec![compute_agent_address("signer"), PIKE_ORG_PREFIX.to_string()];
+        inputs.sort();
+        assert_eq!(payload.inputs("signer"), inputs);
+        assert_eq!(
+            payload.outputs("signer"),
+            vec![compute_org_address("organization")]
+        );
+    }
+
+    #[test]
+    // check that organization payloads declare the index entries of their alternate IDs
+    fn check_organization_alternate_id_addresses() {
+        let action = CreateOrganizationActionBuilder::new()
+            .with_org_id("organization".to_string())
+            .with_name("name".to_string())
+            .with_address("address".to_string())
+            .with_alternate_ids(vec!["gln:0614141000012".parse().unwrap()])
+            .build()
+            .unwrap();
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::CreateOrganization)
+            .with_create_organization(action)
+            .build()
+            .unwrap();
+
+        let addresses = vec![
+            compute_agent_address("signer"),
+            compute_org_address("organization"),
+            compute_alternate_id_address("gln", "0614141000012"),
+        ];
+        assert_eq!(payload.inputs("signer"), addresses);
+        assert_eq!(payload.outputs("signer"), addresses);
+
+        // The index entries of the IDs being replaced are only known from state
+        let action = UpdateOrganizationActionBuilder::new()
+            .with_org_id("organization".to_string())
+            .with_name("name".to_string())
+            .with_address("address".to_string())
+            .with_alternate_ids(vec!["duns:150483782".parse().unwrap()])
+            .build()
+            .unwrap();
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::UpdateOrganization)
+            .with_update_organization(action)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            payload.inputs("signer"),
+            vec![
+                compute_agent_address("signer"),
+                PIKE_ORG_PREFIX.to_string(),
+                PIKE_ALTERNATE_ID_PREFIX.to_string(),
+            ]
+        );
+        assert_eq!(
+            payload.outputs("signer"),
+            vec![
+                compute_org_address("organization"),
+                PIKE_ALTERNATE_ID_PREFIX.to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    // check that updating an agent only writes the agent, but also reads the signer's agent
+    fn check_update_agent_addresses() {
+        let builder = UpdateAgentActionBuilder::new();
+        let action = builder
+            .with_org_id("organization".to_string())
+            .with_public_key("public_key".to_string())
+            .build()
+            .unwrap();
+
+        let builder = PikePayloadBuilder::new();
+        let payload = builder
+            .with_action(Action::UpdateAgent)
+            .with_update_agent(action)
+            .build()
+            .unwrap();
+
+        assert_eq!(payload.inputs("signer").len(), 3);
+        assert!(payload
+            .inputs("signer")
+            .contains(&compute_agent_address("signer")));
+        assert_eq!(
+            payload.outputs("signer"),
+            vec![compute_agent_address("public_key")]
+        );
+        let mut inputs = vec![
+            compute_agent_address("public_key"),
+            PIKE_ORG_PREFIX.to_string(),
+        ];
+        inputs.sort();
+        assert_eq!(payload.inputs("public_key"), inputs);
+    }
+
+    #[test]
+    // check that a transfer_agent can be converted to bytes and back
+    fn check_transfer_agent_bytes() {
+        let builder = TransferAgentActionBuilder::new();
+        let original = builder
+            .with_public_key("public_key".to_string())
+            .with_org_id("organization".to_string())
+            .build()
+            .unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+        let transfer_agent = TransferAgentAction::from_bytes(&bytes).unwrap();
+        assert_eq!(transfer_agent, original);
+        assert!(TransferAgentActionBuilder::new()
+            .with_public_key("public_key".to_string())
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    // check that transferring an agent reads the organizations and writes the agent
+    fn check_transfer_agent_addresses() {
+        let action = TransferAgentActionBuilder::new()
+            .with_public_key("public_key".to_string())
+            .with_org_id("organization".to_string())
+            .build()
+            .unwrap();
+
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::TransferAgent)
+            .with_transfer_agent(action)
+            .build()
+            .unwrap();
+
+        let mut inputs = vec![
+            compute_agent_address("signer"),
+            compute_agent_address("public_key"),
+            PIKE_ORG_PREFIX.to_string(),
+        ];
+        inputs.sort();
+        assert_eq!(payload.inputs("signer"), inputs);
+        assert_eq!(
+            payload.outputs("signer"),
+            vec![compute_agent_address("public_key")]
+        );
+    }
+
+    #[test]
+    // check that a create_role can be converted to bytes and back
+    fn check_create_role_bytes() {
+        let builder = CreateRoleActionBuilder::new();
+        let original = builder
+            .with_org_id("organization".to_string())
+            .with_name("operator".to_string())
+            .with_description("description".to_string())
+            .with_permissions(vec!["tnt::reporter".to_string()])
+            .with_inherit_from(vec!["viewer".to_string()])
+            .build()
+            .unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+        let create_role = CreateRoleAction::from_bytes(&bytes).unwrap();
+        assert_eq!(create_role, original);
+    }
+
+    #[test]
+    // check that a delete_role can be converted to bytes and back
+    fn check_delete_role_bytes() {
+        let builder = DeleteRoleActionBuilder::new();
+        let original = builder
+            .with_org_id("organization".to_string())
+            .with_name("operator".to_string())
+            .build()
+            .unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+        let delete_role = DeleteRoleAction::from_bytes(&bytes).unwrap();
+        assert_eq!(delete_role, original);
+    }
+
+    #[test]
+    // check that role payloads declare the roles of the organization as inputs
+    fn check_role_addresses() {
+        let action = UpdateRoleActionBuilder::new()
+            .with_org_id("organization".to_string())
+            .with_name("operator".to_string())
+            .build()
+            .unwrap();
+
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::UpdateRole)
+            .with_update_role(action)
+            .build()
+            .unwrap();
+
+        let mut inputs = vec![
+            compute_agent_address("signer"),
+            compute_role_address_range("organization"),
+            PIKE_ORG_PREFIX.to_string(),
+        ];
+        inputs.sort();
+        assert_eq!(payload.inputs("signer"), inputs);
+        assert_eq!(
+            payload.outputs("signer"),
+            vec![compute_role_address("organization", "operator")]
+        );
+    }
+
+    #[test]
+    // check that a rotate_agent_key can be converted to bytes and back, and that it writes
+    // both agents, or any agent when an admin may override a former rotation
+    fn check_rotate_agent_key() {
+        let original = RotateAgentKeyActionBuilder::new()
+            .with_public_key("public_key".to_string())
+            .with_new_public_key("new_public_key".to_string())
+            .build()
+            .unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+        let rotate_agent_key = RotateAgentKeyAction::from_bytes(&bytes).unwrap();
+        assert_eq!(rotate_agent_key, original);
+        assert!(RotateAgentKeyActionBuilder::new()
+            .with_public_key("public_key".to_string())
+            .build()
+            .is_err());
+
+        let payload = PikePayloadBuilder::new()
+            .with_action(Action::RotateAgentKey)
+            .with_rotate_agent_key(original)
+            .build()
+            .unwrap();
+
+        let mut agents = vec![
+            compute_agent_address("public_key"),
+            compute_agent_address("new_public_key"),
+        ];
+        agents.sort();
+        assert_eq!(payload.inputs("public_key"), agents);
+        assert_eq!(
+            payload.outputs("public_key"),
+            vec![
+                compute_agent_address("public_key"),
+                compute_agent_address("new_public_key"),
+            ]
+        );
+        assert_eq!(
+            payload.inputs("signer"),
+            vec![PIKE_AGENT_PREFIX.to_string(), PIKE_ORG_PREFIX.to_string()]
+        );
+        assert_eq!(
+            payload.outputs("signer"),
+            vec![PIKE_AGENT_PREFIX.to_string()]
+        );
+    }
 }
@@ -14,16 +14,29 @@
 
 use protobuf::Message;
 use protobuf::RepeatedField;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use std::error::Error as StdError;
 
+use crate::protocol::versioning::{migrate_state, STATE_VERSION};
 use crate::protos;
 use crate::protos::{
     FromBytes, FromNative, FromProto, IntoBytes, IntoNative, IntoProto, ProtoConversionError,
 };
 
+/// Agent metadata key of the IDs of the organizations the agent was
+/// transferred from, comma-separated and oldest first
+pub const ORG_HISTORY_KEY: &str = "org_history";
+/// Agent metadata key of the organization the agent has a pending transfer to
+pub const TRANSFER_ORG_ID_KEY: &str = "transfer_org_id";
+/// Agent metadata key of the organization whose admin approved the pending
+/// transfer of the agent
+pub const TRANSFER_APPROVED_BY_KEY: &str = "transfer_approved_by";
+
 /// Native implementation for KeyValueEntry
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct KeyValueEntry {
     key: String,
     value: String,
@@ -152,12 +165,14 @@ impl KeyValueEntryBuilder {
 
 /// Native implementation of Agent
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Agent {
     org_id: String,
     public_key: String,
     active: bool,
     roles: Vec<String>,
     metadata: Vec<KeyValueEntry>,
+    successor: String,
 }
 
 impl Agent {
@@ -180,6 +195,11 @@ impl Agent {
     pub fn metadata(&self) -> &[KeyValueEntry] {
         &self.metadata
     }
+
+    /// The public key this agent's key was rotated to, or an empty string
+    pub fn successor(&self) -> &str {
+        &self.successor
+    }
 }
 
 impl FromProto<protos::pike_state::Agent> for Agent {
@@ -195,6 +215,7 @@ impl FromProto<protos::pike_state::Agent> for Agent {
                 .into_iter()
                 .map(KeyValueEntry::from_proto)
                 .collect::<Result<Vec<KeyValueEntry>, ProtoConversionError>>()?,
+            successor: agent.get_successor().to_string(),
         })
     }
 }
@@ -217,6 +238,7 @@ impl FromNative<Agent> for protos::pike_state::Agent {
                 .collect::<Result<Vec<protos::pike_state::KeyValueEntry>, ProtoConversionError>>(
                 )?,
         ));
+        agent_proto.set_successor(agent.successor().to_string());
 
         Ok(agent_proto)
     }
@@ -279,6 +301,7 @@ pub struct AgentBuilder {
     pub active: Option<bool>,
     pub roles: Vec<String>,
     pub metadata: Vec<KeyValueEntry>,
+    pub successor: Option<String>,
 }
 
 impl AgentBuilder {
@@ -311,6 +334,11 @@ impl AgentBuilder {
         self
     }
 
+    pub fn with_successor(mut self, successor: String) -> AgentBuilder {
+        self.successor = Some(successor);
+        self
+    }
+
     pub fn build(self) -> Result<Agent, AgentBuildError> {
         let org_id = self.org_id.ok_or_else(|| {
             AgentBuildError::MissingField("'org_id' field is required".to_string())
@@ -323,6 +351,7 @@ impl AgentBuilder {
         let active = self.active.unwrap_or_default();
         let roles = self.roles;
         let metadata = self.metadata;
+        let successor = self.successor.unwrap_or_default();
 
         Ok(Agent {
             org_id,
@@ -330,12 +359,14 @@ impl AgentBuilder {
             active,
             roles,
             metadata,
+            successor,
         })
     }
 }
 
 /// Native implementation of AgentList
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AgentList {
     agents: Vec<Agent>,
 }
@@ -372,14 +403,17 @@ impl FromNative<AgentList> for protos::pike_state::AgentList {
                 .collect::<Result<Vec<protos::pike_state::Agent>, ProtoConversionError>>()?,
         ));
 
+        agent_list_proto.set_version(STATE_VERSION);
+
         Ok(agent_list_proto)
     }
 }
 
 impl FromBytes<AgentList> for AgentList {
     fn from_bytes(bytes: &[u8]) -> Result<AgentList, ProtoConversionError> {
+        let bytes = migrate_state(bytes)?;
         let proto: protos::pike_state::AgentList =
-            protobuf::parse_from_bytes(bytes).map_err(|_| {
+            protobuf::parse_from_bytes(&bytes).map_err(|_| {
                 ProtoConversionError::SerializationError(
                     "Unable to get AgentList from bytes".to_string(),
                 )
@@ -463,11 +497,14 @@ impl AgentListBuilder {
 
 /// Native implementation for Organization
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Organization {
     org_id: String,
     name: String,
     address: String,
     metadata: Vec<KeyValueEntry>,
+    parent_org_id: String,
+    alternate_ids: Vec<AlternateId>,
 }
 
 impl Organization {
@@ -486,6 +523,16 @@ impl Organization {
     pub fn metadata(&self) -> &[KeyValueEntry] {
         &self.metadata
     }
+
+    /// The organization this organization is part of, or an empty string
+    pub fn parent_org_id(&self) -> &str {
+        &self.parent_org_id
+    }
+
+    /// The identifiers of the organization in other identification schemes
+    pub fn alternate_ids(&self) -> &[AlternateId] {
+        &self.alternate_ids
+    }
 }
 
 impl FromProto<protos::pike_state::Organization> for Organization {
@@ -500,6 +547,13 @@ impl FromProto<protos::pike_state::Organization> for Organization {
                 .into_iter()
                 .map(KeyValueEntry::from_proto)
                 .collect::<Result<Vec<KeyValueEntry>, ProtoConversionError>>()?,
+            parent_org_id: org.get_parent_org_id().to_string(),
+            alternate_ids: org
+                .get_alternate_ids()
+                .to_vec()
+                .into_iter()
+                .map(AlternateId::from_proto)
+                .collect::<Result<Vec<AlternateId>, ProtoConversionError>>()?,
         })
     }
 }
@@ -519,6 +573,14 @@ impl FromNative<Organization> for protos::pike_state::Organization {
                 .collect::<Result<Vec<protos::pike_state::KeyValueEntry>, ProtoConversionError>>(
                 )?,
         ));
+        org_proto.set_parent_org_id(org.parent_org_id().to_string());
+        org_proto.set_alternate_ids(RepeatedField::from_vec(
+            org.alternate_ids()
+                .to_vec()
+                .into_iter()
+                .map(AlternateId::into_proto)
+                .collect::<Result<Vec<protos::pike_state::AlternateId>, ProtoConversionError>>()?,
+        ));
 
         Ok(org_proto)
     }
@@ -585,6 +647,8 @@ pub struct OrganizationBuilder {
     pub name: Option<String>,
     pub address: Option<String>,
     pub metadata: Vec<KeyValueEntry>,
+    pub parent_org_id: Option<String>,
+    pub alternate_ids: Vec<AlternateId>,
 }
 
 impl OrganizationBuilder {
@@ -612,6 +676,16 @@ impl OrganizationBuilder {
         self
     }
 
+    pub fn with_parent_org_id(mut self, parent_org_id: String) -> OrganizationBuilder {
+        self.parent_org_id = Some(parent_org_id);
+        self
+    }
+
+    pub fn with_alternate_ids(mut self, alternate_ids: Vec<AlternateId>) -> OrganizationBuilder {
+        self.alternate_ids = alternate_ids;
+        self
+    }
+
     pub fn build(self) -> Result<Organization, OrganizationBuildError> {
         let org_id = self.org_id.ok_or_else(|| {
             OrganizationBuildError::MissingField("'org_id' field is required".to_string())
@@ -627,17 +701,241 @@ impl OrganizationBuilder {
 
         let metadata = self.metadata;
 
+        let parent_org_id = self.parent_org_id.unwrap_or_default();
+
         Ok(Organization {
             org_id,
             name,
             address,
             metadata,
+            parent_org_id,
+            alternate_ids: self.alternate_ids,
+        })
+    }
+}
+
+/// The type of the GS1 Global Location Numbers, checked by `AlternateId::validate`
+pub const GLN_ID_TYPE: &str = "gln";
+/// The type of the Dun & Bradstreet DUNS numbers, checked by `AlternateId::validate`
+pub const DUNS_ID_TYPE: &str = "duns";
+
+/// Native implementation for AlternateId
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlternateId {
+    id_type: String,
+    id: String,
+}
+
+impl AlternateId {
+    /// The identification scheme, such as `gln`, `duns` or `tax`
+    pub fn id_type(&self) -> &str {
+        &self.id_type
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Checks the format of the ID. The type must not be empty nor contain a
+    /// colon. A GLN must be 13 digits ending with its GS1 check digit, and a
+    /// DUNS number 9 digits; IDs of other types are only required to be set.
+    pub fn validate(&self) -> Result<(), AlternateIdBuildError> {
+        if self.id_type.is_empty() || self.id_type.contains(':') {
+            return Err(AlternateIdBuildError::InvalidId(format!(
+                "Invalid alternate ID type: {:?}",
+                self.id_type
+            )));
+        }
+        if self.id.is_empty() {
+            return Err(AlternateIdBuildError::InvalidId(format!(
+                "Empty {} alternate ID",
+                self.id_type
+            )));
+        }
+
+        let all_digits = self.id.chars().all(|c| c.is_ascii_digit());
+        match self.id_type.as_str() {
+            GLN_ID_TYPE if !all_digits || self.id.len() != 13 => Err(
+                AlternateIdBuildError::InvalidId(format!("A GLN must be 13 digits: {}", self.id)),
+            ),
+            GLN_ID_TYPE if !has_gs1_check_digit(&self.id) => Err(AlternateIdBuildError::InvalidId(
+                format!("Invalid GLN check digit: {}", self.id),
+            )),
+            DUNS_ID_TYPE if !all_digits || self.id.len() != 9 => {
+                Err(AlternateIdBuildError::InvalidId(format!(
+                    "A DUNS number must be 9 digits: {}",
+                    self.id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Checks the last digit of a GS1 key against the others, weighted 3 and 1
+/// alternately from the right
+fn has_gs1_check_digit(digits: &str) -> bool {
+    let digits: Vec<u32> = digits.chars().filter_map(|c| c.to_digit(10)).collect();
+    let (check_digit, body) = match digits.split_last() {
+        Some(split) => split,
+        None => return false,
+    };
+    let sum: u32 = body
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, digit)| if i % 2 == 0 { digit * 3 } else { *digit })
+        .sum();
+    (10 - sum % 10) % 10 == *check_digit
+}
+
+/// Formats the ID as `id_type:id`, the form it is looked up with
+impl std::fmt::Display for AlternateId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.id_type, self.id)
+    }
+}
+
+/// Parses an ID of the form `id_type:id`, such as `gln:0614141000012`
+impl std::str::FromStr for AlternateId {
+    type Err = AlternateIdBuildError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, ':');
+        match (parts.next(), parts.next()) {
+            (Some(id_type), Some(id)) => AlternateIdBuilder::new()
+                .with_id_type(id_type.to_string())
+                .with_id(id.to_string())
+                .build(),
+            _ => Err(AlternateIdBuildError::InvalidId(format!(
+                "Alternate ID must be of the form type:id: {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl FromProto<protos::pike_state::AlternateId> for AlternateId {
+    fn from_proto(
+        alternate_id: protos::pike_state::AlternateId,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(AlternateId {
+            id_type: alternate_id.get_id_type().to_string(),
+            id: alternate_id.get_id().to_string(),
         })
     }
 }
 
+impl FromNative<AlternateId> for protos::pike_state::AlternateId {
+    fn from_native(alternate_id: AlternateId) -> Result<Self, ProtoConversionError> {
+        let mut alternate_id_proto = protos::pike_state::AlternateId::new();
+
+        alternate_id_proto.set_id_type(alternate_id.id_type().to_string());
+        alternate_id_proto.set_id(alternate_id.id().to_string());
+
+        Ok(alternate_id_proto)
+    }
+}
+
+impl FromBytes<AlternateId> for AlternateId {
+    fn from_bytes(bytes: &[u8]) -> Result<AlternateId, ProtoConversionError> {
+        let proto: protos::pike_state::AlternateId =
+            protobuf::parse_from_bytes(bytes).map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get AlternateId from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for AlternateId {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from AlternateId".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_state::AlternateId> for AlternateId {}
+impl IntoNative<AlternateId> for protos::pike_state::AlternateId {}
+
+#[derive(Debug)]
+pub enum AlternateIdBuildError {
+    MissingField(String),
+    InvalidId(String),
+}
+
+impl StdError for AlternateIdBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            AlternateIdBuildError::MissingField(ref msg) => msg,
+            AlternateIdBuildError::InvalidId(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            AlternateIdBuildError::MissingField(_) => None,
+            AlternateIdBuildError::InvalidId(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for AlternateIdBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            AlternateIdBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+            AlternateIdBuildError::InvalidId(ref s) => write!(f, "InvalidId: {}", s),
+        }
+    }
+}
+
+/// Builder used to create an AlternateId, whose format is validated
+#[derive(Default, Clone)]
+pub struct AlternateIdBuilder {
+    pub id_type: Option<String>,
+    pub id: Option<String>,
+}
+
+impl AlternateIdBuilder {
+    pub fn new() -> Self {
+        AlternateIdBuilder::default()
+    }
+
+    pub fn with_id_type(mut self, id_type: String) -> AlternateIdBuilder {
+        self.id_type = Some(id_type);
+        self
+    }
+
+    pub fn with_id(mut self, id: String) -> AlternateIdBuilder {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn build(self) -> Result<AlternateId, AlternateIdBuildError> {
+        let id_type = self.id_type.ok_or_else(|| {
+            AlternateIdBuildError::MissingField("'id_type' field is required".to_string())
+        })?;
+
+        let id = self.id.ok_or_else(|| {
+            AlternateIdBuildError::MissingField("'id' field is required".to_string())
+        })?;
+
+        let alternate_id = AlternateId { id_type, id };
+        alternate_id.validate()?;
+        Ok(alternate_id)
+    }
+}
+
 /// Native implementation of OrganizationList
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OrganizationList {
     organizations: Vec<Organization>,
 }
@@ -676,13 +974,16 @@ impl FromNative<OrganizationList> for protos::pike_state::OrganizationList {
                 .collect::<Result<Vec<protos::pike_state::Organization>, ProtoConversionError>>()?,
         ));
 
+        org_list_proto.set_version(STATE_VERSION);
+
         Ok(org_list_proto)
     }
 }
 
 impl FromBytes<OrganizationList> for OrganizationList {
     fn from_bytes(bytes: &[u8]) -> Result<OrganizationList, ProtoConversionError> {
-        let proto: protos::pike_state::OrganizationList = protobuf::parse_from_bytes(bytes)
+        let bytes = migrate_state(bytes)?;
+        let proto: protos::pike_state::OrganizationList = protobuf::parse_from_bytes(&bytes)
             .map_err(|_| {
                 ProtoConversionError::SerializationError(
                     "Unable to get OrganizationList from bytes".to_string(),
@@ -768,6 +1069,593 @@ impl OrganizationListBuilder {
     }
 }
 
+/// Native implementation for Role
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Role {
+    org_id: String,
+    name: String,
+    description: String,
+    permissions: Vec<String>,
+    inherit_from: Vec<String>,
+}
+
+impl Role {
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn permissions(&self) -> &[String] {
+        &self.permissions
+    }
+
+    /// The roles of the same organization whose permissions this role also
+    /// grants
+    pub fn inherit_from(&self) -> &[String] {
+        &self.inherit_from
+    }
+}
+
+impl FromProto<protos::pike_state::Role> for Role {
+    fn from_proto(role: protos::pike_state::Role) -> Result<Self, ProtoConversionError> {
+        Ok(Role {
+            org_id: role.get_org_id().to_string(),
+            name: role.get_name().to_string(),
+            description: role.get_description().to_string(),
+            permissions: role.get_permissions().to_vec(),
+            inherit_from: role.get_inherit_from().to_vec(),
+        })
+    }
+}
+
+impl FromNative<Role> for protos::pike_state::Role {
+    fn from_native(role: Role) -> Result<Self, ProtoConversionError> {
+        let mut role_proto = protos::pike_state::Role::new();
+
+        role_proto.set_org_id(role.org_id().to_string());
+        role_proto.set_name(role.name().to_string());
+        role_proto.set_description(role.description().to_string());
+        role_proto.set_permissions(RepeatedField::from_vec(role.permissions().to_vec()));
+        role_proto.set_inherit_from(RepeatedField::from_vec(role.inherit_from().to_vec()));
+
+        Ok(role_proto)
+    }
+}
+
+impl FromBytes<Role> for Role {
+    fn from_bytes(bytes: &[u8]) -> Result<Role, ProtoConversionError> {
+        let proto: protos::pike_state::Role = protobuf::parse_from_bytes(bytes).map_err(|_| {
+            ProtoConversionError::SerializationError("Unable to get Role from bytes".to_string())
+        })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for Role {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError("Unable to get bytes from Role".to_string())
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_state::Role> for Role {}
+impl IntoNative<Role> for protos::pike_state::Role {}
+
+#[derive(Debug)]
+pub enum RoleBuildError {
+    MissingField(String),
+}
+
+impl StdError for RoleBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            RoleBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            RoleBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RoleBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            RoleBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a Role
+#[derive(Default, Clone)]
+pub struct RoleBuilder {
+    pub org_id: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+    pub inherit_from: Vec<String>,
+}
+
+impl RoleBuilder {
+    pub fn new() -> Self {
+        RoleBuilder::default()
+    }
+
+    pub fn with_org_id(mut self, org_id: String) -> RoleBuilder {
+        self.org_id = Some(org_id);
+        self
+    }
+
+    pub fn with_name(mut self, name: String) -> RoleBuilder {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn with_description(mut self, description: String) -> RoleBuilder {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_permissions(mut self, permissions: Vec<String>) -> RoleBuilder {
+        self.permissions = permissions;
+        self
+    }
+
+    pub fn with_inherit_from(mut self, inherit_from: Vec<String>) -> RoleBuilder {
+        self.inherit_from = inherit_from;
+        self
+    }
+
+    pub fn build(self) -> Result<Role, RoleBuildError> {
+        let org_id = self.org_id.ok_or_else(|| {
+            RoleBuildError::MissingField("'org_id' field is required".to_string())
+        })?;
+
+        let name = self
+            .name
+            .ok_or_else(|| RoleBuildError::MissingField("'name' field is required".to_string()))?;
+
+        let description = self.description.unwrap_or_default();
+
+        Ok(Role {
+            org_id,
+            name,
+            description,
+            permissions: self.permissions,
+            inherit_from: self.inherit_from,
+        })
+    }
+}
+
+/// Native implementation of RoleList
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RoleList {
+    roles: Vec<Role>,
+}
+
+impl RoleList {
+    pub fn roles(&self) -> &[Role] {
+        &self.roles
+    }
+}
+
+impl FromProto<protos::pike_state::RoleList> for RoleList {
+    fn from_proto(role_list: protos::pike_state::RoleList) -> Result<Self, ProtoConversionError> {
+        Ok(RoleList {
+            roles: role_list
+                .get_roles()
+                .to_vec()
+                .into_iter()
+                .map(Role::from_proto)
+                .collect::<Result<Vec<Role>, ProtoConversionError>>()?,
+        })
+    }
+}
+
+impl FromNative<RoleList> for protos::pike_state::RoleList {
+    fn from_native(role_list: RoleList) -> Result<Self, ProtoConversionError> {
+        let mut role_list_proto = protos::pike_state::RoleList::new();
+
+        role_list_proto.set_roles(RepeatedField::from_vec(
+            role_list
+                .roles()
+                .to_vec()
+                .into_iter()
+                .map(Role::into_proto)
+                .collect::<Result<Vec<protos::pike_state::Role>, ProtoConversionError>>()?,
+        ));
+
+        role_list_proto.set_version(STATE_VERSION);
+
+        Ok(role_list_proto)
+    }
+}
+
+impl FromBytes<RoleList> for RoleList {
+    fn from_bytes(bytes: &[u8]) -> Result<RoleList, ProtoConversionError> {
+        let bytes = migrate_state(bytes)?;
+        let proto: protos::pike_state::RoleList =
+            protobuf::parse_from_bytes(&bytes).map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get RoleList from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for RoleList {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from RoleList".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_state::RoleList> for RoleList {}
+impl IntoNative<RoleList> for protos::pike_state::RoleList {}
+
+#[derive(Debug)]
+pub enum RoleListBuildError {
+    MissingField(String),
+}
+
+impl StdError for RoleListBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            RoleListBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            RoleListBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RoleListBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            RoleListBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a RoleList
+#[derive(Default, Clone)]
+pub struct RoleListBuilder {
+    pub roles: Vec<Role>,
+}
+
+impl RoleListBuilder {
+    pub fn new() -> Self {
+        RoleListBuilder::default()
+    }
+
+    pub fn with_roles(mut self, roles: Vec<Role>) -> RoleListBuilder {
+        self.roles = roles;
+        self
+    }
+
+    pub fn build(self) -> Result<RoleList, RoleListBuildError> {
+        let roles = {
+            if self.roles.is_empty() {
+                return Err(RoleListBuildError::MissingField(
+                    "'roles' cannot be empty".to_string(),
+                ));
+            } else {
+                self.roles
+            }
+        };
+
+        Ok(RoleList { roles })
+    }
+}
+
+/// Native implementation for AlternateIdIndex
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlternateIdIndex {
+    id_type: String,
+    id: String,
+    org_id: String,
+}
+
+impl AlternateIdIndex {
+    pub fn id_type(&self) -> &str {
+        &self.id_type
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The organization holding the alternate ID
+    pub fn org_id(&self) -> &str {
+        &self.org_id
+    }
+}
+
+impl FromProto<protos::pike_state::AlternateIdIndex> for AlternateIdIndex {
+    fn from_proto(
+        entry: protos::pike_state::AlternateIdIndex,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(AlternateIdIndex {
+            id_type: entry.get_id_type().to_string(),
+            id: entry.get_id().to_string(),
+            org_id: entry.get_org_id().to_string(),
+        })
+    }
+}
+
+impl FromNative<AlternateIdIndex> for protos::pike_state::AlternateIdIndex {
+    fn from_native(entry: AlternateIdIndex) -> Result<Self, ProtoConversionError> {
+        let mut entry_proto = protos::pike_state::AlternateIdIndex::new();
+
+        entry_proto.set_id_type(entry.id_type().to_string());
+        entry_proto.set_id(entry.id().to_string());
+        entry_proto.set_org_id(entry.org_id().to_string());
+
+        Ok(entry_proto)
+    }
+}
+
+impl FromBytes<AlternateIdIndex> for AlternateIdIndex {
+    fn from_bytes(bytes: &[u8]) -> Result<AlternateIdIndex, ProtoConversionError> {
+        let proto: protos::pike_state::AlternateIdIndex = protobuf::parse_from_bytes(bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get AlternateIdIndex from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for AlternateIdIndex {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from AlternateIdIndex".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_state::AlternateIdIndex> for AlternateIdIndex {}
+impl IntoNative<AlternateIdIndex> for protos::pike_state::AlternateIdIndex {}
+
+#[derive(Debug)]
+pub enum AlternateIdIndexBuildError {
+    MissingField(String),
+}
+
+impl StdError for AlternateIdIndexBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            AlternateIdIndexBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            AlternateIdIndexBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for AlternateIdIndexBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            AlternateIdIndexBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create an AlternateIdIndex
+#[derive(Default, Clone)]
+pub struct AlternateIdIndexBuilder {
+    pub id_type: Option<String>,
+    pub id: Option<String>,
+    pub org_id: Option<String>,
+}
+
+impl AlternateIdIndexBuilder {
+    pub fn new() -> Self {
+        AlternateIdIndexBuilder::default()
+    }
+
+    pub fn with_id_type(mut self, id_type: String) -> AlternateIdIndexBuilder {
+        self.id_type = Some(id_type);
+        self
+    }
+
+    pub fn with_id(mut self, id: String) -> AlternateIdIndexBuilder {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn with_org_id(mut self, org_id: String) -> AlternateIdIndexBuilder {
+        self.org_id = Some(org_id);
+        self
+    }
+
+    pub fn build(self) -> Result<AlternateIdIndex, AlternateIdIndexBuildError> {
+        let id_type = self.id_type.ok_or_else(|| {
+            AlternateIdIndexBuildError::MissingField("'id_type' field is required".to_string())
+        })?;
+
+        let id = self.id.ok_or_else(|| {
+            AlternateIdIndexBuildError::MissingField("'id' field is required".to_string())
+        })?;
+
+        let org_id = self.org_id.ok_or_else(|| {
+            AlternateIdIndexBuildError::MissingField("'org_id' field is required".to_string())
+        })?;
+
+        Ok(AlternateIdIndex {
+            id_type,
+            id,
+            org_id,
+        })
+    }
+}
+
+/// Native implementation of AlternateIdIndexList
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AlternateIdIndexList {
+    entries: Vec<AlternateIdIndex>,
+}
+
+impl AlternateIdIndexList {
+    pub fn entries(&self) -> &[AlternateIdIndex] {
+        &self.entries
+    }
+}
+
+impl FromProto<protos::pike_state::AlternateIdIndexList> for AlternateIdIndexList {
+    fn from_proto(
+        entry_list: protos::pike_state::AlternateIdIndexList,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(AlternateIdIndexList {
+            entries: entry_list
+                .get_entries()
+                .to_vec()
+                .into_iter()
+                .map(AlternateIdIndex::from_proto)
+                .collect::<Result<Vec<AlternateIdIndex>, ProtoConversionError>>()?,
+        })
+    }
+}
+
+impl FromNative<AlternateIdIndexList> for protos::pike_state::AlternateIdIndexList {
+    fn from_native(entry_list: AlternateIdIndexList) -> Result<Self, ProtoConversionError> {
+        let mut entry_list_proto = protos::pike_state::AlternateIdIndexList::new();
+
+        entry_list_proto.set_entries(RepeatedField::from_vec(
+            entry_list
+                .entries()
+                .to_vec()
+                .into_iter()
+                .map(AlternateIdIndex::into_proto)
+                .collect::<Result<Vec<protos::pike_state::AlternateIdIndex>, ProtoConversionError>>(
+                )?,
+        ));
+
+        entry_list_proto.set_version(STATE_VERSION);
+
+        Ok(entry_list_proto)
+    }
+}
+
+impl FromBytes<AlternateIdIndexList> for AlternateIdIndexList {
+    fn from_bytes(bytes: &[u8]) -> Result<AlternateIdIndexList, ProtoConversionError> {
+        let bytes = migrate_state(bytes)?;
+        let proto: protos::pike_state::AlternateIdIndexList = protobuf::parse_from_bytes(&bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get AlternateIdIndexList from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for AlternateIdIndexList {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from AlternateIdIndexList".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::pike_state::AlternateIdIndexList> for AlternateIdIndexList {}
+impl IntoNative<AlternateIdIndexList> for protos::pike_state::AlternateIdIndexList {}
+
+#[derive(Debug)]
+pub enum AlternateIdIndexListBuildError {
+    MissingField(String),
+}
+
+impl StdError for AlternateIdIndexListBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            AlternateIdIndexListBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            AlternateIdIndexListBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for AlternateIdIndexListBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            AlternateIdIndexListBuildError::MissingField(ref s) => {
+                write!(f, "MissingField: {}", s)
+            }
+        }
+    }
+}
+
+/// Builder used to create an AlternateIdIndexList
+#[derive(Default, Clone)]
+pub struct AlternateIdIndexListBuilder {
+    pub entries: Vec<AlternateIdIndex>,
+}
+
+impl AlternateIdIndexListBuilder {
+    pub fn new() -> Self {
+        AlternateIdIndexListBuilder::default()
+    }
+
+    pub fn with_entries(mut self, entries: Vec<AlternateIdIndex>) -> AlternateIdIndexListBuilder {
+        self.entries = entries;
+        self
+    }
+
+    pub fn build(self) -> Result<AlternateIdIndexList, AlternateIdIndexListBuildError> {
+        if self.entries.is_empty() {
+            return Err(AlternateIdIndexListBuildError::MissingField(
+                "'entries' cannot be empty".to_string(),
+            ));
+        }
+
+        Ok(AlternateIdIndexList {
+            entries: self.entries,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -826,6 +1714,7 @@ mod tests {
         assert!(agent.active());
         assert_eq!(agent.roles(), ["Role".to_string()]);
         assert_eq!(agent.metadata(), [key_value]);
+        assert_eq!(agent.successor(), "");
     }
 
     #[test]
@@ -845,6 +1734,7 @@ mod tests {
             .with_active(true)
             .with_roles(vec!["Role".to_string()])
             .with_metadata(vec![key_value.clone()])
+            .with_successor("new_public_key".to_string())
             .build()
             .unwrap();
 
@@ -930,6 +1820,8 @@ mod tests {
         assert_eq!(organization.name(), "name");
         assert_eq!(organization.address(), "address");
         assert_eq!(organization.metadata(), [key_value]);
+        assert_eq!(organization.parent_org_id(), "");
+        assert!(organization.alternate_ids().is_empty());
     }
 
     #[test]
@@ -948,6 +1840,8 @@ mod tests {
             .with_name("name".to_string())
             .with_address("address".to_string())
             .with_metadata(vec![key_value.clone()])
+            .with_parent_org_id("parent_organization".to_string())
+            .with_alternate_ids(vec!["gln:0614141000012".parse().unwrap()])
             .build()
             .unwrap();
 
@@ -1013,4 +1907,95 @@ mod tests {
         let org_list = OrganizationList::from_bytes(&bytes).unwrap();
         assert_eq!(org_list, original);
     }
+
+    #[test]
+    // check that a Role is built correctly
+    fn check_role_builder() {
+        let builder = RoleBuilder::new();
+        let role = builder
+            .with_org_id("organization".to_string())
+            .with_name("operator".to_string())
+            .with_description("description".to_string())
+            .with_permissions(vec!["tnt::reporter".to_string()])
+            .with_inherit_from(vec!["viewer".to_string()])
+            .build()
+            .unwrap();
+
+        assert_eq!(role.org_id(), "organization");
+        assert_eq!(role.name(), "operator");
+        assert_eq!(role.description(), "description");
+        assert_eq!(role.permissions(), ["tnt::reporter".to_string()]);
+        assert_eq!(role.inherit_from(), ["viewer".to_string()]);
+    }
+
+    #[test]
+    // check that a RoleList can be converted to bytes and back
+    fn check_role_list_bytes() {
+        let builder = RoleBuilder::new();
+        let role = builder
+            .with_org_id("organization".to_string())
+            .with_name("operator".to_string())
+            .with_permissions(vec!["tnt::reporter".to_string()])
+            .with_inherit_from(vec!["viewer".to_string()])
+            .build()
+            .unwrap();
+
+        let builder = RoleListBuilder::new();
+        let original = builder.with_roles(vec![role]).build().unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+        let role_list = RoleList::from_bytes(&bytes).unwrap();
+        assert_eq!(role_list, original);
+    }
+
+    #[test]
+    // check that alternate IDs are parsed from and formatted as type:id
+    fn check_alternate_id_from_str() {
+        let alternate_id: AlternateId = "gln:0614141000012".parse().unwrap();
+        assert_eq!(alternate_id.id_type(), GLN_ID_TYPE);
+        assert_eq!(alternate_id.id(), "0614141000012");
+        assert_eq!(alternate_id.to_string(), "gln:0614141000012");
+
+        let tax_id: AlternateId = "tax:US:12-3456789".parse().unwrap();
+        assert_eq!(tax_id.id_type(), "tax");
+        assert_eq!(tax_id.id(), "US:12-3456789");
+
+        assert!("0614141000012".parse::<AlternateId>().is_err());
+        assert!(":0614141000012".parse::<AlternateId>().is_err());
+        assert!("tax:".parse::<AlternateId>().is_err());
+    }
+
+    #[test]
+    // check that GLNs are checked against their check digit, and DUNS numbers for their length
+    fn check_alternate_id_validate() {
+        assert!("gln:0614141000012".parse::<AlternateId>().is_ok());
+        assert!("gln:4012345000009".parse::<AlternateId>().is_ok());
+        assert!("gln:0614141000013".parse::<AlternateId>().is_err());
+        assert!("gln:061414100001".parse::<AlternateId>().is_err());
+        assert!("gln:061414100001a".parse::<AlternateId>().is_err());
+
+        assert!("duns:150483782".parse::<AlternateId>().is_ok());
+        assert!("duns:15048378".parse::<AlternateId>().is_err());
+    }
+
+    #[test]
+    // check that an AlternateIdIndexList can be converted to bytes and back
+    fn check_alternate_id_index_list_bytes() {
+        let entry = AlternateIdIndexBuilder::new()
+            .with_id_type("gln".to_string())
+            .with_id("0614141000012".to_string())
+            .with_org_id("organization".to_string())
+            .build()
+            .unwrap();
+
+        let original = AlternateIdIndexListBuilder::new()
+            .with_entries(vec![entry])
+            .build()
+            .unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+        let entry_list = AlternateIdIndexList::from_bytes(&bytes).unwrap();
+        assert_eq!(entry_list, original);
+        assert!(AlternateIdIndexListBuilder::new().build().is_err());
+    }
 }
@@ -0,0 +1,516 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Serde encodings of PropertyValues.
+//!
+//! A `PropertyValue` serializes on its own with its name, its data type and
+//! only the field matching that data type:
+//!
+//! ```json
+//! {"name": "weight", "data_type": "NUMBER", "number_value": 1250}
+//! ```
+//!
+//! Given the `PropertyDefinition` of a value, `DefinedPropertyValue` and
+//! `PropertyValueSeed` use a human-friendly encoding instead: numbers are
+//! decimal strings scaled by the definition's `number_exponent`, enums are
+//! option names and structs are objects keyed by property name.
+//!
+//! ```json
+//! {"weight": "12.50", "mode": "Air", "origin": {"latitude": 44977753, "longitude": -93265015}}
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde::ser::{self, SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+
+use super::number::GridNumber;
+use super::state::{
+    DataType, LatLong, PropertyDefinition, PropertyValue, PropertyValueBuildError,
+    PropertyValueBuilder,
+};
+
+/// The serialized form of a PropertyValue, holding only the field of its
+/// data type
+#[derive(Serialize, Deserialize)]
+pub struct PropertyValueRepr {
+    name: String,
+    data_type: DataType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    bytes_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    boolean_value: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    number_value: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    string_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    enum_value: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    struct_values: Option<Vec<PropertyValue>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lat_long_value: Option<LatLong>,
+}
+
+impl From<PropertyValue> for PropertyValueRepr {
+    fn from(value: PropertyValue) -> Self {
+        let mut repr = PropertyValueRepr {
+            name: value.name().to_string(),
+            data_type: value.data_type().clone(),
+            bytes_value: None,
+            boolean_value: None,
+            number_value: None,
+            string_value: None,
+            enum_value: None,
+            struct_values: None,
+            lat_long_value: None,
+        };
+
+        match value.data_type() {
+            DataType::Bytes => repr.bytes_value = Some(to_hex(value.bytes_value())),
+            DataType::Boolean => repr.boolean_value = Some(*value.boolean_value()),
+            DataType::Number => repr.number_value = Some(*value.number_value()),
+            DataType::String => repr.string_value = Some(value.string_value().to_string()),
+            DataType::Enum => repr.enum_value = Some(*value.enum_value()),
+            DataType::Struct => repr.struct_values = Some(value.struct_values().to_vec()),
+            DataType::LatLong => repr.lat_long_value = Some(value.lat_long_value().clone()),
+        }
+
+        repr
+    }
+}
+
+impl TryFrom<PropertyValueRepr> for PropertyValue {
+    type Error = PropertyValueBuildError;
+
+    fn try_from(repr: PropertyValueRepr) -> Result<Self, Self::Error> {
+        let mut builder = PropertyValueBuilder::new()
+            .with_name(repr.name)
+            .with_data_type(repr.data_type);
+
+        if let Some(bytes) = repr.bytes_value {
+            builder = builder.with_bytes_value(from_hex(&bytes).ok_or_else(|| {
+                PropertyValueBuildError::MissingField(
+                    "'bytes_value' must be a hex string".to_string(),
+                )
+            })?);
+        }
+        if let Some(boolean) = repr.boolean_value {
+            builder = builder.with_boolean_value(boolean);
+        }
+        if let Some(number) = repr.number_value {
+            builder = builder.with_number_value(number);
+        }
+        if let Some(string) = repr.string_value {
+            builder = builder.with_string_value(string);
+        }
+        if let Some(enum_value) = repr.enum_value {
+            builder = builder.with_enum_value(enum_value);
+        }
+        if let Some(struct_values) = repr.struct_values {
+            builder = builder.with_struct_values(struct_values);
+        }
+        if let Some(lat_long) = repr.lat_long_value {
+            builder = builder.with_lat_long_value(lat_long);
+        }
+
+        builder.build()
+    }
+}
+
+/// Serializes a PropertyValue in the human-friendly encoding of its
+/// definition
+pub struct DefinedPropertyValue<'a> {
+    pub definition: &'a PropertyDefinition,
+    pub value: &'a PropertyValue,
+}
+
+impl<'a> Serialize for DefinedPropertyValue<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = self.value;
+        match value.data_type() {
+            DataType::Bytes => serializer.serialize_str(&to_hex(value.bytes_value())),
+            DataType::Boolean => serializer.serialize_bool(*value.boolean_value()),
+            DataType::Number => serializer
+                .serialize_str(&value.number(*self.definition.number_exponent()).to_string()),
+            DataType::String => serializer.serialize_str(value.string_value()),
+            DataType::Enum => {
+                match self
+                    .definition
+                    .enum_options()
+                    .get(*value.enum_value() as usize)
+                {
+                    Some(option) => serializer.serialize_str(option),
+                    None => Err(ser::Error::custom(format!(
+                        "Enum value {} of {} is not an option",
+                        value.enum_value(),
+                        value.name()
+                    ))),
+                }
+            }
+            DataType::Struct => DefinedPropertyValues {
+                definitions: self.definition.struct_properties(),
+                values: value.struct_values(),
+            }
+            .serialize(serializer),
+            DataType::LatLong => value.lat_long_value().serialize(serializer),
+        }
+    }
+}
+
+/// Serializes a set of PropertyValues in the human-friendly encoding, as an
+/// object keyed by property name
+pub struct DefinedPropertyValues<'a> {
+    pub definitions: &'a [PropertyDefinition],
+    pub values: &'a [PropertyValue],
+}
+
+impl<'a> Serialize for DefinedPropertyValues<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.values.len()))?;
+        for value in self.values {
+            let definition = self
+                .definitions
+                .iter()
+                .find(|definition| definition.name() == value.name())
+                .ok_or_else(|| {
+                    ser::Error::custom(format!("Property {} has no definition", value.name()))
+                })?;
+            map.serialize_entry(value.name(), &DefinedPropertyValue { definition, value })?;
+        }
+        map.end()
+    }
+}
+
+/// Deserializes a PropertyValue from the human-friendly encoding of its
+/// definition
+pub struct PropertyValueSeed<'a>(pub &'a PropertyDefinition);
+
+impl<'de, 'a> DeserializeSeed<'de> for PropertyValueSeed<'a> {
+    type Value = PropertyValue;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let definition = self.0;
+        let builder = PropertyValueBuilder::new()
+            .with_name(definition.name().to_string())
+            .with_data_type(definition.data_type().clone());
+
+        let builder = match definition.data_type() {
+            DataType::Bytes => {
+                let hex = String::deserialize(deserializer)?;
+                builder.with_bytes_value(from_hex(&hex).ok_or_else(|| {
+                    de::Error::custom(format!("{} must be a hex string", definition.name()))
+                })?)
+            }
+            DataType::Boolean => builder.with_boolean_value(bool::deserialize(deserializer)?),
+            DataType::Number => builder.with_number_value(
+                deserializer.deserialize_any(DecimalVisitor(*definition.number_exponent()))?,
+            ),
+            DataType::String => builder.with_string_value(String::deserialize(deserializer)?),
+            DataType::Enum => {
+                let option = String::deserialize(deserializer)?;
+                let index = definition
+                    .enum_options()
+                    .iter()
+                    .position(|enum_option| enum_option == &option)
+                    .ok_or_else(|| {
+                        de::Error::custom(format!(
+                            "{} is not an option of {}",
+                            option,
+                            definition.name()
+                        ))
+                    })?;
+                builder.with_enum_value(index as u32)
+            }
+            DataType::Struct => builder.with_struct_values(
+                PropertyValuesSeed(definition.struct_properties()).deserialize(deserializer)?,
+            ),
+            DataType::LatLong => builder.with_lat_long_value(LatLong::deserialize(deserializer)?),
+        };
+
+        builder.build().map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a set of PropertyValues from an object keyed by property
+/// name, in the human-friendly encoding of their definitions
+pub struct PropertyValuesSeed<'a>(pub &'a [PropertyDefinition]);
+
+impl<'de, 'a> DeserializeSeed<'de> for PropertyValuesSeed<'a> {
+    type Value = Vec<PropertyValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for PropertyValuesSeed<'a> {
+    type Value = Vec<PropertyValue>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of property names to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut values = vec![];
+        while let Some(name) = map.next_key::<String>()? {
+            let definition = self
+                .0
+                .iter()
+                .find(|definition| definition.name() == name)
+                .ok_or_else(|| de::Error::custom(format!("Property {} has no definition", name)))?;
+            values.push(map.next_value_seed(PropertyValueSeed(definition))?);
+        }
+        Ok(values)
+    }
+}
+
+struct DecimalVisitor(i32);
+
+impl<'de> Visitor<'de> for DecimalVisitor {
+    type Value = i64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a decimal string or an integer")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<i64, E> {
+        GridNumber::parse(value, self.0)
+            .map(|number| number.value())
+            .map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<i64, E> {
+        self.visit_str(&value.to_string())
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<i64, E> {
+        self.visit_str(&value.to_string())
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::schema::state::{PropertyDefinitionBuilder, Schema, SchemaBuilder};
+    use crate::protos::{FromBytes, IntoBytes};
+
+    fn lat_long(latitude: i64, longitude: i64) -> LatLong {
+        serde_json::from_value(serde_json::json!({
+            "latitude": latitude,
+            "longitude": longitude,
+        }))
+        .unwrap()
+    }
+
+    fn definition(name: &str, data_type: DataType) -> PropertyDefinitionBuilder {
+        PropertyDefinitionBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(data_type)
+    }
+
+    fn shipment_definition() -> PropertyDefinition {
+        definition("shipment", DataType::Struct)
+            .with_struct_properties(vec![
+                definition("weight", DataType::Number)
+                    .with_number_exponent(-2)
+                    .build()
+                    .unwrap(),
+                definition("mode", DataType::Enum)
+                    .with_enum_options(vec!["Air".to_string(), "Sea".to_string()])
+                    .build()
+                    .unwrap(),
+                definition("label", DataType::Bytes).build().unwrap(),
+                definition("fragile", DataType::Boolean).build().unwrap(),
+                definition("carrier", DataType::String).build().unwrap(),
+                definition("origin", DataType::LatLong).build().unwrap(),
+            ])
+            .build()
+            .unwrap()
+    }
+
+    fn shipment_value(weight: i64, mode: u32, label: Vec<u8>, fragile: bool) -> PropertyValue {
+        let value = |name: &str, data_type| {
+            PropertyValueBuilder::new()
+                .with_name(name.to_string())
+                .with_data_type(data_type)
+        };
+        value("shipment", DataType::Struct)
+            .with_struct_values(vec![
+                value("weight", DataType::Number)
+                    .with_number_value(weight)
+                    .build()
+                    .unwrap(),
+                value("mode", DataType::Enum)
+                    .with_enum_value(mode)
+                    .build()
+                    .unwrap(),
+                value("label", DataType::Bytes)
+                    .with_bytes_value(label)
+                    .build()
+                    .unwrap(),
+                value("fragile", DataType::Boolean)
+                    .with_boolean_value(fragile)
+                    .build()
+                    .unwrap(),
+                value("carrier", DataType::String)
+                    .with_string_value(format!("carrier {}", weight))
+                    .build()
+                    .unwrap(),
+                value("origin", DataType::LatLong)
+                    .with_lat_long_value(lat_long(weight, -weight))
+                    .build()
+                    .unwrap(),
+            ])
+            .build()
+            .unwrap()
+    }
+
+    fn shipment_values() -> Vec<PropertyValue> {
+        let mut values = vec![];
+        for weight in &[
+            0,
+            1,
+            -1,
+            99,
+            100,
+            -12345,
+            i64::max_value(),
+            i64::min_value(),
+        ] {
+            for mode in 0..2 {
+                for fragile in &[true, false] {
+                    let label = (0..(*weight as u8 % 7)).collect();
+                    values.push(shipment_value(*weight, mode, label, *fragile));
+                }
+            }
+        }
+        values
+    }
+
+    #[test]
+    // check that property values survive a JSON round trip and match their protobuf form
+    fn check_property_value_round_trip() {
+        for value in shipment_values() {
+            let json = serde_json::to_string(&value).unwrap();
+            let decoded: PropertyValue = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded, value);
+
+            let from_proto = PropertyValue::from_bytes(&value.clone().into_bytes().unwrap());
+            assert_eq!(serde_json::to_string(&from_proto.unwrap()).unwrap(), json);
+        }
+    }
+
+    #[test]
+    // check that property values survive a round trip through the human-friendly encoding
+    fn check_defined_property_value_round_trip() {
+        let definition = shipment_definition();
+        for value in shipment_values() {
+            let json = serde_json::to_value(&DefinedPropertyValue {
+                definition: &definition,
+                value: &value,
+            })
+            .unwrap();
+            let decoded = PropertyValueSeed(&definition).deserialize(json).unwrap();
+            assert_eq!(decoded, value);
+
+            let from_proto = PropertyValue::from_bytes(&decoded.into_bytes().unwrap()).unwrap();
+            assert_eq!(from_proto, value);
+        }
+    }
+
+    #[test]
+    // check that only the field of the data type is serialized
+    fn check_property_value_encoding() {
+        let value = PropertyValueBuilder::new()
+            .with_name("weight".to_string())
+            .with_data_type(DataType::Number)
+            .with_number_value(1250)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&value).unwrap(),
+            serde_json::json!({"name": "weight", "data_type": "NUMBER", "number_value": 1250})
+        );
+        assert!(serde_json::from_value::<PropertyValue>(
+            serde_json::json!({"name": "weight", "data_type": "NUMBER"})
+        )
+        .is_err());
+    }
+
+    #[test]
+    // check the human-friendly encoding of a struct value
+    fn check_defined_property_value_encoding() {
+        let definition = shipment_definition();
+        let value = shipment_value(1250, 1, vec![0xca, 0xfe], true);
+
+        assert_eq!(
+            serde_json::to_value(&DefinedPropertyValue {
+                definition: &definition,
+                value: &value,
+            })
+            .unwrap(),
+            serde_json::json!({
+                "weight": "12.50",
+                "mode": "Sea",
+                "label": "cafe",
+                "fragile": true,
+                "carrier": "carrier 1250",
+                "origin": {"latitude": 1250, "longitude": -1250},
+            })
+        );
+
+        let unknown_option = serde_json::json!({"mode": "Rail"});
+        assert!(PropertyValueSeed(&definition)
+            .deserialize(unknown_option)
+            .is_err());
+    }
+
+    #[test]
+    // check that a schema survives a JSON round trip and matches its protobuf form
+    fn check_schema_round_trip() {
+        let schema = SchemaBuilder::new()
+            .with_name("shipment".to_string())
+            .with_description("A shipment".to_string())
+            .with_owner("org_001".to_string())
+            .with_properties(vec![shipment_definition()])
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_string(&schema).unwrap();
+        let decoded: Schema = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, schema);
+
+        let from_proto = Schema::from_bytes(&schema.into_bytes().unwrap()).unwrap();
+        assert_eq!(serde_json::to_string(&from_proto).unwrap(), json);
+    }
+}
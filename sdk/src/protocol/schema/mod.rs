@@ -12,5 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "serde")]
+pub mod encoding;
+pub mod number;
 pub mod payload;
 pub mod state;
+pub mod validate;
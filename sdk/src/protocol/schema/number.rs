@@ -0,0 +1,521 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decimal values of NUMBER properties.
+//!
+//! A NUMBER `PropertyValue` only stores an integer, `number_value`, which is
+//! scaled by the `number_exponent` of its `PropertyDefinition`. A `GridNumber`
+//! carries both, so it can be converted, formatted, compared and computed with
+//! without losing track of the scale.
+
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumberError {
+    /// Returned when a string is not a decimal number
+    InvalidDecimal(String),
+    /// Returned when a number has more precision than an exponent allows
+    Inexact(String),
+    /// Returned when a number does not fit in a NUMBER value
+    Overflow(String),
+}
+
+impl StdError for NumberError {
+    fn description(&self) -> &str {
+        match *self {
+            NumberError::InvalidDecimal(ref msg) => msg,
+            NumberError::Inexact(ref msg) => msg,
+            NumberError::Overflow(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            NumberError::InvalidDecimal(_) => None,
+            NumberError::Inexact(_) => None,
+            NumberError::Overflow(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NumberError::InvalidDecimal(ref s) => write!(f, "InvalidDecimal: {}", s),
+            NumberError::Inexact(ref s) => write!(f, "Inexact: {}", s),
+            NumberError::Overflow(ref s) => write!(f, "Overflow: {}", s),
+        }
+    }
+}
+
+/// Exponents beyond which numbers are formatted in scientific notation, rather
+/// than padded with zeros
+const MAX_PLAIN_EXPONENT: i32 = 40;
+
+/// A decimal number, `value * 10^exponent`.
+///
+/// Numbers are equal when they denote the same decimal, whatever their
+/// exponents: `1250e-2 == 125e-1`.
+#[derive(Debug, Clone, Copy)]
+pub struct GridNumber {
+    value: i64,
+    exponent: i32,
+}
+
+impl GridNumber {
+    pub fn new(value: i64, exponent: i32) -> Self {
+        GridNumber { value, exponent }
+    }
+
+    /// The scaled integer, as stored in a NUMBER `PropertyValue`
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    pub fn exponent(&self) -> i32 {
+        self.exponent
+    }
+
+    /// Parses a decimal string, optionally in scientific notation such as
+    /// `"12e50"`, into a number with the given exponent.
+    ///
+    /// Fails if the decimal cannot be represented exactly with the exponent.
+    pub fn parse(decimal: &str, exponent: i32) -> Result<GridNumber, NumberError> {
+        let invalid = || NumberError::InvalidDecimal(format!("{} is not a valid decimal", decimal));
+        let overflow = || NumberError::Overflow(format!("{} is out of range", decimal));
+
+        let (mantissa, shift) = split_exponent(decimal).ok_or_else(invalid)?;
+        let (negative, unsigned) = match mantissa.get(..1) {
+            Some("-") => (true, &mantissa[1..]),
+            _ => (false, mantissa),
+        };
+        let mut parts = unsigned.splitn(2, '.');
+        let integer = parts.next().unwrap_or("");
+        let fraction = parts.next().unwrap_or("");
+        if (integer.is_empty() && fraction.is_empty())
+            || !integer
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let mut digits = integer.to_string();
+        let fraction = fraction.trim_end_matches('0');
+        digits.push_str(fraction);
+        let scale = i64::from(exponent) + fraction.len() as i64 - i64::from(shift);
+
+        // digits * 10^-(scale) must be a whole multiple of 10^exponent
+        let mut magnitude: i128 = if digits.trim_start_matches('0').is_empty() {
+            0
+        } else {
+            digits.parse().map_err(|_| overflow())?
+        };
+        if magnitude != 0 && scale > 0 {
+            match pow10(scale) {
+                Some(divisor) if magnitude % divisor == 0 => magnitude /= divisor,
+                _ => {
+                    return Err(NumberError::Inexact(format!(
+                        "{} has more precision than the exponent {} allows",
+                        decimal, exponent
+                    )))
+                }
+            }
+        } else if magnitude != 0 && scale < 0 {
+            magnitude = pow10(-scale)
+                .and_then(|factor| magnitude.checked_mul(factor))
+                .ok_or_else(overflow)?;
+        }
+
+        let signed = if negative { -magnitude } else { magnitude };
+        let value = i64::try_from(signed).map_err(|_| overflow())?;
+        Ok(GridNumber::new(value, exponent))
+    }
+
+    /// Converts a float into a number with the given exponent.
+    ///
+    /// The float is taken as the shortest decimal that converts back to it,
+    /// so `0.1` is exactly one tenth. Fails if that decimal cannot be
+    /// represented exactly with the exponent.
+    pub fn from_f64(float: f64, exponent: i32) -> Result<GridNumber, NumberError> {
+        if !float.is_finite() {
+            return Err(NumberError::InvalidDecimal(format!(
+                "{} is not a finite number",
+                float
+            )));
+        }
+        GridNumber::parse(&float.to_string(), exponent)
+    }
+
+    /// Returns the nearest float to the number
+    pub fn to_f64(&self) -> f64 {
+        // Dividing by an exact power of ten rounds better than multiplying by
+        // its inexact inverse
+        if self.exponent < 0 {
+            self.value as f64 / 10f64.powi(self.exponent.saturating_neg())
+        } else {
+            self.value as f64 * 10f64.powi(self.exponent)
+        }
+    }
+
+    /// Returns the same number with another exponent.
+    ///
+    /// Fails if the number has more precision than the exponent allows, or if
+    /// its value overflows.
+    pub fn rescale(&self, exponent: i32) -> Result<GridNumber, NumberError> {
+        let shift = i64::from(self.exponent) - i64::from(exponent);
+        let value = if self.value == 0 || shift == 0 {
+            self.value
+        } else if shift > 0 {
+            pow10(shift)
+                .and_then(|factor| i128::from(self.value).checked_mul(factor))
+                .and_then(|value| i64::try_from(value).ok())
+                .ok_or_else(|| {
+                    NumberError::Overflow(format!(
+                        "{} is out of range with the exponent {}",
+                        self, exponent
+                    ))
+                })?
+        } else {
+            match pow10(-shift) {
+                Some(divisor) if i128::from(self.value) % divisor == 0 => {
+                    (i128::from(self.value) / divisor) as i64
+                }
+                _ => {
+                    return Err(NumberError::Inexact(format!(
+                        "{} has more precision than the exponent {} allows",
+                        self, exponent
+                    )))
+                }
+            }
+        };
+        Ok(GridNumber::new(value, exponent))
+    }
+
+    pub fn checked_add(&self, other: &GridNumber) -> Result<GridNumber, NumberError> {
+        let (left, right) = self.align(other)?;
+        left.value
+            .checked_add(right.value)
+            .map(|value| GridNumber::new(value, left.exponent))
+            .ok_or_else(|| NumberError::Overflow(format!("{} + {} is out of range", self, other)))
+    }
+
+    pub fn checked_sub(&self, other: &GridNumber) -> Result<GridNumber, NumberError> {
+        let (left, right) = self.align(other)?;
+        left.value
+            .checked_sub(right.value)
+            .map(|value| GridNumber::new(value, left.exponent))
+            .ok_or_else(|| NumberError::Overflow(format!("{} - {} is out of range", self, other)))
+    }
+
+    pub fn checked_mul(&self, other: &GridNumber) -> Result<GridNumber, NumberError> {
+        let overflow = || NumberError::Overflow(format!("{} * {} is out of range", self, other));
+        let value = self.value.checked_mul(other.value).ok_or_else(overflow)?;
+        let exponent = self
+            .exponent
+            .checked_add(other.exponent)
+            .ok_or_else(overflow)?;
+        Ok(GridNumber::new(value, exponent))
+    }
+
+    pub fn checked_neg(&self) -> Result<GridNumber, NumberError> {
+        self.value
+            .checked_neg()
+            .map(|value| GridNumber::new(value, self.exponent))
+            .ok_or_else(|| NumberError::Overflow(format!("-({}) is out of range", self)))
+    }
+
+    /// Rescales both numbers to the smaller of their exponents
+    fn align(&self, other: &GridNumber) -> Result<(GridNumber, GridNumber), NumberError> {
+        let exponent = self.exponent.min(other.exponent);
+        Ok((self.rescale(exponent)?, other.rescale(exponent)?))
+    }
+
+    /// Returns the number with the largest exponent denoting the same decimal
+    fn normalize(&self) -> GridNumber {
+        if self.value == 0 {
+            return GridNumber::new(0, 0);
+        }
+        let mut normalized = *self;
+        while normalized.value % 10 == 0 && normalized.exponent < i32::max_value() {
+            normalized.value /= 10;
+            normalized.exponent += 1;
+        }
+        normalized
+    }
+}
+
+/// Splits a decimal in scientific notation into its mantissa and exponent. The
+/// exponent is 0 for a plain decimal, and None if it is malformed.
+fn split_exponent(decimal: &str) -> Option<(&str, i32)> {
+    match decimal.find(&['e', 'E'][..]) {
+        Some(index) => decimal[index + 1..]
+            .parse()
+            .ok()
+            .map(|exponent| (&decimal[..index], exponent)),
+        None => Some((decimal, 0)),
+    }
+}
+
+/// Returns 10^exponent, if it fits in an i128
+fn pow10(exponent: i64) -> Option<i128> {
+    u32::try_from(exponent)
+        .ok()
+        .and_then(|exponent| 10i128.checked_pow(exponent))
+}
+
+impl FromStr for GridNumber {
+    type Err = NumberError;
+
+    /// Parses a decimal string, with an exponent keeping every digit of its
+    /// fractional part: `"12.50"` is `1250e-2`, and `"1.5e3"` is `15e2`.
+    fn from_str(decimal: &str) -> Result<GridNumber, NumberError> {
+        let (mantissa, shift) = split_exponent(decimal).ok_or_else(|| {
+            NumberError::InvalidDecimal(format!("{} is not a valid decimal", decimal))
+        })?;
+        let places = mantissa.splitn(2, '.').nth(1).map(str::len).unwrap_or(0);
+        let exponent = i64::try_from(places)
+            .ok()
+            .and_then(|places| i32::try_from(i64::from(shift) - places).ok())
+            .ok_or_else(|| NumberError::Overflow(format!("{} is out of range", decimal)))?;
+        GridNumber::parse(decimal, exponent)
+    }
+}
+
+impl fmt::Display for GridNumber {
+    /// Formats the number as a decimal string, with as many fractional digits
+    /// as its exponent requires. Numbers with exponents too large to write out
+    /// are formatted in scientific notation: `12e50`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.value == 0 {
+            return write!(f, "0");
+        }
+        if self.exponent > MAX_PLAIN_EXPONENT || self.exponent < -MAX_PLAIN_EXPONENT {
+            return write!(f, "{}e{}", self.value, self.exponent);
+        }
+        if self.exponent >= 0 {
+            return write!(f, "{}{}", self.value, "0".repeat(self.exponent as usize));
+        }
+
+        let places = -i64::from(self.exponent) as usize;
+        let digits = format!("{:0>1$}", i128::from(self.value).abs(), places + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - places);
+        write!(
+            f,
+            "{}{}.{}",
+            if self.value < 0 { "-" } else { "" },
+            integer,
+            fraction
+        )
+    }
+}
+
+impl PartialEq for GridNumber {
+    fn eq(&self, other: &GridNumber) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for GridNumber {}
+
+impl PartialOrd for GridNumber {
+    fn partial_cmp(&self, other: &GridNumber) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GridNumber {
+    fn cmp(&self, other: &GridNumber) -> Ordering {
+        let sign = self.value.signum().cmp(&other.value.signum());
+        if sign != Ordering::Equal || self.value == 0 {
+            return sign;
+        }
+
+        // Scale the value with the larger exponent down to the smaller one; a
+        // nonzero value scaled by more than 10^19 exceeds any i64 value
+        let shift = i64::from(self.exponent) - i64::from(other.exponent);
+        match pow10(shift.abs()).filter(|_| shift.abs() <= 19) {
+            Some(factor) if shift >= 0 => {
+                (i128::from(self.value) * factor).cmp(&i128::from(other.value))
+            }
+            Some(factor) => i128::from(self.value).cmp(&(i128::from(other.value) * factor)),
+            None if (shift > 0) == (self.value > 0) => Ordering::Greater,
+            None => Ordering::Less,
+        }
+    }
+}
+
+impl Hash for GridNumber {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let normalized = self.normalize();
+        normalized.value.hash(state);
+        normalized.exponent.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // check that decimals are scaled by the exponent and rejected when they lose precision
+    fn check_parse_and_format() {
+        assert_eq!(GridNumber::new(1250, -2).to_string(), "12.50");
+        assert_eq!(GridNumber::new(-5, -3).to_string(), "-0.005");
+        assert_eq!(GridNumber::new(12, 3).to_string(), "12000");
+        assert_eq!(GridNumber::new(0, -2).to_string(), "0");
+
+        assert_eq!(GridNumber::parse("12.5", -2).unwrap().value(), 1250);
+        assert_eq!(GridNumber::parse("-0.005", -3).unwrap().value(), -5);
+        assert_eq!(GridNumber::parse("12000", 3).unwrap().value(), 12);
+        assert_eq!(GridNumber::parse("7", 0).unwrap().value(), 7);
+        assert_eq!(GridNumber::parse(".5", -1).unwrap().value(), 5);
+        assert_eq!(GridNumber::parse("0.000", 40).unwrap().value(), 0);
+        match GridNumber::parse("12.505", -2) {
+            Err(NumberError::Inexact(_)) => (),
+            res => panic!("Expected Inexact, got {:?}", res),
+        }
+        assert!(GridNumber::parse("12500", 4).is_err());
+        match GridNumber::parse("1.2.3", -2) {
+            Err(NumberError::InvalidDecimal(_)) => (),
+            res => panic!("Expected InvalidDecimal, got {:?}", res),
+        }
+        assert!(GridNumber::parse("-", 0).is_err());
+        match GridNumber::parse("99999999999999999999", 0) {
+            Err(NumberError::Overflow(_)) => (),
+            res => panic!("Expected Overflow, got {:?}", res),
+        }
+
+        for exponent in -6..6 {
+            for value in &[0, 1, -1, 42, -987_654, i64::max_value(), i64::min_value()] {
+                let number = GridNumber::new(*value, exponent);
+                assert_eq!(
+                    GridNumber::parse(&number.to_string(), exponent)
+                        .unwrap()
+                        .value(),
+                    *value
+                );
+            }
+        }
+
+        let number: GridNumber = "12.50".parse().unwrap();
+        assert_eq!((number.value(), number.exponent()), (1250, -2));
+        let number: GridNumber = "-300".parse().unwrap();
+        assert_eq!((number.value(), number.exponent()), (-300, 0));
+    }
+
+    #[test]
+    // check that floats convert to their shortest decimal representation
+    fn check_f64() {
+        assert_eq!(GridNumber::from_f64(0.1, -1).unwrap().value(), 1);
+        assert_eq!(GridNumber::from_f64(-12.5, -3).unwrap().value(), -12_500);
+        assert_eq!(GridNumber::from_f64(1e21, 15).unwrap().value(), 1_000_000);
+        assert!(GridNumber::from_f64(0.125, -2).is_err());
+        assert!(GridNumber::from_f64(std::f64::NAN, 0).is_err());
+        assert!(GridNumber::from_f64(std::f64::INFINITY, 0).is_err());
+        assert_eq!(GridNumber::new(-1250, -2).to_f64(), -12.5);
+        assert_eq!(GridNumber::new(12, 3).to_f64(), 12_000.0);
+        assert_eq!(GridNumber::new(1, -400).to_f64(), 0.0);
+    }
+
+    #[test]
+    // check that numbers with exponents too large to write out are formatted in scientific
+    // notation, which is parsed back
+    fn check_large_exponent() {
+        assert_eq!(
+            GridNumber::new(1, 40).to_string(),
+            format!("1{}", "0".repeat(40))
+        );
+        assert_eq!(GridNumber::new(1, 41).to_string(), "1e41");
+
+        let max = GridNumber::new(12, i32::max_value());
+        assert_eq!(max.to_string(), "12e2147483647");
+        assert_eq!(
+            GridNumber::parse(&max.to_string(), i32::max_value())
+                .unwrap()
+                .value(),
+            12
+        );
+        assert_eq!(max.to_f64(), std::f64::INFINITY);
+
+        let min = GridNumber::new(-5, i32::min_value());
+        assert_eq!(min.to_string(), "-5e-2147483648");
+        let number: GridNumber = min.to_string().parse().unwrap();
+        assert_eq!((number.value(), number.exponent()), (-5, i32::min_value()));
+        assert_eq!(min.to_f64(), 0.0);
+
+        assert_eq!(GridNumber::parse("1.5e3", 0).unwrap().value(), 1500);
+        assert_eq!(GridNumber::parse("1E-2", -2).unwrap().value(), 1);
+        let number: GridNumber = "1.5e3".parse().unwrap();
+        assert_eq!((number.value(), number.exponent()), (15, 2));
+        assert!(GridNumber::parse("1e", 0).is_err());
+        assert!(GridNumber::parse("e5", 0).is_err());
+    }
+
+    #[test]
+    // check that numbers are rescaled exactly or not at all
+    fn check_rescale() {
+        let number = GridNumber::new(1250, -2);
+        assert_eq!(number.rescale(-1).unwrap().value(), 125);
+        assert_eq!(number.rescale(-4).unwrap().value(), 125_000);
+        assert!(number.rescale(0).is_err());
+        assert!(GridNumber::new(i64::max_value(), 0).rescale(-1).is_err());
+        assert!(GridNumber::new(1, 0).rescale(-30).is_err());
+        assert_eq!(GridNumber::new(0, 0).rescale(-30).unwrap().value(), 0);
+    }
+
+    #[test]
+    // check that arithmetic aligns exponents and reports overflows
+    fn check_arithmetic() {
+        let a = GridNumber::new(1250, -2);
+        let b = GridNumber::new(3, -1);
+
+        let sum = a.checked_add(&b).unwrap();
+        assert_eq!((sum.value(), sum.exponent()), (1280, -2));
+        let difference = b.checked_sub(&a).unwrap();
+        assert_eq!(difference.to_string(), "-12.20");
+        let product = a.checked_mul(&b).unwrap();
+        assert_eq!((product.value(), product.exponent()), (3750, -3));
+        assert_eq!(a.checked_neg().unwrap().to_string(), "-12.50");
+
+        let max = GridNumber::new(i64::max_value(), 0);
+        assert!(max.checked_add(&GridNumber::new(1, 0)).is_err());
+        assert!(max.checked_mul(&GridNumber::new(2, 0)).is_err());
+        assert!(GridNumber::new(i64::min_value(), 0).checked_neg().is_err());
+        assert!(max.checked_add(&GridNumber::new(1, -1)).is_err());
+    }
+
+    #[test]
+    // check that numbers compare by the decimal they denote
+    fn check_comparison() {
+        assert_eq!(GridNumber::new(1250, -2), GridNumber::new(125, -1));
+        assert_eq!(GridNumber::new(0, -2), GridNumber::new(0, 5));
+        assert!(GridNumber::new(1, 0) > GridNumber::new(99, -2));
+        assert!(GridNumber::new(-1, 0) < GridNumber::new(-99, -2));
+        assert!(GridNumber::new(1, 30) > GridNumber::new(i64::max_value(), 0));
+        assert!(GridNumber::new(-1, 30) < GridNumber::new(i64::min_value(), 0));
+        assert!(GridNumber::new(1, -30) < GridNumber::new(1, 0));
+        assert!(GridNumber::new(-1, 0) < GridNumber::new(1, -30));
+
+        let mut numbers = std::collections::HashSet::new();
+        numbers.insert(GridNumber::new(1250, -2));
+        assert!(numbers.contains(&GridNumber::new(125, -1)));
+    }
+}
@@ -14,9 +14,18 @@
 
 use protobuf::Message;
 use protobuf::RepeatedField;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use std::error::Error as StdError;
 
+use crate::protocol::addressing::pike::{
+    compute_agent_address, compute_org_address, PIKE_ROLE_PREFIX,
+};
+use crate::protocol::addressing::schema::{
+    compute_property_group_address, compute_schema_address, compute_schema_version_address,
+    compute_schema_version_address_range,
+};
 use crate::protocol::schema::state::PropertyDefinition;
 use crate::protos;
 use crate::protos::{
@@ -25,9 +34,14 @@ use crate::protos::{
 
 /// Native implementation for SchemaPayload_Action
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
 pub enum Action {
     SchemaCreate,
     SchemaUpdate,
+    SchemaTransfer,
+    SchemaDeprecate,
+    PropertyGroupCreate,
 }
 
 impl FromProto<protos::schema_payload::SchemaPayload_Action> for Action {
@@ -37,6 +51,15 @@ impl FromProto<protos::schema_payload::SchemaPayload_Action> for Action {
         match actions {
             protos::schema_payload::SchemaPayload_Action::SCHEMA_CREATE => Ok(Action::SchemaCreate),
             protos::schema_payload::SchemaPayload_Action::SCHEMA_UPDATE => Ok(Action::SchemaUpdate),
+            protos::schema_payload::SchemaPayload_Action::SCHEMA_TRANSFER => {
+                Ok(Action::SchemaTransfer)
+            }
+            protos::schema_payload::SchemaPayload_Action::SCHEMA_DEPRECATE => {
+                Ok(Action::SchemaDeprecate)
+            }
+            protos::schema_payload::SchemaPayload_Action::PROPERTY_GROUP_CREATE => {
+                Ok(Action::PropertyGroupCreate)
+            }
             protos::schema_payload::SchemaPayload_Action::UNSET_ACTION => {
                 Err(ProtoConversionError::InvalidTypeError(
                     "Cannot convert SchemaPayload_Action with type unset.".to_string(),
@@ -51,6 +74,15 @@ impl FromNative<Action> for protos::schema_payload::SchemaPayload_Action {
         match action {
             Action::SchemaCreate => Ok(protos::schema_payload::SchemaPayload_Action::SCHEMA_CREATE),
             Action::SchemaUpdate => Ok(protos::schema_payload::SchemaPayload_Action::SCHEMA_UPDATE),
+            Action::SchemaTransfer => {
+                Ok(protos::schema_payload::SchemaPayload_Action::SCHEMA_TRANSFER)
+            }
+            Action::SchemaDeprecate => {
+                Ok(protos::schema_payload::SchemaPayload_Action::SCHEMA_DEPRECATE)
+            }
+            Action::PropertyGroupCreate => {
+                Ok(protos::schema_payload::SchemaPayload_Action::PROPERTY_GROUP_CREATE)
+            }
         }
     }
 }
@@ -60,10 +92,14 @@ impl IntoNative<Action> for protos::schema_payload::SchemaPayload_Action {}
 
 /// Native implementation for SchemaPayload
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SchemaPayload {
     action: Action,
     schema_create: SchemaCreateAction,
     schema_update: SchemaUpdateAction,
+    schema_transfer: SchemaTransferAction,
+    schema_deprecate: SchemaDeprecateAction,
+    property_group_create: PropertyGroupCreateAction,
 }
 
 impl SchemaPayload {
@@ -78,6 +114,85 @@ impl SchemaPayload {
     pub fn schema_update(&self) -> &SchemaUpdateAction {
         &self.schema_update
     }
+
+    pub fn schema_transfer(&self) -> &SchemaTransferAction {
+        &self.schema_transfer
+    }
+
+    pub fn schema_deprecate(&self) -> &SchemaDeprecateAction {
+        &self.schema_deprecate
+    }
+
+    pub fn property_group_create(&self) -> &PropertyGroupCreateAction {
+        &self.property_group_create
+    }
+
+    /// Returns the addresses written by this payload. A created schema always
+    /// starts at version 1, while the version written by any other schema
+    /// action depends on the schema in state, so only the range of the
+    /// schema's versions is known.
+    fn written_addresses(&self) -> Vec<String> {
+        let (schema_name, version_address) = match self.action {
+            Action::SchemaCreate => {
+                let name = self.schema_create.schema_name();
+                (name, compute_schema_version_address(name, 1))
+            }
+            Action::SchemaUpdate => {
+                let name = self.schema_update.schema_name();
+                (name, compute_schema_version_address_range(name))
+            }
+            Action::SchemaTransfer => {
+                let name = self.schema_transfer.schema_name();
+                (name, compute_schema_version_address_range(name))
+            }
+            Action::SchemaDeprecate => {
+                let name = self.schema_deprecate.schema_name();
+                (name, compute_schema_version_address_range(name))
+            }
+            Action::PropertyGroupCreate => {
+                return vec![compute_property_group_address(
+                    self.property_group_create.name(),
+                )];
+            }
+        };
+        vec![compute_schema_address(schema_name), version_address]
+    }
+
+    /// Returns the addresses the Schema contract reads when
+    /// `signer_public_key` submits this payload: the signer's agent and the
+    /// Pike roles, to check its permissions, and the addresses it writes, to
+    /// check that they have not been written yet. A transfer also reads the
+    /// organization the schema is transferred to, and a create reads the
+    /// parent schema and the property groups the new schema is composed from.
+    pub fn inputs(&self, signer_public_key: &str) -> Vec<String> {
+        let mut inputs = vec![
+            compute_agent_address(signer_public_key),
+            PIKE_ROLE_PREFIX.to_string(),
+        ];
+        inputs.append(&mut self.written_addresses());
+        match self.action {
+            Action::SchemaTransfer => {
+                inputs.push(compute_org_address(self.schema_transfer.new_owner()));
+            }
+            Action::SchemaCreate => {
+                if !self.schema_create.parent().is_empty() {
+                    inputs.push(compute_schema_address(self.schema_create.parent()));
+                }
+                for property_group in self.schema_create.property_groups() {
+                    inputs.push(compute_property_group_address(property_group));
+                }
+            }
+            _ => (),
+        }
+        inputs
+    }
+
+    /// Returns the addresses the Schema contract writes when
+    /// `signer_public_key` submits this payload: the schema and its new
+    /// immutable version, or the new property group.
+    pub fn outputs(&self, _signer_public_key: &str) -> Vec<String> {
+        self.written_addresses()
+    }
 }
 
 impl FromProto<protos::schema_payload::SchemaPayload> for SchemaPayload {
@@ -88,6 +203,15 @@ impl FromProto<protos::schema_payload::SchemaPayload> for SchemaPayload {
             action: Action::from_proto(payload.get_action())?,
             schema_create: SchemaCreateAction::from_proto(payload.get_schema_create().clone())?,
             schema_update: SchemaUpdateAction::from_proto(payload.get_schema_update().clone())?,
+            schema_transfer: SchemaTransferAction::from_proto(
+                payload.get_schema_transfer().clone(),
+            )?,
+            schema_deprecate: SchemaDeprecateAction::from_proto(
+                payload.get_schema_deprecate().clone(),
+            )?,
+            property_group_create: PropertyGroupCreateAction::from_proto(
+                payload.get_property_group_create().clone(),
+            )?,
         })
     }
 }
@@ -99,6 +223,10 @@ impl FromNative<SchemaPayload> for protos::schema_payload::SchemaPayload {
         proto_payload.set_action(payload.action().clone().into_proto()?);
         proto_payload.set_schema_create(payload.schema_create().clone().into_proto()?);
         proto_payload.set_schema_update(payload.schema_update().clone().into_proto()?);
+        proto_payload.set_schema_transfer(payload.schema_transfer().clone().into_proto()?);
+        proto_payload.set_schema_deprecate(payload.schema_deprecate().clone().into_proto()?);
+        proto_payload
+            .set_property_group_create(payload.property_group_create().clone().into_proto()?);
         Ok(proto_payload)
     }
 }
@@ -163,6 +291,9 @@ pub struct SchemaPayloadBuilder {
     action: Option<Action>,
     schema_create: Option<SchemaCreateAction>,
     schema_update: Option<SchemaUpdateAction>,
+    schema_transfer: Option<SchemaTransferAction>,
+    schema_deprecate: Option<SchemaDeprecateAction>,
+    property_group_create: Option<PropertyGroupCreateAction>,
 }
 
 impl SchemaPayloadBuilder {
@@ -185,6 +316,27 @@ impl SchemaPayloadBuilder {
         self
     }
 
+    pub fn with_schema_transfer(mut self, transfer: SchemaTransferAction) -> SchemaPayloadBuilder {
+        self.schema_transfer = Some(transfer);
+        self
+    }
+
+    pub fn with_schema_deprecate(
+        mut self,
+        deprecate: SchemaDeprecateAction,
+    ) -> SchemaPayloadBuilder {
+        self.schema_deprecate = Some(deprecate);
+        self
+    }
+
+    pub fn with_property_group_create(
+        mut self,
+        create: PropertyGroupCreateAction,
+    ) -> SchemaPayloadBuilder {
+        self.property_group_create = Some(create);
+        self
+    }
+
     pub fn build(self) -> Result<SchemaPayload, SchemaPayloadBuildError> {
         let action = self.action.ok_or_else(|| {
             SchemaPayloadBuildError::MissingField("'action' field is required".to_string())
@@ -214,19 +366,61 @@ impl SchemaPayloadBuilder {
             }
         };
 
+        let schema_transfer = {
+            if action == Action::SchemaTransfer {
+                self.schema_transfer.ok_or_else(|| {
+                    SchemaPayloadBuildError::MissingField(
+                        "'schema_transfer' field is required".to_string(),
+                    )
+                })?
+            } else {
+                SchemaTransferAction::default()
+            }
+        };
+
+        let schema_deprecate = {
+            if action == Action::SchemaDeprecate {
+                self.schema_deprecate.ok_or_else(|| {
+                    SchemaPayloadBuildError::MissingField(
+                        "'schema_deprecate' field is required".to_string(),
+                    )
+                })?
+            } else {
+                SchemaDeprecateAction::default()
+            }
+        };
+
+        let property_group_create = {
+            if action == Action::PropertyGroupCreate {
+                self.property_group_create.ok_or_else(|| {
+                    SchemaPayloadBuildError::MissingField(
+                        "'property_group_create' field is required".to_string(),
+                    )
+                })?
+            } else {
+                PropertyGroupCreateAction::default()
+            }
+        };
+
         Ok(SchemaPayload {
             action,
             schema_create,
             schema_update,
+            schema_transfer,
+            schema_deprecate,
+            property_group_create,
         })
     }
 }
 
 /// Native implementation for SchemaCreateAction
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SchemaCreateAction {
     schema_name: String,
     description: String,
+    parent: String,
+    property_groups: Vec<String>,
     properties: Vec<PropertyDefinition>,
 }
 
@@ -239,6 +433,16 @@ impl SchemaCreateAction {
         &self.description
     }
 
+    /// The name of the schema to extend, or an empty string
+    pub fn parent(&self) -> &str {
+        &self.parent
+    }
+
+    /// The names of the property groups to include
+    pub fn property_groups(&self) -> &[String] {
+        &self.property_groups
+    }
+
     pub fn properties(&self) -> &[PropertyDefinition] {
         &self.properties
     }
@@ -251,6 +455,8 @@ impl FromProto<protos::schema_payload::SchemaCreateAction> for SchemaCreateActio
         Ok(SchemaCreateAction {
             schema_name: schema_create.get_schema_name().to_string(),
             description: schema_create.get_description().to_string(),
+            parent: schema_create.get_parent().to_string(),
+            property_groups: schema_create.get_property_groups().to_vec(),
             properties: schema_create
                 .get_properties()
                 .to_vec()
@@ -267,6 +473,10 @@ impl FromNative<SchemaCreateAction> for protos::schema_payload::SchemaCreateActi
 
         proto_schema_create.set_schema_name(schema_create.schema_name().to_string());
         proto_schema_create.set_description(schema_create.description().to_string());
+        proto_schema_create.set_parent(schema_create.parent().to_string());
+        proto_schema_create.set_property_groups(RepeatedField::from_vec(
+            schema_create.property_groups().to_vec(),
+        ));
         proto_schema_create.set_properties(
             RepeatedField::from_vec(
             schema_create.properties().to_vec().into_iter()
@@ -336,6 +546,8 @@ impl std::fmt::Display for SchemaCreateBuildError {
 pub struct SchemaCreateBuilder {
     schema_name: Option<String>,
     description: Option<String>,
+    parent: Option<String>,
+    property_groups: Vec<String>,
     properties: Vec<PropertyDefinition>,
 }
 
@@ -354,6 +566,16 @@ impl SchemaCreateBuilder {
         self
     }
 
+    pub fn with_parent(mut self, parent: String) -> SchemaCreateBuilder {
+        self.parent = Some(parent);
+        self
+    }
+
+    pub fn with_property_groups(mut self, property_groups: Vec<String>) -> SchemaCreateBuilder {
+        self.property_groups = property_groups;
+        self
+    }
+
     pub fn with_properties(mut self, properties: Vec<PropertyDefinition>) -> SchemaCreateBuilder {
         self.properties = properties;
         self
@@ -365,9 +587,13 @@ impl SchemaCreateBuilder {
         })?;
 
         let description = self.description.unwrap_or_default();
+        let parent = self.parent.unwrap_or_default();
+        let property_groups = self.property_groups;
 
+        // A schema composed from a parent or property groups may declare no
+        // property of its own
         let properties = {
-            if !self.properties.is_empty() {
+            if !self.properties.is_empty() || !parent.is_empty() || !property_groups.is_empty() {
                 self.properties
             } else {
                 return Err(SchemaCreateBuildError::MissingField(
@@ -379,12 +605,15 @@ impl SchemaCreateBuilder {
         Ok(SchemaCreateAction {
             schema_name,
             description,
+            parent,
+            property_groups,
             properties,
         })
     }
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SchemaUpdateAction {
     schema_name: String,
     properties: Vec<PropertyDefinition>,
@@ -531,6 +760,447 @@ impl SchemaUpdateBuilder {
     }
 }
 
+/// Native implementation for SchemaTransferAction
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SchemaTransferAction {
+    schema_name: String,
+    new_owner: String,
+}
+
+impl SchemaTransferAction {
+    pub fn schema_name(&self) -> &str {
+        &self.schema_name
+    }
+
+    pub fn new_owner(&self) -> &str {
+        &self.new_owner
+    }
+}
+
+impl FromProto<protos::schema_payload::SchemaTransferAction> for SchemaTransferAction {
+    fn from_proto(
+        schema_transfer: protos::schema_payload::SchemaTransferAction,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(SchemaTransferAction {
+            schema_name: schema_transfer.get_schema_name().to_string(),
+            new_owner: schema_transfer.get_new_owner().to_string(),
+        })
+    }
+}
+
+impl FromNative<SchemaTransferAction> for protos::schema_payload::SchemaTransferAction {
+    fn from_native(schema_transfer: SchemaTransferAction) -> Result<Self, ProtoConversionError> {
+        let mut proto_schema_transfer = protos::schema_payload::SchemaTransferAction::new();
+
+        proto_schema_transfer.set_schema_name(schema_transfer.schema_name().to_string());
+        proto_schema_transfer.set_new_owner(schema_transfer.new_owner().to_string());
+
+        Ok(proto_schema_transfer)
+    }
+}
+
+impl FromBytes<SchemaTransferAction> for SchemaTransferAction {
+    fn from_bytes(bytes: &[u8]) -> Result<SchemaTransferAction, ProtoConversionError> {
+        let proto: protos::schema_payload::SchemaTransferAction = protobuf::parse_from_bytes(bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get SchemaTransferAction from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for SchemaTransferAction {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from SchemaTransferAction".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::schema_payload::SchemaTransferAction> for SchemaTransferAction {}
+impl IntoNative<SchemaTransferAction> for protos::schema_payload::SchemaTransferAction {}
+
+#[derive(Debug)]
+pub enum SchemaTransferBuildError {
+    MissingField(String),
+}
+
+impl StdError for SchemaTransferBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            SchemaTransferBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            SchemaTransferBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaTransferBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SchemaTransferBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a SchemaTransferAction
+#[derive(Default, Clone)]
+pub struct SchemaTransferBuilder {
+    schema_name: Option<String>,
+    new_owner: Option<String>,
+}
+
+impl SchemaTransferBuilder {
+    pub fn new() -> Self {
+        SchemaTransferBuilder::default()
+    }
+
+    pub fn with_schema_name(mut self, schema_name: String) -> SchemaTransferBuilder {
+        self.schema_name = Some(schema_name);
+        self
+    }
+
+    pub fn with_new_owner(mut self, new_owner: String) -> SchemaTransferBuilder {
+        self.new_owner = Some(new_owner);
+        self
+    }
+
+    pub fn build(self) -> Result<SchemaTransferAction, SchemaTransferBuildError> {
+        let schema_name = self.schema_name.ok_or_else(|| {
+            SchemaTransferBuildError::MissingField("'schema_name' field is required".to_string())
+        })?;
+
+        let new_owner = self.new_owner.ok_or_else(|| {
+            SchemaTransferBuildError::MissingField("'new_owner' field is required".to_string())
+        })?;
+
+        Ok(SchemaTransferAction {
+            schema_name,
+            new_owner,
+        })
+    }
+}
+
+/// Native implementation for SchemaDeprecateAction
+///
+/// Deprecates the whole schema when `properties` is empty. Otherwise only the named properties
+/// are deprecated, which must be declared by the schema itself: inherited properties can only be
+/// deprecated on the parent schema declaring them.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SchemaDeprecateAction {
+    schema_name: String,
+    properties: Vec<String>,
+}
+
+impl SchemaDeprecateAction {
+    pub fn schema_name(&self) -> &str {
+        &self.schema_name
+    }
+
+    /// The names of the properties to deprecate. If empty, the schema itself
+    /// is deprecated.
+    pub fn properties(&self) -> &[String] {
+        &self.properties
+    }
+}
+
+impl FromProto<protos::schema_payload::SchemaDeprecateAction> for SchemaDeprecateAction {
+    fn from_proto(
+        schema_deprecate: protos::schema_payload::SchemaDeprecateAction,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(SchemaDeprecateAction {
+            schema_name: schema_deprecate.get_schema_name().to_string(),
+            properties: schema_deprecate.get_properties().to_vec(),
+        })
+    }
+}
+
+impl FromNative<SchemaDeprecateAction> for protos::schema_payload::SchemaDeprecateAction {
+    fn from_native(schema_deprecate: SchemaDeprecateAction) -> Result<Self, ProtoConversionError> {
+        let mut proto_schema_deprecate = protos::schema_payload::SchemaDeprecateAction::new();
+
+        proto_schema_deprecate.set_schema_name(schema_deprecate.schema_name().to_string());
+        proto_schema_deprecate.set_properties(RepeatedField::from_vec(
+            schema_deprecate.properties().to_vec(),
+        ));
+
+        Ok(proto_schema_deprecate)
+    }
+}
+
+impl FromBytes<SchemaDeprecateAction> for SchemaDeprecateAction {
+    fn from_bytes(bytes: &[u8]) -> Result<SchemaDeprecateAction, ProtoConversionError> {
+        let proto: protos::schema_payload::SchemaDeprecateAction =
+            protobuf::parse_from_bytes(bytes).map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get SchemaDeprecateAction from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for SchemaDeprecateAction {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from SchemaDeprecateAction".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::schema_payload::SchemaDeprecateAction> for SchemaDeprecateAction {}
+impl IntoNative<SchemaDeprecateAction> for protos::schema_payload::SchemaDeprecateAction {}
+
+#[derive(Debug)]
+pub enum SchemaDeprecateBuildError {
+    MissingField(String),
+}
+
+impl StdError for SchemaDeprecateBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            SchemaDeprecateBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            SchemaDeprecateBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaDeprecateBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SchemaDeprecateBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a SchemaDeprecateAction
+#[derive(Default, Clone)]
+pub struct SchemaDeprecateBuilder {
+    schema_name: Option<String>,
+    properties: Vec<String>,
+}
+
+impl SchemaDeprecateBuilder {
+    pub fn new() -> Self {
+        SchemaDeprecateBuilder::default()
+    }
+
+    pub fn with_schema_name(mut self, schema_name: String) -> SchemaDeprecateBuilder {
+        self.schema_name = Some(schema_name);
+        self
+    }
+
+    pub fn with_properties(mut self, properties: Vec<String>) -> SchemaDeprecateBuilder {
+        self.properties = properties;
+        self
+    }
+
+    pub fn build(self) -> Result<SchemaDeprecateAction, SchemaDeprecateBuildError> {
+        let schema_name = self.schema_name.ok_or_else(|| {
+            SchemaDeprecateBuildError::MissingField("'schema_name' field is required".to_string())
+        })?;
+
+        Ok(SchemaDeprecateAction {
+            schema_name,
+            properties: self.properties,
+        })
+    }
+}
+
+/// Native implementation for PropertyGroupCreateAction
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PropertyGroupCreateAction {
+    name: String,
+    description: String,
+    properties: Vec<PropertyDefinition>,
+}
+
+impl PropertyGroupCreateAction {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn properties(&self) -> &[PropertyDefinition] {
+        &self.properties
+    }
+}
+
+impl FromProto<protos::schema_payload::PropertyGroupCreateAction> for PropertyGroupCreateAction {
+    fn from_proto(
+        property_group_create: protos::schema_payload::PropertyGroupCreateAction,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(PropertyGroupCreateAction {
+            name: property_group_create.get_name().to_string(),
+            description: property_group_create.get_description().to_string(),
+            properties: property_group_create
+                .get_properties()
+                .to_vec()
+                .into_iter()
+                .map(PropertyDefinition::from_proto)
+                .collect::<Result<Vec<PropertyDefinition>, ProtoConversionError>>()?,
+        })
+    }
+}
+
+impl FromNative<PropertyGroupCreateAction> for protos::schema_payload::PropertyGroupCreateAction {
+    fn from_native(
+        property_group_create: PropertyGroupCreateAction,
+    ) -> Result<Self, ProtoConversionError> {
+        let mut proto_property_group_create =
+            protos::schema_payload::PropertyGroupCreateAction::new();
+
+        proto_property_group_create.set_name(property_group_create.name().to_string());
+        proto_property_group_create
+            .set_description(property_group_create.description().to_string());
+        proto_property_group_create.set_properties(RepeatedField::from_vec(
+            property_group_create
+                .properties()
+                .to_vec()
+                .into_iter()
+                .map(PropertyDefinition::into_proto)
+                .collect::<Result<Vec<protos::schema_state::PropertyDefinition>, ProtoConversionError>>()?,
+        ));
+
+        Ok(proto_property_group_create)
+    }
+}
+
+impl FromBytes<PropertyGroupCreateAction> for PropertyGroupCreateAction {
+    fn from_bytes(bytes: &[u8]) -> Result<PropertyGroupCreateAction, ProtoConversionError> {
+        let proto: protos::schema_payload::PropertyGroupCreateAction =
+            protobuf::parse_from_bytes(bytes).map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get PropertyGroupCreateAction from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for PropertyGroupCreateAction {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from PropertyGroupCreateAction".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::schema_payload::PropertyGroupCreateAction> for PropertyGroupCreateAction {}
+impl IntoNative<PropertyGroupCreateAction> for protos::schema_payload::PropertyGroupCreateAction {}
+
+#[derive(Debug)]
+pub enum PropertyGroupCreateBuildError {
+    MissingField(String),
+}
+
+impl StdError for PropertyGroupCreateBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            PropertyGroupCreateBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            PropertyGroupCreateBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PropertyGroupCreateBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            PropertyGroupCreateBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a PropertyGroupCreateAction
+#[derive(Default, Clone)]
+pub struct PropertyGroupCreateBuilder {
+    name: Option<String>,
+    description: Option<String>,
+    properties: Vec<PropertyDefinition>,
+}
+
+impl PropertyGroupCreateBuilder {
+    pub fn new() -> Self {
+        PropertyGroupCreateBuilder::default()
+    }
+
+    pub fn with_name(mut self, name: String) -> PropertyGroupCreateBuilder {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn with_description(mut self, description: String) -> PropertyGroupCreateBuilder {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_properties(
+        mut self,
+        properties: Vec<PropertyDefinition>,
+    ) -> PropertyGroupCreateBuilder {
+        self.properties = properties;
+        self
+    }
+
+    pub fn build(self) -> Result<PropertyGroupCreateAction, PropertyGroupCreateBuildError> {
+        let name = self.name.ok_or_else(|| {
+            PropertyGroupCreateBuildError::MissingField("'name' field is required".to_string())
+        })?;
+
+        let description = self.description.unwrap_or_default();
+
+        let properties = {
+            if !self.properties.is_empty() {
+                self.properties
+            } else {
+                return Err(PropertyGroupCreateBuildError::MissingField(
+                    "'properties' field is required".to_string(),
+                ));
+            }
+        };
+
+        Ok(PropertyGroupCreateAction {
+            name,
+            description,
+            properties,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -722,4 +1392,132 @@ mod tests {
         let payload = SchemaPayload::from_bytes(&bytes).unwrap();
         assert_eq!(payload, original);
     }
+
+    #[test]
+    // check that a schema payload with transfer action is built correctly and declares the
+    // organization the schema is transferred to
+    fn check_schema_transfer_action_payload() {
+        let action = SchemaTransferBuilder::new()
+            .with_schema_name("TestSchema".to_string())
+            .with_new_owner("new_org".to_string())
+            .build()
+            .unwrap();
+
+        let payload = SchemaPayloadBuilder::new()
+            .with_action(Action::SchemaTransfer)
+            .with_schema_transfer(action.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(payload.action, Action::SchemaTransfer);
+        assert_eq!(payload.schema_transfer, action);
+        assert_eq!(payload.schema_deprecate, SchemaDeprecateAction::default());
+        assert!(payload
+            .inputs("agent_public_key")
+            .contains(&compute_org_address("new_org")));
+        assert_eq!(
+            payload.outputs("agent_public_key"),
+            vec![
+                compute_schema_address("TestSchema"),
+                compute_schema_version_address_range("TestSchema"),
+            ]
+        );
+
+        let bytes = payload.clone().into_bytes().unwrap();
+        assert_eq!(SchemaPayload::from_bytes(&bytes).unwrap(), payload);
+    }
+
+    #[test]
+    // check that a schema payload with deprecate action is built correctly
+    fn check_schema_deprecate_action_payload() {
+        let action = SchemaDeprecateBuilder::new()
+            .with_schema_name("TestSchema".to_string())
+            .with_properties(vec!["TEST".to_string()])
+            .build()
+            .unwrap();
+
+        let payload = SchemaPayloadBuilder::new()
+            .with_action(Action::SchemaDeprecate)
+            .with_schema_deprecate(action.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(payload.action, Action::SchemaDeprecate);
+        assert_eq!(payload.schema_deprecate, action);
+        assert_eq!(payload.schema_transfer, SchemaTransferAction::default());
+
+        let bytes = payload.clone().into_bytes().unwrap();
+        assert_eq!(SchemaPayload::from_bytes(&bytes).unwrap(), payload);
+    }
+
+    #[test]
+    // check that a schema create action extending a parent and including property groups
+    // needs no properties of its own, and reads the schemas and groups it is composed from
+    fn check_schema_create_composed_action_payload() {
+        let action = SchemaCreateBuilder::new()
+            .with_schema_name("TestSchema".to_string())
+            .with_parent("ParentSchema".to_string())
+            .with_property_groups(vec!["gps".to_string()])
+            .build()
+            .unwrap();
+
+        assert_eq!(action.parent(), "ParentSchema");
+        assert_eq!(action.property_groups(), &["gps".to_string()]);
+        assert!(action.properties().is_empty());
+
+        let payload = SchemaPayloadBuilder::new()
+            .with_action(Action::SchemaCreate)
+            .with_schema_create(action)
+            .build()
+            .unwrap();
+
+        let inputs = payload.inputs("agent_public_key");
+        assert!(inputs.contains(&compute_schema_address("ParentSchema")));
+        assert!(inputs.contains(&compute_property_group_address("gps")));
+        assert_eq!(
+            payload.outputs("agent_public_key"),
+            vec![
+                compute_schema_address("TestSchema"),
+                compute_schema_version_address("TestSchema", 1),
+            ]
+        );
+
+        let bytes = payload.clone().into_bytes().unwrap();
+        assert_eq!(SchemaPayload::from_bytes(&bytes).unwrap(), payload);
+    }
+
+    #[test]
+    // check that a schema payload with property group create action is built correctly and
+    // only writes the property group
+    fn check_property_group_create_action_payload() {
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("latitude_longitude".to_string())
+            .with_data_type(DataType::LatLong)
+            .build()
+            .unwrap();
+
+        let action = PropertyGroupCreateBuilder::new()
+            .with_name("gps".to_string())
+            .with_description("GPS location".to_string())
+            .with_properties(vec![property_definition])
+            .build()
+            .unwrap();
+
+        let payload = SchemaPayloadBuilder::new()
+            .with_action(Action::PropertyGroupCreate)
+            .with_property_group_create(action.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(payload.action, Action::PropertyGroupCreate);
+        assert_eq!(payload.property_group_create, action);
+        assert_eq!(payload.schema_create, SchemaCreateAction::default());
+        assert_eq!(
+            payload.outputs("agent_public_key"),
+            vec![compute_property_group_address("gps")]
+        );
+
+        let bytes = payload.clone().into_bytes().unwrap();
+        assert_eq!(SchemaPayload::from_bytes(&bytes).unwrap(), payload);
+    }
 }
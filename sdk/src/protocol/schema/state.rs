@@ -14,9 +14,14 @@
 
 use protobuf::Message;
 use protobuf::RepeatedField;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use std::error::Error as StdError;
 
+use super::number::GridNumber;
+use super::validate::check_constraints;
+use crate::protocol::versioning::{migrate_state, STATE_VERSION};
 use crate::protos;
 use crate::protos::{
     FromBytes, FromNative, FromProto, IntoBytes, IntoNative, IntoProto, ProtoConversionError,
@@ -24,6 +29,8 @@ use crate::protos::{
 
 /// Native implementation of DataType enum
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
 pub enum DataType {
     Bytes,
     Boolean,
@@ -73,6 +80,7 @@ impl IntoProto<protos::schema_state::PropertyDefinition_DataType> for DataType {
 impl IntoNative<DataType> for protos::schema_state::PropertyDefinition_DataType {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LatLong {
     latitude: i64,
     longitude: i64,
@@ -111,6 +119,7 @@ impl IntoNative<LatLong> for protos::schema_state::LatLong {}
 
 /// Native implementation of PropertyDefinition
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PropertyDefinition {
     name: String,
     data_type: DataType,
@@ -119,6 +128,14 @@ pub struct PropertyDefinition {
     number_exponent: i32,
     enum_options: Vec<String>,
     struct_properties: Vec<PropertyDefinition>,
+    number_min: Option<i64>,
+    number_max: Option<i64>,
+    string_pattern: String,
+    string_min_length: u32,
+    string_max_length: u32,
+    bytes_max_size: u32,
+    unit: String,
+    deprecated: bool,
 }
 
 impl PropertyDefinition {
@@ -149,6 +166,64 @@ impl PropertyDefinition {
     pub fn struct_properties(&self) -> &[PropertyDefinition] {
         &self.struct_properties
     }
+
+    /// The inclusive lower bound of a NUMBER property, if any
+    pub fn number_min(&self) -> Option<&i64> {
+        self.number_min.as_ref()
+    }
+
+    /// The inclusive upper bound of a NUMBER property, if any
+    pub fn number_max(&self) -> Option<&i64> {
+        self.number_max.as_ref()
+    }
+
+    /// The regular expression a STRING property must match; empty if unset
+    pub fn string_pattern(&self) -> &str {
+        &self.string_pattern
+    }
+
+    pub fn string_min_length(&self) -> &u32 {
+        &self.string_min_length
+    }
+
+    /// The maximum length of a STRING property; 0 if unbounded
+    pub fn string_max_length(&self) -> &u32 {
+        &self.string_max_length
+    }
+
+    /// The maximum size of a BYTES property; 0 if unbounded
+    pub fn bytes_max_size(&self) -> &u32 {
+        &self.bytes_max_size
+    }
+
+    /// The UCUM unit of measure of the property; empty if unset
+    pub fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    pub fn deprecated(&self) -> &bool {
+        &self.deprecated
+    }
+
+    pub fn into_builder(self) -> PropertyDefinitionBuilder {
+        PropertyDefinitionBuilder {
+            name: Some(self.name),
+            data_type: Some(self.data_type),
+            required: Some(self.required),
+            description: Some(self.description),
+            number_exponent: Some(self.number_exponent),
+            enum_options: self.enum_options,
+            struct_properties: self.struct_properties,
+            number_min: self.number_min,
+            number_max: self.number_max,
+            string_pattern: Some(self.string_pattern),
+            string_min_length: Some(self.string_min_length),
+            string_max_length: Some(self.string_max_length),
+            bytes_max_size: Some(self.bytes_max_size),
+            unit: Some(self.unit),
+            deprecated: Some(self.deprecated),
+        }
+    }
 }
 
 impl FromProto<protos::schema_state::PropertyDefinition> for PropertyDefinition {
@@ -168,6 +243,22 @@ impl FromProto<protos::schema_state::PropertyDefinition> for PropertyDefinition
                 .into_iter()
                 .map(PropertyDefinition::from_proto)
                 .collect::<Result<Vec<PropertyDefinition>, ProtoConversionError>>()?,
+            number_min: if property_definition.has_number_min() {
+                Some(property_definition.get_number_min().get_value())
+            } else {
+                None
+            },
+            number_max: if property_definition.has_number_max() {
+                Some(property_definition.get_number_max().get_value())
+            } else {
+                None
+            },
+            string_pattern: property_definition.get_string_pattern().to_string(),
+            string_min_length: property_definition.get_string_min_length(),
+            string_max_length: property_definition.get_string_max_length(),
+            bytes_max_size: property_definition.get_bytes_max_size(),
+            unit: property_definition.get_unit().to_string(),
+            deprecated: property_definition.get_deprecated(),
         })
     }
 }
@@ -190,6 +281,25 @@ impl FromNative<PropertyDefinition> for protos::schema_state::PropertyDefinition
             property_definition.struct_properties().to_vec().into_iter()
             .map(PropertyDefinition::into_proto)
             .collect::<Result<Vec<protos::schema_state::PropertyDefinition>, ProtoConversionError>>()?,));
+        if let Some(min) = property_definition.number_min() {
+            let mut bound = protos::schema_state::PropertyDefinition_NumberBound::new();
+            bound.set_value(*min);
+            proto_property_definition.set_number_min(bound);
+        }
+        if let Some(max) = property_definition.number_max() {
+            let mut bound = protos::schema_state::PropertyDefinition_NumberBound::new();
+            bound.set_value(*max);
+            proto_property_definition.set_number_max(bound);
+        }
+        proto_property_definition
+            .set_string_pattern(property_definition.string_pattern().to_string());
+        proto_property_definition
+            .set_string_min_length(property_definition.string_min_length().clone());
+        proto_property_definition
+            .set_string_max_length(property_definition.string_max_length().clone());
+        proto_property_definition.set_bytes_max_size(property_definition.bytes_max_size().clone());
+        proto_property_definition.set_unit(property_definition.unit().to_string());
+        proto_property_definition.set_deprecated(*property_definition.deprecated());
         Ok(proto_property_definition)
     }
 }
@@ -225,6 +335,7 @@ impl IntoNative<PropertyDefinition> for protos::schema_state::PropertyDefinition
 pub enum PropertyDefinitionBuildError {
     MissingField(String),
     EmptyVec(String),
+    InvalidConstraint(String),
 }
 
 impl StdError for PropertyDefinitionBuildError {
@@ -232,6 +343,7 @@ impl StdError for PropertyDefinitionBuildError {
         match *self {
             PropertyDefinitionBuildError::MissingField(ref msg) => msg,
             PropertyDefinitionBuildError::EmptyVec(ref msg) => msg,
+            PropertyDefinitionBuildError::InvalidConstraint(ref msg) => msg,
         }
     }
 
@@ -239,6 +351,7 @@ impl StdError for PropertyDefinitionBuildError {
         match *self {
             PropertyDefinitionBuildError::MissingField(_) => None,
             PropertyDefinitionBuildError::EmptyVec(_) => None,
+            PropertyDefinitionBuildError::InvalidConstraint(_) => None,
         }
     }
 }
@@ -248,6 +361,9 @@ impl std::fmt::Display for PropertyDefinitionBuildError {
         match *self {
             PropertyDefinitionBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
             PropertyDefinitionBuildError::EmptyVec(ref s) => write!(f, "EmptyVec: {}", s),
+            PropertyDefinitionBuildError::InvalidConstraint(ref s) => {
+                write!(f, "InvalidConstraint: {}", s)
+            }
         }
     }
 }
@@ -262,6 +378,14 @@ pub struct PropertyDefinitionBuilder {
     pub number_exponent: Option<i32>,
     pub enum_options: Vec<String>,
     pub struct_properties: Vec<PropertyDefinition>,
+    pub number_min: Option<i64>,
+    pub number_max: Option<i64>,
+    pub string_pattern: Option<String>,
+    pub string_min_length: Option<u32>,
+    pub string_max_length: Option<u32>,
+    pub bytes_max_size: Option<u32>,
+    pub unit: Option<String>,
+    pub deprecated: Option<bool>,
 }
 
 impl PropertyDefinitionBuilder {
@@ -307,6 +431,46 @@ impl PropertyDefinitionBuilder {
         self
     }
 
+    pub fn with_number_min(mut self, number_min: i64) -> PropertyDefinitionBuilder {
+        self.number_min = Some(number_min);
+        self
+    }
+
+    pub fn with_number_max(mut self, number_max: i64) -> PropertyDefinitionBuilder {
+        self.number_max = Some(number_max);
+        self
+    }
+
+    pub fn with_string_pattern(mut self, string_pattern: String) -> PropertyDefinitionBuilder {
+        self.string_pattern = Some(string_pattern);
+        self
+    }
+
+    pub fn with_string_min_length(mut self, string_min_length: u32) -> PropertyDefinitionBuilder {
+        self.string_min_length = Some(string_min_length);
+        self
+    }
+
+    pub fn with_string_max_length(mut self, string_max_length: u32) -> PropertyDefinitionBuilder {
+        self.string_max_length = Some(string_max_length);
+        self
+    }
+
+    pub fn with_bytes_max_size(mut self, bytes_max_size: u32) -> PropertyDefinitionBuilder {
+        self.bytes_max_size = Some(bytes_max_size);
+        self
+    }
+
+    pub fn with_unit(mut self, unit: String) -> PropertyDefinitionBuilder {
+        self.unit = Some(unit);
+        self
+    }
+
+    pub fn with_deprecated(mut self, deprecated: bool) -> PropertyDefinitionBuilder {
+        self.deprecated = Some(deprecated);
+        self
+    }
+
     pub fn build(self) -> Result<PropertyDefinition, PropertyDefinitionBuildError> {
         let name = self.name.ok_or_else(|| {
             PropertyDefinitionBuildError::MissingField("'name' field is required".to_string())
@@ -359,7 +523,7 @@ impl PropertyDefinitionBuilder {
             }
         };
 
-        Ok(PropertyDefinition {
+        let definition = PropertyDefinition {
             name,
             data_type,
             required,
@@ -367,17 +531,40 @@ impl PropertyDefinitionBuilder {
             number_exponent,
             enum_options,
             struct_properties,
-        })
+            number_min: self.number_min,
+            number_max: self.number_max,
+            string_pattern: self.string_pattern.unwrap_or_default(),
+            string_min_length: self.string_min_length.unwrap_or_default(),
+            string_max_length: self.string_max_length.unwrap_or_default(),
+            bytes_max_size: self.bytes_max_size.unwrap_or_default(),
+            unit: self.unit.unwrap_or_default(),
+            deprecated: self.deprecated.unwrap_or_default(),
+        };
+
+        if let Some(err) = check_constraints(&definition, "").into_iter().next() {
+            return Err(PropertyDefinitionBuildError::InvalidConstraint(
+                err.to_string(),
+            ));
+        }
+
+        Ok(definition)
     }
 }
 
 /// Native implementation of Schema
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Schema {
     name: String,
     description: String,
     owner: String,
+    version: u32,
+    deprecated: bool,
+    parent: String,
+    parent_version: u32,
+    property_groups: Vec<String>,
     properties: Vec<PropertyDefinition>,
+    inherited_properties: Vec<PropertyDefinition>,
 }
 
 impl Schema {
@@ -393,9 +580,67 @@ impl Schema {
         &self.owner
     }
 
+    /// The version of the schema, starting at 1 and incremented by every
+    /// update. Schemas created before versioning was introduced have version 0.
+    pub fn version(&self) -> &u32 {
+        &self.version
+    }
+
+    /// Whether the schema is deprecated. New records cannot be created with a
+    /// deprecated schema.
+    pub fn deprecated(&self) -> &bool {
+        &self.deprecated
+    }
+
+    /// The name of the schema this schema extends, or an empty string
+    pub fn parent(&self) -> &str {
+        &self.parent
+    }
+
+    /// The version of the parent schema the inherited properties come from
+    pub fn parent_version(&self) -> &u32 {
+        &self.parent_version
+    }
+
+    /// The names of the property groups included in the schema
+    pub fn property_groups(&self) -> &[String] {
+        &self.property_groups
+    }
+
+    /// The property definitions declared by the schema itself
     pub fn properties(&self) -> &[PropertyDefinition] {
         &self.properties
     }
+
+    /// The property definitions resolved from the parent schema and the
+    /// property groups
+    pub fn inherited_properties(&self) -> &[PropertyDefinition] {
+        &self.inherited_properties
+    }
+
+    /// The property definitions items of this schema are validated against:
+    /// the inherited properties followed by the declared ones.
+    pub fn effective_properties(&self) -> Vec<PropertyDefinition> {
+        self.inherited_properties
+            .iter()
+            .chain(self.properties.iter())
+            .cloned()
+            .collect()
+    }
+
+    pub fn into_builder(self) -> SchemaBuilder {
+        SchemaBuilder::new()
+            .with_name(self.name)
+            .with_description(self.description)
+            .with_owner(self.owner)
+            .with_version(self.version)
+            .with_deprecated(self.deprecated)
+            .with_parent(self.parent)
+            .with_parent_version(self.parent_version)
+            .with_property_groups(self.property_groups)
+            .with_properties(self.properties)
+            .with_inherited_properties(self.inherited_properties)
+    }
 }
 
 impl FromProto<protos::schema_state::Schema> for Schema {
@@ -404,12 +649,23 @@ impl FromProto<protos::schema_state::Schema> for Schema {
             name: schema.get_name().to_string(),
             description: schema.get_description().to_string(),
             owner: schema.get_owner().to_string(),
+            version: schema.get_version(),
+            deprecated: schema.get_deprecated(),
+            parent: schema.get_parent().to_string(),
+            parent_version: schema.get_parent_version(),
+            property_groups: schema.get_property_groups().to_vec(),
             properties: schema
                 .get_properties()
                 .to_vec()
                 .into_iter()
                 .map(PropertyDefinition::from_proto)
                 .collect::<Result<Vec<PropertyDefinition>, ProtoConversionError>>()?,
+            inherited_properties: schema
+                .get_inherited_properties()
+                .to_vec()
+                .into_iter()
+                .map(PropertyDefinition::from_proto)
+                .collect::<Result<Vec<PropertyDefinition>, ProtoConversionError>>()?,
         })
     }
 }
@@ -420,6 +676,12 @@ impl FromNative<Schema> for protos::schema_state::Schema {
         proto_schema.set_name(schema.name().to_string());
         proto_schema.set_description(schema.description().to_string());
         proto_schema.set_owner(schema.owner().to_string());
+        proto_schema.set_version(*schema.version());
+        proto_schema.set_deprecated(*schema.deprecated());
+        proto_schema.set_parent(schema.parent().to_string());
+        proto_schema.set_parent_version(*schema.parent_version());
+        proto_schema
+            .set_property_groups(RepeatedField::from_vec(schema.property_groups().to_vec()));
         proto_schema.set_properties(RepeatedField::from_vec(
             schema
                 .properties()
@@ -428,6 +690,14 @@ impl FromNative<Schema> for protos::schema_state::Schema {
                 .map(PropertyDefinition::into_proto)
                 .collect::<Result<Vec<protos::schema_state::PropertyDefinition>, ProtoConversionError>>()?,
         ));
+        proto_schema.set_inherited_properties(RepeatedField::from_vec(
+            schema
+                .inherited_properties()
+                .to_vec()
+                .into_iter()
+                .map(PropertyDefinition::into_proto)
+                .collect::<Result<Vec<protos::schema_state::PropertyDefinition>, ProtoConversionError>>()?,
+        ));
         Ok(proto_schema)
     }
 }
@@ -490,7 +760,13 @@ pub struct SchemaBuilder {
     pub name: Option<String>,
     pub description: Option<String>,
     pub owner: Option<String>,
+    pub version: Option<u32>,
+    pub deprecated: Option<bool>,
+    pub parent: Option<String>,
+    pub parent_version: Option<u32>,
+    pub property_groups: Vec<String>,
     pub properties: Vec<PropertyDefinition>,
+    pub inherited_properties: Vec<PropertyDefinition>,
 }
 
 impl SchemaBuilder {
@@ -513,11 +789,44 @@ impl SchemaBuilder {
         self
     }
 
+    pub fn with_version(mut self, version: u32) -> SchemaBuilder {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn with_deprecated(mut self, deprecated: bool) -> SchemaBuilder {
+        self.deprecated = Some(deprecated);
+        self
+    }
+
+    pub fn with_parent(mut self, parent: String) -> SchemaBuilder {
+        self.parent = Some(parent);
+        self
+    }
+
+    pub fn with_parent_version(mut self, parent_version: u32) -> SchemaBuilder {
+        self.parent_version = Some(parent_version);
+        self
+    }
+
+    pub fn with_property_groups(mut self, property_groups: Vec<String>) -> SchemaBuilder {
+        self.property_groups = property_groups;
+        self
+    }
+
     pub fn with_properties(mut self, properties: Vec<PropertyDefinition>) -> SchemaBuilder {
         self.properties = properties;
         self
     }
 
+    pub fn with_inherited_properties(
+        mut self,
+        inherited_properties: Vec<PropertyDefinition>,
+    ) -> SchemaBuilder {
+        self.inherited_properties = inherited_properties;
+        self
+    }
+
     pub fn build(self) -> Result<Schema, SchemaBuildError> {
         let name = self.name.ok_or_else(|| {
             SchemaBuildError::MissingField("'name' field is required".to_string())
@@ -528,8 +837,16 @@ impl SchemaBuilder {
         })?;
 
         let description = self.description.unwrap_or_else(|| "".to_string());
+        let version = self.version.unwrap_or_default();
+        let deprecated = self.deprecated.unwrap_or_default();
+        let parent = self.parent.unwrap_or_default();
+        let parent_version = self.parent_version.unwrap_or_default();
+        let property_groups = self.property_groups;
+        let inherited_properties = self.inherited_properties;
+        // A schema composed from a parent or property groups may declare no
+        // property of its own
         let properties = {
-            if !self.properties.is_empty() {
+            if !self.properties.is_empty() || !parent.is_empty() || !property_groups.is_empty() {
                 self.properties
             } else {
                 return Err(SchemaBuildError::MissingField(
@@ -542,13 +859,20 @@ impl SchemaBuilder {
             name,
             description,
             owner,
+            version,
+            deprecated,
+            parent,
+            parent_version,
+            property_groups,
             properties,
+            inherited_properties,
         })
     }
 }
 
 /// Native implementation of SchemaList
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SchemaList {
     schemas: Vec<Schema>,
 }
@@ -587,14 +911,17 @@ impl FromNative<SchemaList> for protos::schema_state::SchemaList {
                 .collect::<Result<Vec<protos::schema_state::Schema>, ProtoConversionError>>()?,
         ));
 
+        schema_list_proto.set_version(STATE_VERSION);
+
         Ok(schema_list_proto)
     }
 }
 
 impl FromBytes<SchemaList> for SchemaList {
     fn from_bytes(bytes: &[u8]) -> Result<SchemaList, ProtoConversionError> {
+        let bytes = migrate_state(bytes)?;
         let proto: protos::schema_state::SchemaList =
-            protobuf::parse_from_bytes(bytes).map_err(|_| {
+            protobuf::parse_from_bytes(&bytes).map_err(|_| {
                 ProtoConversionError::SerializationError(
                     "Unable to get SchemaList from bytes".to_string(),
                 )
@@ -676,8 +1003,334 @@ impl SchemaListBuilder {
     }
 }
 
+/// Native implementation of PropertyGroup
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PropertyGroup {
+    name: String,
+    description: String,
+    owner: String,
+    properties: Vec<PropertyDefinition>,
+}
+
+impl PropertyGroup {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn properties(&self) -> &[PropertyDefinition] {
+        &self.properties
+    }
+}
+
+impl FromProto<protos::schema_state::PropertyGroup> for PropertyGroup {
+    fn from_proto(
+        property_group: protos::schema_state::PropertyGroup,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(PropertyGroup {
+            name: property_group.get_name().to_string(),
+            description: property_group.get_description().to_string(),
+            owner: property_group.get_owner().to_string(),
+            properties: property_group
+                .get_properties()
+                .to_vec()
+                .into_iter()
+                .map(PropertyDefinition::from_proto)
+                .collect::<Result<Vec<PropertyDefinition>, ProtoConversionError>>()?,
+        })
+    }
+}
+
+impl FromNative<PropertyGroup> for protos::schema_state::PropertyGroup {
+    fn from_native(property_group: PropertyGroup) -> Result<Self, ProtoConversionError> {
+        let mut proto_property_group = protos::schema_state::PropertyGroup::new();
+        proto_property_group.set_name(property_group.name().to_string());
+        proto_property_group.set_description(property_group.description().to_string());
+        proto_property_group.set_owner(property_group.owner().to_string());
+        proto_property_group.set_properties(RepeatedField::from_vec(
+            property_group
+                .properties()
+                .to_vec()
+                .into_iter()
+                .map(PropertyDefinition::into_proto)
+                .collect::<Result<Vec<protos::schema_state::PropertyDefinition>, ProtoConversionError>>()?,
+        ));
+        Ok(proto_property_group)
+    }
+}
+
+impl FromBytes<PropertyGroup> for PropertyGroup {
+    fn from_bytes(bytes: &[u8]) -> Result<PropertyGroup, ProtoConversionError> {
+        let proto: protos::schema_state::PropertyGroup = protobuf::parse_from_bytes(bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get PropertyGroup from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for PropertyGroup {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from PropertyGroup".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::schema_state::PropertyGroup> for PropertyGroup {}
+impl IntoNative<PropertyGroup> for protos::schema_state::PropertyGroup {}
+
+#[derive(Debug)]
+pub enum PropertyGroupBuildError {
+    MissingField(String),
+}
+
+impl StdError for PropertyGroupBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            PropertyGroupBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            PropertyGroupBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PropertyGroupBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            PropertyGroupBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a PropertyGroup
+#[derive(Default, Clone)]
+pub struct PropertyGroupBuilder {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    pub properties: Vec<PropertyDefinition>,
+}
+
+impl PropertyGroupBuilder {
+    pub fn new() -> Self {
+        PropertyGroupBuilder::default()
+    }
+
+    pub fn with_name(mut self, name: String) -> PropertyGroupBuilder {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn with_description(mut self, description: String) -> PropertyGroupBuilder {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn with_owner(mut self, owner: String) -> PropertyGroupBuilder {
+        self.owner = Some(owner);
+        self
+    }
+
+    pub fn with_properties(mut self, properties: Vec<PropertyDefinition>) -> PropertyGroupBuilder {
+        self.properties = properties;
+        self
+    }
+
+    pub fn build(self) -> Result<PropertyGroup, PropertyGroupBuildError> {
+        let name = self.name.ok_or_else(|| {
+            PropertyGroupBuildError::MissingField("'name' field is required".to_string())
+        })?;
+
+        let owner = self.owner.ok_or_else(|| {
+            PropertyGroupBuildError::MissingField("'owner' field is required".to_string())
+        })?;
+
+        let description = self.description.unwrap_or_default();
+        let properties = {
+            if !self.properties.is_empty() {
+                self.properties
+            } else {
+                return Err(PropertyGroupBuildError::MissingField(
+                    "'properties' field is required".to_string(),
+                ));
+            }
+        };
+
+        Ok(PropertyGroup {
+            name,
+            description,
+            owner,
+            properties,
+        })
+    }
+}
+
+/// Native implementation of PropertyGroupList
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PropertyGroupList {
+    property_groups: Vec<PropertyGroup>,
+}
+
+impl PropertyGroupList {
+    pub fn property_groups(&self) -> &[PropertyGroup] {
+        &self.property_groups
+    }
+}
+
+impl FromProto<protos::schema_state::PropertyGroupList> for PropertyGroupList {
+    fn from_proto(
+        property_group_list: protos::schema_state::PropertyGroupList,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(PropertyGroupList {
+            property_groups: property_group_list
+                .get_property_groups()
+                .to_vec()
+                .into_iter()
+                .map(PropertyGroup::from_proto)
+                .collect::<Result<Vec<PropertyGroup>, ProtoConversionError>>()?,
+        })
+    }
+}
+
+impl FromNative<PropertyGroupList> for protos::schema_state::PropertyGroupList {
+    fn from_native(property_group_list: PropertyGroupList) -> Result<Self, ProtoConversionError> {
+        let mut property_group_list_proto = protos::schema_state::PropertyGroupList::new();
+
+        property_group_list_proto.set_property_groups(RepeatedField::from_vec(
+            property_group_list
+                .property_groups()
+                .to_vec()
+                .into_iter()
+                .map(PropertyGroup::into_proto)
+                .collect::<Result<Vec<protos::schema_state::PropertyGroup>, ProtoConversionError>>(
+                )?,
+        ));
+
+        property_group_list_proto.set_version(STATE_VERSION);
+
+        Ok(property_group_list_proto)
+    }
+}
+
+impl FromBytes<PropertyGroupList> for PropertyGroupList {
+    fn from_bytes(bytes: &[u8]) -> Result<PropertyGroupList, ProtoConversionError> {
+        let bytes = migrate_state(bytes)?;
+        let proto: protos::schema_state::PropertyGroupList = protobuf::parse_from_bytes(&bytes)
+            .map_err(|_| {
+                ProtoConversionError::SerializationError(
+                    "Unable to get PropertyGroupList from bytes".to_string(),
+                )
+            })?;
+        proto.into_native()
+    }
+}
+
+impl IntoBytes for PropertyGroupList {
+    fn into_bytes(self) -> Result<Vec<u8>, ProtoConversionError> {
+        let proto = self.into_proto()?;
+        let bytes = proto.write_to_bytes().map_err(|_| {
+            ProtoConversionError::SerializationError(
+                "Unable to get bytes from PropertyGroupList".to_string(),
+            )
+        })?;
+        Ok(bytes)
+    }
+}
+
+impl IntoProto<protos::schema_state::PropertyGroupList> for PropertyGroupList {}
+impl IntoNative<PropertyGroupList> for protos::schema_state::PropertyGroupList {}
+
+#[derive(Debug)]
+pub enum PropertyGroupListBuildError {
+    MissingField(String),
+}
+
+impl StdError for PropertyGroupListBuildError {
+    fn description(&self) -> &str {
+        match *self {
+            PropertyGroupListBuildError::MissingField(ref msg) => msg,
+        }
+    }
+
+    fn cause(&self) -> Option<&dyn StdError> {
+        match *self {
+            PropertyGroupListBuildError::MissingField(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PropertyGroupListBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            PropertyGroupListBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+        }
+    }
+}
+
+/// Builder used to create a PropertyGroupList
+#[derive(Default, Clone)]
+pub struct PropertyGroupListBuilder {
+    pub property_groups: Vec<PropertyGroup>,
+}
+
+impl PropertyGroupListBuilder {
+    pub fn new() -> Self {
+        PropertyGroupListBuilder::default()
+    }
+
+    pub fn with_property_groups(
+        mut self,
+        property_groups: Vec<PropertyGroup>,
+    ) -> PropertyGroupListBuilder {
+        self.property_groups = property_groups;
+        self
+    }
+
+    pub fn build(self) -> Result<PropertyGroupList, PropertyGroupListBuildError> {
+        let property_groups = {
+            if self.property_groups.is_empty() {
+                return Err(PropertyGroupListBuildError::MissingField(
+                    "'property_groups' cannot be empty".to_string(),
+                ));
+            } else {
+                self.property_groups
+            }
+        };
+
+        Ok(PropertyGroupList { property_groups })
+    }
+}
+
 /// Native implementation of PropertyValue
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "crate::protocol::schema::encoding::PropertyValueRepr",
+        into = "crate::protocol::schema::encoding::PropertyValueRepr"
+    )
+)]
 pub struct PropertyValue {
     name: String,
     data_type: DataType,
@@ -711,6 +1364,12 @@ impl PropertyValue {
         &self.number_value
     }
 
+    /// Returns the NUMBER value as a decimal, given the `number_exponent` of
+    /// the property's definition
+    pub fn number(&self, exponent: i32) -> GridNumber {
+        GridNumber::new(self.number_value, exponent)
+    }
+
     pub fn string_value(&self) -> &str {
         &self.string_value
     }
@@ -874,6 +1533,14 @@ impl PropertyValueBuilder {
         self
     }
 
+    /// Sets the NUMBER value from a decimal, which must already have the
+    /// `number_exponent` of the property's definition; see
+    /// `GridNumber::rescale`.
+    pub fn with_number(mut self, number: GridNumber) -> PropertyValueBuilder {
+        self.number_value = Some(number.value());
+        self
+    }
+
     pub fn with_enum_value(mut self, enum_value: u32) -> PropertyValueBuilder {
         self.enum_value = Some(enum_value);
         self
@@ -1099,6 +1766,43 @@ mod tests {
         assert_eq!(property_definition, original);
     }
 
+    #[test]
+    // check that a property definition with constraints can be converted to bytes and back
+    fn check_property_definition_constraints_bytes() {
+        let original = PropertyDefinitionBuilder::new()
+            .with_name("temperature".to_string())
+            .with_data_type(DataType::Number)
+            .with_number_exponent(0)
+            .with_number_min(-40)
+            .with_number_max(85)
+            .with_unit("Cel".to_string())
+            .build()
+            .unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+
+        let property_definition = PropertyDefinition::from_bytes(&bytes).unwrap();
+        assert_eq!(property_definition, original);
+        assert_eq!(property_definition.number_min(), Some(&-40));
+        assert_eq!(property_definition.number_max(), Some(&85));
+        assert_eq!(property_definition.unit(), "Cel");
+    }
+
+    #[test]
+    // check that constraints not applicable to the data type are rejected
+    fn check_property_definition_builder_invalid_constraint() {
+        let result = PropertyDefinitionBuilder::new()
+            .with_name("serial".to_string())
+            .with_data_type(DataType::String)
+            .with_number_max(10)
+            .build();
+
+        match result {
+            Err(PropertyDefinitionBuildError::InvalidConstraint(_)) => (),
+            res => panic!("Expected InvalidConstraint, got {:?}", res),
+        }
+    }
+
     #[test]
     // check that a schema with a enum property is built correctly
     fn check_schema_builder() {
@@ -1120,6 +1824,7 @@ mod tests {
             .with_name("TestSchema".to_string())
             .with_description("Test Schema".to_string())
             .with_owner("owner".to_string())
+            .with_version(2)
             .with_properties(vec![property_definition.clone()])
             .build()
             .unwrap();
@@ -1127,6 +1832,7 @@ mod tests {
         assert_eq!(schema.name, "TestSchema");
         assert_eq!(schema.description, "Test Schema");
         assert_eq!(schema.owner, "owner");
+        assert_eq!(schema.version, 2);
         assert_eq!(schema.properties, vec![property_definition]);
     }
 
@@ -1143,6 +1849,7 @@ mod tests {
                 "Two".to_string(),
                 "Three".to_string(),
             ])
+            .with_deprecated(true)
             .build()
             .unwrap();
 
@@ -1151,6 +1858,8 @@ mod tests {
             .with_name("TestSchema".to_string())
             .with_description("Test Schema".to_string())
             .with_owner("owner".to_string())
+            .with_version(2)
+            .with_deprecated(true)
             .with_properties(vec![property_definition.clone()])
             .build()
             .unwrap();
@@ -1161,6 +1870,94 @@ mod tests {
         assert_eq!(schema, original);
     }
 
+    #[test]
+    // check that a composed schema keeps its parent, property groups and
+    // inherited properties, and lists the inherited properties first among its
+    // effective properties
+    fn check_schema_builder_composed() {
+        let inherited = PropertyDefinitionBuilder::new()
+            .with_name("weight".to_string())
+            .with_data_type(DataType::Number)
+            .build()
+            .unwrap();
+        let declared = PropertyDefinitionBuilder::new()
+            .with_name("lot".to_string())
+            .with_data_type(DataType::String)
+            .build()
+            .unwrap();
+
+        let original = SchemaBuilder::new()
+            .with_name("TestSchema".to_string())
+            .with_owner("owner".to_string())
+            .with_version(1)
+            .with_parent("ParentSchema".to_string())
+            .with_parent_version(3)
+            .with_property_groups(vec!["shipment".to_string()])
+            .with_properties(vec![declared.clone()])
+            .with_inherited_properties(vec![inherited.clone()])
+            .build()
+            .unwrap();
+
+        assert_eq!(original.parent(), "ParentSchema");
+        assert_eq!(original.parent_version(), &3);
+        assert_eq!(original.property_groups(), &["shipment".to_string()]);
+        assert_eq!(original.effective_properties(), vec![inherited, declared]);
+
+        let bytes = original.clone().into_bytes().unwrap();
+        let schema = Schema::from_bytes(&bytes).unwrap();
+        assert_eq!(schema, original);
+    }
+
+    #[test]
+    // check that a schema only declaring properties requires them, while one
+    // extending a parent does not
+    fn check_schema_builder_properties_required() {
+        match SchemaBuilder::new()
+            .with_name("TestSchema".to_string())
+            .with_owner("owner".to_string())
+            .build()
+        {
+            Err(SchemaBuildError::MissingField(_)) => (),
+            res => panic!("Expected MissingField, got {:?}", res),
+        }
+
+        let schema = SchemaBuilder::new()
+            .with_name("TestSchema".to_string())
+            .with_owner("owner".to_string())
+            .with_parent("ParentSchema".to_string())
+            .build()
+            .unwrap();
+        assert!(schema.properties().is_empty());
+    }
+
+    #[test]
+    // check that a property group list can be converted to bytes and back
+    fn check_property_group_list_bytes() {
+        let property_definition = PropertyDefinitionBuilder::new()
+            .with_name("latitude_longitude".to_string())
+            .with_data_type(DataType::LatLong)
+            .build()
+            .unwrap();
+
+        let property_group = PropertyGroupBuilder::new()
+            .with_name("gps".to_string())
+            .with_description("GPS location".to_string())
+            .with_owner("owner".to_string())
+            .with_properties(vec![property_definition])
+            .build()
+            .unwrap();
+
+        let original = PropertyGroupListBuilder::new()
+            .with_property_groups(vec![property_group])
+            .build()
+            .unwrap();
+
+        let bytes = original.clone().into_bytes().unwrap();
+
+        let property_group_list = PropertyGroupList::from_bytes(&bytes).unwrap();
+        assert_eq!(property_group_list, original);
+    }
+
     #[test]
     // check that a property value with a string data type is built correctly
     fn check_property_value_builder_string() {
@@ -1201,6 +1998,24 @@ mod tests {
         assert_eq!(property_value.struct_values, vec![string_value]);
     }
 
+    #[test]
+    // check that a property value with a number data type is built from a decimal
+    fn check_property_value_builder_number() {
+        let number: GridNumber = "12.50".parse().unwrap();
+        let builder = PropertyValueBuilder::new();
+        let property_value = builder
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::Number)
+            .with_number(number.rescale(-3).unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(property_value.data_type, DataType::Number);
+        assert_eq!(property_value.number_value, 12500);
+        assert_eq!(property_value.number(-3), number);
+        assert_eq!(property_value.number(-3).to_string(), "12.500");
+    }
+
     #[test]
     // check that a property value with a lat_long data type is built correctly
     fn check_property_value_builder_lat_long() {
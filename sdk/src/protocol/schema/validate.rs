@@ -0,0 +1,1126 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of PropertyValues against the PropertyDefinitions of a Schema.
+//!
+//! Struct values are validated recursively. Every error carries the path of
+//! the offending property, with the names of nested struct properties joined
+//! by `.` (for example `shipment.weight`).
+//!
+//! Values are also checked against the optional constraints of their
+//! definition: the bounds of a NUMBER, the pattern and length of a STRING and
+//! the size of BYTES. The constraints themselves are checked by
+//! `validate_property_definitions`.
+//!
+//! The effective properties of a composed schema are resolved from its
+//! sources by `resolve_properties`.
+
+use std::collections::{HashMap, HashSet};
+use std::error::Error as StdError;
+
+use regex::Regex;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::state::{DataType, PropertyDefinition, PropertyValue, Schema};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    /// Returned when a required property has no value
+    MissingProperty { path: String },
+    /// Returned for a value that has no definition
+    UnknownProperty { path: String },
+    /// Returned when more than one value is provided for a property
+    DuplicateProperty { path: String },
+    /// Returned for a value whose name differs from the name of its definition
+    NameMismatch { path: String, name: String },
+    /// Returned for a value whose data type differs from its definition
+    WrongDataType {
+        path: String,
+        expected: DataType,
+        provided: DataType,
+    },
+    /// Returned for an enum value that is not an index of the enum options
+    EnumIndexOutOfRange {
+        path: String,
+        index: u32,
+        options: usize,
+    },
+    /// Returned for a number value outside of the bounds of its definition
+    NumberOutOfRange {
+        path: String,
+        value: i64,
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    /// Returned for a string value that does not match the pattern of its
+    /// definition
+    PatternMismatch { path: String, pattern: String },
+    /// Returned for a string value whose length, in characters, is outside of
+    /// the bounds of its definition
+    LengthOutOfRange {
+        path: String,
+        length: usize,
+        min: u32,
+        max: u32,
+    },
+    /// Returned for a bytes value larger than the size allowed by its
+    /// definition
+    BytesTooLarge { path: String, size: usize, max: u32 },
+    /// Returned for a definition whose constraints are invalid, such as a
+    /// pattern that does not compile or a constraint on the wrong data type
+    InvalidConstraint { path: String, reason: String },
+    /// Returned for a property defined differently by two of the sources a
+    /// schema is composed from
+    ConflictingDefinition {
+        path: String,
+        first: String,
+        second: String,
+    },
+}
+
+impl ValidationError {
+    /// The path of the property the error was found at
+    pub fn path(&self) -> &str {
+        match *self {
+            ValidationError::MissingProperty { ref path }
+            | ValidationError::UnknownProperty { ref path }
+            | ValidationError::DuplicateProperty { ref path }
+            | ValidationError::NameMismatch { ref path, .. }
+            | ValidationError::WrongDataType { ref path, .. }
+            | ValidationError::EnumIndexOutOfRange { ref path, .. }
+            | ValidationError::NumberOutOfRange { ref path, .. }
+            | ValidationError::PatternMismatch { ref path, .. }
+            | ValidationError::LengthOutOfRange { ref path, .. }
+            | ValidationError::BytesTooLarge { ref path, .. }
+            | ValidationError::InvalidConstraint { ref path, .. }
+            | ValidationError::ConflictingDefinition { ref path, .. } => path,
+        }
+    }
+}
+
+impl StdError for ValidationError {}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ValidationError::MissingProperty { ref path } => {
+                write!(f, "Required property {} not provided", path)
+            }
+            ValidationError::UnknownProperty { ref path } => {
+                write!(f, "Provided property {} is not in schema", path)
+            }
+            ValidationError::DuplicateProperty { ref path } => {
+                write!(f, "Property {} was provided more than once", path)
+            }
+            ValidationError::NameMismatch { ref path, ref name } => {
+                write!(f, "Value provided for {} is named {}", path, name)
+            }
+            ValidationError::WrongDataType {
+                ref path,
+                ref expected,
+                ref provided,
+            } => write!(
+                f,
+                "Value provided for {} is the wrong type: {:?} != {:?}",
+                path, provided, expected
+            ),
+            ValidationError::EnumIndexOutOfRange {
+                ref path,
+                index,
+                options,
+            } => write!(
+                f,
+                "Enum value provided for {} is out of range: {} >= {}",
+                path, index, options
+            ),
+            ValidationError::NumberOutOfRange {
+                ref path,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "Number value provided for {} is out of range: {} not in [{}, {}]",
+                path,
+                value,
+                min.map(|min| min.to_string()).unwrap_or_default(),
+                max.map(|max| max.to_string()).unwrap_or_default()
+            ),
+            ValidationError::PatternMismatch {
+                ref path,
+                ref pattern,
+            } => write!(
+                f,
+                "String value provided for {} does not match pattern {}",
+                path, pattern
+            ),
+            ValidationError::LengthOutOfRange {
+                ref path,
+                length,
+                min,
+                max,
+            } => write!(
+                f,
+                "String value provided for {} has length {}; expected at least {} and at most {}",
+                path,
+                length,
+                min,
+                if max == 0 {
+                    "unbounded".to_string()
+                } else {
+                    max.to_string()
+                }
+            ),
+            ValidationError::BytesTooLarge {
+                ref path,
+                size,
+                max,
+            } => write!(
+                f,
+                "Bytes value provided for {} is too large: {} > {}",
+                path, size, max
+            ),
+            ValidationError::InvalidConstraint {
+                ref path,
+                ref reason,
+            } => write!(f, "Invalid constraint on property {}: {}", path, reason),
+            ValidationError::ConflictingDefinition {
+                ref path,
+                ref first,
+                ref second,
+            } => write!(
+                f,
+                "Property {} is defined differently by {} and {}",
+                path, first, second
+            ),
+        }
+    }
+}
+
+/// Validates the values of a new record against the properties of its
+/// schema. Every required property must be provided, at most once, and every
+/// value must match the definition of the same name.
+pub fn validate_schema_values(
+    schema: &Schema,
+    values: &[PropertyValue],
+) -> Result<(), Vec<ValidationError>> {
+    let definitions = schema.effective_properties();
+    let patterns = compile_patterns(&definitions);
+    into_result(check_values(&definitions, values, "", &patterns))
+}
+
+/// Validates a set of values against the definitions they are provided for.
+/// Unlike `validate_schema_values`, required definitions without a value are
+/// accepted, as when updating some of the properties of a record.
+pub fn validate_property_values(
+    definitions: &[PropertyDefinition],
+    values: &[PropertyValue],
+) -> Result<(), Vec<ValidationError>> {
+    let patterns = compile_patterns(definitions);
+    let mut errors = vec![];
+    let mut provided = HashSet::new();
+    for value in values {
+        if !provided.insert(value.name()) {
+            errors.push(ValidationError::DuplicateProperty {
+                path: value.name().to_string(),
+            });
+            continue;
+        }
+
+        match definitions.iter().find(|def| def.name() == value.name()) {
+            Some(definition) => errors.append(&mut check_value(definition, value, "", &patterns)),
+            None => errors.push(ValidationError::UnknownProperty {
+                path: value.name().to_string(),
+            }),
+        }
+    }
+
+    into_result(errors)
+}
+
+/// Validates a single value against its definition
+pub fn validate_property_value(
+    definition: &PropertyDefinition,
+    value: &PropertyValue,
+) -> Result<(), Vec<ValidationError>> {
+    let patterns = compile_patterns(std::slice::from_ref(definition));
+    into_result(check_value(definition, value, "", &patterns))
+}
+
+/// Validates the constraints of a set of definitions, nested struct
+/// properties included. Each constraint must apply to the data type of its
+/// definition, lower bounds must not exceed upper bounds and patterns must be
+/// valid regular expressions.
+pub fn validate_property_definitions(
+    definitions: &[PropertyDefinition],
+) -> Result<(), Vec<ValidationError>> {
+    into_result(check_definitions(definitions, ""))
+}
+
+/// Flattens the definitions of the sources a schema is composed from, such as
+/// its parent, its property groups and its own declarations, into a single
+/// set. A property may come from several sources only if each of them defines
+/// it identically; it is then kept once, at its first position.
+pub fn resolve_properties(
+    sources: &[(&str, &[PropertyDefinition])],
+) -> Result<Vec<PropertyDefinition>, Vec<ValidationError>> {
+    let mut resolved: Vec<(&str, &PropertyDefinition)> = vec![];
+    let mut errors = vec![];
+    for &(source, definitions) in sources {
+        for definition in definitions {
+            match resolved
+                .iter()
+                .find(|(_, existing)| existing.name() == definition.name())
+            {
+                Some((_, existing)) if *existing == definition => (),
+                Some((first, _)) => errors.push(ValidationError::ConflictingDefinition {
+                    path: definition.name().to_string(),
+                    first: first.to_string(),
+                    second: source.to_string(),
+                }),
+                None => resolved.push((source, definition)),
+            }
+        }
+    }
+
+    into_result(errors)?;
+    Ok(resolved
+        .into_iter()
+        .map(|(_, definition)| definition.clone())
+        .collect())
+}
+
+/// Separates the readable message of a transaction rejected for invalid
+/// properties from the JSON array of the ValidationErrorDetails it was
+/// written from. JSON escapes line breaks within strings, so the separator
+/// cannot occur inside the encoded details.
+#[cfg(feature = "json")]
+const DETAILS_SEPARATOR: &str = "\nDetails: ";
+
+/// The path and message of a ValidationError, as reported by a rejected
+/// transaction
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValidationErrorDetail {
+    pub path: String,
+    pub message: String,
+}
+
+impl From<&ValidationError> for ValidationErrorDetail {
+    fn from(error: &ValidationError) -> Self {
+        ValidationErrorDetail {
+            path: error.path().to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Writes the message of a transaction rejected for invalid properties. The
+/// readable messages of the errors are followed by their
+/// ValidationErrorDetails, which `split_validation_message` reads back.
+#[cfg(feature = "json")]
+pub fn validation_errors_to_message(errors: &[ValidationError]) -> String {
+    let message = errors
+        .iter()
+        .map(ValidationError::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let details = errors
+        .iter()
+        .map(ValidationErrorDetail::from)
+        .collect::<Vec<_>>();
+
+    match serde_json::to_string(&details) {
+        Ok(details) => format!("{}{}{}", message, DETAILS_SEPARATOR, details),
+        Err(_) => message,
+    }
+}
+
+/// Splits the message of a rejected transaction into its readable message
+/// and the ValidationErrorDetails written by `validation_errors_to_message`.
+/// Any other message is returned whole, without details.
+#[cfg(feature = "json")]
+pub fn split_validation_message(message: &str) -> (&str, Vec<ValidationErrorDetail>) {
+    if let Some(index) = message.rfind(DETAILS_SEPARATOR) {
+        let details = &message[index + DETAILS_SEPARATOR.len()..];
+        if let Ok(details) = serde_json::from_str(details) {
+            return (&message[..index], details);
+        }
+    }
+    (message, vec![])
+}
+
+fn check_definitions(definitions: &[PropertyDefinition], parent: &str) -> Vec<ValidationError> {
+    let mut errors = vec![];
+    for definition in definitions {
+        errors.append(&mut check_constraints(definition, parent));
+        if *definition.data_type() == DataType::Struct {
+            errors.append(&mut check_definitions(
+                definition.struct_properties(),
+                &join_path(parent, definition.name()),
+            ));
+        }
+    }
+    errors
+}
+
+/// Checks the constraints of a single definition, without its struct
+/// properties
+pub(crate) fn check_constraints(
+    definition: &PropertyDefinition,
+    parent: &str,
+) -> Vec<ValidationError> {
+    let path = join_path(parent, definition.name());
+    let data_type = definition.data_type();
+    let mut errors = vec![];
+    let mut invalid = |reason: String| {
+        errors.push(ValidationError::InvalidConstraint {
+            path: path.clone(),
+            reason,
+        })
+    };
+
+    let number_bounds = definition.number_min().is_some() || definition.number_max().is_some();
+    if number_bounds && *data_type != DataType::Number {
+        invalid("number bounds only apply to NUMBER properties".to_string());
+    }
+    if let (Some(min), Some(max)) = (definition.number_min(), definition.number_max()) {
+        if min > max {
+            invalid(format!(
+                "number_min {} is greater than number_max {}",
+                min, max
+            ));
+        }
+    }
+
+    let string_constraints = !definition.string_pattern().is_empty()
+        || *definition.string_min_length() > 0
+        || *definition.string_max_length() > 0;
+    if string_constraints && *data_type != DataType::String {
+        invalid("string pattern and lengths only apply to STRING properties".to_string());
+    }
+    if *definition.string_max_length() > 0
+        && definition.string_min_length() > definition.string_max_length()
+    {
+        invalid(format!(
+            "string_min_length {} is greater than string_max_length {}",
+            definition.string_min_length(),
+            definition.string_max_length()
+        ));
+    }
+    if let Err(err) = compile_pattern(definition.string_pattern()) {
+        invalid(err);
+    }
+
+    if *definition.bytes_max_size() > 0 && *data_type != DataType::Bytes {
+        invalid("bytes_max_size only applies to BYTES properties".to_string());
+    }
+
+    errors
+}
+
+fn compile_pattern(pattern: &str) -> Result<Option<Regex>, String> {
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+    Regex::new(pattern)
+        .map(Some)
+        .map_err(|err| format!("string_pattern {} is invalid: {}", pattern, err))
+}
+
+fn check_number(definition: &PropertyDefinition, value: i64, path: String) -> Vec<ValidationError> {
+    let below = definition.number_min().map_or(false, |min| value < *min);
+    let above = definition.number_max().map_or(false, |max| value > *max);
+    if below || above {
+        vec![ValidationError::NumberOutOfRange {
+            path,
+            value,
+            min: definition.number_min().cloned(),
+            max: definition.number_max().cloned(),
+        }]
+    } else {
+        vec![]
+    }
+}
+
+/// The compiled string patterns of a set of definitions, keyed by pattern
+type Patterns<'a> = HashMap<&'a str, Regex>;
+
+/// Compiles the string patterns of a set of definitions, nested struct
+/// properties included, so that each is compiled once per validation rather
+/// than once per value. Patterns that do not compile are left out: they are
+/// errors of the definitions, reported by `validate_property_definitions`,
+/// not of the values.
+fn compile_patterns(definitions: &[PropertyDefinition]) -> Patterns<'_> {
+    let mut patterns = HashMap::new();
+    add_patterns(definitions, &mut patterns);
+    patterns
+}
+
+fn add_patterns<'a>(definitions: &'a [PropertyDefinition], patterns: &mut Patterns<'a>) {
+    for definition in definitions {
+        let pattern = definition.string_pattern();
+        if !patterns.contains_key(pattern) {
+            if let Ok(Some(regex)) = compile_pattern(pattern) {
+                patterns.insert(pattern, regex);
+            }
+        }
+        add_patterns(definition.struct_properties(), patterns);
+    }
+}
+
+fn check_string(
+    definition: &PropertyDefinition,
+    value: &str,
+    path: String,
+    patterns: &Patterns,
+) -> Vec<ValidationError> {
+    let mut errors = vec![];
+
+    let length = value.chars().count();
+    let min = *definition.string_min_length();
+    let max = *definition.string_max_length();
+    if length < min as usize || (max > 0 && length > max as usize) {
+        errors.push(ValidationError::LengthOutOfRange {
+            path: path.clone(),
+            length,
+            min,
+            max,
+        });
+    }
+
+    if let Some(regex) = patterns.get(definition.string_pattern()) {
+        if !regex.is_match(value) {
+            errors.push(ValidationError::PatternMismatch {
+                path,
+                pattern: definition.string_pattern().to_string(),
+            });
+        }
+    }
+
+    errors
+}
+
+fn check_bytes(
+    definition: &PropertyDefinition,
+    value: &[u8],
+    path: String,
+) -> Vec<ValidationError> {
+    let max = *definition.bytes_max_size();
+    if max > 0 && value.len() > max as usize {
+        vec![ValidationError::BytesTooLarge {
+            path,
+            size: value.len(),
+            max,
+        }]
+    } else {
+        vec![]
+    }
+}
+
+fn into_result(errors: Vec<ValidationError>) -> Result<(), Vec<ValidationError>> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    }
+}
+
+fn check_values(
+    definitions: &[PropertyDefinition],
+    values: &[PropertyValue],
+    parent: &str,
+    patterns: &Patterns,
+) -> Vec<ValidationError> {
+    let mut errors = vec![];
+
+    for definition in definitions {
+        if *definition.required() && !values.iter().any(|value| value.name() == definition.name()) {
+            errors.push(ValidationError::MissingProperty {
+                path: join_path(parent, definition.name()),
+            });
+        }
+    }
+
+    let mut provided = HashSet::new();
+    for value in values {
+        let path = join_path(parent, value.name());
+        if !provided.insert(value.name()) {
+            errors.push(ValidationError::DuplicateProperty { path });
+            continue;
+        }
+
+        match definitions.iter().find(|def| def.name() == value.name()) {
+            Some(definition) => {
+                errors.append(&mut check_value(definition, value, parent, patterns))
+            }
+            None => errors.push(ValidationError::UnknownProperty { path }),
+        }
+    }
+
+    errors
+}
+
+fn check_value(
+    definition: &PropertyDefinition,
+    value: &PropertyValue,
+    parent: &str,
+    patterns: &Patterns,
+) -> Vec<ValidationError> {
+    let path = join_path(parent, definition.name());
+
+    if value.name() != definition.name() {
+        return vec![ValidationError::NameMismatch {
+            path,
+            name: value.name().to_string(),
+        }];
+    }
+
+    if value.data_type() != definition.data_type() {
+        return vec![ValidationError::WrongDataType {
+            path,
+            expected: definition.data_type().clone(),
+            provided: value.data_type().clone(),
+        }];
+    }
+
+    match value.data_type() {
+        DataType::Enum if *value.enum_value() as usize >= definition.enum_options().len() => {
+            vec![ValidationError::EnumIndexOutOfRange {
+                path,
+                index: *value.enum_value(),
+                options: definition.enum_options().len(),
+            }]
+        }
+        DataType::Struct => check_values(
+            definition.struct_properties(),
+            value.struct_values(),
+            &path,
+            patterns,
+        ),
+        DataType::Number => check_number(definition, *value.number_value(), path),
+        DataType::String => check_string(definition, value.string_value(), path, patterns),
+        DataType::Bytes => check_bytes(definition, value.bytes_value(), path),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use protobuf::RepeatedField;
+
+    use crate::protocol::schema::state::{
+        PropertyDefinitionBuilder, PropertyValueBuilder, SchemaBuilder,
+    };
+    use crate::protos;
+    use crate::protos::IntoNative;
+
+    fn string_definition(name: &str, required: bool) -> PropertyDefinition {
+        PropertyDefinitionBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(DataType::String)
+            .with_required(required)
+            .build()
+            .unwrap()
+    }
+
+    fn string_value(name: &str) -> PropertyValue {
+        PropertyValueBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(DataType::String)
+            .with_string_value("value".to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn number_value(name: &str) -> PropertyValue {
+        PropertyValueBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(DataType::Number)
+            .with_number_value(3)
+            .build()
+            .unwrap()
+    }
+
+    fn enum_value(name: &str, index: u32) -> PropertyValue {
+        PropertyValueBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(DataType::Enum)
+            .with_enum_value(index)
+            .build()
+            .unwrap()
+    }
+
+    fn struct_value(name: &str, values: Vec<PropertyValue>) -> PropertyValue {
+        PropertyValueBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(DataType::Struct)
+            .with_struct_values(values)
+            .build()
+            .unwrap()
+    }
+
+    fn enum_definition(name: &str) -> PropertyDefinition {
+        PropertyDefinitionBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(DataType::Enum)
+            .with_enum_options(vec!["One".to_string(), "Two".to_string()])
+            .build()
+            .unwrap()
+    }
+
+    fn struct_definition(name: &str) -> PropertyDefinition {
+        PropertyDefinitionBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(DataType::Struct)
+            .with_required(true)
+            .with_struct_properties(vec![
+                string_definition("carrier", true),
+                enum_definition("mode"),
+            ])
+            .build()
+            .unwrap()
+    }
+
+    fn schema() -> Schema {
+        SchemaBuilder::new()
+            .with_name("TestSchema".to_string())
+            .with_description("Test Schema".to_string())
+            .with_owner("owner".to_string())
+            .with_properties(vec![
+                string_definition("name", true),
+                string_definition("notes", false),
+                struct_definition("shipment"),
+            ])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    // check that values matching the schema, nested structs included, are valid
+    fn check_valid_schema_values() {
+        let values = vec![
+            string_value("name"),
+            struct_value(
+                "shipment",
+                vec![string_value("carrier"), enum_value("mode", 1)],
+            ),
+        ];
+
+        assert_eq!(validate_schema_values(&schema(), &values), Ok(()));
+    }
+
+    #[test]
+    // check that missing required properties are reported at every level
+    fn check_missing_required_properties() {
+        let values = vec![struct_value("shipment", vec![enum_value("mode", 0)])];
+
+        assert_eq!(
+            validate_schema_values(&schema(), &values),
+            Err(vec![
+                ValidationError::MissingProperty {
+                    path: "name".to_string()
+                },
+                ValidationError::MissingProperty {
+                    path: "shipment.carrier".to_string()
+                },
+            ])
+        );
+    }
+
+    #[test]
+    // check that unknown and duplicate properties are reported
+    fn check_unknown_and_duplicate_properties() {
+        let values = vec![
+            string_value("name"),
+            string_value("name"),
+            string_value("color"),
+            struct_value(
+                "shipment",
+                vec![string_value("carrier"), string_value("weight")],
+            ),
+        ];
+
+        assert_eq!(
+            validate_schema_values(&schema(), &values),
+            Err(vec![
+                ValidationError::DuplicateProperty {
+                    path: "name".to_string()
+                },
+                ValidationError::UnknownProperty {
+                    path: "color".to_string()
+                },
+                ValidationError::UnknownProperty {
+                    path: "shipment.weight".to_string()
+                },
+            ])
+        );
+    }
+
+    #[test]
+    // check that values of the wrong data type are reported with their path
+    fn check_wrong_data_type() {
+        let values = vec![
+            string_value("name"),
+            struct_value("shipment", vec![number_value("carrier")]),
+        ];
+
+        let errors = validate_schema_values(&schema(), &values).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![ValidationError::WrongDataType {
+                path: "shipment.carrier".to_string(),
+                expected: DataType::String,
+                provided: DataType::Number,
+            }]
+        );
+        assert_eq!(errors[0].path(), "shipment.carrier");
+        assert_eq!(
+            errors[0].to_string(),
+            "Value provided for shipment.carrier is the wrong type: Number != String"
+        );
+    }
+
+    #[test]
+    // check that enum values must index the enum options
+    fn check_enum_index_out_of_range() {
+        assert_eq!(
+            validate_property_value(&enum_definition("mode"), &enum_value("mode", 2)),
+            Err(vec![ValidationError::EnumIndexOutOfRange {
+                path: "mode".to_string(),
+                index: 2,
+                options: 2,
+            }])
+        );
+    }
+
+    #[test]
+    // check that a partial update does not require every required property
+    fn check_property_values_update() {
+        let definitions = schema().properties().to_vec();
+
+        assert_eq!(
+            validate_property_values(&definitions, &[string_value("notes")]),
+            Ok(())
+        );
+        assert_eq!(
+            validate_property_values(&definitions, &[number_value("notes")]),
+            Err(vec![ValidationError::WrongDataType {
+                path: "notes".to_string(),
+                expected: DataType::String,
+                provided: DataType::Number,
+            }])
+        );
+    }
+
+    fn temperature_definition() -> PropertyDefinition {
+        PropertyDefinitionBuilder::new()
+            .with_name("temperature".to_string())
+            .with_data_type(DataType::Number)
+            .with_number_exponent(0)
+            .with_number_min(-40)
+            .with_number_max(85)
+            .with_unit("Cel".to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn serial_definition() -> PropertyDefinition {
+        PropertyDefinitionBuilder::new()
+            .with_name("serial".to_string())
+            .with_data_type(DataType::String)
+            .with_string_pattern(r"^[A-Z]{3}\d{6}$".to_string())
+            .with_string_max_length(9)
+            .build()
+            .unwrap()
+    }
+
+    fn temperature_value(number: i64) -> PropertyValue {
+        PropertyValueBuilder::new()
+            .with_name("temperature".to_string())
+            .with_data_type(DataType::Number)
+            .with_number_value(number)
+            .build()
+            .unwrap()
+    }
+
+    fn serial_value(serial: &str) -> PropertyValue {
+        PropertyValueBuilder::new()
+            .with_name("serial".to_string())
+            .with_data_type(DataType::String)
+            .with_string_value(serial.to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    // check that number values must be within the bounds of their definition
+    fn check_number_out_of_range() {
+        let definition = temperature_definition();
+
+        assert_eq!(
+            validate_property_value(&definition, &temperature_value(-40)),
+            Ok(())
+        );
+        assert_eq!(
+            validate_property_value(&definition, &temperature_value(85)),
+            Ok(())
+        );
+        assert_eq!(
+            validate_property_value(&definition, &temperature_value(86)),
+            Err(vec![ValidationError::NumberOutOfRange {
+                path: "temperature".to_string(),
+                value: 86,
+                min: Some(-40),
+                max: Some(85),
+            }])
+        );
+    }
+
+    #[test]
+    // check that string values must match the pattern and length of their definition
+    fn check_string_pattern_and_length() {
+        let definition = serial_definition();
+
+        assert_eq!(
+            validate_property_value(&definition, &serial_value("ABC123456")),
+            Ok(())
+        );
+        assert_eq!(
+            validate_property_value(&definition, &serial_value("abc123456")),
+            Err(vec![ValidationError::PatternMismatch {
+                path: "serial".to_string(),
+                pattern: r"^[A-Z]{3}\d{6}$".to_string(),
+            }])
+        );
+        assert_eq!(
+            validate_property_value(&definition, &serial_value("ABC1234567")),
+            Err(vec![
+                ValidationError::LengthOutOfRange {
+                    path: "serial".to_string(),
+                    length: 10,
+                    min: 0,
+                    max: 9,
+                },
+                ValidationError::PatternMismatch {
+                    path: "serial".to_string(),
+                    pattern: r"^[A-Z]{3}\d{6}$".to_string(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    // check that bytes values cannot exceed the size of their definition
+    fn check_bytes_too_large() {
+        let definition = PropertyDefinitionBuilder::new()
+            .with_name("firmware".to_string())
+            .with_data_type(DataType::Bytes)
+            .with_bytes_max_size(4)
+            .build()
+            .unwrap();
+        let value = PropertyValueBuilder::new()
+            .with_name("firmware".to_string())
+            .with_data_type(DataType::Bytes)
+            .with_bytes_value(vec![0; 5])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            validate_property_value(&definition, &value),
+            Err(vec![ValidationError::BytesTooLarge {
+                path: "firmware".to_string(),
+                size: 5,
+                max: 4,
+            }])
+        );
+    }
+
+    #[test]
+    // check that the constraints of definitions received from a payload are validated,
+    // nested struct properties included, and that they are not reported again for values
+    fn check_invalid_definition_constraints() {
+        let mut bad_pattern = protos::schema_state::PropertyDefinition::new();
+        bad_pattern.set_name("serial".to_string());
+        bad_pattern.set_data_type(protos::schema_state::PropertyDefinition_DataType::STRING);
+        bad_pattern.set_string_pattern("[A-Z".to_string());
+
+        let mut bad_bounds = protos::schema_state::PropertyDefinition::new();
+        bad_bounds.set_name("temperature".to_string());
+        bad_bounds.set_data_type(protos::schema_state::PropertyDefinition_DataType::NUMBER);
+        let mut min = protos::schema_state::PropertyDefinition_NumberBound::new();
+        min.set_value(10);
+        bad_bounds.set_number_min(min);
+        let mut max = protos::schema_state::PropertyDefinition_NumberBound::new();
+        max.set_value(-10);
+        bad_bounds.set_number_max(max);
+
+        let mut device = protos::schema_state::PropertyDefinition::new();
+        device.set_name("device".to_string());
+        device.set_data_type(protos::schema_state::PropertyDefinition_DataType::STRUCT);
+        device.set_struct_properties(RepeatedField::from_vec(vec![bad_pattern]));
+
+        let definitions = vec![
+            temperature_definition(),
+            device.into_native().unwrap(),
+            bad_bounds.into_native().unwrap(),
+        ];
+
+        let errors = validate_property_definitions(&definitions).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].path(), "device.serial");
+        assert_eq!(errors[1].path(), "temperature");
+        assert_eq!(
+            errors[1].to_string(),
+            "Invalid constraint on property temperature: number_min 10 is greater than number_max -10"
+        );
+
+        // A pattern that does not compile is an error of its definition, not of each value
+        assert_eq!(
+            validate_property_values(
+                &definitions,
+                &[struct_value("device", vec![serial_value("abc")])]
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    // check that properties shared identically by several sources are kept once, and
+    // that properties defined differently are reported with both of their sources
+    fn check_resolve_properties() {
+        let parent = vec![string_definition("name", true), enum_definition("status")];
+        let gps = vec![string_definition("name", true)];
+        let lot = vec![string_definition("status", false), serial_definition()];
+
+        assert_eq!(
+            resolve_properties(&[("schema Parent", &parent[..]), ("group gps", &gps[..])]),
+            Ok(parent.clone())
+        );
+
+        let errors = resolve_properties(&[
+            ("schema Parent", &parent[..]),
+            ("group gps", &gps[..]),
+            ("group lot", &lot[..]),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            errors,
+            vec![ValidationError::ConflictingDefinition {
+                path: "status".to_string(),
+                first: "schema Parent".to_string(),
+                second: "group lot".to_string(),
+            }]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "Property status is defined differently by schema Parent and group lot"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    // check that the message of a transaction rejected for invalid properties is split back
+    // into its readable message and the details of its validation errors, even when the
+    // messages themselves contain the separators of the readable message
+    fn check_split_validation_message() {
+        let errors = vec![
+            ValidationError::MissingProperty {
+                path: "name".to_string(),
+            },
+            ValidationError::NumberOutOfRange {
+                path: "temperature".to_string(),
+                value: 90,
+                min: Some(-40),
+                max: Some(85),
+            },
+            ValidationError::PatternMismatch {
+                path: "color.rgb_hex".to_string(),
+                pattern: "^#[0-9a-f]{6}, \nDetails: $".to_string(),
+            },
+        ];
+
+        let message = validation_errors_to_message(&errors);
+        let (readable, details) = split_validation_message(&message);
+        assert_eq!(
+            readable,
+            errors
+                .iter()
+                .map(ValidationError::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        assert_eq!(
+            details,
+            errors
+                .iter()
+                .map(ValidationErrorDetail::from)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(details[2].path, "color.rgb_hex");
+
+        assert_eq!(
+            split_validation_message("Record already exists: record_1"),
+            ("Record already exists: record_1", vec![])
+        );
+    }
+
+    #[test]
+    // check that the values of a composed schema are validated against its inherited
+    // properties as well as its declared ones
+    fn check_composed_schema_values() {
+        let schema = SchemaBuilder::new()
+            .with_name("Composed".to_string())
+            .with_owner("owner".to_string())
+            .with_parent("Parent".to_string())
+            .with_properties(vec![string_definition("notes", false)])
+            .with_inherited_properties(vec![string_definition("name", true)])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            validate_schema_values(&schema, &[string_value("notes")]),
+            Err(vec![ValidationError::MissingProperty {
+                path: "name".to_string(),
+            }])
+        );
+        assert_eq!(
+            validate_schema_values(&schema, &[string_value("name"), string_value("notes")]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    // check that a value named differently than its definition is rejected
+    fn check_name_mismatch() {
+        assert_eq!(
+            validate_property_value(&string_definition("name", true), &string_value("notes")),
+            Err(vec![ValidationError::NameMismatch {
+                path: "name".to_string(),
+                name: "notes".to_string(),
+            }])
+        );
+    }
+}
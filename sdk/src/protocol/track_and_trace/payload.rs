@@ -14,10 +14,20 @@
 
 use protobuf::Message;
 use protobuf::RepeatedField;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use std::default::Default;
 
 use super::errors::BuilderError;
+use crate::protocol::addressing::pike::{compute_agent_address, PIKE_AGENT_PREFIX};
+use crate::protocol::addressing::schema::{
+    compute_schema_address, compute_schema_version_address, GRID_NAMESPACE,
+};
+use crate::protocol::addressing::track_and_trace::{
+    compute_property_address, compute_property_address_range, compute_property_page_address_range,
+    compute_proposal_address, compute_record_address,
+};
 use crate::protocol::{schema::state::PropertyValue, track_and_trace::state::Role};
 use crate::protos;
 use crate::protos::{
@@ -28,10 +38,12 @@ use crate::protos::{
 };
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CreateRecordAction {
     record_id: String,
     schema: String,
     properties: Vec<PropertyValue>,
+    schema_version: u32,
 }
 
 impl CreateRecordAction {
@@ -44,6 +56,11 @@ impl CreateRecordAction {
     pub fn properties(&self) -> &[PropertyValue] {
         &self.properties
     }
+    /// The version of the schema to pin the record to, or 0 to pin it to the
+    /// current version of the schema
+    pub fn schema_version(&self) -> &u32 {
+        &self.schema_version
+    }
 }
 
 #[derive(Default, Debug)]
@@ -51,6 +68,7 @@ pub struct CreateRecordActionBuilder {
     record_id: Option<String>,
     schema: Option<String>,
     properties: Option<Vec<PropertyValue>>,
+    schema_version: Option<u32>,
 }
 
 impl CreateRecordActionBuilder {
@@ -69,6 +87,10 @@ impl CreateRecordActionBuilder {
         self.properties = Some(value);
         self
     }
+    pub fn with_schema_version(mut self, value: u32) -> Self {
+        self.schema_version = Some(value);
+        self
+    }
     pub fn build(self) -> Result<CreateRecordAction, BuilderError> {
         let record_id = self
             .record_id
@@ -79,10 +101,12 @@ impl CreateRecordActionBuilder {
         let properties = self
             .properties
             .ok_or_else(|| BuilderError::MissingField("properties".into()))?;
+        let schema_version = self.schema_version.unwrap_or_default();
         Ok(CreateRecordAction {
             record_id,
             schema,
             properties,
+            schema_version,
         })
     }
 }
@@ -100,6 +124,7 @@ impl FromProto<track_and_trace_payload::CreateRecordAction> for CreateRecordActi
                 .into_iter()
                 .map(PropertyValue::from_proto)
                 .collect::<Result<Vec<PropertyValue>, ProtoConversionError>>()?,
+            schema_version: proto.get_schema_version(),
         })
     }
 }
@@ -118,6 +143,7 @@ impl FromNative<CreateRecordAction> for track_and_trace_payload::CreateRecordAct
                 .collect::<Result<Vec<protos::schema_state::PropertyValue>, ProtoConversionError>>(
                 )?,
         ));
+        proto.set_schema_version(*create_record_action.schema_version());
 
         Ok(proto)
     }
@@ -149,6 +175,7 @@ impl IntoProto<track_and_trace_payload::CreateRecordAction> for CreateRecordActi
 impl IntoNative<CreateRecordAction> for track_and_trace_payload::CreateRecordAction {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FinalizeRecordAction {
     record_id: String,
 }
@@ -224,6 +251,7 @@ impl IntoProto<track_and_trace_payload::FinalizeRecordAction> for FinalizeRecord
 impl IntoNative<FinalizeRecordAction> for track_and_trace_payload::FinalizeRecordAction {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UpdatePropertiesAction {
     record_id: String,
     properties: Vec<PropertyValue>,
@@ -329,6 +357,7 @@ impl IntoProto<track_and_trace_payload::UpdatePropertiesAction> for UpdateProper
 impl IntoNative<UpdatePropertiesAction> for track_and_trace_payload::UpdatePropertiesAction {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CreateProposalAction {
     record_id: String,
     receiving_agent: String,
@@ -471,6 +500,8 @@ impl IntoProto<track_and_trace_payload::CreateProposalAction> for CreateProposal
 impl IntoNative<CreateProposalAction> for track_and_trace_payload::CreateProposalAction {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
 pub enum Response {
     Accept,
     Reject,
@@ -509,6 +540,7 @@ impl IntoProto<track_and_trace_payload::AnswerProposalAction_Response> for Respo
 impl IntoNative<Response> for track_and_trace_payload::AnswerProposalAction_Response {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AnswerProposalAction {
     record_id: String,
     receiving_agent: String,
@@ -632,6 +664,7 @@ impl IntoProto<track_and_trace_payload::AnswerProposalAction> for AnswerProposal
 impl IntoNative<AnswerProposalAction> for track_and_trace_payload::AnswerProposalAction {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RevokeReporterAction {
     record_id: String,
     reporter_id: String,
@@ -745,6 +778,11 @@ impl IntoProto<track_and_trace_payload::RevokeReporterAction> for RevokeReporter
 impl IntoNative<RevokeReporterAction> for track_and_trace_payload::RevokeReporterAction {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "action", rename_all = "SCREAMING_SNAKE_CASE")
+)]
 pub enum Action {
     CreateRecord(CreateRecordAction),
     FinalizeRecord(FinalizeRecordAction),
@@ -755,7 +793,9 @@ pub enum Action {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TrackAndTracePayload {
+    #[cfg_attr(feature = "serde", serde(flatten))]
     action: Action,
     timestamp: u64,
 }
@@ -767,6 +807,143 @@ impl TrackAndTracePayload {
     pub fn timestamp(&self) -> &u64 {
         &self.timestamp
     }
+
+    /// Returns the addresses the Track and Trace contract reads when
+    /// `signer_public_key` submits this payload.
+    ///
+    /// Addresses depending on state rather than on the payload, such as the
+    /// properties of a record's schema or the current page of a property,
+    /// are covered by address ranges. Actions checking that the signer is an
+    /// owner, custodian, reporter or party to a proposal read the Pike agent
+    /// namespace, as the recorded key may have been rotated to the signer's.
+    pub fn inputs(&self, signer_public_key: &str) -> Vec<String> {
+        let mut inputs = match &self.action {
+            Action::CreateRecord(action) => {
+                // The current schema is read to check that it is not
+                // deprecated, even when the record is pinned to a version
+                let mut inputs = vec![
+                    compute_agent_address(signer_public_key),
+                    compute_record_address(action.record_id()),
+                    compute_schema_address(action.schema()),
+                    compute_property_address_range(action.record_id()),
+                ];
+                if *action.schema_version() != 0 {
+                    inputs.push(compute_schema_version_address(
+                        action.schema(),
+                        *action.schema_version(),
+                    ));
+                }
+                inputs
+            }
+            Action::FinalizeRecord(action) => vec![
+                PIKE_AGENT_PREFIX.to_string(),
+                compute_record_address(action.record_id()),
+            ],
+            Action::UpdateProperties(action) => {
+                let mut inputs = vec![
+                    PIKE_AGENT_PREFIX.to_string(),
+                    compute_record_address(action.record_id()),
+                ];
+                inputs.extend(action.properties().iter().map(|property| {
+                    compute_property_page_address_range(action.record_id(), property.name())
+                }));
+                inputs
+            }
+            Action::CreateProposal(action) => vec![
+                PIKE_AGENT_PREFIX.to_string(),
+                compute_agent_address(signer_public_key),
+                compute_agent_address(action.receiving_agent()),
+                compute_proposal_address(action.record_id(), action.receiving_agent()),
+                compute_record_address(action.record_id()),
+            ],
+            Action::AnswerProposal(action) => {
+                let mut inputs = vec![
+                    PIKE_AGENT_PREFIX.to_string(),
+                    compute_proposal_address(action.record_id(), action.receiving_agent()),
+                ];
+                if action.response() == &Response::Accept {
+                    inputs.push(compute_record_address(action.record_id()));
+                    match action.role() {
+                        // The new owner reports every property of the record's schema,
+                        // read from the version the record is pinned to, or from the
+                        // current schema for records created before versioning
+                        Role::Owner => {
+                            inputs.push(GRID_NAMESPACE.to_string());
+                            inputs.push(compute_property_address_range(action.record_id()));
+                        }
+                        Role::Custodian => (),
+                        Role::Reporter => {
+                            inputs.push(compute_property_address_range(action.record_id()))
+                        }
+                    }
+                }
+                inputs
+            }
+            Action::RevokeReporter(action) => {
+                let mut inputs = vec![
+                    PIKE_AGENT_PREFIX.to_string(),
+                    compute_record_address(action.record_id()),
+                ];
+                inputs.extend(action.properties().iter().map(|property_name| {
+                    compute_property_address(action.record_id(), property_name, 0)
+                }));
+                inputs
+            }
+        };
+        inputs.sort();
+        inputs.dedup();
+        inputs
+    }
+
+    /// Returns the addresses the Track and Trace contract writes when
+    /// `signer_public_key` submits this payload.
+    pub fn outputs(&self, _signer_public_key: &str) -> Vec<String> {
+        let mut outputs = match &self.action {
+            Action::CreateRecord(action) => vec![
+                compute_record_address(action.record_id()),
+                compute_property_address_range(action.record_id()),
+            ],
+            Action::FinalizeRecord(action) => vec![compute_record_address(action.record_id())],
+            Action::UpdateProperties(action) => action
+                .properties()
+                .iter()
+                .map(|property| {
+                    compute_property_page_address_range(action.record_id(), property.name())
+                })
+                .collect(),
+            Action::CreateProposal(action) => vec![compute_proposal_address(
+                action.record_id(),
+                action.receiving_agent(),
+            )],
+            Action::AnswerProposal(action) => {
+                let mut outputs = vec![compute_proposal_address(
+                    action.record_id(),
+                    action.receiving_agent(),
+                )];
+                if action.response() == &Response::Accept {
+                    match action.role() {
+                        Role::Owner => {
+                            outputs.push(compute_record_address(action.record_id()));
+                            outputs.push(compute_property_address_range(action.record_id()));
+                        }
+                        Role::Custodian => outputs.push(compute_record_address(action.record_id())),
+                        Role::Reporter => {
+                            outputs.push(compute_property_address_range(action.record_id()))
+                        }
+                    }
+                }
+                outputs
+            }
+            Action::RevokeReporter(action) => action
+                .properties()
+                .iter()
+                .map(|property_name| compute_property_address(action.record_id(), property_name, 0))
+                .collect(),
+        };
+        outputs.sort();
+        outputs.dedup();
+        outputs
+    }
 }
 
 #[derive(Default, Debug)]
@@ -14,14 +14,18 @@
 
 use super::errors::BuilderError;
 use crate::protocol::schema::state::{PropertyDefinition, PropertyValue};
+use crate::protocol::versioning::{migrate_state, STATE_VERSION};
 use crate::protos::track_and_trace_state;
 use crate::protos::{
     FromBytes, FromNative, FromProto, IntoBytes, IntoNative, IntoProto, ProtoConversionError,
 };
 use protobuf::Message;
 use protobuf::RepeatedField;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Reporter {
     public_key: String,
     authorized: bool,
@@ -132,6 +136,7 @@ impl IntoProto<track_and_trace_state::Property_Reporter> for Reporter {}
 impl IntoNative<Reporter> for track_and_trace_state::Property_Reporter {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Property {
     name: String,
     record_id: String,
@@ -301,6 +306,7 @@ impl IntoProto<track_and_trace_state::Property> for Property {}
 impl IntoNative<Property> for track_and_trace_state::Property {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PropertyList {
     properties: Vec<Property>,
 }
@@ -363,13 +369,16 @@ impl FromNative<PropertyList> for track_and_trace_state::PropertyList {
                 .collect::<Result<Vec<track_and_trace_state::Property>, ProtoConversionError>>()?,
         ));
 
+        proto.set_version(STATE_VERSION);
+
         Ok(proto)
     }
 }
 
 impl FromBytes<PropertyList> for PropertyList {
     fn from_bytes(bytes: &[u8]) -> Result<PropertyList, ProtoConversionError> {
-        let proto: track_and_trace_state::PropertyList = protobuf::parse_from_bytes(bytes)
+        let bytes = migrate_state(bytes)?;
+        let proto: track_and_trace_state::PropertyList = protobuf::parse_from_bytes(&bytes)
             .map_err(|_| {
                 ProtoConversionError::SerializationError(
                     "Unable to get PropertyList from Bytes".into(),
@@ -391,6 +400,7 @@ impl IntoProto<track_and_trace_state::PropertyList> for PropertyList {}
 impl IntoNative<PropertyList> for track_and_trace_state::PropertyList {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ReportedValue {
     reporter_index: u32,
     timestamp: u64,
@@ -505,6 +515,7 @@ impl IntoProto<track_and_trace_state::PropertyPage_ReportedValue> for ReportedVa
 impl IntoNative<ReportedValue> for track_and_trace_state::PropertyPage_ReportedValue {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PropertyPage {
     name: String,
     record_id: String,
@@ -635,6 +646,7 @@ impl IntoProto<track_and_trace_state::PropertyPage> for PropertyPage {}
 impl IntoNative<PropertyPage> for track_and_trace_state::PropertyPage {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PropertyPageList {
     property_pages: Vec<PropertyPage>,
 }
@@ -698,13 +710,16 @@ impl FromNative<PropertyPageList> for track_and_trace_state::PropertyPageList {
                 )?,
         ));
 
+        proto.set_version(STATE_VERSION);
+
         Ok(proto)
     }
 }
 
 impl FromBytes<PropertyPageList> for PropertyPageList {
     fn from_bytes(bytes: &[u8]) -> Result<PropertyPageList, ProtoConversionError> {
-        let proto: track_and_trace_state::PropertyPageList = protobuf::parse_from_bytes(bytes)
+        let bytes = migrate_state(bytes)?;
+        let proto: track_and_trace_state::PropertyPageList = protobuf::parse_from_bytes(&bytes)
             .map_err(|_| {
                 ProtoConversionError::SerializationError(
                     "Unable to get PropertyPageList from Bytes".into(),
@@ -728,6 +743,8 @@ impl IntoProto<track_and_trace_state::PropertyPageList> for PropertyPageList {}
 impl IntoNative<PropertyPageList> for track_and_trace_state::PropertyPageList {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
 pub enum Role {
     Owner,
     Custodian,
@@ -766,6 +783,8 @@ impl IntoProto<track_and_trace_state::Proposal_Role> for Role {}
 impl IntoNative<Role> for track_and_trace_state::Proposal_Role {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "SCREAMING_SNAKE_CASE"))]
 pub enum Status {
     Open,
     Accepted,
@@ -807,6 +826,7 @@ impl IntoProto<track_and_trace_state::Proposal_Status> for Status {}
 impl IntoNative<Status> for track_and_trace_state::Proposal_Status {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Proposal {
     record_id: String,
     timestamp: u64,
@@ -1002,6 +1022,7 @@ impl IntoProto<track_and_trace_state::Proposal> for Proposal {}
 impl IntoNative<Proposal> for track_and_trace_state::Proposal {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ProposalList {
     proposals: Vec<Proposal>,
 }
@@ -1064,13 +1085,16 @@ impl FromNative<ProposalList> for track_and_trace_state::ProposalList {
                 .collect::<Result<Vec<track_and_trace_state::Proposal>, ProtoConversionError>>()?,
         ));
 
+        proto.set_version(STATE_VERSION);
+
         Ok(proto)
     }
 }
 
 impl FromBytes<ProposalList> for ProposalList {
     fn from_bytes(bytes: &[u8]) -> Result<ProposalList, ProtoConversionError> {
-        let proto: track_and_trace_state::ProposalList = protobuf::parse_from_bytes(bytes)
+        let bytes = migrate_state(bytes)?;
+        let proto: track_and_trace_state::ProposalList = protobuf::parse_from_bytes(&bytes)
             .map_err(|_| {
                 ProtoConversionError::SerializationError(
                     "Unable to get ProposalList from bytes".into(),
@@ -1094,6 +1118,7 @@ impl IntoProto<track_and_trace_state::ProposalList> for ProposalList {}
 impl IntoNative<ProposalList> for track_and_trace_state::ProposalList {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AssociatedAgent {
     agent_id: String,
     timestamp: u64,
@@ -1194,12 +1219,15 @@ impl IntoProto<track_and_trace_state::Record_AssociatedAgent> for AssociatedAgen
 impl IntoNative<AssociatedAgent> for track_and_trace_state::Record_AssociatedAgent {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Record {
     record_id: String,
     schema: String,
     owners: Vec<AssociatedAgent>,
     custodians: Vec<AssociatedAgent>,
+    #[cfg_attr(feature = "serde", serde(rename = "final"))]
     field_final: bool,
+    schema_version: u32,
 }
 
 impl Record {
@@ -1218,6 +1246,11 @@ impl Record {
     pub fn field_final(&self) -> &bool {
         &self.field_final
     }
+    /// The version of the schema the record is pinned to, or 0 for records
+    /// created before schemas were versioned
+    pub fn schema_version(&self) -> &u32 {
+        &self.schema_version
+    }
     pub fn into_builder(self) -> RecordBuilder {
         RecordBuilder::new()
             .with_record_id(self.record_id)
@@ -1225,6 +1258,7 @@ impl Record {
             .with_owners(self.owners)
             .with_custodians(self.custodians)
             .with_field_final(self.field_final)
+            .with_schema_version(self.schema_version)
     }
 }
 
@@ -1235,6 +1269,7 @@ pub struct RecordBuilder {
     owners: Option<Vec<AssociatedAgent>>,
     custodians: Option<Vec<AssociatedAgent>>,
     field_final: Option<bool>,
+    schema_version: Option<u32>,
 }
 
 impl RecordBuilder {
@@ -1261,6 +1296,10 @@ impl RecordBuilder {
         self.field_final = Some(value);
         self
     }
+    pub fn with_schema_version(mut self, value: u32) -> Self {
+        self.schema_version = Some(value);
+        self
+    }
     pub fn build(self) -> Result<Record, BuilderError> {
         let record_id = self
             .record_id
@@ -1277,12 +1316,14 @@ impl RecordBuilder {
         let field_final = self
             .field_final
             .ok_or_else(|| BuilderError::MissingField("field_final".into()))?;
+        let schema_version = self.schema_version.unwrap_or_default();
         Ok(Record {
             record_id,
             schema,
             owners,
             custodians,
             field_final,
+            schema_version,
         })
     }
 }
@@ -1305,6 +1346,7 @@ impl FromProto<track_and_trace_state::Record> for Record {
                 .map(AssociatedAgent::from_proto)
                 .collect::<Result<Vec<AssociatedAgent>, ProtoConversionError>>()?,
             field_final: proto.get_field_final(),
+            schema_version: proto.get_schema_version(),
         })
     }
 }
@@ -1341,6 +1383,7 @@ impl FromNative<Record> for track_and_trace_state::Record {
             ),
         );
         proto.set_field_final(*native.field_final());
+        proto.set_schema_version(*native.schema_version());
 
         Ok(proto)
     }
@@ -1368,6 +1411,7 @@ impl IntoProto<track_and_trace_state::Record> for Record {}
 impl IntoNative<Record> for track_and_trace_state::Record {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RecordList {
     records: Vec<Record>,
 }
@@ -1428,14 +1472,17 @@ impl FromNative<RecordList> for track_and_trace_state::RecordList {
                 .collect::<Result<Vec<track_and_trace_state::Record>, ProtoConversionError>>()?,
         ));
 
+        proto.set_version(STATE_VERSION);
+
         Ok(proto)
     }
 }
 
 impl FromBytes<RecordList> for RecordList {
     fn from_bytes(bytes: &[u8]) -> Result<RecordList, ProtoConversionError> {
+        let bytes = migrate_state(bytes)?;
         let proto: track_and_trace_state::RecordList =
-            protobuf::parse_from_bytes(bytes).map_err(|_| {
+            protobuf::parse_from_bytes(&bytes).map_err(|_| {
                 ProtoConversionError::SerializationError("Unable to get Record from bytes".into())
             })?;
         proto.into_native()
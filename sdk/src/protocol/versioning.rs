@@ -0,0 +1,310 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Versioning of payloads and state.
+//!
+//! Payloads are versioned by the family version of the transaction carrying
+//! them, and state lists by their `version` field. A `Migrator` upgrades the
+//! encoded bytes of an older version, one step at a time, to the layout of
+//! the current version, so that only the current layout is ever decoded.
+
+use std::error::Error;
+use std::fmt;
+
+use super::addressing::pike::PIKE_FAMILY_VERSION;
+use super::addressing::schema::GRID_SCHEMA_FAMILY_VERSION;
+use super::addressing::track_and_trace::TRACK_AND_TRACE_FAMILY_VERSION;
+use crate::protos::versioning::StateVersion;
+use crate::protos::ProtoConversionError;
+
+#[cfg(target_arch = "wasm32")]
+use sabre_sdk::Header as TransactionHeader;
+#[cfg(not(target_arch = "wasm32"))]
+use sawtooth_sdk::messages::transaction::TransactionHeader;
+
+/// The version of the state layout written by this SDK
+pub const STATE_VERSION: u32 = 1;
+
+/// Upgrades bytes encoded with one version to the layout of the next one
+pub type Upgrade = fn(&[u8]) -> Result<Vec<u8>, VersionError>;
+
+#[derive(Debug)]
+pub enum VersionError {
+    /// Returned when bytes were encoded with a version that cannot be
+    /// upgraded to the current one
+    UnsupportedVersion(String),
+    /// Returned when an upgrade function fails
+    UpgradeFailed(String),
+    /// Returned when the version of state cannot be read
+    ProtoConversion(ProtoConversionError),
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VersionError::UnsupportedVersion(ref msg) => {
+                write!(f, "UnsupportedVersion: {}", msg)
+            }
+            VersionError::UpgradeFailed(ref msg) => write!(f, "UpgradeFailed: {}", msg),
+            VersionError::ProtoConversion(ref err) => write!(f, "ProtoConversion: {}", err),
+        }
+    }
+}
+
+impl Error for VersionError {
+    fn cause(&self) -> Option<&dyn Error> {
+        match *self {
+            VersionError::UnsupportedVersion(_) => None,
+            VersionError::UpgradeFailed(_) => None,
+            VersionError::ProtoConversion(ref err) => Some(err),
+        }
+    }
+}
+
+impl From<ProtoConversionError> for VersionError {
+    fn from(err: ProtoConversionError) -> VersionError {
+        VersionError::ProtoConversion(err)
+    }
+}
+
+impl From<VersionError> for ProtoConversionError {
+    fn from(err: VersionError) -> ProtoConversionError {
+        match err {
+            VersionError::ProtoConversion(err) => err,
+            err => ProtoConversionError::SerializationError(err.to_string()),
+        }
+    }
+}
+
+/// Upgrades encoded bytes from older versions to the current one.
+///
+/// Each upgrade goes from one version to the next, and older versions are
+/// migrated by chaining upgrades until the current version is reached.
+pub struct Migrator<V> {
+    current: V,
+    upgrades: Vec<(V, V, Upgrade)>,
+}
+
+impl<V: Clone + PartialEq + fmt::Display> Migrator<V> {
+    pub fn new(current: V) -> Self {
+        Migrator {
+            current,
+            upgrades: vec![],
+        }
+    }
+
+    /// Registers the upgrade of bytes encoded with `from` to the layout of
+    /// `to`.
+    pub fn with_upgrade(mut self, from: V, to: V, upgrade: Upgrade) -> Self {
+        self.upgrades.push((from, to, upgrade));
+        self
+    }
+
+    pub fn current(&self) -> &V {
+        &self.current
+    }
+
+    /// Returns the versions that can be migrated to the current version,
+    /// newest first.
+    pub fn supported_versions(&self) -> Vec<V> {
+        let mut versions = vec![self.current.clone()];
+        let mut index = 0;
+        while index < versions.len() {
+            for (from, to, _) in &self.upgrades {
+                if *to == versions[index] && !versions.contains(from) {
+                    versions.push(from.clone());
+                }
+            }
+            index += 1;
+        }
+        versions
+    }
+
+    /// Upgrades `bytes`, encoded with `version`, to the layout of the current
+    /// version.
+    pub fn migrate(&self, version: &V, bytes: &[u8]) -> Result<Vec<u8>, VersionError> {
+        let mut version = version.clone();
+        let mut bytes = bytes.to_vec();
+
+        // Every upgrade is applied at most once, which bounds the chain even
+        // if upgrades were registered in a cycle
+        for _ in 0..=self.upgrades.len() {
+            if version == self.current {
+                return Ok(bytes);
+            }
+
+            let (_, to, upgrade) = self
+                .upgrades
+                .iter()
+                .find(|(from, _, _)| *from == version)
+                .ok_or_else(|| {
+                    VersionError::UnsupportedVersion(format!(
+                        "No upgrade from version {} to version {}",
+                        version, self.current
+                    ))
+                })?;
+
+            bytes = upgrade(&bytes)?;
+            version = to.clone();
+        }
+
+        Err(VersionError::UnsupportedVersion(format!(
+            "Upgrades from version {} do not reach version {}",
+            version, self.current
+        )))
+    }
+}
+
+/// Returns the version of the state layout `bytes`, an encoded state list,
+/// were written with.
+pub fn state_version(bytes: &[u8]) -> Result<u32, VersionError> {
+    let proto: StateVersion = protobuf::parse_from_bytes(bytes).map_err(|_| {
+        ProtoConversionError::SerializationError(
+            "Unable to get state version from bytes".to_string(),
+        )
+    })?;
+    Ok(proto.get_version())
+}
+
+/// Returns the migrator of the layouts of state lists
+pub fn state_migrator() -> Migrator<u32> {
+    // Version 0 is the layout of the lists written before versioning was
+    // introduced, which only lacks the version field
+    Migrator::new(STATE_VERSION).with_upgrade(0, 1, |bytes| Ok(bytes.to_vec()))
+}
+
+/// Upgrades an encoded state list to the current state layout
+pub fn migrate_state(bytes: &[u8]) -> Result<Vec<u8>, VersionError> {
+    state_migrator().migrate(&state_version(bytes)?, bytes)
+}
+
+/// Returns the migrator of Pike payloads, keyed by family version
+pub fn pike_payload_migrator() -> Migrator<String> {
+    Migrator::new(PIKE_FAMILY_VERSION.to_string())
+}
+
+/// Returns the migrator of Grid Schema payloads, keyed by family version
+pub fn schema_payload_migrator() -> Migrator<String> {
+    Migrator::new(GRID_SCHEMA_FAMILY_VERSION.to_string())
+}
+
+/// Returns the migrator of Track and Trace payloads, keyed by family version
+pub fn track_and_trace_payload_migrator() -> Migrator<String> {
+    Migrator::new(TRACK_AND_TRACE_FAMILY_VERSION.to_string())
+}
+
+/// Returns the family version the payload of a transaction was encoded with,
+/// given the header of the transaction and the family version of the contract
+/// processing it.
+///
+/// Sabre does not expose the family version of a transaction, but runs the
+/// version of the contract named by the transaction, which only receives
+/// payloads encoded with its own family version.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn payload_version(header: &TransactionHeader, _family_version: &str) -> String {
+    header.get_family_version().to_string()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn payload_version(_header: &TransactionHeader, family_version: &str) -> String {
+    family_version.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn append(suffix: u8, bytes: &[u8]) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        bytes.push(suffix);
+        bytes
+    }
+
+    fn migrator() -> Migrator<u32> {
+        Migrator::new(3)
+            .with_upgrade(2, 3, |bytes| Ok(append(3, bytes)))
+            .with_upgrade(1, 2, |bytes| Ok(append(2, bytes)))
+    }
+
+    #[test]
+    // Test that bytes are upgraded through every version up to the current one
+    fn test_migrate_chains_upgrades() {
+        let migrator = migrator();
+
+        assert_eq!(migrator.migrate(&3, &[0]).unwrap(), vec![0]);
+        assert_eq!(migrator.migrate(&2, &[0]).unwrap(), vec![0, 3]);
+        assert_eq!(migrator.migrate(&1, &[0]).unwrap(), vec![0, 2, 3]);
+        assert_eq!(migrator.supported_versions(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    // Test that versions without a path to the current version are rejected
+    fn test_migrate_unsupported_version() {
+        let migrator = migrator();
+        match migrator.migrate(&0, &[0]) {
+            Err(VersionError::UnsupportedVersion(_)) => (),
+            res => panic!("Expected UnsupportedVersion, got {:?}", res),
+        }
+        match migrator.migrate(&4, &[0]) {
+            Err(VersionError::UnsupportedVersion(_)) => (),
+            res => panic!("Expected UnsupportedVersion, got {:?}", res),
+        }
+
+        let cyclic = Migrator::new(3)
+            .with_upgrade(1, 2, |bytes| Ok(bytes.to_vec()))
+            .with_upgrade(2, 1, |bytes| Ok(bytes.to_vec()));
+        assert!(cyclic.migrate(&1, &[0]).is_err());
+    }
+
+    #[test]
+    // Test that failing upgrades abort the migration
+    fn test_migrate_upgrade_failed() {
+        let migrator = migrator().with_upgrade(0, 1, |_| {
+            Err(VersionError::UpgradeFailed(
+                "layout 0 is corrupt".to_string(),
+            ))
+        });
+        match migrator.migrate(&0, &[0]) {
+            Err(VersionError::UpgradeFailed(_)) => (),
+            res => panic!("Expected UpgradeFailed, got {:?}", res),
+        }
+    }
+
+    #[test]
+    // Test that state written before versioning reads as version 0 and is migrated
+    fn test_migrate_unversioned_state() {
+        // An AgentList with a single agent whose org_id is "org"
+        let unversioned = vec![0x0a, 0x05, 0x0a, 0x03, b'o', b'r', b'g'];
+        assert_eq!(state_version(&unversioned).unwrap(), 0);
+        assert_eq!(migrate_state(&unversioned).unwrap(), unversioned);
+
+        let mut versioned = unversioned.clone();
+        versioned.extend_from_slice(&[0x78, 0x01]);
+        assert_eq!(state_version(&versioned).unwrap(), 1);
+        assert_eq!(migrate_state(&versioned).unwrap(), versioned);
+
+        let mut future = unversioned;
+        future.extend_from_slice(&[0x78, 0x02]);
+        assert!(migrate_state(&future).is_err());
+    }
+
+    #[test]
+    // Test that the payload version is the family version of the transaction
+    fn test_payload_version() {
+        let mut header = TransactionHeader::new();
+        header.set_family_version("2.0".to_string());
+
+        assert_eq!(payload_version(&header, "1.0"), "2.0");
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use sawtooth_sdk::messages::processor::TpProcessRequest;
+use sawtooth_sdk::messages::transaction::TransactionHeader;
+use sawtooth_sdk::processor::handler::{
+    ApplyError, ContextError, TransactionContext, TransactionHandler,
+};
+
+use crate::protocol::addressing::pike::{
+    compute_agent_address, compute_alternate_id_address, compute_org_address, compute_role_address,
+};
+use crate::protocol::addressing::schema::{
+    compute_property_group_address, compute_schema_address, compute_schema_version_address,
+};
+use crate::protocol::addressing::track_and_trace::{
+    compute_property_address, compute_proposal_address, compute_record_address,
+};
+use crate::protocol::pike::state::{
+    Agent, AgentList, AlternateIdIndexList, Organization, OrganizationList, Role, RoleList,
+};
+use crate::protocol::schema::state::{PropertyGroup, PropertyGroupList, Schema, SchemaList};
+use crate::protocol::track_and_trace::state::{
+    Property, PropertyList, PropertyPage, PropertyPageList, Proposal, ProposalList, Record,
+    RecordList,
+};
+use crate::protos::FromBytes;
+use crate::transact::GridPayload;
+
+/// An event added by a transaction handler
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub event_type: String,
+    pub attributes: Vec<(String, String)>,
+    pub data: Vec<u8>,
+}
+
+/// A TransactionContext keeping state in memory.
+///
+/// It records the addresses read, written and deleted, and the events and
+/// receipt data added, since the last payload was applied.
+#[derive(Default, Debug)]
+pub struct MockTransactionContext {
+    state: RefCell<HashMap<String, Vec<u8>>>,
+    reads: RefCell<Vec<String>>,
+    writes: RefCell<Vec<String>>,
+    deletes: RefCell<Vec<String>>,
+    events: RefCell<Vec<Event>>,
+    receipts: RefCell<Vec<Vec<u8>>>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl TransactionContext for MockTransactionContext {
+    fn get_state_entries(
+        &self,
+        addresses: &[String],
+    ) -> Result<Vec<(String, Vec<u8>)>, ContextError> {
+        let mut results = Vec::new();
+        for addr in addresses {
+            self.reads.borrow_mut().push(addr.to_string());
+            let data = match self.state.borrow().get(addr) {
+                Some(data) => data.clone(),
+                None => Vec::new(),
+            };
+            results.push((addr.to_string(), data));
+        }
+        Ok(results)
+    }
+
+    fn set_state_entries(&self, entries: Vec<(String, Vec<u8>)>) -> Result<(), ContextError> {
+        for (addr, data) in entries {
+            self.writes.borrow_mut().push(addr.clone());
+            self.state.borrow_mut().insert(addr, data);
+        }
+        Ok(())
+    }
+
+    fn delete_state_entries(&self, addresses: &[String]) -> Result<Vec<String>, ContextError> {
+        let mut deleted = Vec::new();
+        for addr in addresses {
+            self.deletes.borrow_mut().push(addr.to_string());
+            if self.state.borrow_mut().remove(addr).is_some() {
+                deleted.push(addr.to_string());
+            }
+        }
+        Ok(deleted)
+    }
+
+    fn add_receipt_data(&self, data: &[u8]) -> Result<(), ContextError> {
+        self.receipts.borrow_mut().push(data.to_vec());
+        Ok(())
+    }
+
+    fn add_event(
+        &self,
+        event_type: String,
+        attributes: Vec<(String, String)>,
+        data: &[u8],
+    ) -> Result<(), ContextError> {
+        self.events.borrow_mut().push(Event {
+            event_type,
+            attributes,
+            data: data.to_vec(),
+        });
+        Ok(())
+    }
+}
+
+impl MockTransactionContext {
+    /// Applies a payload submitted by `signer` through `handler`, in a
+    /// transaction declaring the inputs and outputs computed from the payload.
+    ///
+    /// The accesses recorded for earlier payloads are cleared first, so that
+    /// only the accesses of this payload are checked afterwards.
+    pub fn apply<P: GridPayload>(
+        &mut self,
+        handler: &dyn TransactionHandler,
+        payload: &P,
+        signer: &str,
+    ) -> Result<(), ApplyError> {
+        self.inputs = payload.inputs(signer);
+        self.outputs = payload.outputs(signer);
+
+        let mut header = TransactionHeader::new();
+        header.set_signer_public_key(signer.to_string());
+        header.set_family_name(P::FAMILY_NAME.to_string());
+        header.set_family_version(P::FAMILY_VERSION.to_string());
+        header.set_inputs(self.inputs.clone().into());
+        header.set_outputs(self.outputs.clone().into());
+
+        let bytes = payload.clone().into_bytes().map_err(|err| {
+            ApplyError::InvalidTransaction(format!("Cannot serialize payload: {}", err))
+        })?;
+
+        let mut request = TpProcessRequest::new();
+        request.set_header(header);
+        request.set_payload(bytes);
+
+        self.reads.borrow_mut().clear();
+        self.writes.borrow_mut().clear();
+        self.deletes.borrow_mut().clear();
+        self.events.borrow_mut().clear();
+        self.receipts.borrow_mut().clear();
+
+        handler.apply(&request, self)
+    }
+
+    /// Returns the addresses read, in order, including repeated reads
+    pub fn reads(&self) -> Vec<String> {
+        self.reads.borrow().clone()
+    }
+
+    /// Returns the addresses written, in order, including repeated writes
+    pub fn writes(&self) -> Vec<String> {
+        self.writes.borrow().clone()
+    }
+
+    /// Returns the addresses deleted, in order
+    pub fn deletes(&self) -> Vec<String> {
+        self.deletes.borrow().clone()
+    }
+
+    pub fn events(&self) -> Vec<Event> {
+        self.events.borrow().clone()
+    }
+
+    pub fn receipts(&self) -> Vec<Vec<u8>> {
+        self.receipts.borrow().clone()
+    }
+
+    /// Returns the bytes stored at `address`, without recording a read
+    pub fn get_entry(&self, address: &str) -> Option<Vec<u8>> {
+        self.state.borrow().get(address).cloned()
+    }
+
+    /// Stores `data` at `address`, without recording a write
+    pub fn set_entry(&self, address: String, data: Vec<u8>) {
+        self.state.borrow_mut().insert(address, data);
+    }
+
+    pub fn get_agent(&self, public_key: &str) -> Option<Agent> {
+        let agent_list: AgentList = self.get_list(&compute_agent_address(public_key))?;
+        agent_list
+            .agents()
+            .iter()
+            .find(|agent| agent.public_key() == public_key)
+            .cloned()
+    }
+
+    pub fn get_organization(&self, org_id: &str) -> Option<Organization> {
+        let org_list: OrganizationList = self.get_list(&compute_org_address(org_id))?;
+        org_list
+            .organizations()
+            .iter()
+            .find(|org| org.org_id() == org_id)
+            .cloned()
+    }
+
+    pub fn get_role(&self, org_id: &str, name: &str) -> Option<Role> {
+        let role_list: RoleList = self.get_list(&compute_role_address(org_id, name))?;
+        role_list
+            .roles()
+            .iter()
+            .find(|role| role.org_id() == org_id && role.name() == name)
+            .cloned()
+    }
+
+    /// Returns the organization the index entry of an alternate ID points to
+    pub fn get_alternate_id_holder(&self, id_type: &str, id: &str) -> Option<String> {
+        let entry_list: AlternateIdIndexList =
+            self.get_list(&compute_alternate_id_address(id_type, id))?;
+        entry_list
+            .entries()
+            .iter()
+            .find(|entry| entry.id_type() == id_type && entry.id() == id)
+            .map(|entry| entry.org_id().to_string())
+    }
+
+    pub fn get_schema(&self, name: &str) -> Option<Schema> {
+        let schema_list: SchemaList = self.get_list(&compute_schema_address(name))?;
+        schema_list
+            .schemas()
+            .iter()
+            .find(|schema| schema.name() == name)
+            .cloned()
+    }
+
+    pub fn get_schema_version(&self, name: &str, version: u32) -> Option<Schema> {
+        let schema_list: SchemaList =
+            self.get_list(&compute_schema_version_address(name, version))?;
+        schema_list
+            .schemas()
+            .iter()
+            .find(|schema| schema.name() == name)
+            .cloned()
+    }
+
+    pub fn get_property_group(&self, name: &str) -> Option<PropertyGroup> {
+        let property_group_list: PropertyGroupList =
+            self.get_list(&compute_property_group_address(name))?;
+        property_group_list
+            .property_groups()
+            .iter()
+            .find(|property_group| property_group.name() == name)
+            .cloned()
+    }
+
+    pub fn get_record(&self, record_id: &str) -> Option<Record> {
+        let record_list: RecordList = self.get_list(&compute_record_address(record_id))?;
+        record_list
+            .records()
+            .iter()
+            .find(|record| record.record_id() == record_id)
+            .cloned()
+    }
+
+    pub fn get_property(&self, record_id: &str, property_name: &str) -> Option<Property> {
+        let property_list: PropertyList =
+            self.get_list(&compute_property_address(record_id, property_name, 0))?;
+        property_list
+            .properties()
+            .iter()
+            .find(|property| property.name() == property_name)
+            .cloned()
+    }
+
+    pub fn get_property_page(
+        &self,
+        record_id: &str,
+        property_name: &str,
+        page: u32,
+    ) -> Option<PropertyPage> {
+        let page_list: PropertyPageList =
+            self.get_list(&compute_property_address(record_id, property_name, page))?;
+        page_list
+            .property_pages()
+            .iter()
+            .find(|property_page| property_page.name() == property_name)
+            .cloned()
+    }
+
+    /// Returns the proposals received by `agent_id` for a record, whatever
+    /// their status
+    pub fn get_proposals(&self, record_id: &str, agent_id: &str) -> Vec<Proposal> {
+        self.get_list::<ProposalList>(&compute_proposal_address(record_id, agent_id))
+            .map(|proposal_list| proposal_list.proposals().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Checks that every address accessed by the last payload applied is
+    /// under one of the addresses it declared, and that every declared
+    /// address was accessed
+    pub fn assert_accesses_declared(&self) {
+        assert_accesses_declared(&self.reads.borrow(), &self.inputs, "read", "input");
+        let mut writes = self.writes();
+        writes.extend(self.deletes());
+        assert_accesses_declared(&writes, &self.outputs, "written", "output");
+    }
+
+    pub fn assert_no_entry(&self, address: &str) {
+        if let Some(data) = self.state.borrow().get(address) {
+            assert!(
+                data.is_empty(),
+                "Expected no entry at {}, found {} bytes",
+                address,
+                data.len()
+            );
+        }
+    }
+
+    /// Checks that the last payload applied added an event of `event_type`,
+    /// and returns the first one
+    pub fn assert_event(&self, event_type: &str) -> Event {
+        let events = self.events();
+        events
+            .iter()
+            .find(|event| event.event_type == event_type)
+            .cloned()
+            .unwrap_or_else(|| panic!("Expected a {} event, got {:?}", event_type, events))
+    }
+
+    fn get_list<T: FromBytes<T>>(&self, address: &str) -> Option<T> {
+        match self.state.borrow().get(address) {
+            Some(data) if !data.is_empty() => Some(
+                T::from_bytes(data)
+                    .unwrap_or_else(|err| panic!("Cannot deserialize {}: {}", address, err)),
+            ),
+            _ => None,
+        }
+    }
+}
+
+fn assert_accesses_declared(accessed: &[String], declared: &[String], access: &str, kind: &str) {
+    for address in accessed {
+        assert!(
+            declared.iter().any(|prefix| address.starts_with(prefix)),
+            "Address {} was {} but not declared as an {} in {:?}",
+            address,
+            access,
+            kind,
+            declared
+        );
+    }
+    for prefix in declared {
+        assert!(
+            accessed.iter().any(|address| address.starts_with(prefix)),
+            "Address {} was declared as an {} but not {}",
+            prefix,
+            kind,
+            access
+        );
+    }
+}
@@ -0,0 +1,738 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fixtures describing the state a test starts from.
+//!
+//! Each fixture either builds the native state object it describes, writes
+//! it to a `MockTransactionContext` without recording the write, or builds
+//! the payload creating it through the transaction handler of its family.
+
+use super::MockTransactionContext;
+
+use crate::protocol::addressing::pike::{
+    compute_agent_address, compute_alternate_id_address, compute_org_address,
+};
+use crate::protocol::addressing::schema::{
+    compute_property_group_address, compute_schema_address, compute_schema_version_address,
+};
+use crate::protocol::addressing::track_and_trace::{
+    compute_property_address, compute_record_address,
+};
+use crate::protocol::pike::payload::{
+    Action as PikeAction, CreateAgentActionBuilder, CreateOrganizationActionBuilder, PikePayload,
+    PikePayloadBuilder,
+};
+use crate::protocol::pike::state::{
+    Agent, AgentBuilder, AgentListBuilder, AlternateId, AlternateIdIndexBuilder,
+    AlternateIdIndexListBuilder, KeyValueEntry, KeyValueEntryBuilder, Organization,
+    OrganizationBuilder, OrganizationListBuilder,
+};
+use crate::protocol::schema::payload::{
+    Action as SchemaAction, PropertyGroupCreateBuilder, SchemaCreateBuilder, SchemaPayload,
+    SchemaPayloadBuilder,
+};
+use crate::protocol::schema::state::{
+    PropertyDefinition, PropertyGroup, PropertyGroupBuilder, PropertyGroupListBuilder,
+    PropertyValue, Schema, SchemaBuilder, SchemaListBuilder,
+};
+use crate::protocol::track_and_trace::payload::{
+    Action as TrackAndTraceAction, CreateRecordActionBuilder, TrackAndTracePayload,
+    TrackAndTracePayloadBuilder,
+};
+use crate::protocol::track_and_trace::state::{
+    AssociatedAgentBuilder, Property, PropertyBuilder, PropertyListBuilder, PropertyPage,
+    PropertyPageBuilder, PropertyPageListBuilder, Record, RecordBuilder, RecordListBuilder,
+    ReportedValueBuilder, ReporterBuilder,
+};
+use crate::protos::IntoBytes;
+
+fn key_value_entries(entries: &[(String, String)]) -> Vec<KeyValueEntry> {
+    entries
+        .iter()
+        .map(|(key, value)| {
+            KeyValueEntryBuilder::new()
+                .with_key(key.clone())
+                .with_value(value.clone())
+                .build()
+                .expect("Failed to build KeyValueEntry")
+        })
+        .collect()
+}
+
+fn set_list<T: IntoBytes>(context: &MockTransactionContext, address: String, list: T) {
+    let bytes = list.into_bytes().expect("Failed to serialize state list");
+    context.set_entry(address, bytes);
+}
+
+/// An organization, named after its ID unless given a name
+#[derive(Clone, Debug)]
+pub struct OrganizationFixture {
+    org_id: String,
+    name: String,
+    address: String,
+    metadata: Vec<(String, String)>,
+    parent_org_id: String,
+    alternate_ids: Vec<AlternateId>,
+}
+
+impl OrganizationFixture {
+    pub fn new(org_id: &str) -> Self {
+        OrganizationFixture {
+            org_id: org_id.to_string(),
+            name: org_id.to_string(),
+            address: "".to_string(),
+            metadata: vec![],
+            parent_org_id: "".to_string(),
+            alternate_ids: vec![],
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_address(mut self, address: &str) -> Self {
+        self.address = address.to_string();
+        self
+    }
+
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_parent(mut self, parent_org_id: &str) -> Self {
+        self.parent_org_id = parent_org_id.to_string();
+        self
+    }
+
+    /// Adds an alternate ID of the form `id_type:id`, such as `gln:0614141000012`
+    pub fn with_alternate_id(mut self, alternate_id: &str) -> Self {
+        self.alternate_ids
+            .push(alternate_id.parse().expect("Failed to parse AlternateId"));
+        self
+    }
+
+    pub fn build(&self) -> Organization {
+        OrganizationBuilder::new()
+            .with_org_id(self.org_id.clone())
+            .with_name(self.name.clone())
+            .with_address(self.address.clone())
+            .with_metadata(key_value_entries(&self.metadata))
+            .with_parent_org_id(self.parent_org_id.clone())
+            .with_alternate_ids(self.alternate_ids.clone())
+            .build()
+            .expect("Failed to build Organization")
+    }
+
+    /// Returns the Pike payload creating the organization
+    pub fn payload(&self) -> PikePayload {
+        let action = CreateOrganizationActionBuilder::new()
+            .with_org_id(self.org_id.clone())
+            .with_name(self.name.clone())
+            .with_address(self.address.clone())
+            .with_metadata(key_value_entries(&self.metadata))
+            .with_parent_org_id(self.parent_org_id.clone())
+            .with_alternate_ids(self.alternate_ids.clone())
+            .build()
+            .expect("Failed to build CreateOrganizationAction");
+
+        PikePayloadBuilder::new()
+            .with_action(PikeAction::CreateOrganization)
+            .with_create_organization(action)
+            .build()
+            .expect("Failed to build PikePayload")
+    }
+
+    pub fn add_to(&self, context: &MockTransactionContext) -> Organization {
+        let org = self.build();
+        let org_list = OrganizationListBuilder::new()
+            .with_organizations(vec![org.clone()])
+            .build()
+            .expect("Failed to build OrganizationList");
+        set_list(context, compute_org_address(&self.org_id), org_list);
+
+        for alternate_id in &self.alternate_ids {
+            let entry = AlternateIdIndexBuilder::new()
+                .with_id_type(alternate_id.id_type().to_string())
+                .with_id(alternate_id.id().to_string())
+                .with_org_id(self.org_id.clone())
+                .build()
+                .expect("Failed to build AlternateIdIndex");
+            let entry_list = AlternateIdIndexListBuilder::new()
+                .with_entries(vec![entry])
+                .build()
+                .expect("Failed to build AlternateIdIndexList");
+            set_list(
+                context,
+                compute_alternate_id_address(alternate_id.id_type(), alternate_id.id()),
+                entry_list,
+            );
+        }
+        org
+    }
+}
+
+/// An active agent of an organization, without roles unless given some
+#[derive(Clone, Debug)]
+pub struct AgentFixture {
+    public_key: String,
+    org_id: String,
+    active: bool,
+    roles: Vec<String>,
+    metadata: Vec<(String, String)>,
+    successor: String,
+}
+
+impl AgentFixture {
+    pub fn new(public_key: &str, org_id: &str) -> Self {
+        AgentFixture {
+            public_key: public_key.to_string(),
+            org_id: org_id.to_string(),
+            active: true,
+            roles: vec![],
+            metadata: vec![],
+            successor: String::new(),
+        }
+    }
+
+    pub fn with_roles(mut self, roles: &[&str]) -> Self {
+        self.roles = roles.iter().map(|role| role.to_string()).collect();
+        self
+    }
+
+    pub fn inactive(mut self) -> Self {
+        self.active = false;
+        self
+    }
+
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Makes the agent one whose key was rotated to `successor`, which
+    /// deactivates it. The payload creating the agent does not carry it.
+    pub fn rotated_to(mut self, successor: &str) -> Self {
+        self.active = false;
+        self.successor = successor.to_string();
+        self
+    }
+
+    pub fn build(&self) -> Agent {
+        AgentBuilder::new()
+            .with_public_key(self.public_key.clone())
+            .with_org_id(self.org_id.clone())
+            .with_active(self.active)
+            .with_roles(self.roles.clone())
+            .with_metadata(key_value_entries(&self.metadata))
+            .with_successor(self.successor.clone())
+            .build()
+            .expect("Failed to build Agent")
+    }
+
+    /// Returns the Pike payload creating the agent
+    pub fn payload(&self) -> PikePayload {
+        let action = CreateAgentActionBuilder::new()
+            .with_public_key(self.public_key.clone())
+            .with_org_id(self.org_id.clone())
+            .with_active(self.active)
+            .with_roles(self.roles.clone())
+            .with_metadata(key_value_entries(&self.metadata))
+            .build()
+            .expect("Failed to build CreateAgentAction");
+
+        PikePayloadBuilder::new()
+            .with_action(PikeAction::CreateAgent)
+            .with_create_agent(action)
+            .build()
+            .expect("Failed to build PikePayload")
+    }
+
+    pub fn add_to(&self, context: &MockTransactionContext) -> Agent {
+        let agent = self.build();
+        let agent_list = AgentListBuilder::new()
+            .with_agents(vec![agent.clone()])
+            .build()
+            .expect("Failed to build AgentList");
+        set_list(context, compute_agent_address(&self.public_key), agent_list);
+        agent
+    }
+}
+
+/// A schema owned by an organization, which needs at least one property
+/// unless it extends another schema or includes a property group
+#[derive(Clone, Debug)]
+pub struct SchemaFixture {
+    name: String,
+    owner: String,
+    description: String,
+    version: u32,
+    deprecated: bool,
+    parent: Option<(String, u32)>,
+    property_groups: Vec<String>,
+    properties: Vec<PropertyDefinition>,
+    inherited_properties: Vec<PropertyDefinition>,
+}
+
+impl SchemaFixture {
+    pub fn new(name: &str, owner: &str) -> Self {
+        SchemaFixture {
+            name: name.to_string(),
+            owner: owner.to_string(),
+            description: "".to_string(),
+            version: 1,
+            deprecated: false,
+            parent: None,
+            property_groups: vec![],
+            properties: vec![],
+            inherited_properties: vec![],
+        }
+    }
+
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
+    pub fn with_property(mut self, property: PropertyDefinition) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    /// Extends the current version of `parent`, inheriting its effective
+    /// properties as the Schema contract resolves them
+    pub fn extending(mut self, parent: &SchemaFixture) -> Self {
+        self.parent = Some((parent.name.clone(), parent.version));
+        self.inherited_properties
+            .extend(parent.build().effective_properties());
+        self
+    }
+
+    /// Includes the properties of `property_group`
+    pub fn with_property_group(mut self, property_group: &PropertyGroupFixture) -> Self {
+        self.property_groups.push(property_group.name.clone());
+        self.inherited_properties
+            .extend(property_group.properties.iter().cloned());
+        self
+    }
+
+    pub fn build(&self) -> Schema {
+        let mut builder = SchemaBuilder::new()
+            .with_name(self.name.clone())
+            .with_owner(self.owner.clone())
+            .with_description(self.description.clone())
+            .with_version(self.version)
+            .with_deprecated(self.deprecated)
+            .with_property_groups(self.property_groups.clone())
+            .with_properties(self.properties.clone())
+            .with_inherited_properties(self.inherited_properties.clone());
+        if let Some((ref parent, parent_version)) = self.parent {
+            builder = builder
+                .with_parent(parent.clone())
+                .with_parent_version(parent_version);
+        }
+        builder.build().expect("Failed to build Schema")
+    }
+
+    /// Returns the Grid Schema payload creating the schema. The owner is not
+    /// part of the payload, as schemas are owned by the organization of the
+    /// agent creating them.
+    pub fn payload(&self) -> SchemaPayload {
+        let mut action = SchemaCreateBuilder::new()
+            .with_schema_name(self.name.clone())
+            .with_description(self.description.clone())
+            .with_property_groups(self.property_groups.clone())
+            .with_properties(self.properties.clone());
+        if let Some((ref parent, _)) = self.parent {
+            action = action.with_parent(parent.clone());
+        }
+        let action = action.build().expect("Failed to build SchemaCreateAction");
+
+        SchemaPayloadBuilder::new()
+            .with_action(SchemaAction::SchemaCreate)
+            .with_schema_create(action)
+            .build()
+            .expect("Failed to build SchemaPayload")
+    }
+
+    /// Writes the schema to state, along with the immutable entry of its version
+    pub fn add_to(&self, context: &MockTransactionContext) -> Schema {
+        let schema = self.build();
+        let schema_list = SchemaListBuilder::new()
+            .with_schemas(vec![schema.clone()])
+            .build()
+            .expect("Failed to build SchemaList");
+        set_list(
+            context,
+            compute_schema_address(&self.name),
+            schema_list.clone(),
+        );
+        set_list(
+            context,
+            compute_schema_version_address(&self.name, self.version),
+            schema_list,
+        );
+        schema
+    }
+}
+
+/// A reusable group of property definitions, created by an organization
+#[derive(Clone, Debug)]
+pub struct PropertyGroupFixture {
+    name: String,
+    owner: String,
+    description: String,
+    properties: Vec<PropertyDefinition>,
+}
+
+impl PropertyGroupFixture {
+    pub fn new(name: &str, owner: &str) -> Self {
+        PropertyGroupFixture {
+            name: name.to_string(),
+            owner: owner.to_string(),
+            description: "".to_string(),
+            properties: vec![],
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn with_property(mut self, property: PropertyDefinition) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    pub fn build(&self) -> PropertyGroup {
+        PropertyGroupBuilder::new()
+            .with_name(self.name.clone())
+            .with_owner(self.owner.clone())
+            .with_description(self.description.clone())
+            .with_properties(self.properties.clone())
+            .build()
+            .expect("Failed to build PropertyGroup")
+    }
+
+    /// Returns the Grid Schema payload creating the property group
+    pub fn payload(&self) -> SchemaPayload {
+        let action = PropertyGroupCreateBuilder::new()
+            .with_name(self.name.clone())
+            .with_description(self.description.clone())
+            .with_properties(self.properties.clone())
+            .build()
+            .expect("Failed to build PropertyGroupCreateAction");
+
+        SchemaPayloadBuilder::new()
+            .with_action(SchemaAction::PropertyGroupCreate)
+            .with_property_group_create(action)
+            .build()
+            .expect("Failed to build SchemaPayload")
+    }
+
+    pub fn add_to(&self, context: &MockTransactionContext) -> PropertyGroup {
+        let property_group = self.build();
+        let property_group_list = PropertyGroupListBuilder::new()
+            .with_property_groups(vec![property_group.clone()])
+            .build()
+            .expect("Failed to build PropertyGroupList");
+        set_list(
+            context,
+            compute_property_group_address(&self.name),
+            property_group_list,
+        );
+        property_group
+    }
+}
+
+/// A record of a schema, owned and held by one agent.
+///
+/// Written to state, each property of the record has the owner as its only
+/// reporter, and a first page holding the value reported for it, if any.
+#[derive(Clone, Debug)]
+pub struct RecordFixture {
+    record_id: String,
+    schema: String,
+    schema_version: u32,
+    owner: String,
+    custodian: String,
+    timestamp: u64,
+    finalized: bool,
+    properties: Vec<PropertyDefinition>,
+    values: Vec<PropertyValue>,
+}
+
+impl RecordFixture {
+    pub fn new(record_id: &str, schema: &str, owner: &str) -> Self {
+        RecordFixture {
+            record_id: record_id.to_string(),
+            schema: schema.to_string(),
+            schema_version: 1,
+            owner: owner.to_string(),
+            custodian: owner.to_string(),
+            timestamp: 1,
+            finalized: false,
+            properties: vec![],
+            values: vec![],
+        }
+    }
+
+    /// Pins the record to a version of its schema, by default the first one
+    pub fn with_schema_version(mut self, schema_version: u32) -> Self {
+        self.schema_version = schema_version;
+        self
+    }
+
+    pub fn with_custodian(mut self, custodian: &str) -> Self {
+        self.custodian = custodian.to_string();
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    pub fn finalized(mut self) -> Self {
+        self.finalized = true;
+        self
+    }
+
+    pub fn with_property(mut self, property: PropertyDefinition) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    /// Adds the value reported for the property of the same name
+    pub fn with_value(mut self, value: PropertyValue) -> Self {
+        self.values.push(value);
+        self
+    }
+
+    pub fn build(&self) -> Record {
+        let owner = AssociatedAgentBuilder::new()
+            .with_agent_id(self.owner.clone())
+            .with_timestamp(self.timestamp)
+            .build()
+            .expect("Failed to build AssociatedAgent");
+        let custodian = AssociatedAgentBuilder::new()
+            .with_agent_id(self.custodian.clone())
+            .with_timestamp(self.timestamp)
+            .build()
+            .expect("Failed to build AssociatedAgent");
+
+        RecordBuilder::new()
+            .with_record_id(self.record_id.clone())
+            .with_schema(self.schema.clone())
+            .with_schema_version(self.schema_version)
+            .with_owners(vec![owner])
+            .with_custodians(vec![custodian])
+            .with_field_final(self.finalized)
+            .build()
+            .expect("Failed to build Record")
+    }
+
+    /// Returns the properties of the record, as written to state
+    pub fn build_properties(&self) -> Vec<Property> {
+        let reporter = ReporterBuilder::new()
+            .with_public_key(self.owner.clone())
+            .with_authorized(true)
+            .with_index(0)
+            .build()
+            .expect("Failed to build Reporter");
+
+        self.properties
+            .iter()
+            .map(|property| {
+                PropertyBuilder::new()
+                    .with_name(property.name().to_string())
+                    .with_record_id(self.record_id.clone())
+                    .with_property_definition(property.clone())
+                    .with_reporters(vec![reporter.clone()])
+                    .with_current_page(1)
+                    .with_wrapped(false)
+                    .build()
+                    .expect("Failed to build Property")
+            })
+            .collect()
+    }
+
+    /// Returns the first page of each property of the record
+    pub fn build_pages(&self) -> Vec<PropertyPage> {
+        self.properties
+            .iter()
+            .map(|property| {
+                let reported_values = self
+                    .values
+                    .iter()
+                    .filter(|value| value.name() == property.name())
+                    .map(|value| {
+                        ReportedValueBuilder::new()
+                            .with_reporter_index(0)
+                            .with_timestamp(self.timestamp)
+                            .with_value(value.clone())
+                            .build()
+                            .expect("Failed to build ReportedValue")
+                    })
+                    .collect();
+
+                PropertyPageBuilder::new()
+                    .with_name(property.name().to_string())
+                    .with_record_id(self.record_id.clone())
+                    .with_reported_values(reported_values)
+                    .build()
+                    .expect("Failed to build PropertyPage")
+            })
+            .collect()
+    }
+
+    /// Returns the Track and Trace payload creating the record, with the
+    /// values of the fixture as initial values
+    pub fn payload(&self) -> TrackAndTracePayload {
+        let action = CreateRecordActionBuilder::new()
+            .with_record_id(self.record_id.clone())
+            .with_schema(self.schema.clone())
+            .with_schema_version(self.schema_version)
+            .with_properties(self.values.clone())
+            .build()
+            .expect("Failed to build CreateRecordAction");
+
+        TrackAndTracePayloadBuilder::new()
+            .with_action(TrackAndTraceAction::CreateRecord(action))
+            .with_timestamp(self.timestamp)
+            .build()
+            .expect("Failed to build TrackAndTracePayload")
+    }
+
+    pub fn add_to(&self, context: &MockTransactionContext) -> Record {
+        let record = self.build();
+        let record_list = RecordListBuilder::new()
+            .with_records(vec![record.clone()])
+            .build()
+            .expect("Failed to build RecordList");
+        set_list(
+            context,
+            compute_record_address(&self.record_id),
+            record_list,
+        );
+
+        for property in self.build_properties() {
+            let address = compute_property_address(&self.record_id, property.name(), 0);
+            let property_list = PropertyListBuilder::new()
+                .with_properties(vec![property])
+                .build()
+                .expect("Failed to build PropertyList");
+            set_list(context, address, property_list);
+        }
+
+        for page in self.build_pages() {
+            let address = compute_property_address(&self.record_id, page.name(), 1);
+            let page_list = PropertyPageListBuilder::new()
+                .with_property_pages(vec![page])
+                .build()
+                .expect("Failed to build PropertyPageList");
+            set_list(context, address, page_list);
+        }
+
+        record
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::schema::state::{
+        DataType, PropertyDefinitionBuilder, PropertyValueBuilder,
+    };
+
+    #[test]
+    // Test that fixtures written to a context can be read back from it
+    fn test_add_to_context() {
+        let context = MockTransactionContext::default();
+        OrganizationFixture::new("grid_org").add_to(&context);
+        AgentFixture::new("agent_key", "grid_org")
+            .with_roles(&["admin"])
+            .add_to(&context);
+
+        let definition = PropertyDefinitionBuilder::new()
+            .with_name("weight".to_string())
+            .with_data_type(DataType::Number)
+            .with_number_exponent(0)
+            .build()
+            .unwrap();
+        let value = PropertyValueBuilder::new()
+            .with_name("weight".to_string())
+            .with_data_type(DataType::Number)
+            .with_number_value(5)
+            .build()
+            .unwrap();
+        SchemaFixture::new("crate", "grid_org")
+            .with_property(definition.clone())
+            .add_to(&context);
+        PropertyGroupFixture::new("dimensions", "grid_org")
+            .with_property(definition.clone())
+            .add_to(&context);
+        RecordFixture::new("crate_1", "crate", "agent_key")
+            .with_property(definition)
+            .with_value(value.clone())
+            .add_to(&context);
+
+        assert_eq!(
+            context.get_organization("grid_org").unwrap().name(),
+            "grid_org"
+        );
+        assert_eq!(
+            context.get_agent("agent_key").unwrap().roles(),
+            ["admin".to_string()]
+        );
+        assert_eq!(context.get_schema("crate").unwrap().owner(), "grid_org");
+        assert_eq!(
+            context.get_property_group("dimensions").unwrap().owner(),
+            "grid_org"
+        );
+        assert_eq!(context.get_record("crate_1").unwrap().schema(), "crate");
+        assert_eq!(
+            context
+                .get_property("crate_1", "weight")
+                .unwrap()
+                .current_page(),
+            &1
+        );
+        assert_eq!(
+            context
+                .get_property_page("crate_1", "weight", 1)
+                .unwrap()
+                .reported_values()[0]
+                .value(),
+            &value
+        );
+        assert!(context.get_record("crate_2").is_none());
+        assert!(context.reads().is_empty());
+        assert!(context.writes().is_empty());
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Utilities to test the Grid transaction handlers, enabled by the `test-kit`
+//! feature.
+//!
+//! `MockTransactionContext` keeps state in memory and records what handlers
+//! do with it. Fixtures describe the organizations, agents, schemas and
+//! records a test needs: they are either written straight to state, to set up
+//! the state of other families, or turned into the payload creating them, to
+//! go through the handler under test.
+//!
+//! ```ignore
+//! let mut context = MockTransactionContext::default();
+//! AgentFixture::new(ADMIN_KEY, "grid_org")
+//!     .with_roles(&["admin"])
+//!     .add_to(&context);
+//!
+//! let payload = OrganizationFixture::new("other_org").payload();
+//! context.apply(&PikeTransactionHandler::new(), &payload, ADMIN_KEY)?;
+//! context.assert_accesses_declared();
+//! assert!(context.get_organization("other_org").is_some());
+//! ```
+
+mod context;
+mod fixtures;
+
+pub use context::{Event, MockTransactionContext};
+pub use fixtures::{
+    AgentFixture, OrganizationFixture, PropertyGroupFixture, RecordFixture, SchemaFixture,
+};
@@ -0,0 +1,379 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Creation of signed transactions and batches carrying Grid payloads.
+//!
+//! Payloads are either wrapped in a Sabre `ExecuteContract` action, for the
+//! smart contracts deployed to Sabre, or sent to the native transaction
+//! processor of their family. The input and output addresses of each
+//! transaction are computed from its payload, and its headers are signed by
+//! a `Signer`.
+//!
+//! ```no_run
+//! # use grid_sdk::protocol::pike::payload::PikePayload;
+//! # use grid_sdk::transact::{BatchBuilder, KeySigner, TransactError};
+//! # fn example(payload: PikePayload) -> Result<(), TransactError> {
+//! let signer = KeySigner::from_file("/home/alice/.sawtooth/keys/alice.priv")?;
+//! let batch_list = BatchBuilder::new(&signer)
+//!     .add_transaction(&payload)?
+//!     .create_batch_list();
+//! # Ok(())
+//! # }
+//! ```
+
+mod payload;
+pub mod sabre;
+mod signer;
+
+pub use payload::GridPayload;
+pub use signer::{KeySigner, Signer};
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crypto::digest::Digest;
+use crypto::sha2::Sha512;
+use protobuf::{Message, RepeatedField};
+use sabre_sdk::protocol::payload::{Action, ExecuteContractActionBuilder, SabrePayloadBuilder};
+use sawtooth_sdk::messages::batch::{Batch, BatchHeader, BatchList};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
+
+use crate::protos::ProtoConversionError;
+
+use self::sabre::{
+    compute_contract_address, compute_contract_registry_address,
+    compute_namespace_registry_address, SABRE_FAMILY_NAME, SABRE_FAMILY_VERSION,
+};
+
+#[derive(Debug)]
+pub enum TransactError {
+    /// Returned when a payload cannot be converted to bytes.
+    ProtoConversion(ProtoConversionError),
+    /// Returned when a transaction or batch header cannot be serialized.
+    Protobuf(protobuf::ProtobufError),
+    /// Returned when the Sabre payload wrapping a Grid payload is invalid.
+    Sabre(String),
+    /// Returned when a signer fails to provide its public key or a signature.
+    Signing(String),
+    /// Returned when a transaction address is too short to have a namespace.
+    InvalidAddress(String),
+}
+
+impl fmt::Display for TransactError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TransactError::ProtoConversion(ref e) => e.fmt(f),
+            TransactError::Protobuf(ref e) => e.fmt(f),
+            TransactError::Sabre(ref msg) => write!(f, "Sabre: {}", msg),
+            TransactError::Signing(ref msg) => write!(f, "Signing: {}", msg),
+            TransactError::InvalidAddress(ref msg) => write!(f, "InvalidAddress: {}", msg),
+        }
+    }
+}
+
+impl StdError for TransactError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match *self {
+            TransactError::ProtoConversion(ref e) => Some(e),
+            TransactError::Protobuf(ref e) => Some(e),
+            TransactError::Sabre(_) => None,
+            TransactError::Signing(_) => None,
+            TransactError::InvalidAddress(_) => None,
+        }
+    }
+}
+
+impl From<ProtoConversionError> for TransactError {
+    fn from(err: ProtoConversionError) -> TransactError {
+        TransactError::ProtoConversion(err)
+    }
+}
+
+impl From<protobuf::ProtobufError> for TransactError {
+    fn from(err: protobuf::ProtobufError) -> TransactError {
+        TransactError::Protobuf(err)
+    }
+}
+
+/// How the transactions of a `BatchBuilder` are executed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExecutionMode {
+    /// The payload is wrapped in a Sabre `ExecuteContract` action, run by the
+    /// smart contract of the payload's family.
+    Sabre,
+    /// The payload is sent as is to the transaction processor of its family.
+    Native,
+}
+
+impl Default for ExecutionMode {
+    fn default() -> Self {
+        ExecutionMode::Sabre
+    }
+}
+
+/// Builds a list of batches, each holding a single transaction, signed by
+/// the same signer.
+pub struct BatchBuilder<'a> {
+    signer: &'a dyn Signer,
+    mode: ExecutionMode,
+    batches: Vec<Batch>,
+}
+
+impl<'a> BatchBuilder<'a> {
+    /// Returns a BatchBuilder creating Sabre transactions signed by `signer`.
+    pub fn new(signer: &'a dyn Signer) -> BatchBuilder<'a> {
+        BatchBuilder {
+            signer,
+            mode: ExecutionMode::default(),
+            batches: Vec::new(),
+        }
+    }
+
+    pub fn with_mode(mut self, mode: ExecutionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Adds a batch holding a transaction for `payload`.
+    pub fn add_transaction<P: GridPayload>(mut self, payload: &P) -> Result<Self, TransactError> {
+        let transaction = create_transaction(payload, self.signer, self.mode)?;
+        self.batches
+            .push(create_batch(vec![transaction], self.signer)?);
+        Ok(self)
+    }
+
+    pub fn create_batch_list(self) -> BatchList {
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(RepeatedField::from_vec(self.batches));
+        batch_list
+    }
+}
+
+/// Creates a transaction for `payload`, signed by `signer`, with the input
+/// and output addresses declared by the payload.
+///
+/// Sabre transactions additionally read and write the contract registry and
+/// contract addresses of the payload's family, and the namespace registry
+/// addresses of every namespace they access.
+pub fn create_transaction<P: GridPayload>(
+    payload: &P,
+    signer: &dyn Signer,
+    mode: ExecutionMode,
+) -> Result<Transaction, TransactError> {
+    let public_key = signer.public_key()?;
+    let inputs = payload.inputs(&public_key);
+    let outputs = payload.outputs(&public_key);
+    let payload_bytes = payload.clone().into_bytes()?;
+
+    let mut header = TransactionHeader::new();
+    header.set_nonce(create_nonce());
+    header.set_signer_public_key(public_key.clone());
+    header.set_batcher_public_key(public_key);
+
+    let payload_bytes = match mode {
+        ExecutionMode::Native => {
+            header.set_family_name(P::FAMILY_NAME.into());
+            header.set_family_version(P::FAMILY_VERSION.into());
+            header.set_inputs(RepeatedField::from_vec(inputs));
+            header.set_outputs(RepeatedField::from_vec(outputs));
+            payload_bytes
+        }
+        ExecutionMode::Sabre => {
+            let execute_contract = ExecuteContractActionBuilder::new()
+                .with_name(P::FAMILY_NAME.into())
+                .with_version(P::FAMILY_VERSION.into())
+                .with_inputs(inputs.clone())
+                .with_outputs(outputs.clone())
+                .with_payload(payload_bytes)
+                .build()
+                .map_err(|err| TransactError::Sabre(err.to_string()))?;
+
+            let sabre_payload = SabrePayloadBuilder::new()
+                .with_action(Action::ExecuteContract(execute_contract))
+                .build()
+                .map_err(|err| TransactError::Sabre(err.to_string()))?;
+
+            header.set_family_name(SABRE_FAMILY_NAME.into());
+            header.set_family_version(SABRE_FAMILY_VERSION.into());
+            header.set_inputs(RepeatedField::from_vec(sabre_addresses::<P>(inputs)?));
+            header.set_outputs(RepeatedField::from_vec(sabre_addresses::<P>(outputs)?));
+
+            sabre_sdk::protos::IntoBytes::into_bytes(sabre_payload)
+                .map_err(|err| TransactError::Sabre(err.to_string()))?
+        }
+    };
+
+    header.set_payload_sha512(sha512_hex(&payload_bytes));
+    let header_bytes = header.write_to_bytes()?;
+
+    let mut transaction = Transaction::new();
+    transaction.set_header_signature(signer.sign(&header_bytes)?);
+    transaction.set_header(header_bytes);
+    transaction.set_payload(payload_bytes);
+
+    Ok(transaction)
+}
+
+/// Creates a batch of `transactions`, signed by `signer`.
+pub fn create_batch(
+    transactions: Vec<Transaction>,
+    signer: &dyn Signer,
+) -> Result<Batch, TransactError> {
+    let mut header = BatchHeader::new();
+    header.set_signer_public_key(signer.public_key()?);
+    header.set_transaction_ids(RepeatedField::from_vec(
+        transactions
+            .iter()
+            .map(|transaction| transaction.get_header_signature().to_string())
+            .collect(),
+    ));
+    let header_bytes = header.write_to_bytes()?;
+
+    let mut batch = Batch::new();
+    batch.set_header_signature(signer.sign(&header_bytes)?);
+    batch.set_header(header_bytes);
+    batch.set_transactions(RepeatedField::from_vec(transactions));
+
+    Ok(batch)
+}
+
+/// Returns the addresses a Sabre transaction accesses for a contract
+/// accessing `addresses`
+fn sabre_addresses<P: GridPayload>(addresses: Vec<String>) -> Result<Vec<String>, TransactError> {
+    let mut sabre_addresses = vec![
+        compute_contract_registry_address(P::FAMILY_NAME),
+        compute_contract_address(P::FAMILY_NAME, P::FAMILY_VERSION),
+    ];
+
+    for address in &addresses {
+        let namespace_registry_address = compute_namespace_registry_address(address)?;
+        if !sabre_addresses.contains(&namespace_registry_address) {
+            sabre_addresses.push(namespace_registry_address);
+        }
+    }
+
+    sabre_addresses.extend(addresses);
+    Ok(sabre_addresses)
+}
+
+/// Creates a nonce appropriate for a TransactionHeader
+fn create_nonce() -> String {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("{}{:09}", elapsed.as_secs(), elapsed.subsec_nanos())
+}
+
+/// Returns the hex encoded sha512 hash of `bytes`
+fn sha512_hex(bytes: &[u8]) -> String {
+    let mut sha = Sha512::new();
+    sha.input(bytes);
+    sha.result_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::addressing::pike::{PIKE_FAMILY_NAME, PIKE_NAMESPACE};
+    use crate::protocol::pike::payload::{
+        Action, CreateOrganizationActionBuilder, PikePayload, PikePayloadBuilder,
+    };
+    use crate::protos::IntoBytes;
+
+    const KEY: &str = "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088";
+
+    fn create_org_payload() -> PikePayload {
+        PikePayloadBuilder::new()
+            .with_action(Action::CreateOrganization)
+            .with_create_organization(
+                CreateOrganizationActionBuilder::new()
+                    .with_org_id("organization".to_string())
+                    .with_name("Organization".to_string())
+                    .with_address("address".to_string())
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    // Test that a Sabre transaction executes the Pike contract and also accesses the Sabre
+    // registries
+    fn test_sabre_transaction() {
+        let signer = KeySigner::from_hex(KEY).unwrap();
+        let transaction =
+            create_transaction(&create_org_payload(), &signer, ExecutionMode::Sabre).unwrap();
+        let header: TransactionHeader =
+            protobuf::parse_from_bytes(transaction.get_header()).unwrap();
+
+        assert_eq!(header.get_family_name(), SABRE_FAMILY_NAME);
+        assert_eq!(header.get_signer_public_key(), signer.public_key().unwrap());
+        assert_eq!(
+            header.get_payload_sha512(),
+            sha512_hex(transaction.get_payload())
+        );
+        assert!(header
+            .get_inputs()
+            .contains(&compute_contract_registry_address(PIKE_FAMILY_NAME)));
+        assert!(header
+            .get_outputs()
+            .contains(&compute_namespace_registry_address(PIKE_NAMESPACE).unwrap()));
+    }
+
+    #[test]
+    // Test that a native transaction carries the payload as is, for the family of the payload
+    fn test_native_transaction() {
+        let signer = KeySigner::from_hex(KEY).unwrap();
+        let payload = create_org_payload();
+        let transaction = create_transaction(&payload, &signer, ExecutionMode::Native).unwrap();
+        let header: TransactionHeader =
+            protobuf::parse_from_bytes(transaction.get_header()).unwrap();
+
+        assert_eq!(header.get_family_name(), PIKE_FAMILY_NAME);
+        assert_eq!(
+            header.get_inputs().to_vec(),
+            payload.inputs(&signer.public_key().unwrap())
+        );
+        assert_eq!(
+            transaction.get_payload(),
+            &payload.into_bytes().unwrap()[..]
+        );
+    }
+
+    #[test]
+    // Test that every batch of a batch list holds one transaction, whose id is in its header
+    fn test_batch_list() {
+        let signer = KeySigner::from_hex(KEY).unwrap();
+        let batch_list = BatchBuilder::new(&signer)
+            .add_transaction(&create_org_payload())
+            .unwrap()
+            .add_transaction(&create_org_payload())
+            .unwrap()
+            .create_batch_list();
+
+        assert_eq!(batch_list.get_batches().len(), 2);
+        for batch in batch_list.get_batches() {
+            let header: BatchHeader = protobuf::parse_from_bytes(batch.get_header()).unwrap();
+            assert_eq!(
+                header.get_transaction_ids().to_vec(),
+                vec![batch.get_transactions()[0]
+                    .get_header_signature()
+                    .to_string()]
+            );
+        }
+    }
+}
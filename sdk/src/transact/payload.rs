@@ -0,0 +1,68 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::protocol::addressing::pike::{PIKE_FAMILY_NAME, PIKE_FAMILY_VERSION};
+use crate::protocol::addressing::schema::{GRID_SCHEMA_FAMILY_NAME, GRID_SCHEMA_FAMILY_VERSION};
+use crate::protocol::addressing::track_and_trace::{
+    TRACK_AND_TRACE_FAMILY_NAME, TRACK_AND_TRACE_FAMILY_VERSION,
+};
+use crate::protocol::pike::payload::PikePayload;
+use crate::protocol::schema::payload::SchemaPayload;
+use crate::protocol::track_and_trace::payload::TrackAndTracePayload;
+use crate::protos::IntoBytes;
+
+/// A payload of a Grid family, which knows the addresses its transactions
+/// access.
+///
+/// Addresses may be full addresses or address prefixes.
+pub trait GridPayload: IntoBytes + Clone {
+    const FAMILY_NAME: &'static str;
+    const FAMILY_VERSION: &'static str;
+
+    /// Returns the addresses read when `signer_public_key` submits the payload.
+    fn inputs(&self, signer_public_key: &str) -> Vec<String>;
+
+    /// Returns the addresses written when `signer_public_key` submits the
+    /// payload.
+    fn outputs(&self, signer_public_key: &str) -> Vec<String>;
+}
+
+macro_rules! impl_grid_payload {
+    ($payload:ty, $family_name:expr, $family_version:expr) => {
+        impl GridPayload for $payload {
+            const FAMILY_NAME: &'static str = $family_name;
+            const FAMILY_VERSION: &'static str = $family_version;
+
+            fn inputs(&self, signer_public_key: &str) -> Vec<String> {
+                <$payload>::inputs(self, signer_public_key)
+            }
+
+            fn outputs(&self, signer_public_key: &str) -> Vec<String> {
+                <$payload>::outputs(self, signer_public_key)
+            }
+        }
+    };
+}
+
+impl_grid_payload!(PikePayload, PIKE_FAMILY_NAME, PIKE_FAMILY_VERSION);
+impl_grid_payload!(
+    SchemaPayload,
+    GRID_SCHEMA_FAMILY_NAME,
+    GRID_SCHEMA_FAMILY_VERSION
+);
+impl_grid_payload!(
+    TrackAndTracePayload,
+    TRACK_AND_TRACE_FAMILY_NAME,
+    TRACK_AND_TRACE_FAMILY_VERSION
+);
@@ -0,0 +1,78 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Computation of the state addresses of the Sabre registries.
+
+use crate::protocol::addressing::hash;
+
+use super::TransactError;
+
+pub const SABRE_FAMILY_NAME: &str = "sabre";
+pub const SABRE_FAMILY_VERSION: &str = "0.3";
+pub const SABRE_NAMESPACE_REGISTRY_PREFIX: &str = "00ec00";
+pub const SABRE_CONTRACT_REGISTRY_PREFIX: &str = "00ec01";
+pub const SABRE_CONTRACT_PREFIX: &str = "00ec02";
+
+/// Computes the address of the contract registry of the contract `name`
+pub fn compute_contract_registry_address(name: &str) -> String {
+    String::from(SABRE_CONTRACT_REGISTRY_PREFIX) + &hash(name, 64)
+}
+
+/// Computes the address of the version `version` of the contract `name`
+pub fn compute_contract_address(name: &str, version: &str) -> String {
+    String::from(SABRE_CONTRACT_PREFIX) + &hash(&format!("{},{}", name, version), 64)
+}
+
+/// Computes the address of the namespace registry of the namespace `address`
+/// belongs to, which is its first 6 characters
+pub fn compute_namespace_registry_address(address: &str) -> Result<String, TransactError> {
+    let namespace = address.get(..6).ok_or_else(|| {
+        TransactError::InvalidAddress(format!(
+            "Address must be at least 6 characters long: {}",
+            address
+        ))
+    })?;
+
+    Ok(String::from(SABRE_NAMESPACE_REGISTRY_PREFIX) + &hash(namespace, 64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Test that namespace registry addresses only depend on the namespace of an address
+    fn test_compute_namespace_registry_address() {
+        assert_eq!(
+            compute_namespace_registry_address("cad11d").unwrap(),
+            compute_namespace_registry_address("cad11d00").unwrap()
+        );
+        assert_eq!(
+            compute_namespace_registry_address("cad11d").unwrap(),
+            String::from(SABRE_NAMESPACE_REGISTRY_PREFIX) + &hash("cad11d", 64)
+        );
+        assert!(compute_namespace_registry_address("cad11").is_err());
+    }
+
+    #[test]
+    // Test that contract addresses hash the name and version of the contract
+    fn test_compute_contract_address() {
+        assert_eq!(
+            compute_contract_address("pike", "0.1"),
+            String::from(SABRE_CONTRACT_PREFIX) + &hash("pike,0.1", 64)
+        );
+        assert_eq!(compute_contract_address("pike", "0.1").len(), 70);
+        assert_eq!(compute_contract_registry_address("pike").len(), 70);
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::Path;
+
+use sawtooth_sdk::signing::secp256k1::Secp256k1PrivateKey;
+use sawtooth_sdk::signing::{create_context, Context};
+
+use super::TransactError;
+
+/// Signs the headers of transactions and batches.
+///
+/// Implementations may hold their key in memory, or delegate to an external
+/// signing service.
+pub trait Signer {
+    /// Returns the hex encoded public key of the signer.
+    fn public_key(&self) -> Result<String, TransactError>;
+
+    /// Returns the hex encoded signature of `message`.
+    fn sign(&self, message: &[u8]) -> Result<String, TransactError>;
+}
+
+/// A Signer holding a secp256k1 private key in memory
+pub struct KeySigner {
+    context: Box<dyn Context>,
+    private_key: Secp256k1PrivateKey,
+    public_key: String,
+}
+
+impl KeySigner {
+    pub fn new(private_key: Secp256k1PrivateKey) -> Result<KeySigner, TransactError> {
+        let context = create_context("secp256k1").map_err(signing_error)?;
+        let public_key = context
+            .get_public_key(&private_key)
+            .map_err(signing_error)?
+            .as_hex();
+
+        Ok(KeySigner {
+            context,
+            private_key,
+            public_key,
+        })
+    }
+
+    /// Returns a KeySigner for a hex encoded private key.
+    pub fn from_hex(private_key: &str) -> Result<KeySigner, TransactError> {
+        KeySigner::new(Secp256k1PrivateKey::from_hex(private_key).map_err(signing_error)?)
+    }
+
+    /// Returns a KeySigner for the private key stored on the first line of a
+    /// file, such as the `.priv` files of `~/.sawtooth/keys`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<KeySigner, TransactError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|err| {
+            TransactError::Signing(format!("Unable to read {}: {}", path.display(), err))
+        })?;
+
+        match contents.lines().next() {
+            Some(key) => KeySigner::from_hex(key.trim()),
+            None => Err(TransactError::Signing(format!(
+                "Empty key file: {}",
+                path.display()
+            ))),
+        }
+    }
+}
+
+impl Signer for KeySigner {
+    fn public_key(&self) -> Result<String, TransactError> {
+        Ok(self.public_key.clone())
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<String, TransactError> {
+        self.context
+            .sign(message, &self.private_key)
+            .map_err(signing_error)
+    }
+}
+
+fn signing_error(err: sawtooth_sdk::signing::Error) -> TransactError {
+    TransactError::Signing(err.to_string())
+}
@@ -0,0 +1,172 @@
+// Copyright 2019 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decodes the encoded bytes of the corpus, which holds state and payloads as
+//! written by each released version, with the current SDK.
+//!
+//! Corpus files must never be modified: when a layout changes, the bytes of
+//! the new version are added next to the existing ones.
+
+use std::fmt::Debug;
+
+use grid_sdk::protocol::pike::payload::{Action as PikeAction, PikePayload};
+use grid_sdk::protocol::pike::state::{AgentList, OrganizationList};
+use grid_sdk::protocol::schema::payload::{Action as SchemaAction, SchemaPayload};
+use grid_sdk::protocol::schema::state::{DataType, SchemaList};
+use grid_sdk::protocol::track_and_trace::payload::{
+    Action as TrackAndTraceAction, TrackAndTracePayload,
+};
+use grid_sdk::protocol::track_and_trace::state::{
+    PropertyList, PropertyPageList, ProposalList, RecordList,
+};
+use grid_sdk::protocol::versioning::{state_version, STATE_VERSION};
+use grid_sdk::protos::{FromBytes, IntoBytes};
+
+macro_rules! corpus {
+    ($path:expr) => {
+        decode_hex(include_str!(concat!("corpus/", $path, ".hex")))
+    };
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    let hex = hex.trim();
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("Invalid hex in corpus"))
+        .collect()
+}
+
+/// Decodes a state list from every version, and checks that they all decode
+/// to the same list, which the current version encodes as the newest bytes.
+fn assert_state_compatible<T>(versions: &[Vec<u8>]) -> T
+where
+    T: FromBytes<T> + IntoBytes + Clone + PartialEq + Debug,
+{
+    let newest = versions.last().expect("No version in corpus");
+    assert_eq!(state_version(newest).unwrap(), STATE_VERSION);
+
+    let decoded = T::from_bytes(newest).expect("Unable to decode newest version");
+    for bytes in versions {
+        assert_eq!(T::from_bytes(bytes).unwrap(), decoded);
+    }
+    assert_eq!(&decoded.clone().into_bytes().unwrap(), newest);
+
+    decoded
+}
+
+#[test]
+// Test that Pike state lists of every version decode
+fn test_pike_state_compatibility() {
+    let agent_list: AgentList = assert_state_compatible(&[
+        corpus!("state/v0/agent_list"),
+        corpus!("state/v1/agent_list"),
+    ]);
+    let agent = &agent_list.agents()[0];
+    assert_eq!(agent.public_key(), "agent_key");
+    assert_eq!(agent.org_id(), "grid_org");
+    assert!(*agent.active());
+    assert_eq!(agent.roles(), ["admin".to_string()]);
+    assert_eq!(agent.metadata()[0].value(), "agent@grid.org");
+
+    let org_list: OrganizationList = assert_state_compatible(&[
+        corpus!("state/v0/organization_list"),
+        corpus!("state/v1/organization_list"),
+    ]);
+    assert_eq!(org_list.organizations()[0].name(), "Grid Org");
+}
+
+#[test]
+// Test that Grid Schema state lists of every version decode
+fn test_schema_state_compatibility() {
+    let schema_list: SchemaList = assert_state_compatible(&[
+        corpus!("state/v0/schema_list"),
+        corpus!("state/v1/schema_list"),
+    ]);
+    let schema = &schema_list.schemas()[0];
+    assert_eq!(schema.name(), "temperature_schema");
+    assert_eq!(schema.owner(), "grid_org");
+    assert_eq!(schema.properties()[0].data_type(), &DataType::Number);
+    assert_eq!(schema.properties()[0].number_exponent(), &-3);
+}
+
+#[test]
+// Test that Track and Trace state lists of every version decode
+fn test_track_and_trace_state_compatibility() {
+    let record_list: RecordList = assert_state_compatible(&[
+        corpus!("state/v0/record_list"),
+        corpus!("state/v1/record_list"),
+    ]);
+    assert_eq!(record_list.records()[0].schema(), "temperature_schema");
+    assert_eq!(
+        record_list.records()[0].owners()[0].timestamp(),
+        &1_546_300_800
+    );
+
+    let property_list: PropertyList = assert_state_compatible(&[
+        corpus!("state/v0/property_list"),
+        corpus!("state/v1/property_list"),
+    ]);
+    assert_eq!(property_list.properties()[0].current_page(), &1);
+    assert!(*property_list.properties()[0].reporters()[0].authorized());
+
+    let page_list: PropertyPageList = assert_state_compatible(&[
+        corpus!("state/v0/property_page_list"),
+        corpus!("state/v1/property_page_list"),
+    ]);
+    assert_eq!(
+        page_list.property_pages()[0].reported_values()[0]
+            .value()
+            .number_value(),
+        &21_500
+    );
+
+    let proposal_list: ProposalList = assert_state_compatible(&[
+        corpus!("state/v0/proposal_list"),
+        corpus!("state/v1/proposal_list"),
+    ]);
+    assert_eq!(
+        proposal_list.proposals()[0].receiving_agent(),
+        "reporter_key"
+    );
+    assert_eq!(
+        proposal_list.proposals()[0].properties(),
+        ["temperature".to_string()]
+    );
+}
+
+#[test]
+// Test that payloads of every family version decode
+fn test_payload_compatibility() {
+    let payload = PikePayload::from_bytes(&corpus!("payload/pike/0.1/create_agent")).unwrap();
+    assert_eq!(payload.action(), &PikeAction::CreateAgent);
+    assert_eq!(payload.create_agent().public_key(), "agent_key");
+
+    let payload =
+        SchemaPayload::from_bytes(&corpus!("payload/grid_schema/1.0/schema_create")).unwrap();
+    assert_eq!(payload.action(), &SchemaAction::SchemaCreate);
+    assert_eq!(payload.schema_create().schema_name(), "temperature_schema");
+
+    let payload = TrackAndTracePayload::from_bytes(&corpus!(
+        "payload/grid_track_and_trace/1.0/create_record"
+    ))
+    .unwrap();
+    assert_eq!(payload.timestamp(), &1_546_300_800);
+    match payload.action() {
+        TrackAndTraceAction::CreateRecord(action) => {
+            assert_eq!(action.record_id(), "record_1");
+            assert_eq!(action.properties()[0].number_value(), &21_500);
+        }
+        action => panic!("Expected CreateRecord, got {:?}", action),
+    }
+}